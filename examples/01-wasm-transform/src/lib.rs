@@ -0,0 +1,56 @@
+//! WebAssembly transform which masks digits of record values and drops empty records.
+//!
+//! Build module and consume with it:
+//!
+//! ```text
+//! $ cargo build -p wasm-transform --target wasm32-unknown-unknown --release
+//! $ fluvio consume my-topic -B --transform target/wasm32-unknown-unknown/release/wasm_transform.wasm
+//! ```
+//!
+//! SPU creates new instance of module for each record, so memory is never freed.
+
+/// Allocate `len` bytes for key or value written by SPU
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    let mut buf = Vec::<u8>::with_capacity(len);
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+
+/// Return new value as `ptr << 32 | len`, or negative to drop record
+///
+/// # Safety
+///
+/// Key and value are written by SPU to memory returned by `alloc`
+#[no_mangle]
+pub unsafe extern "C" fn transform(
+    _key_ptr: *const u8,
+    _key_len: usize,
+    value_ptr: *const u8,
+    value_len: usize,
+) -> i64 {
+    let value = std::slice::from_raw_parts(value_ptr, value_len);
+    match mask_digits(value) {
+        Some(value) => {
+            let ptr = value.as_ptr() as usize as u32;
+            let len = value.len() as u32;
+            std::mem::forget(value);
+            (ptr as i64) << 32 | len as i64
+        }
+        None => -1,
+    }
+}
+
+fn mask_digits(value: &[u8]) -> Option<Vec<u8>> {
+    if value.is_empty() {
+        return None;
+    }
+
+    Some(
+        value
+            .iter()
+            .map(|byte| if byte.is_ascii_digit() { b'*' } else { *byte })
+            .collect(),
+    )
+}
@@ -0,0 +1,522 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+pub use policy::{BasicRbacPolicy, Permissions, Rule, Effect, Action};
+
+use fluvio_future::net::TcpStream;
+use fluvio_controlplane_metadata::extended::ObjectType;
+
+use crate::{AuthContext, Authorization, TypeAction, InstanceAction, AuthError};
+use crate::x509::X509Identity;
+
+#[derive(Debug, Clone)]
+pub struct BasicAuthorization {
+    policy: Arc<BasicRbacPolicy>,
+}
+
+impl BasicAuthorization {
+    pub fn new(policy: BasicRbacPolicy) -> Self {
+        Self {
+            policy: Arc::new(policy),
+        }
+    }
+}
+
+#[async_trait]
+impl Authorization for BasicAuthorization {
+    type Stream = TcpStream;
+    type Context = BasicAuthContext;
+
+    async fn create_auth_context(
+        &self,
+        socket: &mut fluvio_socket::InnerFlvSocket<Self::Stream>,
+    ) -> Result<Self::Context, AuthError> {
+        let identity = X509Identity::create_from_connection::<Self::Stream>(socket).await?;
+        Ok(BasicAuthContext {
+            identity,
+            policy: self.policy.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct BasicAuthContext {
+    identity: X509Identity,
+    policy: Arc<BasicRbacPolicy>,
+}
+
+#[async_trait]
+impl AuthContext for BasicAuthContext {
+    async fn allow_type_action(
+        &self,
+        ty: ObjectType,
+        action: TypeAction,
+    ) -> Result<bool, AuthError> {
+        self.policy
+            .evaluate(action.into(), ty, None, &self.identity)
+            .await
+    }
+
+    /// check if action on specific instance can be permitted
+    async fn allow_instance_action(
+        &self,
+        ty: ObjectType,
+        action: InstanceAction,
+        key: &str,
+    ) -> Result<bool, AuthError> {
+        self.policy
+            .evaluate(action.into(), ty, Some(key), &self.identity)
+            .await
+    }
+}
+
+/// basic policy module
+/// does imple subtitution
+mod policy {
+
+    use std::fs::read;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::convert::TryFrom;
+
+    use tracing::debug;
+    use serde::{Serialize, Deserialize};
+
+    use crate::{AuthError, TypeAction, InstanceAction};
+    use crate::x509::X509Identity;
+
+    use super::ObjectType;
+
+    type Role = String;
+
+    #[derive(Debug, Clone, PartialEq, Hash, Eq, Deserialize, Serialize)]
+    pub enum Action {
+        Create,
+        Read,
+        Update,
+        Delete,
+        /// write records, ex: produce to topic
+        Write,
+        All,
+    }
+
+    impl From<TypeAction> for Action {
+        fn from(action: TypeAction) -> Self {
+            match action {
+                TypeAction::Create => Action::Create,
+                TypeAction::Read => Action::Read,
+            }
+        }
+    }
+
+    impl From<InstanceAction> for Action {
+        fn from(action: InstanceAction) -> Self {
+            match action {
+                InstanceAction::Create => Action::Create,
+                InstanceAction::Update => Action::Update,
+                InstanceAction::Delete => Action::Delete,
+                InstanceAction::Read => Action::Read,
+                InstanceAction::Write => Action::Write,
+            }
+        }
+    }
+
+    /// permissions of role on object type.
+    /// list of actions applies to all instances, rules match instances by name
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    pub enum Permissions {
+        Actions(Vec<Action>),
+        Rules(Vec<Rule>),
+    }
+
+    impl From<Vec<Action>> for Permissions {
+        fn from(actions: Vec<Action>) -> Self {
+            Self::Actions(actions)
+        }
+    }
+
+    impl From<Vec<Rule>> for Permissions {
+        fn from(rules: Vec<Rule>) -> Self {
+            Self::Rules(rules)
+        }
+    }
+
+    impl Permissions {
+        /// iterate rules as (name pattern, actions, effect)
+        fn rules(&self) -> Box<dyn Iterator<Item = (&str, &[Action], Effect)> + '_> {
+            match self {
+                Self::Actions(actions) => Box::new(std::iter::once((
+                    ANY_NAME,
+                    actions.as_slice(),
+                    Effect::Allow,
+                ))),
+                Self::Rules(rules) => Box::new(
+                    rules
+                        .iter()
+                        .map(|rule| (rule.name.as_str(), rule.actions.as_slice(), rule.effect)),
+                ),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    pub enum Effect {
+        Allow,
+        Deny,
+    }
+
+    impl Default for Effect {
+        fn default() -> Self {
+            Self::Allow
+        }
+    }
+
+    /// pattern matching any instance name
+    const ANY_NAME: &str = "*";
+
+    fn any_name() -> String {
+        ANY_NAME.to_owned()
+    }
+
+    /// actions on instances whose name matches pattern.
+    /// pattern is exact name or glob, where `*` matches any characters and `?` single character.
+    /// ex: `team-a.*` matches all instances with prefix `team-a.`
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct Rule {
+        #[serde(default = "any_name")]
+        pub name: String,
+        pub actions: Vec<Action>,
+        #[serde(default)]
+        pub effect: Effect,
+    }
+
+    impl Rule {
+        pub fn allow(name: &str, actions: Vec<Action>) -> Self {
+            Self {
+                name: name.to_owned(),
+                actions,
+                effect: Effect::Allow,
+            }
+        }
+
+        pub fn deny(name: &str, actions: Vec<Action>) -> Self {
+            Self {
+                name: name.to_owned(),
+                actions,
+                effect: Effect::Deny,
+            }
+        }
+    }
+
+    /// check if name matches glob pattern
+    pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+
+        let (mut p, mut n) = (0, 0);
+        // position of last `*` in pattern and name position it was matched from
+        let mut star: Option<(usize, usize)> = None;
+
+        while n < name.len() {
+            match pattern.get(p) {
+                Some('*') => {
+                    star = Some((p, n));
+                    p += 1;
+                }
+                Some(c) if *c == '?' || *c == name[n] => {
+                    p += 1;
+                    n += 1;
+                }
+                _ => match star {
+                    // let last `*` consume one more character
+                    Some((star_p, star_n)) => {
+                        p = star_p + 1;
+                        n = star_n + 1;
+                        star = Some((star_p, star_n + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        pattern[p..].iter().all(|c| *c == '*')
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct BasicRbacPolicy(pub HashMap<Role, HashMap<ObjectType, Permissions>>);
+
+    impl From<HashMap<Role, HashMap<ObjectType, Permissions>>> for BasicRbacPolicy {
+        fn from(map: HashMap<Role, HashMap<ObjectType, Permissions>>) -> Self {
+            Self(map)
+        }
+    }
+
+    impl TryFrom<PathBuf> for BasicRbacPolicy {
+        type Error = std::io::Error;
+        fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
+            debug!("reading basic policy: {:#?}", path);
+            let file = read(path)?;
+            let policy: BasicRbacPolicy = serde_json::from_slice(&file)?;
+            Ok(policy)
+        }
+    }
+
+    impl BasicRbacPolicy {
+        /// evaluate action of identity on object type, or on its instance if specified.
+        /// action is permitted if any rule of identity's scopes allows it and none denies it.
+        /// without instance, only deny rules matching all instances apply
+        pub async fn evaluate(
+            &self,
+            action: Action,
+            object_type: ObjectType,
+            instance: Option<&str>,
+            identity: &X509Identity,
+        ) -> Result<bool, AuthError> {
+            let rules = identity
+                .scopes()
+                .iter()
+                .filter_map(|scope| self.0.get(scope))
+                .filter_map(|objects| objects.get(&object_type))
+                .flat_map(|permissions| permissions.rules())
+                .filter(|(_, actions, _)| {
+                    actions
+                        .iter()
+                        .any(|permission| permission == &action || permission == &Action::All)
+                });
+
+            let mut is_allowed = false;
+            for (name, _, effect) in rules {
+                let matched = match instance {
+                    Some(instance) => glob_match(name, instance),
+                    None => effect == Effect::Allow || name == ANY_NAME,
+                };
+                if !matched {
+                    continue;
+                }
+                match effect {
+                    Effect::Allow => is_allowed = true,
+                    Effect::Deny => {
+                        debug!("action: {:?} denied on: {:?}", action, instance);
+                        return Ok(false);
+                    }
+                }
+            }
+
+            Ok(is_allowed)
+        }
+    }
+
+    impl Default for BasicRbacPolicy {
+        // default only allows the `Root` role to have full permissions;
+        fn default() -> Self {
+            let mut root_policy = HashMap::new();
+
+            root_policy.insert(ObjectType::Spu, vec![Action::All].into());
+            root_policy.insert(ObjectType::CustomSpu, vec![Action::All].into());
+            root_policy.insert(ObjectType::SpuGroup, vec![Action::All].into());
+            root_policy.insert(ObjectType::Topic, vec![Action::All].into());
+            root_policy.insert(ObjectType::Partition, vec![Action::All].into());
+
+            let mut policy = HashMap::new();
+
+            policy.insert(String::from("Root"), root_policy);
+
+            Self(policy)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::convert::TryFrom;
+    use std::collections::HashMap;
+
+    use fluvio_future::test_async;
+
+    use crate::x509::X509Identity;
+
+    use super::policy::*;
+    use super::ObjectType;
+
+    #[test]
+    fn test_policy_serialization() {
+        let mut policy = BasicRbacPolicy::default();
+
+        let mut default_role = HashMap::new();
+
+        default_role.insert(ObjectType::Topic, vec![Action::All].into());
+        default_role.insert(ObjectType::Partition, vec![Action::All].into());
+        default_role.insert(ObjectType::SpuGroup, vec![Action::Read].into());
+        default_role.insert(ObjectType::CustomSpu, vec![Action::Read].into());
+        default_role.insert(ObjectType::Spu, vec![Action::Read].into());
+
+        policy.0.insert(String::from("Default"), default_role);
+
+        let tmp_file_path = PathBuf::from("/tmp/policy.json");
+        let tmp = File::create(tmp_file_path.clone()).expect("failed to create policy file");
+        serde_json::to_writer(&tmp, &policy).expect("failed to serialize policy to json file");
+
+        let recovered_policy =
+            BasicRbacPolicy::try_from(tmp_file_path).expect("failed to parse policy from file");
+
+        assert_eq!(
+            policy, recovered_policy,
+            "serialized and deserialized policies from file should match"
+        )
+    }
+
+    #[test_async]
+    async fn test_policy_enforcement_simple() -> Result<(), ()> {
+        let mut policy = BasicRbacPolicy::default();
+        let identity = X509Identity::new("User".to_owned(), vec!["Default".to_owned()]);
+
+        let mut role1 = HashMap::new();
+        role1.insert(ObjectType::Topic, vec![Action::Delete, Action::Read].into());
+
+        policy.0.insert(String::from("Default"), role1);
+
+        assert!(!policy
+            .evaluate(Action::Create, ObjectType::CustomSpu, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(Action::Create, ObjectType::Topic, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Read, ObjectType::Topic, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Delete, ObjectType::Topic, Some("test"), &identity)
+            .await
+            .expect("eval"));
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_policy_enforcement_records() -> Result<(), ()> {
+        let mut policy = BasicRbacPolicy::default();
+        let consumer = X509Identity::new("Consumer".to_owned(), vec!["Reader".to_owned()]);
+        let root = X509Identity::new("Admin".to_owned(), vec!["Root".to_owned()]);
+
+        let mut reader = HashMap::new();
+        reader.insert(ObjectType::Topic, vec![Action::Read].into());
+        policy.0.insert(String::from("Reader"), reader);
+
+        assert!(policy
+            .evaluate(Action::Read, ObjectType::Topic, Some("test"), &consumer)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(Action::Write, ObjectType::Topic, Some("test"), &consumer)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Write, ObjectType::Topic, Some("test"), &root)
+            .await
+            .expect("eval"));
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_policy_enforcement_rules() -> Result<(), ()> {
+        // actions list and rules can be mixed
+        let policy: BasicRbacPolicy = serde_json::from_str(
+            r#"{
+                "TeamA": {
+                    "Spu": ["Read"],
+                    "Topic": [
+                        { "name": "team-a.*", "actions": ["All"] },
+                        { "name": "shared", "actions": ["Read"] },
+                        { "name": "team-a.secret", "actions": ["Read"], "effect": "Deny" }
+                    ]
+                }
+            }"#,
+        )
+        .expect("parse policy");
+        let identity = X509Identity::new("User".to_owned(), vec!["TeamA".to_owned()]);
+
+        let allowed = |action: Action, instance: Option<&'static str>| {
+            let policy = policy.clone();
+            let identity = identity.clone();
+            async move {
+                policy
+                    .evaluate(action, ObjectType::Topic, instance, &identity)
+                    .await
+                    .expect("eval")
+            }
+        };
+
+        assert!(allowed(Action::Write, Some("team-a.events")).await);
+        assert!(allowed(Action::Delete, Some("team-a.events")).await);
+        assert!(allowed(Action::Read, Some("shared")).await);
+        assert!(!allowed(Action::Write, Some("shared")).await);
+        assert!(!allowed(Action::Read, Some("team-b.events")).await);
+        assert!(!allowed(Action::Read, Some("team-a.secret")).await);
+        assert!(allowed(Action::Write, Some("team-a.secret")).await);
+        // type level, some instance may be read
+        assert!(allowed(Action::Read, None).await);
+        assert!(!allowed(Action::Update, Some("shared")).await);
+
+        assert!(policy
+            .evaluate(Action::Read, ObjectType::Spu, Some("spu-5001"), &identity)
+            .await
+            .expect("eval"));
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_policy_deny_all_instances() -> Result<(), ()> {
+        let mut policy = BasicRbacPolicy::default();
+        let identity = X509Identity::new(
+            "User".to_owned(),
+            vec!["Root".to_owned(), "NoDelete".to_owned()],
+        );
+
+        let mut no_delete = HashMap::new();
+        no_delete.insert(
+            ObjectType::Topic,
+            vec![Rule::deny("*", vec![Action::Delete])].into(),
+        );
+        policy.0.insert(String::from("NoDelete"), no_delete);
+
+        assert!(!policy
+            .evaluate(Action::Delete, ObjectType::Topic, Some("test"), &identity)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(Action::Delete, ObjectType::Topic, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Create, ObjectType::Topic, Some("test"), &identity)
+            .await
+            .expect("eval"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("test", "test"));
+        assert!(!glob_match("test", "test1"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("team-a.*", "team-a.events"));
+        assert!(glob_match("team-a.*", "team-a."));
+        assert!(!glob_match("team-a.*", "team-b.events"));
+        assert!(glob_match("*-logs", "app-logs"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(glob_match("topic-?", "topic-1"));
+        assert!(!glob_match("topic-?", "topic-10"));
+    }
+}
@@ -2,6 +2,7 @@ mod policy;
 mod error;
 
 pub mod x509;
+pub mod basic;
 
 pub use policy::*;
 pub use error::AuthError;
@@ -16,7 +16,14 @@ pub enum TypeAction {
 }
 
 pub enum InstanceAction {
+    /// create instance with name
+    Create,
+    Update,
     Delete,
+    /// read records of instance, ex: consume from topic
+    Read,
+    /// write records to instance, ex: produce to topic
+    Write,
 }
 
 #[async_trait]
@@ -1,9 +1,10 @@
 use serde::{Serialize, Deserialize};
 
 use futures_util::stream::StreamExt;
+use futures_util::io::{AsyncRead, AsyncWrite};
 
 use fluvio_protocol::api::{ResponseMessage};
-use fluvio_socket::FlvSocket;
+use fluvio_socket::InnerFlvSocket;
 
 use super::request::{AuthorizationScopes, AuthorizationApiRequest, AuthResponse};
 
@@ -23,7 +24,12 @@ impl X509Identity {
     }
 
     /// extract x509 identity from TCP Socket
-    pub async fn create_from_connection<S>(socket: &mut FlvSocket) -> Result<Self, std::io::Error> {
+    pub async fn create_from_connection<S>(
+        socket: &mut InnerFlvSocket<S>,
+    ) -> Result<Self, std::io::Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send,
+    {
         let identity = {
             let stream = &mut socket.get_mut_stream();
 
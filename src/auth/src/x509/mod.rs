@@ -6,3 +6,4 @@ mod request;
 #[cfg(unix)]
 pub use authenticator::*;
 pub use identity::*;
+pub use request::{AuthRequest, AuthResponse};
@@ -4,6 +4,10 @@
 //! CLI command for Consume operation
 //!
 
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
 use structopt::StructOpt;
 
 use fluvio::FluvioConfig;
@@ -24,6 +28,10 @@ pub struct ConsumeLogOpt {
     #[structopt(short = "p", long, default_value = "0", value_name = "integer")]
     pub partition: i32,
 
+    /// Consume records from all partitions of the topic
+    #[structopt(short = "A", long = "all-partitions", conflicts_with = "partition")]
+    pub all_partitions: bool,
+
     /// Starts consuming from the beginning
     #[structopt(short = "B", long = "from-beginning")]
     pub from_beginning: bool,
@@ -36,6 +44,16 @@ pub struct ConsumeLogOpt {
     #[structopt(short, long, value_name = "integer")]
     pub offset: Option<i64>,
 
+    /// Starts consuming from records stored at or after this time.
+    /// Either time ago such as 30s, 10m, 2h, 1d or RFC 3339 time such as 2021-01-20T10:00:00Z
+    #[structopt(
+        long = "from-time",
+        value_name = "time",
+        parse(try_from_str = parse_from_time),
+        conflicts_with_all = &["offset", "from-beginning"]
+    )]
+    pub from_time: Option<SystemTime>,
+
     /// The maximum number of bytes to be retrieved
     #[structopt(short = "b", long = "maxbytes", value_name = "integer")]
     pub max_bytes: Option<i32>,
@@ -44,6 +62,23 @@ pub struct ConsumeLogOpt {
     #[structopt(short = "s", long = "suppress-unknown")]
     pub suppress_unknown: bool,
 
+    /// Print the headers of each record
+    #[structopt(short = "H", long = "headers")]
+    pub show_headers: bool,
+
+    /// Consume as member of Consumer Group, resuming from its committed offsets
+    #[structopt(short = "g", long = "group", value_name = "string")]
+    pub group: Option<String>,
+
+    /// Path to WebAssembly module which SPU runs over each record before sending it
+    #[structopt(
+        long = "transform",
+        value_name = "module.wasm",
+        parse(from_os_str),
+        conflicts_with = "disable-continuous"
+    )]
+    pub transform: Option<PathBuf>,
+
     /// Output
     #[structopt(
         short = "O",
@@ -68,12 +103,17 @@ impl ConsumeLogOpt {
         let consume_log_cfg = ConsumeLogConfig {
             topic: self.topic,
             partition: self.partition,
+            all_partitions: self.all_partitions,
             from_beginning: self.from_beginning,
             disable_continuous: self.disable_continuous,
             offset: self.offset,
+            from_time: self.from_time,
             max_bytes: self.max_bytes,
             output: self.output,
             suppress_unknown: self.suppress_unknown,
+            show_headers: self.show_headers,
+            group: self.group,
+            transform: self.transform,
         };
 
         // return server separately from config
@@ -86,10 +126,41 @@ impl ConsumeLogOpt {
 pub struct ConsumeLogConfig {
     pub topic: String,
     pub partition: i32,
+    pub all_partitions: bool,
     pub from_beginning: bool,
     pub disable_continuous: bool,
     pub offset: Option<Offset>,
+    pub from_time: Option<SystemTime>,
     pub max_bytes: Option<i32>,
     pub output: ConsumeOutputType,
     pub suppress_unknown: bool,
+    pub show_headers: bool,
+    pub group: Option<String>,
+    pub transform: Option<PathBuf>,
+}
+
+/// parse time ago such as `10m` or RFC 3339 time
+fn parse_from_time(value: &str) -> Result<SystemTime, String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(SystemTime::from(time));
+    }
+
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid time: {}", value))?;
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 60 * 60,
+        "d" => amount * 60 * 60 * 24,
+        _ => return Err(format!("invalid time unit in: {}, use s, m, h or d", value)),
+    };
+
+    SystemTime::now()
+        .checked_sub(Duration::from_secs(seconds))
+        .ok_or_else(|| format!("time is too far in the past: {}", value))
 }
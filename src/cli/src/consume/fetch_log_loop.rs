@@ -4,12 +4,17 @@
 //! Fetch logs from SPU
 //!
 
+use std::collections::BTreeMap;
 use std::io::Error as IoError;
 use std::io::ErrorKind;
 use std::convert::TryFrom;
+use std::time::{Duration, Instant};
 
 use tracing::debug;
-use fluvio::{PartitionConsumer, Offset, ConsumerConfig};
+use fluvio::{Fluvio, PartitionConsumer, TopicConsumer, Offset, ConsumerConfig, Record};
+use fluvio::dataplane::batch::DefaultBatch;
+use fluvio::dataplane::fetch::FetchablePartitionResponse;
+use fluvio::dataplane::record::RecordSet;
 
 use crate::error::CliError;
 use crate::Terminal;
@@ -17,6 +22,11 @@ use crate::Terminal;
 use super::ConsumeLogConfig;
 use super::process_fetch_topic_response;
 use futures_lite::StreamExt;
+use futures_lite::FutureExt;
+use fluvio_future::timer::sleep;
+
+/// records consumed from all partitions are committed at most once per interval
+const COMMIT_INTERVAL: Duration = Duration::from_secs(1);
 
 // -----------------------------------
 // SPU - Fetch Loop
@@ -26,7 +36,7 @@ use futures_lite::StreamExt;
 #[allow(clippy::neg_multiply)]
 pub async fn fetch_log_loop<O>(
     out: std::sync::Arc<O>,
-    consumer: PartitionConsumer,
+    client: &Fluvio,
     opt: ConsumeLogConfig,
 ) -> Result<(), CliError>
 where
@@ -47,7 +57,9 @@ where
     }
 
     // compute offset
-    let maybe_initial_offset = if opt.from_beginning {
+    let maybe_initial_offset = if let Some(from_time) = opt.from_time {
+        Some(Offset::from_timestamp(from_time))
+    } else if opt.from_beginning {
         let big_offset = opt.offset.unwrap_or(0);
         // Try to convert to u32
         u32::try_from(big_offset).ok().map(Offset::from_beginning)
@@ -75,9 +87,41 @@ where
         if let Some(max_bytes) = opt.max_bytes {
             config = config.with_max_bytes(max_bytes);
         }
+        if let Some(group) = &opt.group {
+            config = config.with_group(group.clone());
+        }
+        if let Some(transform) = &opt.transform {
+            let module = std::fs::read(transform).map_err(|err| {
+                IoError::new(
+                    ErrorKind::InvalidInput,
+                    format!("cannot read transform module {:?}: {}", transform, err),
+                )
+            })?;
+            config = config.with_wasm_module(module);
+        }
         config
     };
 
+    if opt.all_partitions {
+        let consumer = client.topic_consumer(&opt.topic).await?;
+        return fetch_all_partitions(out, consumer, initial_offset, fetch_config, &opt).await;
+    }
+
+    let consumer = client.partition_consumer(&opt.topic, opt.partition).await?;
+    fetch_partition(out, consumer, initial_offset, fetch_config, &opt).await
+}
+
+/// fetch from single partition
+async fn fetch_partition<O>(
+    out: std::sync::Arc<O>,
+    consumer: PartitionConsumer,
+    initial_offset: Offset,
+    fetch_config: ConsumerConfig,
+    opt: &ConsumeLogConfig,
+) -> Result<(), CliError>
+where
+    O: Terminal,
+{
     if opt.disable_continuous {
         let response = consumer
             .fetch_with_config(initial_offset, fetch_config)
@@ -89,7 +133,11 @@ where
             response.records.batches.len(),
         );
 
-        process_fetch_topic_response(out.clone(), response, &opt).await?;
+        let next_offset = next_offset(&response);
+        process_fetch_topic_response(out.clone(), response, opt).await?;
+        if let (Some(group), Some(offset)) = (&opt.group, next_offset) {
+            consumer.commit_offset(group, offset).await?;
+        }
     } else {
         let mut log_stream = consumer
             ._stream_batches_with_config(initial_offset, fetch_config)
@@ -103,7 +151,11 @@ where
                 partition.records.batches.len(),
             );
 
-            process_fetch_topic_response(out.clone(), partition, &opt).await?;
+            let next_offset = next_offset(&partition);
+            process_fetch_topic_response(out.clone(), partition, opt).await?;
+            if let (Some(group), Some(offset)) = (&opt.group, next_offset) {
+                consumer.commit_offset(group, offset).await?;
+            }
 
             if opt.disable_continuous {
                 debug!("finishing fetch loop");
@@ -116,3 +168,112 @@ where
 
     Ok(())
 }
+
+/// fetch from all partitions of topic
+async fn fetch_all_partitions<O>(
+    out: std::sync::Arc<O>,
+    consumer: TopicConsumer,
+    initial_offset: Offset,
+    fetch_config: ConsumerConfig,
+    opt: &ConsumeLogConfig,
+) -> Result<(), CliError>
+where
+    O: Terminal,
+{
+    if opt.disable_continuous {
+        let responses = consumer
+            .fetch_with_config(initial_offset, fetch_config)
+            .await?;
+
+        for response in responses {
+            let partition = response.partition_index;
+            let next_offset = next_offset(&response);
+            process_fetch_topic_response(out.clone(), response, opt).await?;
+            if let (Some(group), Some(offset)) = (&opt.group, next_offset) {
+                consumer.commit_offset(group, partition, offset).await?;
+            }
+        }
+    } else {
+        let mut log_stream = consumer
+            .stream_with_config(initial_offset, fetch_config)
+            .await?;
+
+        // offsets of records printed since last commit, by partition
+        let mut uncommitted: BTreeMap<i32, i64> = BTreeMap::new();
+        let mut last_commit = Instant::now();
+        loop {
+            // wake up to commit printed records even if no new records arrive
+            let wait = COMMIT_INTERVAL
+                .checked_sub(last_commit.elapsed())
+                .unwrap_or_default();
+            let next = async { Some(log_stream.next().await) }
+                .or(async {
+                    sleep(wait).await;
+                    None
+                })
+                .await;
+
+            match next {
+                Some(Some(Ok(record))) => {
+                    let partition = record.partition();
+                    let next_offset = record.offset() + 1;
+                    process_fetch_topic_response(out.clone(), record_response(record), opt).await?;
+                    if opt.group.is_some() {
+                        uncommitted.insert(partition, next_offset);
+                    }
+                }
+                Some(_) => break,
+                None => {}
+            }
+
+            if last_commit.elapsed() >= COMMIT_INTERVAL {
+                commit_offsets(&consumer, opt, &mut uncommitted).await?;
+                last_commit = Instant::now();
+            }
+        }
+        commit_offsets(&consumer, opt, &mut uncommitted).await?;
+
+        debug!("fetch loop exited");
+    }
+
+    Ok(())
+}
+
+/// commit offsets of printed records for consumer group
+async fn commit_offsets(
+    consumer: &TopicConsumer,
+    opt: &ConsumeLogConfig,
+    uncommitted: &mut BTreeMap<i32, i64>,
+) -> Result<(), CliError> {
+    if let Some(group) = &opt.group {
+        for (partition, offset) in std::mem::take(uncommitted) {
+            consumer.commit_offset(group, partition, offset).await?;
+        }
+    }
+    Ok(())
+}
+
+/// offset after last record of response, `None` if there are no records
+fn next_offset(response: &FetchablePartitionResponse<RecordSet>) -> Option<i64> {
+    response
+        .records
+        .batches
+        .last()
+        .map(|batch| batch.get_last_offset() + 1)
+}
+
+/// wrap single record as partition response so it can be printed as fetched records
+fn record_response(record: Record) -> FetchablePartitionResponse<RecordSet> {
+    let mut batch = DefaultBatch::default();
+    batch.set_base_offset(record.offset());
+    let partition_index = record.partition();
+    batch.add_record(record.into_inner());
+
+    FetchablePartitionResponse {
+        partition_index,
+        records: RecordSet {
+            batches: vec![batch],
+        },
+        ..Default::default()
+    }
+}
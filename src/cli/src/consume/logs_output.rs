@@ -8,6 +8,7 @@ use tracing::debug;
 use serde_json::Value;
 
 use fluvio::dataplane::record::RecordSet;
+use fluvio::dataplane::record::DefaultRecord;
 use fluvio::dataplane::fetch::FetchablePartitionResponse;
 
 use crate::error::CliError;
@@ -31,24 +32,30 @@ where
     let partition_res = vec![response];
 
     let topic = &config.topic;
+    let headers = config.show_headers;
 
     match config.output {
         ConsumeOutputType::json => {
-            let records =
-                generate_json_records(out.clone(), topic, &partition_res, config.suppress_unknown);
+            let records = generate_json_records(
+                out.clone(),
+                topic,
+                &partition_res,
+                config.suppress_unknown,
+                headers,
+            );
             print_json_records(out, &records);
         }
         ConsumeOutputType::text => {
-            print_text_records(out, topic, &partition_res, config.suppress_unknown);
+            print_text_records(out, topic, &partition_res, config.suppress_unknown, headers);
         }
         ConsumeOutputType::binary => {
-            print_binary_records(out, topic, &partition_res);
+            print_binary_records(out, topic, &partition_res, headers);
         }
         ConsumeOutputType::dynamic => {
-            print_dynamic_records(out, topic, &partition_res);
+            print_dynamic_records(out, topic, &partition_res, headers);
         }
         ConsumeOutputType::raw => {
-            print_raw_records(out, topic, &partition_res);
+            print_raw_records(out, topic, &partition_res, headers);
         }
     }
 
@@ -65,6 +72,7 @@ pub fn generate_json_records<O>(
     topic_name: &str,
     response_partitions: &[FetchablePartitionResponse<RecordSet>],
     suppress: bool,
+    headers: bool,
 ) -> Vec<Value>
 where
     O: Terminal,
@@ -77,7 +85,7 @@ where
             continue;
         }
 
-        let mut new_records = partition_to_json_records(&r_partition, suppress);
+        let mut new_records = partition_to_json_records(&r_partition, suppress, headers);
         json_records.append(&mut new_records);
     }
 
@@ -88,22 +96,33 @@ where
 pub fn partition_to_json_records(
     partition: &FetchablePartitionResponse<RecordSet>,
     suppress: bool,
+    headers: bool,
 ) -> Vec<Value> {
     let mut json_records: Vec<Value> = vec![];
 
     // convert all batches to json records
     for batch in &partition.records.batches {
-        for record in &batch.records {
+        for record in batch.records() {
             if let Some(batch_record) = record.get_value().inner_value_ref() {
-                match serde_json::from_slice(&batch_record) {
-                    Ok(value) => json_records.push(value),
+                let json_record = match serde_json::from_slice(&batch_record) {
+                    Ok(value) => value,
                     Err(_) => {
-                        if !suppress {
-                            json_records.push(serde_json::json!({
-                                "error": record.get_value().describe()
-                            }));
+                        if suppress {
+                            continue;
                         }
+                        serde_json::json!({
+                            "error": record.get_value().describe()
+                        })
                     }
+                };
+
+                if headers {
+                    json_records.push(serde_json::json!({
+                        "headers": headers_to_json(record),
+                        "value": json_record
+                    }));
+                } else {
+                    json_records.push(json_record);
                 }
             }
         }
@@ -112,6 +131,21 @@ pub fn partition_to_json_records(
     json_records
 }
 
+/// convert record headers to json object, values are shown as text
+fn headers_to_json(record: &DefaultRecord) -> Value {
+    let headers = record
+        .get_headers()
+        .iter()
+        .map(|header| {
+            (
+                header.key.clone(),
+                Value::String(String::from_utf8_lossy(&header.value).to_string()),
+            )
+        })
+        .collect();
+    Value::Object(headers)
+}
+
 /// Print json records to screen
 fn print_json_records<O>(out: std::sync::Arc<O>, records: &[Value])
 where
@@ -130,6 +164,7 @@ pub fn print_text_records<O>(
     topic_name: &str,
     response_partitions: &[FetchablePartitionResponse<RecordSet>],
     suppress: bool,
+    headers: bool,
 ) where
     O: Terminal,
 {
@@ -142,14 +177,15 @@ pub fn print_text_records<O>(
         }
 
         for batch in &r_partition.records.batches {
-            for record in &batch.records {
+            for record in batch.records() {
                 if record.get_value().inner_value_ref().is_some() {
+                    let prefix = headers_prefix(record, headers);
                     if record.get_value().is_binary() {
                         if !suppress {
-                            t_println!(out, "{}", record.get_value().describe());
+                            t_println!(out, "{}{}", prefix, record.get_value().describe());
                         }
                     } else {
-                        t_println!(out, "{}", record.get_value());
+                        t_println!(out, "{}{}", prefix, record.get_value());
                     }
                 }
             }
@@ -166,6 +202,7 @@ pub fn print_binary_records<O>(
     out: std::sync::Arc<O>,
     topic_name: &str,
     response_partitions: &[FetchablePartitionResponse<RecordSet>],
+    headers: bool,
 ) where
     O: Terminal,
 {
@@ -184,9 +221,12 @@ pub fn print_binary_records<O>(
         }
 
         for batch in &r_partition.records.batches {
-            for record in &batch.records {
+            for record in batch.records() {
                 if let Some(batch_record) = record.get_value().inner_value_ref() {
                     t_println!(out, "{}", hex_dump_separator());
+                    if let Some(record_headers) = format_headers(record, headers) {
+                        t_println!(out, "{}", record_headers);
+                    }
                     t_println!(out, "{}", bytes_to_hex_dump(&batch_record));
                     printed = true;
                 }
@@ -207,6 +247,7 @@ pub fn print_dynamic_records<O>(
     out: std::sync::Arc<O>,
     topic_name: &str,
     response_partitions: &[FetchablePartitionResponse<RecordSet>],
+    headers: bool,
 ) where
     O: Terminal,
 {
@@ -217,7 +258,7 @@ pub fn print_dynamic_records<O>(
         }
 
         for batch in &r_partition.records.batches {
-            for record in &batch.records {
+            for record in batch.records() {
                 if let Some(batch_record) = record.get_value().inner_value_ref() {
                     // TODO: this should be refactored
                     if let Some(bytes) = record.get_value().inner_value_ref() {
@@ -225,10 +266,18 @@ pub fn print_dynamic_records<O>(
                     }
                     if record.get_value().is_binary() {
                         t_println!(out, "{}", hex_dump_separator());
+                        if let Some(record_headers) = format_headers(record, headers) {
+                            t_println!(out, "{}", record_headers);
+                        }
                         t_println!(out, "{}", bytes_to_hex_dump(&batch_record));
                         t_println!(out, "{}", hex_dump_separator());
                     } else {
-                        t_println!(out, "{}", record.get_value());
+                        t_println!(
+                            out,
+                            "{}{}",
+                            headers_prefix(record, headers),
+                            record.get_value()
+                        );
                     }
                 }
             }
@@ -245,6 +294,7 @@ pub fn print_raw_records<O>(
     out: std::sync::Arc<O>,
     topic_name: &str,
     response_partitions: &[FetchablePartitionResponse<RecordSet>],
+    headers: bool,
 ) where
     O: Terminal,
 {
@@ -255,10 +305,10 @@ pub fn print_raw_records<O>(
         }
 
         for batch in &r_partition.records.batches {
-            for record in &batch.records {
+            for record in batch.records() {
                 if let Some(value) = record.get_value().inner_value_ref() {
                     let str_value = std::str::from_utf8(value).unwrap();
-                    t_println!(out, "{}", str_value);
+                    t_println!(out, "{}{}", headers_prefix(record, headers), str_value);
                 }
             }
         }
@@ -269,6 +319,27 @@ pub fn print_raw_records<O>(
 //  Utilities
 // -----------------------------------
 
+/// Format record headers as `[key=value, ...]` if they should be shown
+pub fn format_headers(record: &DefaultRecord, show: bool) -> Option<String> {
+    if !show || record.get_headers().is_empty() {
+        return None;
+    }
+
+    let headers: Vec<String> = record
+        .get_headers()
+        .iter()
+        .map(|header| format!("{}={}", header.key, String::from_utf8_lossy(&header.value)))
+        .collect();
+    Some(format!("[{}]", headers.join(", ")))
+}
+
+/// headers followed by space, or empty if headers should not be shown
+fn headers_prefix(record: &DefaultRecord, show: bool) -> String {
+    format_headers(record, show)
+        .map(|headers| format!("{} ", headers))
+        .unwrap_or_default()
+}
+
 /// If header has error, format and return
 pub fn error_in_header(
     topic_name: &str,
@@ -35,8 +35,7 @@ mod process {
         debug!("spu  leader consume config: {:#?}", cfg);
 
         let client = Fluvio::connect_with_config(&target_server).await?;
-        let consumer = client.partition_consumer(&cfg.topic, cfg.partition).await?;
-        fetch_log_loop(out, consumer, cfg).await?;
+        fetch_log_loop(out, &client, cfg).await?;
 
         Ok("".to_owned())
     }
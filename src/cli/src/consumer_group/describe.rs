@@ -0,0 +1,120 @@
+//!
+//! # Describe Consumer Group
+//!
+//! CLI tree and processing to describe committed offsets of a Consumer Group
+//!
+
+use structopt::StructOpt;
+
+use fluvio::{Fluvio, FluvioConfig};
+
+use crate::error::CliError;
+use crate::OutputType;
+use crate::Terminal;
+use crate::target::ClusterTarget;
+use crate::common::OutputFormat;
+
+use super::fetch_group_offsets;
+
+/// Option for Describing Consumer Group
+#[derive(Debug, StructOpt)]
+pub struct DescribeConsumerGroupOpt {
+    /// The name of the Consumer Group
+    #[structopt(value_name = "group")]
+    group: String,
+
+    /// Only describe offsets of this Topic
+    #[structopt(short = "t", long = "topic", value_name = "string")]
+    topic: Option<String>,
+
+    #[structopt(flatten)]
+    output: OutputFormat,
+
+    #[structopt(flatten)]
+    target: ClusterTarget,
+}
+
+impl DescribeConsumerGroupOpt {
+    /// perform actions
+    pub async fn process<O>(self, out: std::sync::Arc<O>) -> Result<String, CliError>
+    where
+        O: Terminal,
+    {
+        let target_server: FluvioConfig = self.target.load()?;
+        let output: OutputType = self.output.as_output();
+
+        let client = Fluvio::connect_with_config(&target_server).await?;
+
+        let mut offsets = vec![];
+        for (topic, partitions) in fetch_group_offsets(&client, self.topic).await? {
+            for partition in partitions {
+                if let Some(offset) = partition.offset(&self.group) {
+                    offsets.push(display::PartitionOffset {
+                        topic: topic.clone(),
+                        partition: partition.partition,
+                        offset,
+                        end_offset: partition.end_offset,
+                        lag: partition.lag(&self.group).unwrap_or(0),
+                    });
+                }
+            }
+        }
+
+        if offsets.is_empty() {
+            return Err(CliError::InvalidArg(format!(
+                "consumer group \"{}\" has no committed offsets",
+                self.group
+            )));
+        }
+
+        // format and dump to screen
+        out.render_list(&offsets, output)?;
+        Ok("".to_owned())
+    }
+}
+
+mod display {
+
+    use prettytable::Row;
+    use prettytable::row;
+    use prettytable::cell;
+    use serde::Serialize;
+
+    use crate::TableOutputHandler;
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PartitionOffset {
+        pub topic: String,
+        pub partition: i32,
+        pub offset: i64,
+        pub end_offset: i64,
+        pub lag: i64,
+    }
+
+    impl TableOutputHandler for Vec<PartitionOffset> {
+        /// table header implementation
+        fn header(&self) -> Row {
+            row!["TOPIC", "PARTITION", "OFFSET", "END OFFSET", "LAG"]
+        }
+
+        /// return errors in string format
+        fn errors(&self) -> Vec<String> {
+            vec![]
+        }
+
+        fn content(&self) -> Vec<Row> {
+            self.iter()
+                .map(|offset| {
+                    row![
+                        l -> offset.topic,
+                        r -> offset.partition.to_string(),
+                        r -> offset.offset.to_string(),
+                        r -> offset.end_offset.to_string(),
+                        r -> offset.lag.to_string(),
+                    ]
+                })
+                .collect()
+        }
+    }
+}
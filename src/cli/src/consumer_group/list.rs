@@ -0,0 +1,146 @@
+//!
+//! # List Consumer Groups
+//!
+//! CLI tree and processing to list Consumer Groups
+//!
+
+use std::collections::BTreeMap;
+
+use structopt::StructOpt;
+
+use fluvio::{Fluvio, FluvioConfig};
+
+use crate::error::CliError;
+use crate::OutputType;
+use crate::Terminal;
+use crate::target::ClusterTarget;
+use crate::common::OutputFormat;
+
+use super::fetch_group_offsets;
+
+/// Option for Listing Consumer Groups
+#[derive(Debug, StructOpt)]
+pub struct ListConsumerGroupsOpt {
+    /// Only list groups consuming this Topic
+    #[structopt(short = "t", long = "topic", value_name = "string")]
+    topic: Option<String>,
+
+    #[structopt(flatten)]
+    output: OutputFormat,
+
+    #[structopt(flatten)]
+    target: ClusterTarget,
+}
+
+impl ListConsumerGroupsOpt {
+    /// Validate cli options and generate config
+    fn validate(self) -> Result<(FluvioConfig, Option<String>, OutputType), CliError> {
+        let target_server = self.target.load()?;
+
+        Ok((target_server, self.topic, self.output.as_output()))
+    }
+
+    /// perform actions
+    pub async fn process<O>(self, out: std::sync::Arc<O>) -> Result<String, CliError>
+    where
+        O: Terminal,
+    {
+        let (target_server, topic, output) = self.validate()?;
+
+        let client = Fluvio::connect_with_config(&target_server).await?;
+
+        // summarize committed partitions and lag per group and topic
+        let mut summary: BTreeMap<(String, String), display::ConsumerGroupSummary> =
+            BTreeMap::new();
+        for (topic, partitions) in fetch_group_offsets(&client, topic).await? {
+            for partition in partitions {
+                for consumer_offset in &partition.offsets {
+                    let entry = summary
+                        .entry((consumer_offset.group.clone(), topic.clone()))
+                        .or_insert_with(|| display::ConsumerGroupSummary {
+                            group: consumer_offset.group.clone(),
+                            topic: topic.clone(),
+                            partitions: 0,
+                            lag: 0,
+                        });
+                    entry.partitions += 1;
+                    entry.lag += partition.lag(&consumer_offset.group).unwrap_or(0);
+                }
+            }
+        }
+
+        let groups: Vec<display::ConsumerGroupSummary> = summary.into_values().collect();
+
+        // format and dump to screen
+        display::format_consumer_groups_output(out, groups, output)?;
+        Ok("".to_owned())
+    }
+}
+
+mod display {
+
+    use prettytable::Row;
+    use prettytable::row;
+    use prettytable::cell;
+    use serde::Serialize;
+
+    use crate::error::CliError;
+    use crate::OutputType;
+    use crate::Terminal;
+    use crate::TableOutputHandler;
+    use crate::t_println;
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConsumerGroupSummary {
+        pub group: String,
+        pub topic: String,
+        pub partitions: usize,
+        pub lag: i64,
+    }
+
+    type ListConsumerGroups = Vec<ConsumerGroupSummary>;
+
+    /// Process server based on output type
+    pub fn format_consumer_groups_output<O>(
+        out: std::sync::Arc<O>,
+        groups: ListConsumerGroups,
+        output_type: OutputType,
+    ) -> Result<(), CliError>
+    where
+        O: Terminal,
+    {
+        if !groups.is_empty() {
+            out.render_list(&groups, output_type)?;
+        } else {
+            t_println!(out, "No consumer groups found");
+        }
+
+        Ok(())
+    }
+
+    impl TableOutputHandler for ListConsumerGroups {
+        /// table header implementation
+        fn header(&self) -> Row {
+            row!["GROUP", "TOPIC", "PARTITIONS", "LAG"]
+        }
+
+        /// return errors in string format
+        fn errors(&self) -> Vec<String> {
+            vec![]
+        }
+
+        fn content(&self) -> Vec<Row> {
+            self.iter()
+                .map(|summary| {
+                    row![
+                        l -> summary.group,
+                        l -> summary.topic,
+                        l -> summary.partitions.to_string(),
+                        l -> summary.lag.to_string(),
+                    ]
+                })
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,91 @@
+mod list;
+mod describe;
+mod reset;
+
+pub use cli::*;
+
+mod cli {
+
+    use structopt::StructOpt;
+
+    use crate::COMMAND_TEMPLATE;
+    use crate::Terminal;
+    use crate::CliError;
+
+    use super::list::ListConsumerGroupsOpt;
+    use super::describe::DescribeConsumerGroupOpt;
+    use super::reset::ResetConsumerGroupOpt;
+
+    #[derive(Debug, StructOpt)]
+    pub enum ConsumerGroupOpt {
+        /// List all of the Consumer Groups in this cluster
+        #[structopt(
+            name = "list",
+            template = COMMAND_TEMPLATE,
+        )]
+        List(ListConsumerGroupsOpt),
+
+        /// Show committed offsets and lag of a Consumer Group
+        #[structopt(
+            name = "describe",
+            template = COMMAND_TEMPLATE,
+        )]
+        Describe(DescribeConsumerGroupOpt),
+
+        /// Move committed offsets of a Consumer Group
+        #[structopt(
+            name = "reset",
+            template = COMMAND_TEMPLATE,
+        )]
+        Reset(ResetConsumerGroupOpt),
+    }
+
+    impl ConsumerGroupOpt {
+        pub(crate) async fn process_consumer_group<O>(
+            self,
+            out: std::sync::Arc<O>,
+        ) -> Result<String, CliError>
+        where
+            O: Terminal,
+        {
+            match self {
+                Self::List(list) => list.process(out).await,
+                Self::Describe(describe) => describe.process(out).await,
+                Self::Reset(reset) => reset.process().await,
+            }
+        }
+    }
+}
+
+use fluvio::{Fluvio, PartitionGroupOffsets};
+use fluvio::metadata::topic::TopicSpec;
+
+use crate::error::CliError;
+
+/// group offsets of topic, or every topic if none is given, ordered by topic
+async fn fetch_group_offsets(
+    client: &Fluvio,
+    topic: Option<String>,
+) -> Result<Vec<(String, Vec<PartitionGroupOffsets>)>, CliError> {
+    let topics = match topic {
+        Some(topic) => vec![topic],
+        None => {
+            let mut admin = client.admin().await;
+            let mut topics: Vec<String> = admin
+                .list::<TopicSpec, _>(vec![])
+                .await?
+                .into_iter()
+                .map(|topic| topic.name)
+                .collect();
+            topics.sort();
+            topics
+        }
+    };
+
+    let mut offsets = vec![];
+    for topic in topics {
+        let consumer = client.topic_consumer(&topic).await?;
+        offsets.push((topic, consumer.group_offsets().await?));
+    }
+    Ok(offsets)
+}
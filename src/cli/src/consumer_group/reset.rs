@@ -0,0 +1,108 @@
+//!
+//! # Reset Consumer Group
+//!
+//! CLI tree and processing to move committed offsets of a Consumer Group
+//!
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::{Fluvio, PartitionGroupOffsets};
+
+use crate::error::CliError;
+use crate::target::ClusterTarget;
+
+/// Option for Resetting Consumer Group
+#[derive(Debug, StructOpt)]
+#[structopt(group = structopt::clap::ArgGroup::with_name("position").required(true))]
+pub struct ResetConsumerGroupOpt {
+    /// The name of the Consumer Group
+    #[structopt(value_name = "group")]
+    group: String,
+
+    /// Topic whose offsets are reset
+    #[structopt(short = "t", long = "topic", value_name = "string")]
+    topic: String,
+
+    /// Only reset offset of this Partition, all partitions if omitted
+    #[structopt(short = "p", long = "partition", value_name = "integer")]
+    partition: Option<i32>,
+
+    /// Read from the first record still in the partition
+    #[structopt(long = "to-beginning", group = "position")]
+    to_beginning: bool,
+
+    /// Skip every record currently in the partition
+    #[structopt(long = "to-end", group = "position")]
+    to_end: bool,
+
+    /// Read from absolute offset
+    #[structopt(long = "to-offset", value_name = "integer", group = "position")]
+    to_offset: Option<i64>,
+
+    #[structopt(flatten)]
+    target: ClusterTarget,
+}
+
+impl ResetConsumerGroupOpt {
+    /// perform actions
+    pub async fn process(self) -> Result<String, CliError> {
+        let Self {
+            group,
+            topic,
+            partition,
+            to_beginning,
+            to_end,
+            to_offset,
+            target,
+        } = self;
+
+        // offset to commit for partition
+        let reset_offset = |offsets: &PartitionGroupOffsets| {
+            if to_beginning {
+                offsets.start_offset
+            } else if to_end {
+                offsets.end_offset
+            } else {
+                to_offset.unwrap_or(offsets.start_offset)
+            }
+        };
+
+        let target_server = target.load()?;
+        let client = Fluvio::connect_with_config(&target_server).await?;
+        let consumer = client.topic_consumer(&topic).await?;
+
+        let partitions: Vec<PartitionGroupOffsets> = consumer
+            .group_offsets()
+            .await?
+            .into_iter()
+            .filter(|offsets| partition.map(|p| p == offsets.partition).unwrap_or(true))
+            .collect();
+
+        if partitions.is_empty() {
+            return Err(CliError::InvalidArg(format!(
+                "topic \"{}\" has no partition {}",
+                topic,
+                partition.unwrap_or_default()
+            )));
+        }
+
+        for offsets in &partitions {
+            let offset = reset_offset(offsets);
+            debug!(
+                "resetting group: {} partition: {} to offset: {}",
+                group, offsets.partition, offset
+            );
+            consumer
+                .commit_offset(&group, offsets.partition, offset)
+                .await?;
+        }
+
+        Ok(format!(
+            "consumer group \"{}\" reset on {} partition(s) of topic \"{}\"",
+            group,
+            partitions.len(),
+            topic
+        ))
+    }
+}
@@ -16,6 +16,7 @@ mod group;
 mod custom;
 mod install;
 mod partition;
+mod consumer_group;
 
 #[cfg(any(feature = "cluster_components", feature = "cluster_components_rustls"))]
 mod run;
@@ -0,0 +1,91 @@
+//!
+//! # Elect Partition Leaders
+//!
+//! CLI tree and processing to move leaders back to preferred replicas
+//!
+
+use std::convert::TryInto;
+
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::partition::*;
+use fluvio::dataplane::PartitionError;
+
+use crate::error::CliError;
+use crate::Terminal;
+use crate::target::ClusterTarget;
+use crate::t_println;
+
+/// Option for Electing Partition Leaders
+#[derive(Debug, StructOpt)]
+pub struct ElectLeaderOpt {
+    /// Topic of partitions, all partitions if not set
+    #[structopt(short = "t", long = "topic", value_name = "string")]
+    topic: Option<String>,
+
+    /// Partition of topic, all partitions of topic if not set
+    #[structopt(
+        short = "p",
+        long = "partition",
+        value_name = "integer",
+        requires = "topic"
+    )]
+    partition: Option<i32>,
+
+    #[structopt(flatten)]
+    target: ClusterTarget,
+}
+
+impl ElectLeaderOpt {
+    /// perform actions
+    pub async fn process<O>(self, out: std::sync::Arc<O>) -> Result<String, CliError>
+    where
+        O: Terminal,
+    {
+        let target_server = self.target.load()?;
+
+        let client = Fluvio::connect_with_config(&target_server).await?;
+        let mut admin = client.admin().await;
+
+        let partitions: Vec<ReplicaKey> = match (self.topic, self.partition) {
+            (Some(topic), Some(partition)) => vec![ReplicaKey::new(topic, partition)],
+            (Some(topic), None) => {
+                let keys = admin
+                    .list::<PartitionSpec, _>(vec![])
+                    .await?
+                    .into_iter()
+                    .filter_map(|metadata| {
+                        let key: Result<ReplicaKey, PartitionError> = metadata.name.try_into();
+                        key.ok()
+                    })
+                    .filter(|key| key.topic == topic)
+                    .collect::<Vec<ReplicaKey>>();
+                if keys.is_empty() {
+                    return Err(CliError::invalid_arg(format!(
+                        "no partitions found for topic: {}",
+                        topic
+                    )));
+                }
+                keys
+            }
+            _ => vec![],
+        };
+
+        let results = admin.elect_leaders(partitions).await?;
+        for result in results {
+            match result.error_message {
+                Some(message) if result.error_code.is_error() => t_println!(
+                    out,
+                    "{}: leader {}, not elected: {}",
+                    result.partition,
+                    result.leader,
+                    message
+                ),
+                _ => t_println!(out, "{}: leader {}", result.partition, result.leader),
+            }
+        }
+
+        Ok("".to_owned())
+    }
+}
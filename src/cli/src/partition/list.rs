@@ -101,6 +101,8 @@ mod display {
                 "HW",
                 "LEO",
                 "LSR",
+                "ISR",
+                "UNDER-REPLICATED",
                 "FOLLOWER OFFSETS"
             ]
         }
@@ -133,6 +135,8 @@ mod display {
                         l -> status.leader.hw.to_string(),
                         l -> status.leader.leo.to_string(),
                         l -> status.lsr.to_string(),
+                        l -> format!("{:?}",status.in_sync_replicas),
+                        l -> if status.is_under_replicated(spec.replicas.len()) { "yes" } else { "no" },
                         l -> format!("{:?}",status.replicas)
                     ]
                 })
@@ -1,4 +1,6 @@
+mod elect;
 mod list;
+mod reassign;
 pub use cli::*;
 
 mod cli {
@@ -9,7 +11,9 @@ mod cli {
     use crate::Terminal;
     use crate::CliError;
 
+    use super::elect::ElectLeaderOpt;
     use super::list::ListPartitionOpt;
+    use super::reassign::ReassignPartitionOpt;
 
     #[derive(Debug, StructOpt)]
     pub enum PartitionOpt {
@@ -19,6 +23,20 @@ mod cli {
             template = COMMAND_TEMPLATE,
         )]
         List(ListPartitionOpt),
+
+        /// Move replicas of Partitions to other SPUs
+        #[structopt(
+            name = "reassign",
+            template = COMMAND_TEMPLATE,
+        )]
+        Reassign(ReassignPartitionOpt),
+
+        /// Move leaders of Partitions back to preferred replicas
+        #[structopt(
+            name = "elect-leader",
+            template = COMMAND_TEMPLATE,
+        )]
+        ElectLeader(ElectLeaderOpt),
     }
 
     impl PartitionOpt {
@@ -31,6 +49,8 @@ mod cli {
         {
             match self {
                 Self::List(list) => list.process(out).await,
+                Self::Reassign(reassign) => reassign.process(out).await,
+                Self::ElectLeader(elect) => elect.process(out).await,
             }
         }
     }
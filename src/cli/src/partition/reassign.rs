@@ -0,0 +1,202 @@
+//!
+//! # Reassign Partitions
+//!
+//! CLI tree and processing to move partitions to other SPUs
+//!
+
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::partition::*;
+
+use crate::error::CliError;
+use crate::Terminal;
+use crate::target::ClusterTarget;
+use crate::t_println;
+
+/// Option for Reassigning Partitions
+#[derive(Debug, StructOpt)]
+pub struct ReassignPartitionOpt {
+    /// Print balanced plan for all partitions of topics, nothing is moved
+    #[structopt(
+        long = "plan",
+        requires = "topics",
+        conflicts_with_all = &["execute", "status"]
+    )]
+    plan: bool,
+
+    /// Move partitions according to plan in JSON file
+    #[structopt(
+        long = "execute",
+        value_name = "file.json",
+        parse(from_os_str),
+        conflicts_with = "status"
+    )]
+    execute: Option<PathBuf>,
+
+    /// Show partitions which are being moved
+    #[structopt(long = "status")]
+    status: bool,
+
+    /// Topics to generate plan for
+    #[structopt(long = "topic", value_name = "string")]
+    topics: Vec<String>,
+
+    /// Max bytes per second copied to new replicas, unlimited if not set
+    #[structopt(long = "throttle", value_name = "bytes")]
+    throttle: Option<u32>,
+
+    #[structopt(flatten)]
+    target: ClusterTarget,
+}
+
+impl ReassignPartitionOpt {
+    /// perform actions
+    pub async fn process<O>(self, out: std::sync::Arc<O>) -> Result<String, CliError>
+    where
+        O: Terminal,
+    {
+        let target_server = self.target.load()?;
+
+        let request = if self.status {
+            None
+        } else if let Some(file) = &self.execute {
+            let file_str = read_to_string(file)?;
+            let plan: ReassignmentPlan = serde_json::from_str(&file_str)
+                .map_err(|err| CliError::invalid_arg(format!("invalid plan: {}", err)))?;
+            Some(ReassignPartitionsRequest {
+                plan,
+                throttle: self.throttle.unwrap_or(0),
+                ..Default::default()
+            })
+        } else if self.plan {
+            Some(ReassignPartitionsRequest {
+                topics: self.topics,
+                dry_run: true,
+                ..Default::default()
+            })
+        } else {
+            return Err(CliError::invalid_arg(
+                "one of --plan, --execute or --status must be provided",
+            ));
+        };
+
+        let client = Fluvio::connect_with_config(&target_server).await?;
+        let mut admin = client.admin().await;
+
+        match request {
+            Some(request) => {
+                let dry_run = request.dry_run;
+                let plan = admin.reassign_partitions(request).await?;
+                if dry_run {
+                    let serialized = serde_json::to_string_pretty(&plan)
+                        .map_err(|err| CliError::Other(err.to_string()))?;
+                    t_println!(out, "{}", serialized);
+                    Ok("".to_owned())
+                } else {
+                    Ok(format!(
+                        "started moving {} partitions",
+                        plan.partitions.len()
+                    ))
+                }
+            }
+            None => {
+                let partitions = admin.list::<PartitionSpec, _>(vec![]).await?;
+                display::format_reassignment_output(out, partitions);
+                Ok("".to_owned())
+            }
+        }
+    }
+}
+
+mod display {
+
+    use std::convert::TryInto;
+
+    use prettytable::Row;
+    use prettytable::row;
+    use prettytable::cell;
+
+    use fluvio::metadata::objects::Metadata;
+    use fluvio::metadata::partition::*;
+    use fluvio::dataplane::PartitionError;
+
+    use crate::Terminal;
+    use crate::TableOutputHandler;
+    use crate::t_println;
+
+    struct Reassignments(Vec<Metadata<PartitionSpec>>);
+
+    /// show only partitions which are being moved
+    pub fn format_reassignment_output<O>(
+        out: std::sync::Arc<O>,
+        partitions: Vec<Metadata<PartitionSpec>>,
+    ) where
+        O: Terminal,
+    {
+        let moving: Vec<Metadata<PartitionSpec>> = partitions
+            .into_iter()
+            .filter(|partition| partition.spec.is_reassigning())
+            .collect();
+
+        if !moving.is_empty() {
+            out.render_table(&Reassignments(moving), false);
+        } else {
+            t_println!(out, "No partitions are being reassigned");
+        }
+    }
+
+    impl TableOutputHandler for Reassignments {
+        /// table header implementation
+        fn header(&self) -> Row {
+            row![
+                "TOPIC",
+                "PARTITION",
+                "LEADER",
+                "REPLICAS",
+                "TARGET",
+                "THROTTLE"
+            ]
+        }
+
+        /// return errors in string format
+        fn errors(&self) -> Vec<String> {
+            vec![]
+        }
+
+        fn content(&self) -> Vec<Row> {
+            self.0
+                .iter()
+                .filter_map(|metadata| {
+                    let spec = &metadata.spec;
+                    let reassignment = spec.reassignment.as_ref()?;
+                    let (topic, partition) = {
+                        let parse_key: Result<ReplicaKey, PartitionError> =
+                            metadata.name.clone().try_into();
+                        match parse_key {
+                            Ok(key) => key.split(),
+                            Err(err) => (err.to_string(), -1),
+                        }
+                    };
+                    let throttle = if reassignment.throttle > 0 {
+                        reassignment.throttle.to_string()
+                    } else {
+                        "unlimited".to_owned()
+                    };
+
+                    Some(row![
+                        l -> topic,
+                        l -> partition.to_string(),
+                        l -> spec.leader.to_string(),
+                        l -> format!("{:?}", spec.replicas),
+                        l -> format!("{:?}", reassignment.target_replicas),
+                        l -> throttle
+                    ])
+                })
+                .collect()
+        }
+    }
+}
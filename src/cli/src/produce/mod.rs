@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use tracing::debug;
 use structopt::StructOpt;
 
-use fluvio::{Fluvio, FluvioConfig};
+use fluvio::{Fluvio, FluvioConfig, ProducerConfig, Compression, Acks};
 
 use crate::target::ClusterTarget;
 use crate::CliError;
@@ -15,6 +15,10 @@ pub struct ProduceLogConfig {
     pub topic: String,
     pub partition: i32,
     pub continuous: bool,
+    pub compression: Compression,
+    pub acks: Acks,
+    pub retries: usize,
+    pub idempotent: bool,
 }
 
 #[derive(Debug)]
@@ -65,6 +69,22 @@ pub struct ProduceLogOpt {
     )]
     record_file: Vec<PathBuf>,
 
+    /// Compression codec of sent batches: none, gzip, snappy, lz4 or zstd
+    #[structopt(long = "compression", value_name = "codec", default_value = "none")]
+    pub compression: Compression,
+
+    /// Replicas which must write records before they are acknowledged: 0, 1 or all
+    #[structopt(long = "acks", value_name = "acks", default_value = "1")]
+    pub acks: Acks,
+
+    /// Times a failed batch is sent again
+    #[structopt(long = "retries", value_name = "integer", default_value = "0")]
+    pub retries: usize,
+
+    /// Number records so that retried batches are not written twice
+    #[structopt(long = "idempotent")]
+    pub idempotent: bool,
+
     #[structopt(flatten)]
     target: ClusterTarget,
 }
@@ -88,6 +108,10 @@ impl ProduceLogOpt {
             topic: self.topic,
             partition: self.partition,
             continuous: self.continuous,
+            compression: self.compression,
+            acks: self.acks,
+            retries: self.retries,
+            idempotent: self.idempotent,
         };
 
         Ok((target_server, (produce_log_cfg, file_records)))
@@ -104,7 +128,14 @@ where
 {
     let (target_server, (cfg, file_records)) = opt.validate()?;
     let target = Fluvio::connect_with_config(&target_server).await?;
-    let producer = target.topic_producer(&cfg.topic).await?;
+    let config = ProducerConfig::default()
+        .with_compression(cfg.compression)
+        .with_acks(cfg.acks)
+        .with_retries(cfg.retries)
+        .with_idempotence(cfg.idempotent);
+    let producer = target
+        .topic_producer_with_config(&cfg.topic, config)
+        .await?;
 
     debug!("got producer");
     if let Some(records) = file_records {
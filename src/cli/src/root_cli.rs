@@ -27,6 +27,7 @@ use super::topic::TopicOpt;
 use super::profile::ProfileCommand;
 use super::cluster::ClusterCommands;
 use super::partition::PartitionOpt;
+use super::consumer_group::ConsumerGroupOpt;
 use crate::install::update::UpdateOpt;
 use crate::install::plugins::InstallOpt;
 
@@ -111,6 +112,14 @@ enum Root {
     #[structopt(name = "partition")]
     Partition(PartitionOpt),
 
+    /// Manage and view Consumer Groups
+    ///
+    /// A Consumer Group is a named consumer whose position in each partition
+    /// is remembered by the cluster. Consumers that join a group resume from
+    /// the offsets committed by the group.
+    #[structopt(name = "consumer-group")]
+    ConsumerGroup(ConsumerGroupOpt),
+
     /// Manage Profiles, which describe linked clusters
     ///
     /// Each Profile describes a particular Fluvio cluster you may be connected to.
@@ -176,6 +185,7 @@ pub fn run_cli(args: &[String]) -> eyre::Result<String> {
             Root::CustomSPU(custom_spu) => process_custom_spu(terminal.clone(), custom_spu).await?,
             Root::Topic(topic) => process_topic(terminal.clone(), topic).await?,
             Root::Partition(partition) => partition.process_partition(terminal.clone()).await?,
+            Root::ConsumerGroup(group) => group.process_consumer_group(terminal.clone()).await?,
             Root::Profile(profile) => process_profile(terminal.clone(), profile).await?,
             Root::Cluster(cluster) => process_cluster(terminal.clone(), cluster).await?,
             #[cfg(any(feature = "cluster_components", feature = "cluster_components_rustls"))]
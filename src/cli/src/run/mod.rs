@@ -15,7 +15,7 @@ pub enum RunOpt {
 pub fn process_run(run_opt: RunOpt) -> Result<String, CliError> {
     match run_opt {
         RunOpt::SPU(opt) => fluvio_spu::main_loop(opt),
-        RunOpt::SC(opt) => fluvio_sc::k8::main_k8_loop(opt),
+        RunOpt::SC(opt) => fluvio_sc::main_loop(opt),
     }
 
     Ok("".to_owned())
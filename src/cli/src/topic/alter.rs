@@ -0,0 +1,61 @@
+//!
+//! # Alter Topic
+//!
+//! CLI tree to change existing Topic
+//!
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::{Fluvio, FluvioConfig};
+use fluvio::metadata::objects::TopicUpdate;
+use crate::error::CliError;
+use crate::target::ClusterTarget;
+
+#[derive(Debug, StructOpt)]
+pub struct AlterTopicOpt {
+    /// The name of the Topic to alter
+    #[structopt(value_name = "name")]
+    topic: String,
+
+    /// The new number of partitions, must be greater than current number
+    #[structopt(short = "p", long = "partitions", value_name = "partitions")]
+    partitions: i32,
+
+    #[structopt(flatten)]
+    target: ClusterTarget,
+}
+
+impl AlterTopicOpt {
+    /// Validate cli options. Generate target-server and topic update.
+    fn validate(self) -> Result<(FluvioConfig, (String, TopicUpdate)), CliError> {
+        let target_server = self.target.load()?;
+
+        let update = TopicUpdate {
+            partitions: self.partitions,
+        };
+
+        // return server separately from config
+        Ok((target_server, (self.topic, update)))
+    }
+}
+
+// -----------------------------------
+//  CLI Processing
+// -----------------------------------
+
+/// Process alter topic cli request
+pub async fn process_alter_topic(opt: AlterTopicOpt) -> Result<String, CliError> {
+    let (target_server, (name, update)) = opt.validate()?;
+
+    debug!("altering topic: {}, update: {:?}", name, update);
+
+    let partitions = update.partitions;
+    let client = Fluvio::connect_with_config(&target_server).await?;
+    let mut admin = client.admin().await;
+    admin.update(name.clone(), update).await?;
+    Ok(format!(
+        "topic \"{}\" altered, partitions: {}",
+        name, partitions
+    ))
+}
@@ -13,6 +13,8 @@ use structopt::StructOpt;
 
 use fluvio::{Fluvio, FluvioConfig};
 use fluvio::metadata::topic::TopicSpec;
+use fluvio::metadata::topic::CompressionAlgorithm;
+use fluvio::metadata::topic::RetentionPolicy;
 
 use crate::error::CliError;
 use crate::target::ClusterTarget;
@@ -79,6 +81,37 @@ pub struct CreateTopicOpt {
     )]
     replica_assignment: Option<PathBuf>,
 
+    /// Compression of batches stored in the Topic
+    ///
+    /// One of: producer, none, gzip, snappy, lz4, zstd.
+    /// With "producer", batches are stored as compressed by the producer.
+    #[structopt(
+        long = "compression-type",
+        value_name = "compression",
+        default_value = "producer"
+    )]
+    compression_type: CompressionAlgorithm,
+
+    /// Delete records older than this many seconds, records are kept forever if omitted
+    #[structopt(long = "retention-secs", value_name = "integer")]
+    retention_secs: Option<u32>,
+
+    /// Delete oldest records when partition grows beyond this many bytes
+    #[structopt(long = "max-partition-size", value_name = "bytes")]
+    max_partition_size: Option<i64>,
+
+    /// Keep only the latest record of each key, records with null value delete the key
+    #[structopt(long = "compact")]
+    compact: bool,
+
+    /// Reject produce when fewer replicas are in sync with leader, SPU setting is used if omitted
+    #[structopt(long = "min-in-sync-replicas", value_name = "integer")]
+    min_in_sync_replicas: Option<u16>,
+
+    /// Name of WASM module in transform directory of SPU, run over records sent to consumers
+    #[structopt(long = "transform", value_name = "module")]
+    transform: Option<String>,
+
     /// Validates configuration, does not provision
     #[structopt(short = "d", long)]
     dry_run: bool,
@@ -96,7 +129,7 @@ impl CreateTopicOpt {
 
         let target_server = self.target.load()?;
 
-        let topic = if let Some(replica_assign_file) = &self.replica_assignment {
+        let mut topic = if let Some(replica_assign_file) = &self.replica_assignment {
             TopicSpec::Assigned(
                 PartitionMaps::file_decode(replica_assign_file).map_err(|err| {
                     IoError::new(
@@ -109,12 +142,20 @@ impl CreateTopicOpt {
                 })?,
             )
         } else {
-            TopicSpec::Computed(TopicReplicaParam {
-                partitions: self.partitions,
-                replication_factor: self.replication as i32,
-                ignore_rack_assignment: self.ignore_rack_assigment,
-            })
+            TopicSpec::Computed(TopicReplicaParam::new(
+                self.partitions,
+                self.replication as i32,
+                self.ignore_rack_assigment,
+            ))
         };
+        topic.set_compression_type(self.compression_type);
+        topic.set_retention(RetentionPolicy {
+            max_age_seconds: self.retention_secs,
+            max_partition_size: self.max_partition_size,
+            compact: self.compact,
+        });
+        topic.set_min_in_sync_replicas(self.min_in_sync_replicas);
+        topic.set_transform(self.transform);
 
         // return server separately from config
         Ok((target_server, (self.topic, topic)))
@@ -170,6 +170,20 @@ mod display {
                 }
             }
 
+            key_values.push((
+                "Compression Type".to_owned(),
+                Some(spec.compression_type().to_string()),
+            ));
+            key_values.push(("Retention".to_owned(), Some(spec.retention().to_string())));
+            if let Some(min_in_sync_replicas) = spec.min_in_sync_replicas() {
+                key_values.push((
+                    "Min In Sync Replicas".to_owned(),
+                    Some(min_in_sync_replicas.to_string()),
+                ));
+            }
+            if let Some(transform) = spec.transform() {
+                key_values.push(("Transform".to_owned(), Some(transform.to_owned())));
+            }
             key_values.push((
                 "Status".to_owned(),
                 Some(status.resolution.resolution_label().to_string()),
@@ -1,3 +1,4 @@
+mod alter;
 mod create;
 mod delete;
 mod describe;
@@ -10,11 +11,13 @@ mod cli {
     use structopt::StructOpt;
     use super::*;
 
+    use alter::AlterTopicOpt;
     use create::CreateTopicOpt;
     use delete::DeleteTopicOpt;
     use describe::DescribeTopicsOpt;
     use list::ListTopicsOpt;
 
+    use alter::process_alter_topic;
     use create::process_create_topic;
     use delete::process_delete_topic;
     use describe::process_describe_topics;
@@ -41,6 +44,13 @@ mod cli {
         )]
         Delete(DeleteTopicOpt),
 
+        /// Adds partitions to a Topic with the given name
+        #[structopt(
+            name = "alter",
+            template = COMMAND_TEMPLATE,
+        )]
+        Alter(AlterTopicOpt),
+
         /// Prints detailed information about a Topic
         #[structopt(
             name = "describe",
@@ -66,6 +76,7 @@ mod cli {
         let output = match topic_opt {
             TopicOpt::Create(create_topic_opt) => process_create_topic(create_topic_opt).await?,
             TopicOpt::Delete(delete_topic_opt) => process_delete_topic(delete_topic_opt).await?,
+            TopicOpt::Alter(alter_topic_opt) => process_alter_topic(alter_topic_opt).await?,
             TopicOpt::Describe(describe_topics_opt) => {
                 process_describe_topics(out, describe_topics_opt).await?
             }
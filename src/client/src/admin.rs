@@ -4,8 +4,11 @@ use std::fmt::Display;
 use tracing::debug;
 use dataplane::core::Encoder;
 use dataplane::core::Decoder;
-use fluvio_sc_schema::objects::{Metadata, AllCreatableSpec};
+use fluvio_sc_schema::objects::{Metadata, AllCreatableSpec, AllUpdatableSpec, UpdateRequest};
 use fluvio_sc_schema::AdminRequest;
+use fluvio_sc_schema::ApiError;
+use fluvio_sc_schema::partition::{ReassignPartitionsRequest, ReassignmentPlan};
+use fluvio_sc_schema::partition::{ElectLeadersRequest, LeaderElectionResult, ReplicaKey};
 use fluvio_socket::FlvSocketError;
 use fluvio_socket::AllMultiplexerSocket;
 
@@ -147,6 +150,45 @@ impl FluvioAdmin {
         Ok(())
     }
 
+    /// apply change to existing object
+    pub async fn update<S>(&mut self, name: String, spec: S) -> Result<(), FluvioError>
+    where
+        S: Into<AllUpdatableSpec>,
+    {
+        let update_request = UpdateRequest {
+            name,
+            spec: spec.into(),
+        };
+
+        self.send_receive(update_request).await?.as_result()?;
+
+        Ok(())
+    }
+
+    /// generate plan to move partitions or start moving them, returns plan used by SC
+    pub async fn reassign_partitions(
+        &mut self,
+        request: ReassignPartitionsRequest,
+    ) -> Result<ReassignmentPlan, FluvioError> {
+        let response = self.send_receive(request).await?;
+        if response.error_code.is_error() {
+            return Err(ApiError::Code(response.error_code, response.error_message).into());
+        }
+
+        Ok(response.plan)
+    }
+
+    /// move leaders of partitions back to preferred replicas, all partitions if empty
+    pub async fn elect_leaders(
+        &mut self,
+        partitions: Vec<ReplicaKey>,
+    ) -> Result<Vec<LeaderElectionResult>, FluvioError> {
+        let response = self
+            .send_receive(ElectLeadersRequest { partitions })
+            .await?;
+        Ok(response.results)
+    }
+
     /// delete object by key
     /// key is depend on spec, most are string but some allow multiple types
     pub async fn delete<S, K>(&mut self, key: K) -> Result<(), FluvioError>
@@ -228,6 +228,11 @@ impl VersionedSerialSocket {
             versions,
         }
     }
+
+    /// versions supported by end point
+    pub fn versions(&self) -> &Versions {
+        &self.versions
+    }
 }
 
 #[async_trait]
@@ -13,7 +13,9 @@ use fluvio_future::tls::AllDomainConnector;
 use crate::config::ConfigFile;
 use crate::admin::FluvioAdmin;
 use crate::TopicProducer;
+use crate::ProducerConfig;
 use crate::PartitionConsumer;
+use crate::TopicConsumer;
 use crate::FluvioError;
 use crate::FluvioConfig;
 use crate::sync::MetadataStores;
@@ -97,8 +99,8 @@ impl Fluvio {
     /// Creates a new `TopicProducer` for the given topic name
     ///
     /// Currently, producers are scoped to a specific Fluvio topic.
-    /// That means when you send events via a producer, you must specify
-    /// which partition each event should go to.
+    /// Events sent via a producer either go to a partition you specify,
+    /// or to a partition chosen from the event's key.
     ///
     /// # Example
     ///
@@ -113,10 +115,37 @@ impl Fluvio {
     pub async fn topic_producer<S: Into<String>>(
         &self,
         topic: S,
+    ) -> Result<TopicProducer, FluvioError> {
+        self.topic_producer_with_config(topic, ProducerConfig::default())
+            .await
+    }
+
+    /// Creates a new `TopicProducer` for the given topic name using
+    /// a specific batching configuration
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{Fluvio, FluvioError, ProducerConfig};
+    /// # async fn do_produce_with_config(fluvio: &Fluvio) -> Result<(), FluvioError> {
+    /// use std::time::Duration;
+    /// let config = ProducerConfig::default()
+    ///     .with_batch_size(64_000)
+    ///     .with_linger(Duration::from_millis(50));
+    /// let producer = fluvio.topic_producer_with_config("my-topic", config).await?;
+    /// producer.send("key", "Hello, Fluvio!").await?;
+    /// producer.flush().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn topic_producer_with_config<S: Into<String>>(
+        &self,
+        topic: S,
+        config: ProducerConfig,
     ) -> Result<TopicProducer, FluvioError> {
         let topic = topic.into();
         debug!(topic = &*topic, "Creating producer");
-        Ok(TopicProducer::new(topic, self.spu_pool.clone()))
+        Ok(TopicProducer::new(topic, self.spu_pool.clone(), config))
     }
 
     /// Creates a new `PartitionConsumer` for the given topic and partition
@@ -154,6 +183,29 @@ impl Fluvio {
         ))
     }
 
+    /// Creates a new `TopicConsumer` for all partitions of the given topic
+    ///
+    /// Records of every partition are merged into a single stream.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{Fluvio, Offset, FluvioError};
+    /// # async fn do_create_topic_consumer(fluvio: &Fluvio) -> Result<(), FluvioError> {
+    /// let consumer = fluvio.topic_consumer("my-topic").await?;
+    /// let records = consumer.fetch(Offset::beginning()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn topic_consumer<S: Into<String>>(
+        &self,
+        topic: S,
+    ) -> Result<TopicConsumer, FluvioError> {
+        let topic = topic.into();
+        debug!(topic = &*topic, "Creating topic consumer");
+        Ok(TopicConsumer::new(topic, self.spu_pool.clone()))
+    }
+
     /// Provides an interface for managing a Fluvio cluster
     ///
     /// # Example
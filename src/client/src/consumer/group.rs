@@ -0,0 +1,163 @@
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+
+use tracing::{debug, trace};
+use dataplane::ReplicaKey;
+use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
+
+use crate::FluvioError;
+use crate::client::SerialFrame;
+
+/// Offsets committed by consumer groups for a single partition
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionGroupOffsets {
+    /// The partition index
+    pub partition: i32,
+    /// First readable offset of the partition
+    pub start_offset: i64,
+    /// Offset after the last committed record of the partition
+    pub end_offset: i64,
+    /// Committed offsets, ordered by group
+    pub offsets: Vec<ConsumerOffset>,
+}
+
+impl PartitionGroupOffsets {
+    /// offset of next record to be read by group, `None` if group has not committed
+    pub fn offset(&self, group: &str) -> Option<i64> {
+        self.offsets
+            .iter()
+            .find(|consumer_offset| consumer_offset.group == group)
+            .map(|consumer_offset| consumer_offset.offset)
+    }
+
+    /// number of records group has not read yet
+    pub fn lag(&self, group: &str) -> Option<i64> {
+        self.offset(group)
+            .map(|offset| (self.end_offset - offset).max(0))
+    }
+}
+
+/// commit offset of next record to be read by group
+pub(crate) async fn commit_group_offset<F: SerialFrame>(
+    client: &mut F,
+    replica: &ReplicaKey,
+    group: &str,
+    offset: i64,
+) -> Result<(), FluvioError> {
+    debug!(
+        "committing group: {} offset: {} for replica: {}",
+        group, offset, replica
+    );
+
+    let response = client
+        .send_receive(CommitConsumerOffsetsRequest::new(
+            group.to_owned(),
+            replica.topic.to_owned(),
+            replica.partition,
+            offset,
+        ))
+        .await?;
+
+    trace!(
+        "receive commit response replica: {}, {:#?}",
+        replica,
+        response
+    );
+
+    match response.find_partition(replica) {
+        Some(partition_response) => {
+            if partition_response.error_code.is_error() {
+                return Err(IoError::new(
+                    ErrorKind::Other,
+                    partition_response.error_code.to_sentence(),
+                )
+                .into());
+            }
+            Ok(())
+        }
+        None => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("no commit response for: {}", replica),
+        )
+        .into()),
+    }
+}
+
+/// fetch offsets committed by groups, all groups if none is specified
+pub(crate) async fn fetch_group_offsets<F: SerialFrame>(
+    client: &mut F,
+    replica: &ReplicaKey,
+    groups: Vec<String>,
+) -> Result<PartitionGroupOffsets, FluvioError> {
+    debug!("fetching group offsets for replica: {}", replica);
+
+    let response = client
+        .send_receive(FetchConsumerOffsetsRequest::new(
+            groups,
+            replica.topic.to_owned(),
+            replica.partition,
+        ))
+        .await?;
+
+    trace!(
+        "receive group offsets response replica: {}, {:#?}",
+        replica,
+        response
+    );
+
+    match response.find_partition(replica) {
+        Some(partition_response) => {
+            if partition_response.error_code.is_error() {
+                return Err(IoError::new(
+                    ErrorKind::Other,
+                    partition_response.error_code.to_sentence(),
+                )
+                .into());
+            }
+            Ok(PartitionGroupOffsets {
+                partition: partition_response.partition_index,
+                start_offset: partition_response.start_offset,
+                end_offset: partition_response.last_stable_offset,
+                offsets: partition_response.offsets,
+            })
+        }
+        None => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("no group offsets for: {}", replica),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::PartitionGroupOffsets;
+    use super::ConsumerOffset;
+
+    #[test]
+    fn test_group_lag() {
+        let offsets = PartitionGroupOffsets {
+            partition: 0,
+            start_offset: 2,
+            end_offset: 10,
+            offsets: vec![
+                ConsumerOffset {
+                    group: "a".to_owned(),
+                    offset: 4,
+                },
+                ConsumerOffset {
+                    group: "b".to_owned(),
+                    offset: 10,
+                },
+            ],
+        };
+
+        assert_eq!(offsets.offset("a"), Some(4));
+        assert_eq!(offsets.lag("a"), Some(6));
+        assert_eq!(offsets.lag("b"), Some(0));
+        assert_eq!(offsets.lag("c"), None);
+    }
+}
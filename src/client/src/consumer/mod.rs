@@ -1,7 +1,16 @@
+mod topic;
+mod group;
+
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+
 use futures_util::stream::Stream;
 use tracing::debug;
+use tracing::trace;
 
+use fluvio_spu_schema::server::SpuServerApiKey;
 use fluvio_spu_schema::server::stream_fetch::{DefaultStreamFetchRequest, DefaultStreamFetchResponse};
+use fluvio_spu_schema::server::stream_fetch::StreamFetchAckRequest;
 use dataplane::Isolation;
 use dataplane::ReplicaKey;
 use dataplane::fetch::DefaultFetchRequest;
@@ -10,11 +19,20 @@ use dataplane::fetch::FetchableTopic;
 use dataplane::fetch::FetchablePartitionResponse;
 use dataplane::record::RecordSet;
 use dataplane::record::DefaultRecord;
+use dataplane::record::Header;
 use crate::FluvioError;
 use crate::offset::Offset;
 use crate::client::SerialFrame;
 use crate::spu::SpuPool;
 
+pub use self::topic::TopicConsumer;
+pub use self::group::PartitionGroupOffsets;
+pub use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
+pub use fluvio_spu_schema::server::stream_fetch::RecordFilter;
+
+/// id of next flow controlled stream
+static NEXT_STREAM_ID: AtomicI32 = AtomicI32::new(1);
+
 /// An interface for consuming events from a particular partition
 ///
 /// There are two ways to consume events: by "fetching" events
@@ -152,9 +170,7 @@ impl PartitionConsumer {
 
         debug!("found spu leader {}", leader);
 
-        let offset = offset
-            .to_absolute(&mut leader, &self.topic, self.partition)
-            .await?;
+        let offset = self.start_offset(&mut leader, offset, &option).await?;
 
         let partition = FetchPartition {
             partition_index: self.partition,
@@ -286,30 +302,34 @@ impl PartitionConsumer {
         use futures_util::future::{Either, err};
         use futures_util::stream::{StreamExt, once, iter};
 
+        let partition = self.partition;
         let stream = self._stream_batches_with_config(offset, config).await?;
-        let flattened = stream.flat_map(|batch_result| {
+        let flattened = stream.flat_map(move |batch_result| {
             let batch = match batch_result {
                 Ok(batch) => batch,
                 Err(e) => return Either::Right(once(err(e))),
             };
 
+            let error_code = batch.partition.error_code;
+            if error_code.is_error() {
+                return Either::Right(once(err(FluvioError::Other(error_code.to_sentence()))));
+            }
+
             let records = batch
                 .partition
                 .records
                 .batches
                 .into_iter()
-                .flat_map(|batch| {
+                .flat_map(move |batch| {
                     let base_offset = batch.base_offset;
-                    batch
-                        .records
-                        .into_iter()
-                        .enumerate()
-                        .map(move |(relative, record)| {
-                            Ok(Record {
-                                offset: base_offset + relative as i64,
-                                record,
-                            })
+                    // compacted batches may skip offsets, use offset delta of the record
+                    batch.into_records().into_iter().map(move |record| {
+                        Ok(Record {
+                            partition,
+                            offset: base_offset + record.get_offset_delta(),
+                            record,
                         })
+                    })
                 });
             Either::Left(iter(records))
         });
@@ -335,10 +355,37 @@ impl PartitionConsumer {
 
         let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
         debug!("created serial socket {}", serial_socket);
-        let offset = offset
-            .to_absolute(&mut serial_socket, &self.topic, self.partition)
+        let offset = self
+            .start_offset(&mut serial_socket, offset, &config)
             .await?;
-        drop(serial_socket);
+
+        // SPU only sends more records once received ones are acknowledged,
+        // so slow consumer doesn't overflow its stream
+        let stream_id = if serial_socket
+            .versions()
+            .lookup_version(SpuServerApiKey::StreamFetchAck as u16)
+            .is_some()
+        {
+            NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed)
+        } else {
+            debug!("SPU doesn't support stream flow control");
+            0
+        };
+
+        let stream_fetch_version = serial_socket
+            .versions()
+            .lookup_version(SpuServerApiKey::StreamFetch as u16)
+            .unwrap_or(-1);
+        if config.filter.is_some() && stream_fetch_version < 12 {
+            return Err(FluvioError::Other(
+                "SPU doesn't support record filter".to_owned(),
+            ));
+        }
+        if config.wasm_module.is_some() && stream_fetch_version < 13 {
+            return Err(FluvioError::Other(
+                "SPU doesn't support wasm transform".to_owned(),
+            ));
+        }
 
         let stream_request = DefaultStreamFetchRequest {
             topic: self.topic.to_owned(),
@@ -346,12 +393,128 @@ impl PartitionConsumer {
             fetch_offset: offset,
             isolation: config.isolation,
             max_bytes: config.max_bytes,
+            stream_id,
+            filter: config.filter,
+            wasm_module: config.wasm_module,
             ..Default::default()
         };
 
         use futures_util::StreamExt;
+        use futures_util::future::Either;
+        use futures_util::stream::unfold;
+
         let stream = self.pool.create_stream(&replica, stream_request).await?;
-        Ok(stream.map(|item| item.map_err(|e| e.into())))
+        if stream_id == 0 {
+            drop(serial_socket);
+            return Ok(Either::Left(stream.map(|item| item.map_err(|e| e.into()))));
+        }
+
+        let acked = unfold(
+            (stream, serial_socket),
+            move |(mut stream, mut socket)| async move {
+                let item = match stream.next().await? {
+                    Ok(response) => response,
+                    Err(e) => return Some((Err(e.into()), (stream, socket))),
+                };
+
+                // with filter or transform, records after last one sent may have been skipped
+                let next_offset = if item.next_offset >= 0 {
+                    Some(item.next_offset)
+                } else {
+                    item.partition
+                        .records
+                        .batches
+                        .last()
+                        .map(|batch| batch.get_last_offset() + 1)
+                };
+                if let Some(offset) = next_offset {
+                    let ack = StreamFetchAckRequest { stream_id, offset };
+                    trace!("acknowledging stream: {:?}", ack);
+                    if let Err(e) = socket.send_receive(ack).await {
+                        return Some((Err(e.into()), (stream, socket)));
+                    }
+                }
+
+                Some((Ok(item), (stream, socket)))
+            },
+        );
+        Ok(Either::Right(Box::pin(acked)))
+    }
+
+    /// Commits the offset of the next record the consumer group will read
+    ///
+    /// A consumer created with [`ConsumerConfig::with_group`] resumes
+    /// from the committed offset. To mark a record as processed, commit
+    /// the offset after it:
+    ///
+    /// ```no_run
+    /// # use fluvio::{PartitionConsumer, FluvioError, Offset, ConsumerConfig};
+    /// # mod futures {
+    /// #     pub use futures_util::stream::StreamExt;
+    /// # }
+    /// # async fn do_commit(consumer: &PartitionConsumer) -> Result<(), FluvioError> {
+    /// use futures::StreamExt;
+    /// let config = ConsumerConfig::default().with_group("my-group");
+    /// let mut stream = consumer.stream_with_config(Offset::beginning(), config).await?;
+    /// while let Some(Ok(record)) = stream.next().await {
+    ///     let offset = record.offset();
+    ///     // process record
+    ///     consumer.commit_offset("my-group", offset + 1).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ConsumerConfig::with_group`]: struct.ConsumerConfig.html#method.with_group
+    pub async fn commit_offset(&self, group: &str, offset: i64) -> Result<(), FluvioError> {
+        let replica = ReplicaKey::new(&self.topic, self.partition);
+        let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
+        group::commit_group_offset(&mut serial_socket, &replica, group, offset).await
+    }
+
+    /// Offset committed by consumer group, `None` if group has not committed any offset
+    pub async fn committed_offset(&self, group: &str) -> Result<Option<i64>, FluvioError> {
+        let offsets = self.fetch_group_offsets(vec![group.to_owned()]).await?;
+        Ok(offsets.offset(group))
+    }
+
+    /// Offsets committed by every consumer group of the partition
+    pub async fn group_offsets(&self) -> Result<PartitionGroupOffsets, FluvioError> {
+        self.fetch_group_offsets(vec![]).await
+    }
+
+    async fn fetch_group_offsets(
+        &self,
+        groups: Vec<String>,
+    ) -> Result<PartitionGroupOffsets, FluvioError> {
+        let replica = ReplicaKey::new(&self.topic, self.partition);
+        let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
+        group::fetch_group_offsets(&mut serial_socket, &replica, groups).await
+    }
+
+    /// absolute offset to start reading from.
+    /// offset committed by consumer group takes precedence over requested offset
+    async fn start_offset<F: SerialFrame>(
+        &self,
+        leader: &mut F,
+        offset: Offset,
+        config: &ConsumerConfig,
+    ) -> Result<i64, FluvioError> {
+        if let Some(group) = &config.group {
+            let replica = ReplicaKey::new(&self.topic, self.partition);
+            let offsets = group::fetch_group_offsets(leader, &replica, vec![group.clone()]).await?;
+            if let Some(committed) = offsets.offset(group) {
+                debug!(
+                    "resuming group: {} from committed offset: {}",
+                    group, committed
+                );
+                return Ok(committed);
+            }
+        }
+
+        offset
+            .to_absolute(leader, &self.topic, self.partition)
+            .await
     }
 }
 
@@ -362,7 +525,7 @@ fn bytes_count(records: &RecordSet) -> usize {
         .iter()
         .map(|batch| {
             batch
-                .records
+                .records()
                 .iter()
                 .map(|record| record.value.len())
                 .sum::<usize>()
@@ -373,10 +536,13 @@ fn bytes_count(records: &RecordSet) -> usize {
 const MAX_FETCH_BYTES: i32 = 1000000;
 
 /// Configures the behavior of consumer fetching and streaming
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ConsumerConfig {
     pub(crate) max_bytes: i32,
     pub(crate) isolation: Isolation,
+    pub(crate) group: Option<String>,
+    pub(crate) filter: Option<RecordFilter>,
+    pub(crate) wasm_module: Option<Vec<u8>>,
 }
 
 impl Default for ConsumerConfig {
@@ -384,6 +550,9 @@ impl Default for ConsumerConfig {
         Self {
             max_bytes: MAX_FETCH_BYTES,
             isolation: Isolation::default(),
+            group: None,
+            filter: None,
+            wasm_module: None,
         }
     }
 }
@@ -394,19 +563,85 @@ impl ConsumerConfig {
         self.max_bytes = max_bytes;
         self
     }
+
+    /// Consume as member of a consumer group
+    ///
+    /// If the group has committed an offset for the partition, consuming
+    /// resumes from that offset and the requested [`Offset`] is only used
+    /// when the group has not committed yet.
+    ///
+    /// [`Offset`]: struct.Offset.html
+    pub fn with_group<S: Into<String>>(mut self, group: S) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Only stream records matching filter, records are filtered by SPU
+    ///
+    /// ```
+    /// # use fluvio::{ConsumerConfig, RecordFilter};
+    /// let config = ConsumerConfig::default()
+    ///     .with_filter(RecordFilter::default().with_key_prefix("user-"));
+    /// ```
+    ///
+    /// Filter only applies to streams, not to single fetch.
+    pub fn with_filter(mut self, filter: RecordFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Run WebAssembly module over each record before it is sent back by SPU
+    ///
+    /// Module exports `memory`, `alloc(len: i32) -> i32` and
+    /// `transform(key_ptr: i32, key_len: i32, value_ptr: i32, value_len: i32) -> i64`
+    /// which returns new value as `ptr << 32 | len`, or negative to drop the record.
+    /// Transform runs after filter and after transform of topic.
+    ///
+    /// ```no_run
+    /// # use fluvio::ConsumerConfig;
+    /// let module = std::fs::read("transform.wasm").expect("module");
+    /// let config = ConsumerConfig::default().with_wasm_module(module);
+    /// ```
+    ///
+    /// Transform only applies to streams, not to single fetch.
+    pub fn with_wasm_module(mut self, module: Vec<u8>) -> Self {
+        self.wasm_module = Some(module);
+        self
+    }
 }
 
 pub struct Record {
+    partition: i32,
     offset: i64,
     record: DefaultRecord,
 }
 
 impl Record {
+    /// partition the record was read from
+    pub fn partition(&self) -> i32 {
+        self.partition
+    }
+
     pub fn offset(&self) -> i64 {
         self.offset
     }
 
+    /// key of the record, `None` if record was sent without key
+    pub fn key(&self) -> Option<&[u8]> {
+        self.record.key.inner_value_ref().as_deref()
+    }
+
+    /// headers of the record
+    pub fn headers(&self) -> &[Header] {
+        self.record.get_headers()
+    }
+
     pub fn try_into_bytes(self) -> Option<Vec<u8>> {
         self.record.value.inner_value()
     }
+
+    /// record as stored in the batch
+    pub fn into_inner(self) -> DefaultRecord {
+        self.record
+    }
 }
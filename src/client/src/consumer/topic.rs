@@ -0,0 +1,494 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::debug;
+use async_channel::Sender;
+use async_channel::Receiver;
+use futures_util::stream::Stream;
+use futures_util::stream::StreamExt;
+
+use fluvio_types::SpuId;
+use dataplane::fetch::FetchablePartitionResponse;
+use dataplane::ReplicaKey;
+use dataplane::record::RecordSet;
+
+use crate::FluvioError;
+use crate::offset::Offset;
+use crate::spu::SpuPool;
+use super::PartitionConsumer;
+use super::ConsumerConfig;
+use super::Record;
+use super::PartitionGroupOffsets;
+
+/// number of records buffered in merged stream
+const MERGED_QUEUE_SIZE: usize = 100;
+
+/// how often partitions are checked if there are no metadata changes
+const PARTITION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// delay before partition stream is restarted after error
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// An interface for consuming events from all partitions of a topic
+///
+/// Records of every partition are merged into a single stream.
+/// Records from same partition are always in order, but there is
+/// no ordering between records of different partitions.
+/// Each [`Record`] tells which partition it was read from.
+///
+/// When consuming as member of a consumer group, each partition resumes
+/// from the offset committed by the group.
+///
+/// Partitions added to the topic after the stream has started are
+/// consumed from the beginning. If the leader of a partition changes,
+/// the stream reconnects to the new leader and resumes after the last
+/// record received. Partitions which are deleted are no longer consumed,
+/// and the stream ends once the topic has no partitions left.
+///
+/// # Example
+///
+/// ```no_run
+/// # use fluvio::{Fluvio, Offset, FluvioError};
+/// # mod futures {
+/// #     pub use futures_util::stream::StreamExt;
+/// # }
+/// # async fn do_stream(fluvio: &Fluvio) -> Result<(), FluvioError> {
+/// use futures::StreamExt;
+/// let consumer = fluvio.topic_consumer("my-topic").await?;
+/// let mut stream = consumer.stream(Offset::beginning()).await?;
+/// while let Some(Ok(record)) = stream.next().await {
+///     println!("partition: {}, offset: {}", record.partition(), record.offset());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Record`]: struct.Record.html
+pub struct TopicConsumer {
+    topic: String,
+    pool: SpuPool,
+}
+
+impl TopicConsumer {
+    pub(crate) fn new(topic: String, pool: SpuPool) -> Self {
+        Self { topic, pool }
+    }
+
+    /// Fetches events from a particular offset in every partition of the topic
+    pub async fn fetch(
+        &self,
+        offset: Offset,
+    ) -> Result<Vec<FetchablePartitionResponse<RecordSet>>, FluvioError> {
+        self.fetch_with_config(offset, ConsumerConfig::default())
+            .await
+    }
+
+    /// Fetches events from every partition of the topic using a specific fetching configuration
+    ///
+    /// Responses are ordered by partition.
+    pub async fn fetch_with_config(
+        &self,
+        offset: Offset,
+        config: ConsumerConfig,
+    ) -> Result<Vec<FetchablePartitionResponse<RecordSet>>, FluvioError> {
+        let mut responses = vec![];
+        for (partition, _) in self.partitions().await? {
+            let consumer = PartitionConsumer::new(self.topic.clone(), partition, self.pool.clone());
+            responses.push(
+                consumer
+                    .fetch_with_config(offset.clone(), config.clone())
+                    .await?,
+            );
+        }
+        Ok(responses)
+    }
+
+    /// Continuously streams events from a particular offset in every partition of the topic
+    pub async fn stream(
+        &self,
+        offset: Offset,
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>>, FluvioError> {
+        self.stream_with_config(offset, ConsumerConfig::default())
+            .await
+    }
+
+    /// Continuously streams events from every partition of the topic using a specific configuration
+    ///
+    /// The offset is applied to each partition separately, for example
+    /// `Offset::end()` starts after the last record of every partition.
+    pub async fn stream_with_config(
+        &self,
+        offset: Offset,
+        config: ConsumerConfig,
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>>, FluvioError> {
+        use fluvio_future::task::spawn;
+
+        // make sure topic exists before starting
+        self.partitions().await?;
+
+        let (sender, receiver) = async_channel::bounded(MERGED_QUEUE_SIZE);
+        spawn(supervise_partitions(
+            self.topic.clone(),
+            self.pool.clone(),
+            offset,
+            config,
+            sender,
+        ));
+
+        Ok(receiver)
+    }
+
+    /// Commits the offset of the next record the consumer group will read from partition
+    pub async fn commit_offset(
+        &self,
+        group: &str,
+        partition: i32,
+        offset: i64,
+    ) -> Result<(), FluvioError> {
+        PartitionConsumer::new(self.topic.clone(), partition, self.pool.clone())
+            .commit_offset(group, offset)
+            .await
+    }
+
+    /// Offsets committed by every consumer group, ordered by partition
+    pub async fn group_offsets(&self) -> Result<Vec<PartitionGroupOffsets>, FluvioError> {
+        let mut offsets = vec![];
+        for (partition, _) in self.partitions().await? {
+            let consumer = PartitionConsumer::new(self.topic.clone(), partition, self.pool.clone());
+            offsets.push(consumer.group_offsets().await?);
+        }
+        Ok(offsets)
+    }
+
+    /// partitions of the topic and their leaders, ordered by partition
+    async fn partitions(&self) -> Result<Vec<(i32, SpuId)>, FluvioError> {
+        let partitions = self.pool.metadata().partitions();
+        partitions.topic_partition_count(&self.topic).await?;
+        Ok(topic_leaders(&self.pool, &self.topic).await)
+    }
+}
+
+async fn topic_leaders(pool: &SpuPool, topic: &str) -> Vec<(i32, SpuId)> {
+    let read_lock = pool.metadata().partitions().store().read().await;
+    let mut leaders: Vec<(i32, SpuId)> = read_lock
+        .values()
+        .filter(|partition| partition.key.topic == topic)
+        .map(|partition| (partition.key.partition, partition.spec.leader))
+        .collect();
+    leaders.sort_unstable();
+    leaders
+}
+
+/// partition stream started by supervisor
+struct PartitionHandle {
+    leader: SpuId,
+    reconnect: Sender<()>,
+}
+
+/// start stream for each partition of the topic and keep track of their leaders.
+/// terminates once merged stream has been dropped or topic has no partitions
+async fn supervise_partitions(
+    topic: String,
+    pool: SpuPool,
+    offset: Offset,
+    config: ConsumerConfig,
+    sender: Sender<Result<Record, FluvioError>>,
+) {
+    use tokio::select;
+    use fluvio_future::task::spawn;
+    use fluvio_future::timer::sleep;
+
+    debug!(topic = &*topic, "starting topic consumer");
+
+    let mut partitions: HashMap<i32, PartitionHandle> = HashMap::new();
+    let mut initial = true;
+
+    while !sender.is_closed() {
+        // listen before reading store so changes in between are not missed
+        let listener = pool.metadata().partitions().listen();
+
+        let leaders = topic_leaders(&pool, &topic).await;
+        if leaders.is_empty() {
+            debug!(topic = &*topic, "topic has no partitions");
+            break;
+        }
+
+        // dropping handle stops stream of partition which no longer exists
+        partitions.retain(|partition, _| {
+            let exists = leaders.iter().any(|(other, _)| other == partition);
+            if !exists {
+                debug!(topic = &*topic, partition, "partition deleted");
+            }
+            exists
+        });
+
+        for (partition, leader) in leaders {
+            match partitions.get_mut(&partition) {
+                Some(handle) => {
+                    if handle.leader != leader {
+                        debug!(
+                            topic = &*topic,
+                            partition, leader, "partition leader changed, reconnecting"
+                        );
+                        handle.leader = leader;
+                        let _ = handle.reconnect.try_send(());
+                    }
+                }
+                None => {
+                    debug!(topic = &*topic, partition, leader, "consuming partition");
+                    // partitions created later have to be read completely
+                    let start_offset = if initial {
+                        offset.clone()
+                    } else {
+                        Offset::beginning()
+                    };
+                    let (reconnect_sender, reconnect_receiver) = async_channel::bounded(1);
+                    spawn(stream_partition(
+                        PartitionConsumer::new(topic.clone(), partition, pool.clone()),
+                        start_offset,
+                        config.clone(),
+                        reconnect_receiver,
+                        sender.clone(),
+                    ));
+                    partitions.insert(
+                        partition,
+                        PartitionHandle {
+                            leader,
+                            reconnect: reconnect_sender,
+                        },
+                    );
+                }
+            }
+        }
+        initial = false;
+
+        select! {
+            _ = listener => {},
+            _ = sleep(PARTITION_CHECK_INTERVAL) => {}
+        }
+    }
+
+    debug!(topic = &*topic, "topic consumer terminated");
+}
+
+/// stream records of single partition into merged stream.
+/// stream is restarted after the last received record if it fails or a reconnect is requested
+async fn stream_partition(
+    consumer: PartitionConsumer,
+    mut offset: Offset,
+    mut config: ConsumerConfig,
+    reconnect: Receiver<()>,
+    sender: Sender<Result<Record, FluvioError>>,
+) {
+    use tokio::select;
+    use fluvio_future::timer::sleep;
+
+    let partition = consumer.partition;
+    let replica = ReplicaKey::new(&consumer.topic, partition);
+
+    loop {
+        let requested = match consumer
+            .stream_with_config(offset.clone(), config.clone())
+            .await
+        {
+            Ok(stream) => {
+                let mut stream = Box::pin(stream);
+                loop {
+                    select! {
+                        item = stream.next() => match item {
+                            Some(Ok(record)) => {
+                                let next_offset = record.offset() + 1;
+                                if sender.send(Ok(record)).await.is_err() {
+                                    return;
+                                }
+                                if let Ok(next) = Offset::absolute(next_offset) {
+                                    offset = next;
+                                    // resume after last record instead of group's committed offset
+                                    config.group = None;
+                                }
+                            }
+                            Some(Err(err)) => {
+                                debug!(partition, "partition stream failed: {}", err);
+                                break false;
+                            }
+                            None => {
+                                debug!(partition, "partition stream ended");
+                                break false;
+                            }
+                        },
+                        request = reconnect.recv() => {
+                            if request.is_err() {
+                                return;
+                            }
+                            break true;
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                debug!(partition, "unable to stream partition: {}", err);
+                false
+            }
+        };
+
+        if sender.is_closed() {
+            return;
+        }
+
+        if consumer
+            .pool
+            .metadata()
+            .partitions()
+            .try_lookup_by_key(&replica)
+            .await
+            .is_none()
+        {
+            debug!(partition, "partition no longer exists");
+            return;
+        }
+
+        // wait before retrying unless leader is already known to have changed
+        if !requested {
+            select! {
+                _ = sleep(RECONNECT_DELAY) => {},
+                request = reconnect.recv() => {
+                    if request.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use futures_util::stream::Stream;
+    use futures_util::stream::StreamExt;
+    use tokio::select;
+
+    use fluvio_future::test_async;
+    use fluvio_future::timer::sleep;
+    use fluvio_spu::fixture::TestSpu;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::{DefaultRecord, RecordSet};
+
+    use crate::FluvioError;
+    use crate::offset::Offset;
+    use crate::fixture::{spu_pool, set_partitions};
+    use super::{TopicConsumer, Record};
+
+    const TOPIC: &str = "test";
+
+    fn records(values: &[&str]) -> RecordSet {
+        let mut batch = DefaultBatch::default();
+        for value in values {
+            batch.add_record(DefaultRecord::from(value.to_string()));
+        }
+        RecordSet::default().add(batch)
+    }
+
+    /// partition and value of next record, none if stream has ended or is idle
+    async fn next_record<S>(stream: &mut S) -> Option<(i32, String)>
+    where
+        S: Stream<Item = Result<Record, FluvioError>> + Unpin,
+    {
+        select! {
+            item = stream.next() => item.map(|record| {
+                let record = record.expect("record");
+                (record.partition(), record.into_inner().value.to_string())
+            }),
+            _ = sleep(Duration::from_secs(5)) => None
+        }
+    }
+
+    #[test_async]
+    async fn test_merge_partitions() -> Result<(), ()> {
+        let spu = TestSpu::start("test-topic-consumer-merge", 5001, 9222).await;
+        spu.add_leader(TOPIC, 0).await;
+        spu.add_leader(TOPIC, 1).await;
+        let pool = spu_pool(&[&spu]).await;
+        set_partitions(&pool, TOPIC, &[spu.id(), spu.id()]).await;
+
+        spu.send_records(TOPIC, 0, records(&["a0", "a1"])).await;
+        spu.send_records(TOPIC, 1, records(&["b0"])).await;
+
+        let consumer = TopicConsumer::new(TOPIC.to_owned(), pool);
+        let mut stream = Box::pin(consumer.stream(Offset::beginning()).await.expect("stream"));
+
+        let mut received = vec![];
+        for _ in 0..3 {
+            received.push(next_record(&mut stream).await.expect("record"));
+        }
+        spu.send_records(TOPIC, 1, records(&["b1"])).await;
+        received.push(next_record(&mut stream).await.expect("record"));
+
+        // order is only kept within partition
+        let partition = |index: i32| -> Vec<&str> {
+            received
+                .iter()
+                .filter(|(partition, _)| *partition == index)
+                .map(|(_, value)| value.as_str())
+                .collect()
+        };
+        assert_eq!(partition(0), vec!["a0", "a1"]);
+        assert_eq!(partition(1), vec!["b0", "b1"]);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_reconnect_to_new_leader() -> Result<(), ()> {
+        let spu1 = TestSpu::start("test-topic-consumer-leader1", 5001, 9223).await;
+        let spu2 = TestSpu::start("test-topic-consumer-leader2", 5002, 9224).await;
+        spu1.add_leader(TOPIC, 0).await;
+        let pool = spu_pool(&[&spu1, &spu2]).await;
+        set_partitions(&pool, TOPIC, &[spu1.id()]).await;
+
+        spu1.send_records(TOPIC, 0, records(&["r0", "r1"])).await;
+        let consumer = TopicConsumer::new(TOPIC.to_owned(), pool.clone());
+        let mut stream = Box::pin(consumer.stream(Offset::beginning()).await.expect("stream"));
+        assert_eq!(next_record(&mut stream).await, Some((0, "r0".to_owned())));
+        assert_eq!(next_record(&mut stream).await, Some((0, "r1".to_owned())));
+
+        // new leader has replicated records of old leader
+        spu2.add_leader(TOPIC, 0).await;
+        spu2.send_records(TOPIC, 0, records(&["r0", "r1"])).await;
+        set_partitions(&pool, TOPIC, &[spu2.id()]).await;
+        spu1.remove_leader(TOPIC, 0).await;
+        spu2.send_records(TOPIC, 0, records(&["r2"])).await;
+
+        // stream resumes after last record
+        assert_eq!(next_record(&mut stream).await, Some((0, "r2".to_owned())));
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_partition_changes() -> Result<(), ()> {
+        let spu = TestSpu::start("test-topic-consumer-partitions", 5001, 9225).await;
+        spu.add_leader(TOPIC, 0).await;
+        let pool = spu_pool(&[&spu]).await;
+        set_partitions(&pool, TOPIC, &[spu.id()]).await;
+
+        spu.send_records(TOPIC, 0, records(&["a0"])).await;
+        let consumer = TopicConsumer::new(TOPIC.to_owned(), pool.clone());
+        let mut stream = Box::pin(consumer.stream(Offset::end()).await.expect("stream"));
+
+        // new partition is consumed from beginning
+        spu.add_leader(TOPIC, 1).await;
+        spu.send_records(TOPIC, 1, records(&["b0"])).await;
+        set_partitions(&pool, TOPIC, &[spu.id(), spu.id()]).await;
+        assert_eq!(next_record(&mut stream).await, Some((1, "b0".to_owned())));
+
+        // stream ends once topic is deleted
+        set_partitions(&pool, TOPIC, &[]).await;
+        let ended = select! {
+            item = stream.next() => item.is_none(),
+            _ = sleep(Duration::from_secs(5)) => false
+        };
+        assert!(ended);
+        Ok(())
+    }
+}
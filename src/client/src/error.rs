@@ -32,6 +32,8 @@ pub enum FluvioError {
         #[from]
         source: ConfigError,
     },
+    #[error("Record delivery failed: {0}")]
+    DeliveryFailed(String),
     #[error("Attempted to create negative offset: {0}")]
     NegativeOffset(i64),
     #[error("Unknown error: {0}")]
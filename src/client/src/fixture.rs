@@ -0,0 +1,57 @@
+//!
+//! # Test Cluster
+//!
+//! Connects clients to SPUs started by test.
+//! There is no SC, so metadata is set directly on local store of pool.
+//!
+use fluvio_spu::fixture::TestSpu;
+use fluvio_types::SpuId;
+
+use crate::client::ClientConfig;
+use crate::metadata::spu::{SpuSpec, IngressPort};
+use crate::metadata::partition::{PartitionSpec, ReplicaKey};
+use crate::metadata::store::MetadataStoreObject;
+use crate::spu::SpuPool;
+use crate::sync::MetadataStores;
+
+/// pool connecting to test spus
+pub(crate) async fn spu_pool(spus: &[&TestSpu]) -> SpuPool {
+    let metadata = MetadataStores::local();
+    let specs = spus
+        .iter()
+        .map(|spu| {
+            let (host, port) = spu.public_endpoint().rsplit_once(':').expect("endpoint");
+            let mut spec = SpuSpec::new(spu.id());
+            spec.public_endpoint =
+                IngressPort::from_port_host(port.parse().expect("port"), host.to_owned());
+            MetadataStoreObject::with_spec(spu.id().to_string(), spec)
+        })
+        .collect();
+    metadata.spus().store().sync_all(specs).await;
+    metadata.spus().notify_spec_changes();
+
+    SpuPool::new(ClientConfig::with_addr(String::new()), metadata)
+}
+
+/// replace partitions known to pool with partitions of topic, partition n is led by leaders[n]
+pub(crate) async fn set_partitions(pool: &SpuPool, topic: &str, leaders: &[SpuId]) {
+    let specs = leaders
+        .iter()
+        .map(|leader| PartitionSpec::new(*leader, vec![*leader]))
+        .collect();
+    set_partition_specs(pool, topic, specs).await
+}
+
+/// replace partitions known to pool with partitions of topic
+pub(crate) async fn set_partition_specs(pool: &SpuPool, topic: &str, specs: Vec<PartitionSpec>) {
+    let partitions = pool.metadata().partitions();
+    let objects = specs
+        .into_iter()
+        .enumerate()
+        .map(|(partition, spec)| {
+            MetadataStoreObject::with_spec(ReplicaKey::new(topic, partition as i32), spec)
+        })
+        .collect();
+    partitions.store().sync_all(objects).await;
+    partitions.notify_spec_changes();
+}
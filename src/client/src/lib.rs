@@ -92,12 +92,18 @@ mod offset;
 mod sync;
 mod spu;
 
+#[cfg(test)]
+mod fixture;
+
 pub mod config;
 
 pub use error::FluvioError;
 pub use config::FluvioConfig;
-pub use producer::TopicProducer;
-pub use consumer::{PartitionConsumer, ConsumerConfig};
+pub use producer::{TopicProducer, ProducerConfig, DeliveryFuture, RecordKey, Acks};
+pub use producer::{Partitioner, PartitionerConfig, DefaultPartitioner};
+pub use dataplane::compression::Compression;
+pub use consumer::{PartitionConsumer, TopicConsumer, ConsumerConfig, Record};
+pub use consumer::{PartitionGroupOffsets, ConsumerOffset, RecordFilter};
 pub use offset::Offset;
 
 pub use crate::admin::FluvioAdmin;
@@ -1,5 +1,7 @@
 use std::io::Error as IoError;
 use std::io::ErrorKind;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use tracing::{debug, trace};
 use dataplane::ReplicaKey;
@@ -14,6 +16,8 @@ pub(crate) enum OffsetInner {
     Absolute(i64),
     FromBeginning(i64),
     FromEnd(i64),
+    /// millis since epoch
+    Timestamp(i64),
 }
 
 /// Describes the location of an event stored in a Fluvio partition
@@ -255,6 +259,31 @@ impl Offset {
         }
     }
 
+    /// Creates an offset pointing to the first event stored at or after the given time
+    ///
+    /// Events are timestamped by the SPU when they are stored, unless the
+    /// producer already set a timestamp. If all events are older than the
+    /// given time, the offset points to the end of the log, so only new
+    /// events are read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::Offset;
+    /// # use std::time::{Duration, SystemTime};
+    /// // Creates an offset pointing to events from 10 minutes ago
+    /// let offset: Offset = Offset::from_timestamp(SystemTime::now() - Duration::from_secs(600));
+    /// ```
+    pub fn from_timestamp(time: SystemTime) -> Offset {
+        let timestamp = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        Self {
+            inner: OffsetInner::Timestamp(timestamp),
+        }
+    }
+
     /// Converts this offset into an absolute offset
     ///
     /// If this offset is relative from the beginning (i.e. it was created
@@ -267,6 +296,10 @@ impl Offset {
     /// the absolute offset by finding the last stably-committed event and subtracting
     /// the relative offset from it.
     ///
+    /// If this offset is a point in time (i.e. it was created using the
+    /// [`from_timestamp`] function), then the SPU looks up the first event
+    /// stored at or after that time.
+    ///
     /// Calling `to_absolute` on an offset that is already absolute just returns
     /// that same offset.
     ///
@@ -293,12 +326,62 @@ impl Offset {
                 let offsets = fetch_offsets(client, &replica).await?;
                 offsets.last_stable_offset - offset
             }
+            OffsetInner::Timestamp(timestamp) => {
+                let replica = ReplicaKey::new(topic, partition);
+                fetch_timestamp_offset(client, &replica, timestamp).await?
+            }
         };
 
         Ok(offset)
     }
 }
 
+/// first offset at or after timestamp, SPU must support version 1 of fetch offsets
+async fn fetch_timestamp_offset<F: SerialFrame>(
+    client: &mut F,
+    replica: &ReplicaKey,
+    timestamp: i64,
+) -> Result<i64, FluvioError> {
+    debug!(
+        "fetching offset of timestamp: {} for replica: {}",
+        timestamp, replica
+    );
+
+    let response = client
+        .send_receive(FetchOffsetsRequest::with_timestamp(
+            replica.topic.to_owned(),
+            replica.partition,
+            timestamp,
+        ))
+        .await?;
+
+    trace!(
+        "receive fetch response replica: {}, {:#?}",
+        replica,
+        response
+    );
+
+    match response.find_partition(replica) {
+        Some(FetchOffsetPartitionResponse {
+            timestamp_offset: Some(offset),
+            ..
+        }) => Ok(offset),
+        Some(partition_response) => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!(
+                "no offset of timestamp for: {}, error: {:?}",
+                replica, partition_response.error_code
+            ),
+        )
+        .into()),
+        None => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("no replica offset for: {}", replica),
+        )
+        .into()),
+    }
+}
+
 async fn fetch_offsets<F: SerialFrame>(
     client: &mut F,
     replica: &ReplicaKey,
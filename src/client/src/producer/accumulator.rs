@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_channel::Sender;
+
+use dataplane::core::Encoder;
+use dataplane::batch::DefaultBatch;
+use dataplane::batch::BATCH_HEADER_SIZE;
+use dataplane::batch::BATCH_PREAMBLE_SIZE;
+use dataplane::record::DefaultRecord;
+
+/// outcome of delivering a batch, shared by every record in the batch
+pub(crate) type DeliveryResult = Result<(), String>;
+
+/// Records waiting to be sent to a single partition
+pub(crate) struct ProducerBatch {
+    partition: i32,
+    batch: DefaultBatch,
+    size: usize,
+    created_at: Instant,
+    senders: Vec<Sender<DeliveryResult>>,
+}
+
+impl ProducerBatch {
+    fn new(partition: i32) -> Self {
+        Self {
+            partition,
+            batch: DefaultBatch::default(),
+            size: BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE,
+            created_at: Instant::now(),
+            senders: vec![],
+        }
+    }
+
+    pub fn partition(&self) -> i32 {
+        self.partition
+    }
+
+    /// number of records in this batch
+    pub fn len(&self) -> usize {
+        self.batch.records().len()
+    }
+
+    /// encoded size of the batch in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[allow(unused)]
+    pub fn records(&self) -> &[DefaultRecord] {
+        self.batch.records()
+    }
+
+    fn push(&mut self, record: DefaultRecord, record_size: usize, sender: Sender<DeliveryResult>) {
+        self.batch.add_record(record);
+        self.size += record_size;
+        self.senders.push(sender);
+    }
+
+    fn is_expired(&self, linger: Duration, now: Instant) -> bool {
+        now.duration_since(self.created_at) >= linger
+    }
+
+    /// take out the batch so it can be sent, leaving empty batch behind
+    pub fn take_batch(&mut self) -> DefaultBatch {
+        std::mem::take(&mut self.batch)
+    }
+
+    /// notify every record in this batch about the outcome of delivery
+    pub fn notify(self, result: DeliveryResult) {
+        for sender in self.senders {
+            // receiver may have been dropped if caller is not interested in delivery
+            let _ = sender.try_send(result.clone());
+        }
+    }
+}
+
+/// Accumulates records into per-partition batches
+///
+/// A batch is ready to be sent once adding another record would make it
+/// larger than `batch_size`, or once it has been waiting longer than `linger`.
+pub(crate) struct RecordAccumulator {
+    batch_size: usize,
+    linger: Duration,
+    batches: HashMap<i32, ProducerBatch>,
+}
+
+impl RecordAccumulator {
+    pub fn new(batch_size: usize, linger: Duration) -> Self {
+        Self {
+            batch_size,
+            linger,
+            batches: HashMap::new(),
+        }
+    }
+
+    /// add record to batch of its partition.
+    /// return batches which are full and must be sent, in the order they must be sent
+    pub fn push(
+        &mut self,
+        partition: i32,
+        record: DefaultRecord,
+        sender: Sender<DeliveryResult>,
+    ) -> Vec<ProducerBatch> {
+        let record_size = record.write_size(0);
+        let mut ready = vec![];
+
+        let full = self
+            .batches
+            .get(&partition)
+            .map(|batch| batch.len() > 0 && batch.size() + record_size > self.batch_size)
+            .unwrap_or(false);
+        if full {
+            ready.extend(self.batches.remove(&partition));
+        }
+
+        let batch = self
+            .batches
+            .entry(partition)
+            .or_insert_with(|| ProducerBatch::new(partition));
+        batch.push(record, record_size, sender);
+
+        // single record may be bigger than batch size, no point waiting for more
+        if batch.size() >= self.batch_size {
+            ready.extend(self.batches.remove(&partition));
+        }
+
+        ready
+    }
+
+    /// remove batches which have been waiting longer than linger time
+    pub fn drain_expired(&mut self, now: Instant) -> Vec<ProducerBatch> {
+        let linger = self.linger;
+        let expired: Vec<i32> = self
+            .batches
+            .values()
+            .filter(|batch| batch.is_expired(linger, now))
+            .map(|batch| batch.partition())
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|partition| self.batches.remove(&partition))
+            .collect()
+    }
+
+    /// remove all pending batches
+    pub fn drain_all(&mut self) -> Vec<ProducerBatch> {
+        self.batches.drain().map(|(_, batch)| batch).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use dataplane::core::Encoder;
+    use dataplane::record::DefaultRecord;
+    use dataplane::batch::BATCH_HEADER_SIZE;
+    use dataplane::batch::BATCH_PREAMBLE_SIZE;
+
+    use super::RecordAccumulator;
+    use super::ProducerBatch;
+
+    fn record(value: &str) -> DefaultRecord {
+        value.to_owned().into()
+    }
+
+    fn values(batch: &ProducerBatch) -> Vec<String> {
+        batch
+            .records()
+            .iter()
+            .map(|record| record.value.to_string())
+            .collect()
+    }
+
+    /// batch size which fits exactly `count` records of the same size as `value`
+    fn batch_size_for(count: usize, value: &str) -> usize {
+        BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE + count * record(value).write_size(0)
+    }
+
+    #[test]
+    fn test_batch_boundaries() {
+        let mut accumulator =
+            RecordAccumulator::new(batch_size_for(3, "r0"), Duration::from_secs(60));
+
+        let mut ready = vec![];
+        for i in 0..7 {
+            let (sender, _) = async_channel::bounded(1);
+            ready.extend(accumulator.push(0, record(&format!("r{}", i)), sender));
+        }
+
+        assert_eq!(ready.len(), 2);
+        assert_eq!(values(&ready[0]), vec!["r0", "r1", "r2"]);
+        assert_eq!(values(&ready[1]), vec!["r3", "r4", "r5"]);
+        assert_eq!(ready[0].size(), batch_size_for(3, "r0"));
+
+        let remainder = accumulator.drain_all();
+        assert_eq!(remainder.len(), 1);
+        assert_eq!(values(&remainder[0]), vec!["r6"]);
+        assert!(accumulator.drain_all().is_empty());
+    }
+
+    #[test]
+    fn test_offset_delta_in_batch() {
+        let mut accumulator =
+            RecordAccumulator::new(batch_size_for(2, "a"), Duration::from_secs(60));
+
+        let mut ready = vec![];
+        for value in &["a", "b", "c"] {
+            let (sender, _) = async_channel::bounded(1);
+            ready.extend(accumulator.push(0, record(value), sender));
+        }
+        let mut batch = ready.remove(0);
+        let batch = batch.take_batch();
+        assert_eq!(batch.records()[0].get_offset_delta(), 0);
+        assert_eq!(batch.records()[1].get_offset_delta(), 1);
+        assert_eq!(batch.get_last_offset_delta(), 1);
+    }
+
+    #[test]
+    fn test_oversized_record_is_sent_alone() {
+        let mut accumulator = RecordAccumulator::new(10, Duration::from_secs(60));
+        let (sender, _) = async_channel::bounded(1);
+        let ready = accumulator.push(0, record("larger than batch size"), sender);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].len(), 1);
+        assert!(accumulator.drain_all().is_empty());
+    }
+
+    #[test]
+    fn test_partitions_are_batched_separately() {
+        let mut accumulator =
+            RecordAccumulator::new(batch_size_for(2, "p0-0"), Duration::from_secs(60));
+
+        let mut ready = vec![];
+        for i in 0..3 {
+            for partition in 0..2 {
+                let (sender, _) = async_channel::bounded(1);
+                let value = format!("p{}-{}", partition, i);
+                ready.extend(accumulator.push(partition, record(&value), sender));
+            }
+        }
+
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].partition(), 0);
+        assert_eq!(values(&ready[0]), vec!["p0-0", "p0-1"]);
+        assert_eq!(ready[1].partition(), 1);
+        assert_eq!(values(&ready[1]), vec!["p1-0", "p1-1"]);
+
+        let mut remainder = accumulator.drain_all();
+        remainder.sort_by_key(|batch| batch.partition());
+        assert_eq!(values(&remainder[0]), vec!["p0-2"]);
+        assert_eq!(values(&remainder[1]), vec!["p1-2"]);
+    }
+
+    #[test]
+    fn test_linger_expiry() {
+        let linger = Duration::from_millis(100);
+        let mut accumulator = RecordAccumulator::new(1000, linger);
+        let (sender, _) = async_channel::bounded(1);
+        assert!(accumulator.push(0, record("a"), sender).is_empty());
+
+        assert!(accumulator.drain_expired(Instant::now()).is_empty());
+        let expired = accumulator.drain_expired(Instant::now() + linger);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(values(&expired[0]), vec!["a"]);
+    }
+
+    #[test]
+    fn test_notify_delivery() {
+        let mut accumulator = RecordAccumulator::new(1000, Duration::from_secs(60));
+        let (sender1, receiver1) = async_channel::bounded(1);
+        let (sender2, receiver2) = async_channel::bounded(1);
+        accumulator.push(0, record("a"), sender1);
+        accumulator.push(0, record("b"), sender2);
+
+        let batch = accumulator.drain_all().pop().expect("batch");
+        batch.notify(Err("failed".to_owned()));
+        assert_eq!(receiver1.try_recv(), Ok(Err("failed".to_owned())));
+        assert_eq!(receiver2.try_recv(), Ok(Err("failed".to_owned())));
+    }
+}
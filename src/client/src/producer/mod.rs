@@ -0,0 +1,1124 @@
+mod accumulator;
+mod partitioning;
+mod sequence;
+
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Weak;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use std::time::Instant;
+
+use tracing::{debug, error, trace, instrument};
+use async_mutex::Mutex;
+use async_channel::Sender;
+use async_channel::Receiver;
+use futures_util::future::BoxFuture;
+use futures_util::future::FutureExt;
+
+use dataplane::ErrorCode;
+use dataplane::ReplicaKey;
+use dataplane::batch::DefaultBatch;
+use dataplane::compression::Compression;
+use dataplane::record::DefaultRecord;
+use dataplane::record::Header;
+use dataplane::produce::DefaultProduceRequest;
+use dataplane::produce::DefaultPartitionRequest;
+use dataplane::produce::DefaultTopicRequest;
+use fluvio_types::SpuId;
+
+use crate::FluvioError;
+use crate::spu::SpuPool;
+use crate::client::SerialFrame;
+use crate::client::VersionedSocket;
+
+use self::accumulator::RecordAccumulator;
+use self::accumulator::ProducerBatch;
+use self::accumulator::DeliveryResult;
+use self::sequence::ProducerSequences;
+
+pub use self::partitioning::{Partitioner, PartitionerConfig, DefaultPartitioner};
+
+const DEFAULT_BATCH_SIZE: usize = 16_384;
+const DEFAULT_LINGER_MS: u64 = 10;
+const DEFAULT_TIMEOUT_MS: u64 = 1500;
+const RETRY_BACKOFF_MS: u64 = 100;
+
+/// How many replicas must have a batch before it is delivered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Acks {
+    /// do not wait for partition leader
+    None,
+    /// wait until partition leader has written batch
+    Leader,
+    /// wait until all in sync replicas have written batch
+    All,
+}
+
+impl Default for Acks {
+    fn default() -> Self {
+        Self::Leader
+    }
+}
+
+impl std::str::FromStr for Acks {
+    type Err = IoError;
+
+    fn from_str(s: &str) -> Result<Self, IoError> {
+        match s.to_lowercase().as_str() {
+            "0" | "none" => Ok(Self::None),
+            "1" | "leader" => Ok(Self::Leader),
+            "all" | "-1" => Ok(Self::All),
+            _ => Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("unknown acks: {}, expected 0, 1 or all", s),
+            )),
+        }
+    }
+}
+
+impl Acks {
+    /// value of acks in produce request
+    fn as_i16(&self) -> i16 {
+        match self {
+            Self::None => 0,
+            Self::Leader => 1,
+            Self::All => -1,
+        }
+    }
+}
+
+/// Configures how a `TopicProducer` batches records
+///
+/// Records sent to the same partition are collected into a batch.
+/// A batch is sent once it reaches `batch_size` bytes, or once its
+/// oldest record has been waiting for longer than `linger`.
+///
+/// The partition of each record sent with [`TopicProducer::send`] is chosen
+/// by the configured [`Partitioner`], which is [`DefaultPartitioner`] unless
+/// set otherwise.
+///
+/// Batches are sent uncompressed unless a compression codec is set with
+/// [`with_compression`]. Codec set on the topic takes precedence, since
+/// partition leaders store batches as they are sent.
+///
+/// A batch is delivered once the partition leader has written it, this can be
+/// changed with [`with_acks`].
+///
+/// Failed batches are not sent again unless retries are set with [`with_retries`].
+/// To make sure retried batches are not written twice, enable [`with_idempotence`].
+///
+/// [`TopicProducer::send`]: struct.TopicProducer.html#method.send
+/// [`with_compression`]: struct.ProducerConfig.html#method.with_compression
+/// [`with_acks`]: struct.ProducerConfig.html#method.with_acks
+/// [`with_retries`]: struct.ProducerConfig.html#method.with_retries
+/// [`with_idempotence`]: struct.ProducerConfig.html#method.with_idempotence
+/// [`Partitioner`]: trait.Partitioner.html
+/// [`DefaultPartitioner`]: struct.DefaultPartitioner.html
+#[derive(Clone)]
+pub struct ProducerConfig {
+    pub(crate) batch_size: usize,
+    pub(crate) linger: Duration,
+    pub(crate) compression: Compression,
+    pub(crate) acks: Acks,
+    pub(crate) timeout: Duration,
+    pub(crate) retries: usize,
+    pub(crate) idempotent: bool,
+    pub(crate) partitioner: Arc<dyn Partitioner + Send + Sync>,
+}
+
+impl fmt::Debug for ProducerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProducerConfig")
+            .field("batch_size", &self.batch_size)
+            .field("linger", &self.linger)
+            .field("compression", &self.compression)
+            .field("acks", &self.acks)
+            .field("timeout", &self.timeout)
+            .field("retries", &self.retries)
+            .field("idempotent", &self.idempotent)
+            .finish()
+    }
+}
+
+impl Default for ProducerConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            linger: Duration::from_millis(DEFAULT_LINGER_MS),
+            compression: Compression::None,
+            acks: Acks::default(),
+            timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
+            retries: 0,
+            idempotent: false,
+            partitioner: Arc::new(DefaultPartitioner::default()),
+        }
+    }
+}
+
+impl ProducerConfig {
+    /// Sets the maximum size in bytes of a batch sent to a partition
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Sets how long a record may wait for more records to join its batch
+    pub fn with_linger(mut self, linger: Duration) -> Self {
+        self.linger = linger;
+        self
+    }
+
+    /// Sets the codec used to compress batches of topics which don't set their own codec
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets how many replicas must have a batch before it is delivered
+    ///
+    /// With [`Acks::None`], events are considered delivered as soon as
+    /// their batch is sent and errors are only logged.
+    ///
+    /// [`Acks::None`]: enum.Acks.html#variant.None
+    pub fn with_acks(mut self, acks: Acks) -> Self {
+        self.acks = acks;
+        self
+    }
+
+    /// Sets how long partition leader waits for replicas when acks is [`Acks::All`]
+    ///
+    /// [`Acks::All`]: enum.Acks.html#variant.All
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times a failed batch is sent again before its events fail
+    ///
+    /// Without idempotence, a batch which was written but not acknowledged,
+    /// for example because of a timeout, is written again.
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets whether partition leaders discard batches which they have already written
+    ///
+    /// The producer gets a producer id from an SPU and numbers the events
+    /// sent to each partition. Partition leaders use these numbers to skip
+    /// batches that are sent again, so retries don't duplicate events.
+    pub fn with_idempotence(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// Sets the partitioner used to choose the partition of each record
+    pub fn with_partitioner<P>(mut self, partitioner: P) -> Self
+    where
+        P: Partitioner + Send + Sync + 'static,
+    {
+        self.partitioner = Arc::new(partitioner);
+        self
+    }
+}
+
+/// Key of a record
+///
+/// Records with the same key are sent to the same partition by the
+/// [`DefaultPartitioner`]. Use `RecordKey::NULL` to send a record without key.
+///
+/// # Example
+///
+/// ```
+/// use fluvio::RecordKey;
+/// let key: RecordKey = "device-1".into();
+/// assert_eq!(key.as_bytes(), Some(&b"device-1"[..]));
+/// assert_eq!(RecordKey::NULL.as_bytes(), None);
+/// ```
+///
+/// [`DefaultPartitioner`]: struct.DefaultPartitioner.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordKey(Option<Vec<u8>>);
+
+impl RecordKey {
+    /// key of records without key
+    pub const NULL: RecordKey = RecordKey(None);
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        self.0.as_deref()
+    }
+}
+
+impl<K: AsRef<[u8]>> From<K> for RecordKey {
+    fn from(key: K) -> Self {
+        Self(Some(key.as_ref().to_vec()))
+    }
+}
+
+/// messages processed by dispatcher in order
+enum DispatchMessage {
+    Batch(ProducerBatch),
+    Flush(Sender<()>),
+}
+
+struct ProducerInner {
+    accumulator: Mutex<RecordAccumulator>,
+    dispatcher: Sender<DispatchMessage>,
+}
+
+impl ProducerInner {
+    fn dispatch(&self, message: DispatchMessage) -> Result<(), FluvioError> {
+        self.dispatcher
+            .try_send(message)
+            .map_err(|_| FluvioError::DeliveryFailed("producer has been closed".to_owned()))
+    }
+
+    /// send batches whose linger time has passed
+    async fn dispatch_expired(&self) {
+        let mut accumulator = self.accumulator.lock().await;
+        for batch in accumulator.drain_expired(Instant::now()) {
+            if let Err(err) = self.dispatch(DispatchMessage::Batch(batch)) {
+                error!("unable to dispatch batch: {}", err);
+            }
+        }
+    }
+}
+
+impl Drop for ProducerInner {
+    fn drop(&mut self) {
+        // hand off what is left, dispatcher exits after it sends them
+        for batch in self.accumulator.get_mut().drain_all() {
+            let _ = self.dispatcher.try_send(DispatchMessage::Batch(batch));
+        }
+    }
+}
+
+/// An interface for producing events to a particular topic
+///
+/// A `TopicProducer` allows you to send events to the specific
+/// topic it was initialized for. Once you have a `TopicProducer`,
+/// you can send events to the topic, either choosing which partition
+/// each event should be delivered to, or letting the producer pick
+/// the partition from the event's key.
+///
+/// Events are not sent one by one. Events for the same partition
+/// are collected into batches as configured by [`ProducerConfig`],
+/// and batches are sent to the partition leaders in the order in
+/// which they were filled.
+///
+/// [`ProducerConfig`]: struct.ProducerConfig.html
+pub struct TopicProducer {
+    topic: String,
+    pool: SpuPool,
+    partitioner: Arc<dyn Partitioner + Send + Sync>,
+    inner: Arc<ProducerInner>,
+}
+
+impl TopicProducer {
+    pub(crate) fn new(topic: String, pool: SpuPool, config: ProducerConfig) -> Self {
+        use fluvio_future::task::spawn;
+
+        let (sender, receiver) = async_channel::unbounded();
+        let inner = Arc::new(ProducerInner {
+            accumulator: Mutex::new(RecordAccumulator::new(config.batch_size, config.linger)),
+            dispatcher: sender,
+        });
+
+        spawn(dispatch_loop(
+            topic.clone(),
+            pool.clone(),
+            config.clone(),
+            receiver,
+        ));
+        spawn(linger_loop(Arc::downgrade(&inner), config.linger));
+
+        Self {
+            topic,
+            pool,
+            partitioner: config.partitioner,
+            inner,
+        }
+    }
+
+    /// Sends an event to a specific partition within this producer's topic
+    /// and waits until it has been delivered
+    ///
+    /// This sends out any records that are still waiting in a batch.
+    /// To send many records efficiently, use [`send`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn do_send_record(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// let partition = 0;
+    /// producer.send_record("Hello, Fluvio!", partition).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`send`]: struct.TopicProducer.html#method.send
+    #[instrument(
+        skip(self, buffer),
+        fields(topic = &*self.topic),
+    )]
+    pub async fn send_record<B: AsRef<[u8]>>(
+        &self,
+        buffer: B,
+        partition: i32,
+    ) -> Result<(), FluvioError> {
+        let record: DefaultRecord = buffer.as_ref().into();
+        let delivery = self.push_record(partition, record).await?;
+        self.flush().await?;
+        delivery.await
+    }
+
+    /// Adds a keyed event to this producer's topic
+    ///
+    /// The partition of the event is chosen by the [`Partitioner`] of this
+    /// producer, using the number of partitions of the topic. By default,
+    /// events with the same key go to the same partition, and events
+    /// without key are spread over all partitions.
+    ///
+    /// This does not wait for the event to be sent. The returned
+    /// [`DeliveryFuture`] completes once the batch containing the event
+    /// has been acknowledged by the partition leader.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError, RecordKey};
+    /// # async fn do_send(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// let mut deliveries = vec![];
+    /// for i in 0..100 {
+    ///     let key = format!("device-{}", i % 10);
+    ///     deliveries.push(producer.send(key, format!("Hello, Fluvio {}!", i)).await?);
+    /// }
+    /// deliveries.push(producer.send(RecordKey::NULL, "no key").await?);
+    /// for delivery in deliveries {
+    ///     delivery.await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Partitioner`]: trait.Partitioner.html
+    /// [`DeliveryFuture`]: struct.DeliveryFuture.html
+    pub async fn send<K, V>(&self, key: K, value: V) -> Result<DeliveryFuture, FluvioError>
+    where
+        K: Into<RecordKey>,
+        V: AsRef<[u8]>,
+    {
+        self.send_with_headers(key, value, vec![]).await
+    }
+
+    /// Adds a keyed event with headers to this producer's topic
+    ///
+    /// Headers carry metadata about the event, such as trace ids or content
+    /// types. Apart from the headers, this works like [`send`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn do_send_with_headers(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// use fluvio::dataplane::record::Header;
+    /// let headers = vec![
+    ///     Header::new("trace-id", "4bf92f3577b34da6"),
+    ///     Header::new("content-type", "application/json"),
+    /// ];
+    /// let delivery = producer.send_with_headers("key", r#"{"hello":"fluvio"}"#, headers).await?;
+    /// delivery.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`send`]: struct.TopicProducer.html#method.send
+    #[instrument(
+        skip(self, key, value, headers),
+        fields(topic = &*self.topic),
+    )]
+    pub async fn send_with_headers<K, V>(
+        &self,
+        key: K,
+        value: V,
+        headers: Vec<Header>,
+    ) -> Result<DeliveryFuture, FluvioError>
+    where
+        K: Into<RecordKey>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.into();
+        let value = value.as_ref();
+
+        let partition_count = self
+            .pool
+            .metadata()
+            .partitions()
+            .topic_partition_count(&self.topic)
+            .await?;
+        let config = PartitionerConfig { partition_count };
+        let partition = self.partitioner.partition(&config, key.as_bytes(), value);
+        trace!(partition, partition_count, "selected partition");
+
+        let mut record: DefaultRecord = value.into();
+        if let Some(key) = key.0 {
+            record.key = key.into();
+        }
+        record.headers = headers;
+
+        self.push_record(partition, record).await
+    }
+
+    /// Deletes a key from a compacted topic
+    ///
+    /// Sends a record with the key and no value. Once the topic is compacted,
+    /// earlier records with the same key are removed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn do_send_tombstone(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// let delivery = producer.send_tombstone("device-1").await?;
+    /// delivery.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        skip(self, key),
+        fields(topic = &*self.topic),
+    )]
+    pub async fn send_tombstone<K>(&self, key: K) -> Result<DeliveryFuture, FluvioError>
+    where
+        K: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+
+        let partition_count = self
+            .pool
+            .metadata()
+            .partitions()
+            .topic_partition_count(&self.topic)
+            .await?;
+        let config = PartitionerConfig { partition_count };
+        let partition = self.partitioner.partition(&config, Some(key), &[]);
+
+        // default record has null value
+        let record = DefaultRecord {
+            key: key.into(),
+            ..Default::default()
+        };
+
+        self.push_record(partition, record).await
+    }
+
+    /// add record to batch of partition, dispatching batches which are full
+    async fn push_record(
+        &self,
+        partition: i32,
+        record: DefaultRecord,
+    ) -> Result<DeliveryFuture, FluvioError> {
+        let (sender, receiver) = async_channel::bounded(1);
+
+        // hold the lock while dispatching so batches are queued in order
+        let mut accumulator = self.inner.accumulator.lock().await;
+        for batch in accumulator.push(partition, record, sender) {
+            self.inner.dispatch(DispatchMessage::Batch(batch))?;
+        }
+
+        Ok(DeliveryFuture::new(receiver))
+    }
+
+    /// Sends all batched events and waits until they have been delivered
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn do_flush(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// producer.send("key", "Hello, Fluvio!").await?;
+    /// producer.flush().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn flush(&self) -> Result<(), FluvioError> {
+        let (sender, receiver) = async_channel::bounded(1);
+        {
+            let mut accumulator = self.inner.accumulator.lock().await;
+            for batch in accumulator.drain_all() {
+                self.inner.dispatch(DispatchMessage::Batch(batch))?;
+            }
+            self.inner.dispatch(DispatchMessage::Flush(sender))?;
+        }
+
+        receiver
+            .recv()
+            .await
+            .map_err(|_| FluvioError::DeliveryFailed("producer has been closed".to_owned()))
+    }
+}
+
+/// Completes when the event has been delivered to the partition leader
+pub struct DeliveryFuture {
+    inner: BoxFuture<'static, Result<(), FluvioError>>,
+}
+
+impl DeliveryFuture {
+    fn new(receiver: Receiver<DeliveryResult>) -> Self {
+        let inner = async move {
+            match receiver.recv().await {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(err)) => Err(FluvioError::DeliveryFailed(err)),
+                Err(_) => Err(FluvioError::DeliveryFailed(
+                    "producer has been closed".to_owned(),
+                )),
+            }
+        };
+
+        Self {
+            inner: inner.boxed(),
+        }
+    }
+}
+
+impl Future for DeliveryFuture {
+    type Output = Result<(), FluvioError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+/// send batches to partition leaders one by one so that ordering is preserved.
+/// terminates once producer has been dropped and all batches are sent
+async fn dispatch_loop(
+    topic: String,
+    pool: SpuPool,
+    config: ProducerConfig,
+    receiver: Receiver<DispatchMessage>,
+) {
+    debug!(topic = &*topic, "starting producer dispatcher");
+
+    let mut sequences = ProducerSequences::default();
+    let mut no_ack_connection: Option<NoAckConnection> = None;
+
+    while let Ok(message) = receiver.recv().await {
+        match message {
+            DispatchMessage::Batch(mut producer_batch) => {
+                let replica = ReplicaKey::new(&topic, producer_batch.partition());
+                let mut batch = producer_batch.take_batch();
+                let compression = batch_compression(&pool, &replica, &config).await;
+                batch.get_mut_header().set_compression(compression);
+                // compressed once, even if batch is sent again
+                if let Err(err) = batch.compress(0) {
+                    error!("error compressing batch to: {}, {}", replica, err);
+                    producer_batch.notify(Err(err.to_string()));
+                    continue;
+                }
+                if let Err(err) = assign_producer_id(&pool, &replica, &config, &mut sequences).await
+                {
+                    error!("error getting producer id from: {}, {}", replica, err);
+                    producer_batch.notify(Err(err.to_string()));
+                    continue;
+                }
+                sequences.stamp(replica.partition, &mut batch);
+
+                // without acks, events are delivered once they are sent
+                let result = if config.acks == Acks::None {
+                    send_batch_without_ack(&pool, &replica, batch, &config, &mut no_ack_connection)
+                        .await
+                } else {
+                    send_batch_in_sequence(&pool, &replica, batch, &config, &mut sequences).await
+                };
+                if let Err(err) = &result {
+                    error!("error sending batch to: {}, {}", replica, err);
+                }
+                producer_batch.notify(result.map_err(|err| err.to_string()));
+            }
+            DispatchMessage::Flush(done) => {
+                let _ = done.try_send(());
+            }
+        }
+    }
+
+    debug!(topic = &*topic, "producer dispatcher terminated");
+}
+
+/// periodically send out batches whose linger time has passed
+async fn linger_loop(inner: Weak<ProducerInner>, linger: Duration) {
+    use fluvio_future::timer::sleep;
+
+    const MIN_LINGER_CHECK: Duration = Duration::from_millis(1);
+
+    let interval = std::cmp::max(linger, MIN_LINGER_CHECK);
+    loop {
+        sleep(interval).await;
+        match inner.upgrade() {
+            Some(inner) => inner.dispatch_expired().await,
+            None => break,
+        }
+    }
+}
+
+/// get producer id for idempotent producer unless it already has one
+async fn assign_producer_id(
+    pool: &SpuPool,
+    replica: &ReplicaKey,
+    config: &ProducerConfig,
+    sequences: &mut ProducerSequences,
+) -> Result<(), FluvioError> {
+    if config.idempotent && !sequences.has_producer_id() {
+        let (producer_id, epoch) = init_producer_id(pool, replica).await?;
+        sequences.set_producer_id(producer_id, epoch);
+    }
+    Ok(())
+}
+
+/// get new producer id from leader of replica
+async fn init_producer_id(pool: &SpuPool, replica: &ReplicaKey) -> Result<(i64, i16), FluvioError> {
+    use fluvio_spu_schema::server::producer_id::InitProducerIdRequest;
+
+    let mut spu_client = pool.create_serial_socket(replica).await?;
+    let response = spu_client
+        .send_receive(InitProducerIdRequest::default())
+        .await?;
+    if response.error_code.is_error() {
+        return Err(IoError::new(ErrorKind::Other, response.error_code.to_sentence()).into());
+    }
+
+    debug!(
+        producer_id = response.producer_id,
+        epoch = response.producer_epoch,
+        "assigned producer id"
+    );
+    Ok((response.producer_id, response.producer_epoch))
+}
+
+/// codec of batches sent to partition, codec of topic overrides producer's
+async fn batch_compression(
+    pool: &SpuPool,
+    replica: &ReplicaKey,
+    config: &ProducerConfig,
+) -> Compression {
+    pool.metadata()
+        .partitions()
+        .try_lookup_by_key(replica)
+        .await
+        .and_then(|partition| partition.spec.compression_type.batch_compression())
+        .unwrap_or(config.compression)
+}
+
+/// errors of partition which may be gone when batch is sent again
+fn is_retriable(error_code: ErrorCode) -> bool {
+    matches!(
+        error_code,
+        ErrorCode::RequestTimedOut
+            | ErrorCode::NotLeaderForPartition
+            | ErrorCode::StorageError
+            | ErrorCode::UnknownServerError
+    )
+}
+
+/// errors of leader which has lost or rejected sequence of producer, batch is not written
+fn is_sequence_error(error_code: ErrorCode) -> bool {
+    matches!(
+        error_code,
+        ErrorCode::OutOfOrderSequenceNumber
+            | ErrorCode::UnknownProducerId
+            | ErrorCode::InvalidProducerEpoch
+    )
+}
+
+/// send batch stamped with sequence of producer.
+///
+/// Producer id is kept after retriable failures, since batch may have been written;
+/// if it is sent again, leader recognizes it as duplicate.  If leader rejects
+/// sequence of batch, it is sent once more with new producer id.
+async fn send_batch_in_sequence(
+    pool: &SpuPool,
+    replica: &ReplicaKey,
+    mut batch: DefaultBatch,
+    config: &ProducerConfig,
+    sequences: &mut ProducerSequences,
+) -> Result<(), FluvioError> {
+    let mut result = send_batch_with_retries(pool, replica, &batch, config).await;
+    if config.idempotent && matches!(result, Ok(error_code) if is_sequence_error(error_code)) {
+        debug!(
+            "sequence of batch to: {} rejected, getting new producer id",
+            replica
+        );
+        sequences.reset();
+        result = match assign_producer_id(pool, replica, config, sequences).await {
+            Ok(()) => {
+                sequences.stamp(replica.partition, &mut batch);
+                send_batch_with_retries(pool, replica, &batch, config).await
+            }
+            Err(err) => Err(err),
+        };
+    }
+
+    match result? {
+        // duplicate means batch was written by earlier attempt
+        ErrorCode::None | ErrorCode::DuplicateSequenceNumber => Ok(()),
+        error_code => {
+            if is_sequence_error(error_code) {
+                sequences.reset();
+            } else if !is_retriable(error_code) {
+                // leader has refused batch, its sequences are not used
+                sequences.rollback(replica.partition, &batch);
+            }
+            Err(IoError::new(ErrorKind::Other, error_code.to_sentence()).into())
+        }
+    }
+}
+
+/// send batch, sending it again after failures up to configured retries.
+/// return error code of last attempt
+async fn send_batch_with_retries(
+    pool: &SpuPool,
+    replica: &ReplicaKey,
+    batch: &DefaultBatch,
+    config: &ProducerConfig,
+) -> Result<ErrorCode, FluvioError> {
+    use fluvio_future::timer::sleep;
+
+    let mut attempt = 0;
+    loop {
+        let result = send_batch(pool, replica, batch.clone(), config).await;
+        if attempt >= config.retries {
+            return result;
+        }
+
+        match result {
+            Ok(error_code) if !is_retriable(error_code) => return Ok(error_code),
+            Ok(error_code) => debug!(
+                attempt,
+                "batch to: {} failed with: {:?}, retrying", replica, error_code
+            ),
+            Err(err) => debug!(attempt, "batch to: {} failed: {}, retrying", replica, err),
+        }
+
+        attempt += 1;
+        sleep(Duration::from_millis(RETRY_BACKOFF_MS)).await;
+    }
+}
+
+/// connection to partition leader used by producer without acks
+struct NoAckConnection {
+    leader: SpuId,
+    socket: VersionedSocket,
+}
+
+/// send batch without waiting for response, leader doesn't respond without acks.
+/// batch is sent again over new connection after failures up to configured retries
+async fn send_batch_without_ack(
+    pool: &SpuPool,
+    replica: &ReplicaKey,
+    batch: DefaultBatch,
+    config: &ProducerConfig,
+    connection: &mut Option<NoAckConnection>,
+) -> Result<(), FluvioError> {
+    use fluvio_future::timer::sleep;
+
+    let mut attempt = 0;
+    loop {
+        let leader = pool.lookup_leader(replica).await?;
+        let socket = match connection {
+            Some(connection) if connection.leader == leader => &mut connection.socket,
+            _ => {
+                let socket = pool.connect_to_spu(leader).await?;
+                &mut connection.insert(NoAckConnection { leader, socket }).socket
+            }
+        };
+
+        let request = produce_request(replica, batch.clone(), config);
+        match socket.send_request(request).await {
+            Ok(_) => return Ok(()),
+            Err(err) => {
+                *connection = None;
+                if attempt >= config.retries {
+                    return Err(err.into());
+                }
+                debug!(attempt, "batch to: {} failed: {}, retrying", replica, err);
+            }
+        }
+
+        attempt += 1;
+        sleep(Duration::from_millis(RETRY_BACKOFF_MS)).await;
+    }
+}
+
+/// send batch to partition leader, return error code of partition
+async fn send_batch(
+    pool: &SpuPool,
+    replica: &ReplicaKey,
+    batch: DefaultBatch,
+    config: &ProducerConfig,
+) -> Result<ErrorCode, FluvioError> {
+    debug!(
+        "sending batch: {} records to: {}",
+        batch.records().len(),
+        replica
+    );
+
+    let spu_client = pool.create_serial_socket(replica).await?;
+
+    debug!("connect to replica leader at: {}", spu_client);
+
+    send_batch_raw(spu_client, replica, batch, config).await
+}
+
+/// Sends batch to a target server (Kf, SPU, or SC)
+async fn send_batch_raw<F: SerialFrame>(
+    mut leader: F,
+    replica: &ReplicaKey,
+    batch: DefaultBatch,
+    config: &ProducerConfig,
+) -> Result<ErrorCode, FluvioError> {
+    debug!(
+        "send batch {} records to: replica: {}, {}",
+        batch.records().len(),
+        replica,
+        leader
+    );
+
+    let request = produce_request(replica, batch, config);
+
+    trace!("produce request: {:#?}", request);
+
+    let response = leader.send_receive(request).await?;
+
+    trace!("received response: {:?}", response);
+
+    // process response
+    match response.find_partition_response(&replica.topic, replica.partition) {
+        Some(partition_response) => Ok(partition_response.error_code),
+        None => Err(IoError::new(ErrorKind::Other, "unknown error").into()),
+    }
+}
+
+/// build produce log request message for batch
+fn produce_request(
+    replica: &ReplicaKey,
+    batch: DefaultBatch,
+    config: &ProducerConfig,
+) -> DefaultProduceRequest {
+    let mut request = DefaultProduceRequest::default();
+    let mut topic_request = DefaultTopicRequest::default();
+    let mut partition_request = DefaultPartitionRequest::default();
+
+    partition_request.partition_index = replica.partition;
+    partition_request.records.batches.push(batch);
+    topic_request.name = replica.topic.to_owned();
+    topic_request.partitions.push(partition_request);
+
+    request.acks = config.acks.as_i16();
+    request.timeout_ms = config.timeout.as_millis() as i32;
+    request.topics.push(topic_request);
+    request
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use fluvio_future::test_async;
+    use fluvio_future::timer::sleep;
+    use fluvio_spu::fixture::TestSpu;
+    use dataplane::core::Encoder;
+    use dataplane::batch::{BATCH_HEADER_SIZE, BATCH_PREAMBLE_SIZE};
+    use dataplane::compression::Compression;
+    use dataplane::record::DefaultRecord;
+
+    use futures_util::future::join;
+
+    use crate::fixture::{spu_pool, set_partitions, set_partition_specs};
+    use crate::metadata::partition::PartitionSpec;
+    use crate::metadata::topic::CompressionAlgorithm;
+    use super::{TopicProducer, ProducerConfig, RecordKey, Acks};
+
+    const TOPIC: &str = "test";
+
+    #[test_async]
+    async fn test_produce_batches() -> Result<(), ()> {
+        let spu = TestSpu::start("test-produce-batches", 5001, 9220).await;
+        spu.add_leader(TOPIC, 0).await;
+        let pool = spu_pool(&[&spu]).await;
+        set_partitions(&pool, TOPIC, &[spu.id()]).await;
+
+        // three records fill a batch, batches are only sent once full or flushed
+        let record_size = DefaultRecord::from("record-0".to_owned()).write_size(0);
+        let config = ProducerConfig::default()
+            .with_batch_size(BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE + 3 * record_size)
+            .with_linger(Duration::from_secs(3600));
+        let producer = TopicProducer::new(TOPIC.to_owned(), pool, config);
+
+        let mut deliveries = vec![];
+        for i in 0..10 {
+            let delivery = producer
+                .send(RecordKey::NULL, format!("record-{}", i))
+                .await
+                .expect("send");
+            deliveries.push(delivery);
+        }
+        producer.flush().await.expect("flush");
+        for delivery in deliveries {
+            delivery.await.expect("delivered");
+        }
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        let sizes: Vec<usize> = batches.iter().map(|batch| batch.records().len()).collect();
+        assert_eq!(sizes, vec![3, 3, 3, 1]);
+        let base_offsets: Vec<i64> = batches
+            .iter()
+            .map(|batch| batch.get_base_offset())
+            .collect();
+        assert_eq!(base_offsets, vec![0, 3, 6, 9]);
+
+        let values: Vec<String> = batches
+            .iter()
+            .flat_map(|batch| batch.records().iter())
+            .map(|record| record.value.to_string())
+            .collect();
+        let expected: Vec<String> = (0..10).map(|i| format!("record-{}", i)).collect();
+        assert_eq!(values, expected);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_produce_with_topic_compression() -> Result<(), ()> {
+        let spu = TestSpu::start("test-produce-topic-compression", 5001, 9221).await;
+        spu.add_leader(TOPIC, 0).await;
+        let pool = spu_pool(&[&spu]).await;
+        let mut spec = PartitionSpec::new(spu.id(), vec![spu.id()]);
+        spec.compression_type = CompressionAlgorithm::Gzip;
+        set_partition_specs(&pool, TOPIC, vec![spec]).await;
+
+        // codec of topic overrides codec of producer, leader stores batch as it is sent
+        let config = ProducerConfig::default().with_compression(Compression::Lz4);
+        let producer = TopicProducer::new(TOPIC.to_owned(), pool, config);
+        for i in 0..3 {
+            producer
+                .send(RecordKey::NULL, format!("log line {}", i))
+                .await
+                .expect("send");
+        }
+        producer.flush().await.expect("flush");
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batches[0].get_header().get_compression().expect("codec"),
+            Compression::Gzip
+        );
+        assert_eq!(batches[0].records().len(), 3);
+        assert_eq!(batches[0].records()[2].value.to_string(), "log line 2");
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_produce_without_acks() -> Result<(), ()> {
+        let spu = TestSpu::start("test-produce-without-acks", 5001, 9227).await;
+        spu.add_leader(TOPIC, 0).await;
+        let pool = spu_pool(&[&spu]).await;
+        set_partitions(&pool, TOPIC, &[spu.id()]).await;
+
+        // events are delivered once batches are sent, leader doesn't respond
+        let config = ProducerConfig::default()
+            .with_acks(Acks::None)
+            .with_linger(Duration::from_secs(3600));
+        let producer = TopicProducer::new(TOPIC.to_owned(), pool, config);
+        for i in 0..3 {
+            let delivery = producer
+                .send(RecordKey::NULL, format!("record-{}", i))
+                .await
+                .expect("send");
+            producer.flush().await.expect("flush");
+            delivery.await.expect("delivered");
+        }
+
+        // leader writes records after they are delivered
+        let mut values: Vec<String> = vec![];
+        for _ in 0..50 {
+            values = spu
+                .read_batches(TOPIC, 0)
+                .await
+                .iter()
+                .flat_map(|batch| batch.records().iter())
+                .map(|record| record.value.to_string())
+                .collect();
+            if values.len() == 3 {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(values, vec!["record-0", "record-1", "record-2"]);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_idempotent_retries() -> Result<(), ()> {
+        const FOLLOWER: i32 = 5002;
+
+        let spu = TestSpu::start("test-idempotent-retries", 5001, 9231).await;
+        spu.add_leader_with_followers(TOPIC, 0, &[FOLLOWER]).await;
+        // follower is in sync, but doesn't fetch records
+        spu.update_follower(TOPIC, 0, FOLLOWER, 0, 0).await;
+        let pool = spu_pool(&[&spu]).await;
+        set_partitions(&pool, TOPIC, &[spu.id()]).await;
+
+        // leader writes batch, but times out waiting for follower.
+        // batch is sent again until follower catches up, leader writes it once
+        let config = ProducerConfig::default()
+            .with_acks(Acks::All)
+            .with_timeout(Duration::from_millis(200))
+            .with_retries(20)
+            .with_idempotence(true)
+            .with_linger(Duration::from_secs(3600));
+        let producer = TopicProducer::new(TOPIC.to_owned(), pool, config);
+        let delivery = producer
+            .send(RecordKey::NULL, "record-0")
+            .await
+            .expect("send");
+        let (result, _) = join(producer.flush(), async {
+            sleep(Duration::from_millis(700)).await;
+            spu.update_follower(TOPIC, 0, FOLLOWER, 1, 0).await;
+        })
+        .await;
+        result.expect("flush");
+        delivery.await.expect("delivered");
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].get_header().first_sequence, 0);
+
+        // producer id is kept, next batch continues sequence
+        let delivery = producer
+            .send(RecordKey::NULL, "record-1")
+            .await
+            .expect("send");
+        let (result, _) = join(producer.flush(), async {
+            sleep(Duration::from_millis(100)).await;
+            spu.update_follower(TOPIC, 0, FOLLOWER, 2, 1).await;
+        })
+        .await;
+        result.expect("flush");
+        delivery.await.expect("delivered");
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[1].get_header().producer_id,
+            batches[0].get_header().producer_id
+        );
+        assert_eq!(batches[1].get_header().first_sequence, 1);
+        Ok(())
+    }
+}
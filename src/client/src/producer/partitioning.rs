@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Information about the topic used to pick a partition
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionerConfig {
+    /// number of partitions in the topic
+    pub partition_count: i32,
+}
+
+/// Chooses the partition a record is sent to
+///
+/// A partitioner must return a partition between `0` and
+/// `config.partition_count - 1`. Records with the same key should
+/// be sent to the same partition so that their order is preserved.
+///
+/// # Example
+///
+/// ```
+/// use fluvio::{Partitioner, PartitionerConfig};
+///
+/// /// sends records with key to first partition, everything else to the last one
+/// struct PriorityPartitioner;
+///
+/// impl Partitioner for PriorityPartitioner {
+///     fn partition(&self, config: &PartitionerConfig, key: Option<&[u8]>, _value: &[u8]) -> i32 {
+///         match key {
+///             Some(_) => 0,
+///             None => config.partition_count - 1,
+///         }
+///     }
+/// }
+/// ```
+pub trait Partitioner {
+    fn partition(&self, config: &PartitionerConfig, key: Option<&[u8]>, value: &[u8]) -> i32;
+}
+
+/// Default partitioner
+///
+/// Records with a key are sent to the partition given by the murmur2 hash
+/// of the key, the same hash used by Kafka clients, so a key always maps
+/// to the same partition as long as the partition count does not change.
+/// Records without a key are spread over all partitions in round-robin order.
+#[derive(Debug, Default)]
+pub struct DefaultPartitioner {
+    index: AtomicUsize,
+}
+
+impl Partitioner for DefaultPartitioner {
+    fn partition(&self, config: &PartitionerConfig, key: Option<&[u8]>, _value: &[u8]) -> i32 {
+        let partition_count = config.partition_count.max(1) as u32;
+        match key {
+            Some(key) => ((murmur2(key) & 0x7fff_ffff) % partition_count) as i32,
+            None => {
+                let index = self.index.fetch_add(1, Ordering::Relaxed);
+                (index % partition_count as usize) as i32
+            }
+        }
+    }
+}
+
+/// murmur2 hash, compatible with Kafka's `Utils.murmur2`
+fn murmur2(data: &[u8]) -> u32 {
+    const SEED: u32 = 0x9747_b28c;
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let length = data.len();
+    let mut h: u32 = SEED ^ length as u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    let remainder = chunks.remainder();
+    if remainder.len() >= 3 {
+        h ^= (remainder[2] as u32) << 16;
+    }
+    if remainder.len() >= 2 {
+        h ^= (remainder[1] as u32) << 8;
+    }
+    if !remainder.is_empty() {
+        h ^= remainder[0] as u32;
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn config(partition_count: i32) -> PartitionerConfig {
+        PartitionerConfig { partition_count }
+    }
+
+    #[test]
+    fn test_murmur2() {
+        // reference values from Kafka's murmur2 implementation
+        assert_eq!(murmur2(b"21") as i32, -973932308);
+        assert_eq!(murmur2(b"foobar") as i32, -790332482);
+        assert_eq!(murmur2(b"a-little-bit-long-string") as i32, -985981536);
+        assert_eq!(murmur2(b"a-little-bit-longer-string") as i32, -1486304829);
+        assert_eq!(
+            murmur2(b"lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8") as i32,
+            -58897971
+        );
+        assert_eq!(murmur2(b"abc") as i32, 479470107);
+    }
+
+    #[test]
+    fn test_key_is_stable() {
+        let partitioner = DefaultPartitioner::default();
+        let first = partitioner.partition(&config(7), Some(b"device-1"), b"");
+        for _ in 0..10 {
+            assert_eq!(
+                partitioner.partition(&config(7), Some(b"device-1"), b""),
+                first
+            );
+        }
+        assert!((0..7).contains(&first));
+    }
+
+    #[test]
+    fn test_round_robin_without_key() {
+        let partitioner = DefaultPartitioner::default();
+        let partitions: Vec<i32> = (0..6)
+            .map(|_| partitioner.partition(&config(3), None, b"value"))
+            .collect();
+        assert_eq!(partitions, vec![0, 1, 2, 0, 1, 2]);
+    }
+}
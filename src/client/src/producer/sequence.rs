@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use dataplane::batch::DefaultBatch;
+use dataplane::batch::increment_sequence;
+
+/// Producer id and next sequence number of each partition
+///
+/// Batches of idempotent producer are stamped with producer id and
+/// sequence number before they are sent, so partition leader can
+/// recognize batches which are sent again after a failure.
+#[derive(Debug, Default)]
+pub(crate) struct ProducerSequences {
+    producer: Option<(i64, i16)>,
+    next_sequences: HashMap<i32, i32>,
+}
+
+impl ProducerSequences {
+    pub fn has_producer_id(&self) -> bool {
+        self.producer.is_some()
+    }
+
+    /// start sequences of new producer id
+    pub fn set_producer_id(&mut self, producer_id: i64, epoch: i16) {
+        self.producer = Some((producer_id, epoch));
+        self.next_sequences.clear();
+    }
+
+    /// forget producer id, next batch needs new one.
+    /// used after leader has rejected sequence of producer
+    pub fn reset(&mut self) {
+        self.producer = None;
+        self.next_sequences.clear();
+    }
+
+    /// set producer id and sequence of batch, batch is unchanged if there is no producer id
+    pub fn stamp(&mut self, partition: i32, batch: &mut DefaultBatch) {
+        if let Some((producer_id, epoch)) = self.producer {
+            let next_sequence = self.next_sequences.entry(partition).or_insert(0);
+            let header = batch.get_mut_header();
+            header.producer_id = producer_id;
+            header.producer_epoch = epoch;
+            header.first_sequence = *next_sequence;
+            *next_sequence = increment_sequence(*next_sequence, batch.records().len() as i32);
+        }
+    }
+
+    /// give back sequences of batch which leader has not written, next batch uses them
+    pub fn rollback(&mut self, partition: i32, batch: &DefaultBatch) {
+        let first_sequence = batch.get_header().first_sequence;
+        if self.producer.is_some() && first_sequence >= 0 {
+            self.next_sequences.insert(partition, first_sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::DefaultRecord;
+
+    use super::ProducerSequences;
+
+    fn batch(records: usize) -> DefaultBatch {
+        let mut batch = DefaultBatch::default();
+        for _ in 0..records {
+            batch.add_record(DefaultRecord::from("hello".to_owned()));
+        }
+        batch
+    }
+
+    #[test]
+    fn test_stamp_sequences() {
+        let mut sequences = ProducerSequences::default();
+
+        let mut first = batch(2);
+        sequences.stamp(0, &mut first);
+        assert_eq!(first.get_header().producer_id, -1);
+        assert_eq!(first.get_header().first_sequence, -1);
+
+        sequences.set_producer_id(10, 0);
+        let mut first = batch(2);
+        sequences.stamp(0, &mut first);
+        assert_eq!(first.get_header().producer_id, 10);
+        assert_eq!(first.get_header().producer_epoch, 0);
+        assert_eq!(first.get_header().first_sequence, 0);
+
+        let mut second = batch(3);
+        sequences.stamp(0, &mut second);
+        assert_eq!(second.get_header().first_sequence, 2);
+
+        // each partition has its own sequence
+        let mut other = batch(1);
+        sequences.stamp(1, &mut other);
+        assert_eq!(other.get_header().first_sequence, 0);
+
+        // rejected batch doesn't use up sequences
+        let mut rejected = batch(2);
+        sequences.stamp(0, &mut rejected);
+        assert_eq!(rejected.get_header().first_sequence, 5);
+        sequences.rollback(0, &rejected);
+        let mut fourth = batch(1);
+        sequences.stamp(0, &mut fourth);
+        assert_eq!(fourth.get_header().first_sequence, 5);
+
+        sequences.reset();
+        assert!(!sequences.has_producer_id());
+        sequences.set_producer_id(11, 0);
+        let mut third = batch(1);
+        sequences.stamp(0, &mut third);
+        assert_eq!(third.get_header().producer_id, 11);
+        assert_eq!(third.get_header().first_sequence, 0);
+    }
+
+    #[test]
+    fn test_sequence_wrap_around() {
+        let mut sequences = ProducerSequences::default();
+        sequences.set_producer_id(10, 0);
+        sequences.next_sequences.insert(0, i32::MAX - 1);
+
+        let mut first = batch(3);
+        sequences.stamp(0, &mut first);
+        assert_eq!(first.get_header().first_sequence, i32::MAX - 1);
+
+        let mut second = batch(1);
+        sequences.stamp(0, &mut second);
+        assert_eq!(second.get_header().first_sequence, 1);
+    }
+}
@@ -94,7 +94,7 @@ pub trait ReplicaLeader: Send + Sync {
 
         let record_msg: DefaultRecord = record.into();
         let mut batch = DefaultBatch::default();
-        batch.records.push(record_msg);
+        batch.records_mut().push(record_msg);
 
         partition_request.partition_index = self.partition();
         partition_request.records.batches.push(batch);
@@ -160,7 +160,7 @@ impl SpuClient {
 
         let record_msg: DefaultRecord = record.into();
         let mut batch = DefaultBatch::default();
-        batch.records.push(record_msg);
+        batch.records_mut().push(record_msg);
 
         partition_request.partition_index = replica.partition;
         partition_request.records.batches.push(batch);
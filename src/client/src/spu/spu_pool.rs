@@ -14,6 +14,7 @@ use crate::FluvioError;
 use crate::client::ClientConfig;
 use crate::sync::MetadataStores;
 use crate::client::VersionedSerialSocket;
+use crate::client::VersionedSocket;
 use crate::client::Versions;
 
 const DEFAULT_STREAM_QUEUE_SIZE: usize = 10;
@@ -63,16 +64,13 @@ impl SpuPool {
         }
     }
 
+    pub fn metadata(&self) -> &MetadataStores {
+        &self.metadata
+    }
+
     /// create new spu socket
     async fn connect_to_leader(&self, leader: SpuId) -> Result<SpuSocket, FluvioError> {
-        let spu = self.metadata.spus().look_up_by_id(leader).await?;
-
-        debug!("connecting to spu: {}", spu.spec);
-        let mut client_config = self.config.clone();
-        let spu_addr = spu.spec.public_endpoint.addr();
-        debug!("spu addr: {}", spu_addr);
-        client_config.set_addr(spu_addr);
-        let versioned_socket = client_config.connect().await?;
+        let versioned_socket = self.connect_to_spu(leader).await?;
         let (socket, config, versions) = versioned_socket.split();
         Ok(SpuSocket {
             socket: AllMultiplexerSocket::shared(socket),
@@ -81,11 +79,24 @@ impl SpuPool {
         })
     }
 
-    // create serial socket connection to replica
-    pub async fn create_serial_socket(
+    /// connect to spu with socket which is not shared with other requests,
+    /// so requests spu doesn't respond to can be sent
+    pub(crate) async fn connect_to_spu(
         &self,
-        replica: &ReplicaKey,
-    ) -> Result<VersionedSerialSocket, FluvioError> {
+        spu_id: SpuId,
+    ) -> Result<VersionedSocket, FluvioError> {
+        let spu = self.metadata.spus().look_up_by_id(spu_id).await?;
+
+        debug!("connecting to spu: {}", spu.spec);
+        let mut client_config = self.config.clone();
+        let spu_addr = spu.spec.public_endpoint.addr();
+        debug!("spu addr: {}", spu_addr);
+        client_config.set_addr(spu_addr);
+        client_config.connect().await
+    }
+
+    /// current leader of replica
+    pub(crate) async fn lookup_leader(&self, replica: &ReplicaKey) -> Result<SpuId, FluvioError> {
         use std::io::ErrorKind;
 
         let partition = match self.metadata.partitions().lookup_by_key(replica).await {
@@ -104,7 +115,15 @@ impl SpuPool {
             }),
         }?;
 
-        let leader_id = partition.spec.leader;
+        Ok(partition.spec.leader)
+    }
+
+    // create serial socket connection to replica
+    pub async fn create_serial_socket(
+        &self,
+        replica: &ReplicaKey,
+    ) -> Result<VersionedSerialSocket, FluvioError> {
+        let leader_id = self.lookup_leader(replica).await?;
 
         // check if already have existing leader
         let mut client_lock = self.spu_clients.lock().await;
@@ -21,6 +21,8 @@ mod context {
     use crate::metadata::store::DualEpochMap;
     use crate::metadata::store::MetadataStoreObject;
     use crate::metadata::spu::SpuSpec;
+    use crate::metadata::partition::PartitionSpec;
+    use crate::metadata::partition::ReplicaKey;
     use crate::metadata::core::MetadataItem;
 
     pub(crate) type CacheMetadataStoreObject<S> = MetadataStoreObject<S, AlwaysNewContext>;
@@ -36,8 +38,9 @@ mod context {
             &0
         }
 
+        /// existing value is never newer, so changes sent by SC are always applied
         fn is_newer(&self, _another: &Self) -> bool {
-            true
+            false
         }
     }
 
@@ -87,7 +90,6 @@ mod context {
         }
 
         /// look up object by index key
-        #[allow(unused)]
         pub async fn try_lookup_by_key(
             &self,
             key: &S::IndexKey,
@@ -186,4 +188,24 @@ mod context {
             .await
         }
     }
+
+    impl StoreContext<PartitionSpec> {
+        /// number of partitions in topic, waits until partitions of topic are known
+        pub async fn topic_partition_count(&self, topic: &str) -> Result<i32, FluvioError> {
+            use std::io::ErrorKind;
+
+            let first_partition = ReplicaKey::new(topic, 0);
+            if let Err(err) = self.lookup_by_key(&first_partition).await {
+                return Err(match err {
+                    FluvioError::IoError { source } if source.kind() == ErrorKind::TimedOut => {
+                        FluvioError::TopicNotFound(topic.to_owned())
+                    }
+                    _ => err,
+                });
+            }
+
+            let read_lock = self.store().read().await;
+            Ok(read_lock.keys().filter(|key| key.topic == topic).count() as i32)
+        }
+    }
 }
@@ -34,6 +34,16 @@ impl MetadataStores {
         Ok(store)
     }
 
+    /// store without sync controllers, filled in by tests
+    #[cfg(test)]
+    pub(crate) fn local() -> Self {
+        Self {
+            shutdown: SimpleEvent::shared(),
+            spus: StoreContext::new(),
+            partitions: StoreContext::new(),
+        }
+    }
+
     pub fn spus(&self) -> &StoreContext<SpuSpec> {
         &self.spus
     }
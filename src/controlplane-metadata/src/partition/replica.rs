@@ -8,6 +8,8 @@ use crate::partition::ReplicaKey;
 use crate::core::*;
 use crate::store::MetadataStoreObject;
 use crate::partition::PartitionSpec;
+use crate::topic::CompressionAlgorithm;
+use crate::topic::RetentionPolicy;
 use super::store::*;
 
 #[derive(Decode, Encode, Debug, PartialEq, Clone, Default)]
@@ -15,6 +17,14 @@ pub struct Replica {
     pub id: ReplicaKey,
     pub leader: SpuId,
     pub replicas: Vec<SpuId>,
+    pub compression_type: CompressionAlgorithm,
+    pub retention: RetentionPolicy,
+    /// max bytes per second leader copies to followers, 0 is unlimited
+    pub throttle: u32,
+    /// min replicas in sync with leader to accept produce, spu setting is used if not set
+    pub min_in_sync_replicas: Option<u16>,
+    /// wasm module in transform directory of spu, run over records sent to consumers
+    pub transform: Option<String>,
 }
 
 impl Replica {
@@ -23,6 +33,11 @@ impl Replica {
             id,
             leader,
             replicas,
+            compression_type: CompressionAlgorithm::default(),
+            retention: RetentionPolicy::default(),
+            throttle: 0,
+            min_in_sync_replicas: None,
+            transform: None,
         }
     }
 }
@@ -33,10 +48,21 @@ where
 {
     fn from(item: PartitionMetadata<C>) -> Self {
         let inner: MetadataStoreObject<PartitionSpec, C> = item;
+        let throttle = inner
+            .spec
+            .reassignment
+            .as_ref()
+            .map(|reassignment| reassignment.throttle)
+            .unwrap_or(0);
         Self {
             id: inner.key,
             leader: inner.spec.leader,
             replicas: inner.spec.replicas,
+            compression_type: inner.spec.compression_type,
+            retention: inner.spec.retention,
+            throttle,
+            min_in_sync_replicas: inner.spec.min_in_sync_replicas,
+            transform: inner.spec.transform,
         }
     }
 }
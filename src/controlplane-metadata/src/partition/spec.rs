@@ -7,6 +7,9 @@
 use fluvio_types::SpuId;
 use dataplane::derive::{Decode, Encode};
 
+use crate::topic::CompressionAlgorithm;
+use crate::topic::RetentionPolicy;
+
 /// Spec for Partition
 /// Each partition has replicas spread among SPU
 /// one of replica is leader which is duplicated in the leader field
@@ -19,6 +22,25 @@ use dataplane::derive::{Decode, Encode};
 pub struct PartitionSpec {
     pub leader: SpuId,
     pub replicas: Vec<SpuId>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub compression_type: CompressionAlgorithm,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub retention: RetentionPolicy,
+    /// replica move in progress
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub reassignment: Option<PartitionReassignment>,
+    /// min replicas in sync with leader to accept produce, spu setting is used if not set
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub min_in_sync_replicas: Option<u16>,
+    /// wasm module in transform directory of spu, run over records sent to consumers
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub transform: Option<String>,
 }
 
 impl std::default::Default for PartitionSpec {
@@ -26,19 +48,46 @@ impl std::default::Default for PartitionSpec {
         PartitionSpec {
             leader: 0,
             replicas: Vec::default(),
+            compression_type: CompressionAlgorithm::default(),
+            retention: RetentionPolicy::default(),
+            reassignment: None,
+            min_in_sync_replicas: None,
+            transform: None,
         }
     }
 }
 
 impl PartitionSpec {
     pub fn new(leader: SpuId, replicas: Vec<SpuId>) -> Self {
-        Self { leader, replicas }
+        Self {
+            leader,
+            replicas,
+            compression_type: CompressionAlgorithm::default(),
+            retention: RetentionPolicy::default(),
+            reassignment: None,
+            min_in_sync_replicas: None,
+            transform: None,
+        }
     }
 
     pub fn has_spu(&self, spu: &SpuId) -> bool {
         self.replicas.contains(spu)
     }
 
+    /// true if partition is being moved to other replicas
+    pub fn is_reassigning(&self) -> bool {
+        self.reassignment.is_some()
+    }
+
+    /// first replica is preferred leader, none while partition is reassigned
+    pub fn preferred_leader(&self) -> Option<SpuId> {
+        if self.is_reassigning() {
+            None
+        } else {
+            self.replicas.first().copied()
+        }
+    }
+
     /// follower replicas
     pub fn followers(&self) -> Vec<SpuId> {
         self.replicas
@@ -57,3 +106,41 @@ impl From<Vec<i32>> for PartitionSpec {
         }
     }
 }
+
+/// Target of partition reassignment.
+///
+/// While partition is moved, replicas contain both old and target replicas.
+/// Once target replicas have caught up with leader, leadership is moved to
+/// first target replica if needed and old replicas are dropped.
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct PartitionReassignment {
+    /// replicas after reassignment, first one is preferred leader
+    pub target_replicas: Vec<SpuId>,
+    /// max bytes per second copied to followers while moving, 0 is unlimited
+    pub throttle: u32,
+}
+
+impl PartitionReassignment {
+    pub fn new(target_replicas: Vec<SpuId>, throttle: u32) -> Self {
+        Self {
+            target_replicas,
+            throttle,
+        }
+    }
+
+    /// replicas while partition is moved: current replicas followed by new target replicas
+    pub fn moving_replicas(&self, replicas: &[SpuId]) -> Vec<SpuId> {
+        let mut moving = replicas.to_vec();
+        for target in &self.target_replicas {
+            if !moving.contains(target) {
+                moving.push(*target);
+            }
+        }
+        moving
+    }
+}
@@ -31,6 +31,9 @@ pub struct PartitionStatus {
     pub leader: ReplicaStatus,
     pub lsr: u32,
     pub replicas: Vec<ReplicaStatus>,
+    /// leader and followers in sync with it, as reported by leader
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub in_sync_replicas: Vec<SpuId>,
 }
 
 impl fmt::Display for PartitionStatus {
@@ -91,6 +94,11 @@ impl PartitionStatus {
         self.lsr
     }
 
+    /// true if fewer replicas than replication factor are in sync with leader
+    pub fn is_under_replicated(&self, replication: usize) -> bool {
+        self.in_sync_replicas.len() < replication
+    }
+
     pub fn replica_iter(&self) -> Iter<ReplicaStatus> {
         self.replicas.iter()
     }
@@ -107,6 +115,22 @@ impl PartitionStatus {
         !self.replicas.is_empty()
     }
 
+    /// true if replica on spu is in sync with leader.
+    /// leader reports followers which have caught up recently, so followers of partition
+    /// which is written continuously count as well. Until leader has reported them,
+    /// replica must have all records of leader
+    pub fn is_caught_up(&self, spu: SpuId) -> bool {
+        if self.leader.spu == spu {
+            return true;
+        }
+        if !self.in_sync_replicas.is_empty() {
+            return self.in_sync_replicas.contains(&spu);
+        }
+        self.replicas
+            .iter()
+            .any(|status| status.spu == spu && status.leo >= 0 && status.leo == self.leader.leo)
+    }
+
     /// Fnd best candidate from online replicas
     /// If there are multiple matches, find with best score (lowest lag)
     pub fn candidate_leader<P>(&self, online: &HashSet<SpuId>, policy: &P) -> Option<SpuId>
@@ -371,6 +395,23 @@ mod test {
         assert!(status.candidate_leader(&online_spu, &policy).is_none());
     }
 
+    #[test]
+    fn test_is_caught_up() {
+        let mut status = PartitionStatus::new(
+            (5000, 100, 110),
+            vec![(5001, 100, 108).into(), (5002, 100, 110).into()],
+        );
+        // without in sync replicas from leader, end offsets must match
+        assert!(status.is_caught_up(5000));
+        assert!(!status.is_caught_up(5001));
+        assert!(status.is_caught_up(5002));
+
+        // follower behind partition which is being written is still in sync
+        status.in_sync_replicas = vec![5000, 5001];
+        assert!(status.is_caught_up(5001));
+        assert!(!status.is_caught_up(5002));
+    }
+
     #[test]
     fn test_merge_initial() {
         let mut target = PartitionStatus::default();
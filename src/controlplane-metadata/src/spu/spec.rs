@@ -37,6 +37,12 @@ pub struct SpuSpec {
     pub private_endpoint: Endpoint,
     #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
     pub rack: Option<String>,
+    /// endpoint of kafka compatible listener, advertised by spu when enabled
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub kafka_endpoint: Option<Endpoint>,
 }
 
 impl fmt::Display for SpuSpec {
@@ -64,6 +70,7 @@ impl Default for SpuSpec {
                 encryption: EncryptionEnum::default(),
             },
             rack: None,
+            kafka_endpoint: None,
         }
     }
 }
@@ -144,6 +151,7 @@ impl From<CustomSpuSpec> for SpuSpec {
             private_endpoint: spec.private_endpoint,
             rack: spec.rack,
             spu_type: SpuType::Custom,
+            kafka_endpoint: None,
         }
     }
 }
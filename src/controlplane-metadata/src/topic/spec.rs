@@ -18,6 +18,7 @@ use dataplane::core::Version;
 use dataplane::bytes::{Buf, BufMut};
 use dataplane::derive::{Decode, Encode};
 use dataplane::core::{Decoder, Encoder};
+use dataplane::compression::Compression;
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(
@@ -85,6 +86,66 @@ impl TopicSpec {
         }
     }
 
+    pub fn compression_type(&self) -> &CompressionAlgorithm {
+        match self {
+            TopicSpec::Computed(param) => &param.compression_type,
+            TopicSpec::Assigned(partition_map) => &partition_map.compression_type,
+        }
+    }
+
+    pub fn set_compression_type(&mut self, compression_type: CompressionAlgorithm) {
+        match self {
+            TopicSpec::Computed(param) => param.compression_type = compression_type,
+            TopicSpec::Assigned(partition_map) => partition_map.compression_type = compression_type,
+        }
+    }
+
+    pub fn retention(&self) -> &RetentionPolicy {
+        match self {
+            TopicSpec::Computed(param) => &param.retention,
+            TopicSpec::Assigned(partition_map) => &partition_map.retention,
+        }
+    }
+
+    pub fn set_retention(&mut self, retention: RetentionPolicy) {
+        match self {
+            TopicSpec::Computed(param) => param.retention = retention,
+            TopicSpec::Assigned(partition_map) => partition_map.retention = retention,
+        }
+    }
+
+    /// min replicas in sync with leader to accept produce, spu setting is used if not set
+    pub fn min_in_sync_replicas(&self) -> Option<u16> {
+        match self {
+            TopicSpec::Computed(param) => param.min_in_sync_replicas,
+            TopicSpec::Assigned(partition_map) => partition_map.min_in_sync_replicas,
+        }
+    }
+
+    pub fn set_min_in_sync_replicas(&mut self, min_in_sync_replicas: Option<u16>) {
+        match self {
+            TopicSpec::Computed(param) => param.min_in_sync_replicas = min_in_sync_replicas,
+            TopicSpec::Assigned(partition_map) => {
+                partition_map.min_in_sync_replicas = min_in_sync_replicas
+            }
+        }
+    }
+
+    /// name of wasm module in transform directory of spu, run over records sent to consumers
+    pub fn transform(&self) -> Option<&str> {
+        match self {
+            TopicSpec::Computed(param) => param.transform.as_deref(),
+            TopicSpec::Assigned(partition_map) => partition_map.transform.as_deref(),
+        }
+    }
+
+    pub fn set_transform(&mut self, transform: Option<String>) {
+        match self {
+            TopicSpec::Computed(param) => param.transform = transform,
+            TopicSpec::Assigned(partition_map) => partition_map.transform = transform,
+        }
+    }
+
     pub fn ignore_rack_assignment(&self) -> IgnoreRackAssignment {
         match self {
             TopicSpec::Computed(param) => param.ignore_rack_assignment,
@@ -174,6 +235,27 @@ impl TopicSpec {
 
         Ok(())
     }
+
+    /// min in sync replicas can't be more than replicas of partition
+    pub fn valid_min_in_sync_replicas(
+        min_in_sync_replicas: &Option<u16>,
+        replication: &ReplicationFactor,
+    ) -> Result<(), Error> {
+        match min_in_sync_replicas {
+            Some(0) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "min in sync replicas must be greater than 0",
+            )),
+            Some(min) if *min as ReplicationFactor > *replication => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "min in sync replicas: {} is greater than replication factor: {}",
+                    min, replication
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl Decoder for TopicSpec {
@@ -274,6 +356,20 @@ pub struct TopicReplicaParam {
     pub replication_factor: ReplicationFactor,
     #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "bool::clone"))]
     pub ignore_rack_assignment: IgnoreRackAssignment,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub compression_type: CompressionAlgorithm,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub retention: RetentionPolicy,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub min_in_sync_replicas: Option<u16>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub transform: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -291,6 +387,10 @@ impl TopicReplicaParam {
             partitions,
             replication_factor,
             ignore_rack_assignment,
+            compression_type: CompressionAlgorithm::default(),
+            retention: RetentionPolicy::default(),
+            min_in_sync_replicas: None,
+            transform: None,
         }
     }
 }
@@ -312,16 +412,166 @@ impl std::fmt::Display for TopicReplicaParam {
     }
 }
 
+/// Compression applied to batches stored in a topic
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+#[fluvio(encode_discriminant)]
+#[repr(u8)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CompressionAlgorithm {
+    /// keep batches compressed as sent by producer
+    Producer = 0,
+    None = 1,
+    Gzip = 2,
+    Snappy = 3,
+    Lz4 = 4,
+    Zstd = 5,
+}
+
+impl Default for CompressionAlgorithm {
+    fn default() -> Self {
+        CompressionAlgorithm::Producer
+    }
+}
+
+impl CompressionAlgorithm {
+    /// codec batches must be stored with, none if producer's codec is kept
+    pub fn batch_compression(&self) -> Option<Compression> {
+        match self {
+            Self::Producer => None,
+            Self::None => Some(Compression::None),
+            Self::Gzip => Some(Compression::Gzip),
+            Self::Snappy => Some(Compression::Snappy),
+            Self::Lz4 => Some(Compression::Lz4),
+            Self::Zstd => Some(Compression::Zstd),
+        }
+    }
+}
+
+impl std::fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.batch_compression() {
+            Some(compression) => write!(f, "{}", compression),
+            None => write!(f, "producer"),
+        }
+    }
+}
+
+impl std::str::FromStr for CompressionAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        if s.eq_ignore_ascii_case("producer") {
+            return Ok(Self::Producer);
+        }
+        let compression = match s.parse::<Compression>() {
+            Ok(compression) => compression,
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "unknown compression type: {}, expected one of: producer, none, gzip, snappy, lz4, zstd",
+                        s
+                    ),
+                ))
+            }
+        };
+        Ok(compression.into())
+    }
+}
+
+impl From<Compression> for CompressionAlgorithm {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => Self::None,
+            Compression::Gzip => Self::Gzip,
+            Compression::Snappy => Self::Snappy,
+            Compression::Lz4 => Self::Lz4,
+            Compression::Zstd => Self::Zstd,
+        }
+    }
+}
+
+/// How long records of a topic are kept, records are kept forever if no limit is set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct RetentionPolicy {
+    /// segments with records older than this are deleted
+    #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_age_seconds: Option<u32>,
+    /// oldest segments are deleted while partition is bigger than this
+    #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_partition_size: Option<i64>,
+    /// only latest record of each key is kept, records with null value delete the key
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub compact: bool,
+}
+
+impl RetentionPolicy {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age_seconds.is_none() && self.max_partition_size.is_none() && !self.compact
+    }
+}
+
+impl std::fmt::Display for RetentionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_unlimited() {
+            return write!(f, "unlimited");
+        }
+        let mut limits = vec![];
+        if let Some(seconds) = self.max_age_seconds {
+            limits.push(format!("{} secs", seconds));
+        }
+        if let Some(bytes) = self.max_partition_size {
+            limits.push(format!("{} bytes", bytes));
+        }
+        if self.compact {
+            limits.push("compact".to_owned());
+        }
+        write!(f, "{}", limits.join(", "))
+    }
+}
+
 /// Hack: field instead of new type to get around encode and decode limitations
 #[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
 #[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PartitionMaps {
     maps: Vec<PartitionMap>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    compression_type: CompressionAlgorithm,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    retention: RetentionPolicy,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    min_in_sync_replicas: Option<u16>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    transform: Option<String>,
 }
 
 impl From<Vec<PartitionMap>> for PartitionMaps {
     fn from(maps: Vec<PartitionMap>) -> Self {
-        Self { maps }
+        Self {
+            maps,
+            compression_type: CompressionAlgorithm::default(),
+            retention: RetentionPolicy::default(),
+            min_in_sync_replicas: None,
+            transform: None,
+        }
     }
 }
 
@@ -505,7 +755,10 @@ impl PartitionMaps {
             id += 1;
         }
 
-        Ok(())
+        TopicSpec::valid_min_in_sync_replicas(
+            &self.min_in_sync_replicas,
+            &(replica_len as ReplicationFactor),
+        )
     }
 }
 
@@ -590,6 +843,24 @@ pub mod test {
         // positive numbers are OK
         let t6_result = TopicSpec::valid_replication_factor(&1);
         assert!(t6_result.is_ok());
+
+        // min in sync replicas must be between 1 and replication factor
+        assert!(TopicSpec::valid_min_in_sync_replicas(&None, &1).is_ok());
+        assert!(TopicSpec::valid_min_in_sync_replicas(&Some(2), &3).is_ok());
+        assert_eq!(
+            format!(
+                "{}",
+                TopicSpec::valid_min_in_sync_replicas(&Some(0), &3).unwrap_err()
+            ),
+            "min in sync replicas must be greater than 0"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                TopicSpec::valid_min_in_sync_replicas(&Some(3), &2).unwrap_err()
+            ),
+            "min in sync replicas: 3 is greater than replication factor: 2"
+        );
     }
 
     //  Replica Map ids:
@@ -729,6 +1000,12 @@ pub mod test {
             0x00, 0x00, 0x00, 0x02, // replica cnt
             0x00, 0x00, 0x13, 0x89, // spu id: 5001
             0x00, 0x00, 0x13, 0x8a, // spu id: 5002
+            0x00, // compression type
+            0x00, // retention max age
+            0x00, // retention max partition size
+            0x00, // retention compact
+            0x00, // min in sync replicas
+            0x00, // transform
         ];
         assert_eq!(dest, expected_dest);
 
@@ -758,7 +1035,13 @@ pub mod test {
 
     #[test]
     fn test_encode_decode_computed_topic_spec() {
-        let topic_spec = TopicSpec::Computed((2, 3, true).into());
+        let mut topic_spec = TopicSpec::Computed((2, 3, true).into());
+        topic_spec.set_compression_type(CompressionAlgorithm::Zstd);
+        topic_spec.set_retention(RetentionPolicy {
+            max_age_seconds: Some(3600),
+            max_partition_size: None,
+            compact: true,
+        });
         let mut dest = vec![];
 
         // test encode
@@ -770,6 +1053,12 @@ pub mod test {
             0x00, 0x00, 0x00, 0x02, // partition cnt
             0x00, 0x00, 0x00, 0x03, // replica cnt
             0x01, // ignore_rack_assignment
+            0x05, // compression type
+            0x01, 0x00, 0x00, 0x0e, 0x10, // retention max age
+            0x00, // retention max partition size
+            0x01, // retention compact
+            0x00, // min in sync replicas
+            0x00, // transform
         ];
         assert_eq!(dest, expected_dest);
 
@@ -783,6 +1072,10 @@ pub mod test {
                 assert_eq!(param.partitions, 2);
                 assert_eq!(param.replication_factor, 3);
                 assert_eq!(param.ignore_rack_assignment, true);
+                assert_eq!(param.compression_type, CompressionAlgorithm::Zstd);
+                assert_eq!(param.retention.max_age_seconds, Some(3600));
+                assert_eq!(param.retention.max_partition_size, None);
+                assert!(param.retention.compact);
             }
             _ => assert!(
                 false,
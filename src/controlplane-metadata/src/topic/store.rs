@@ -36,8 +36,13 @@ where
             let replica_key = ReplicaKey::new(self.key(), *idx);
             debug!("Topic: {} creating partition: {}", self.key(), replica_key);
             if !partition_store.contains_key(&replica_key).await {
+                let mut partition_spec: PartitionSpec = replicas.clone().into();
+                partition_spec.compression_type = *self.spec.compression_type();
+                partition_spec.retention = *self.spec.retention();
+                partition_spec.min_in_sync_replicas = self.spec.min_in_sync_replicas();
+                partition_spec.transform = self.spec.transform().map(|name| name.to_owned());
                 partitions.push(
-                    MetadataStoreObject::with_spec(replica_key, replicas.clone().into())
+                    MetadataStoreObject::with_spec(replica_key, partition_spec)
                         .with_context(self.ctx.create_child()),
                 )
             }
@@ -17,6 +17,7 @@ use dataplane::ErrorCode;
 use dataplane::derive::Decode;
 use dataplane::derive::Encode;
 use fluvio_types::SpuId;
+use fluvio_controlplane_metadata::spu::Endpoint;
 
 use crate::InternalScKey;
 
@@ -27,6 +28,8 @@ use crate::InternalScKey;
 #[derive(Decode, Encode, Debug, Default)]
 pub struct RegisterSpuRequest {
     spu: SpuId,
+    /// kafka compatible listener of spu, if enabled
+    kafka_endpoint: Option<Endpoint>,
 }
 
 impl Request for RegisterSpuRequest {
@@ -46,12 +49,24 @@ pub struct RegisterSpuResponse {
 
 impl RegisterSpuRequest {
     pub fn new(spu: SpuId) -> Self {
-        Self { spu }
+        Self {
+            spu,
+            kafka_endpoint: None,
+        }
+    }
+
+    pub fn with_kafka_endpoint(mut self, kafka_endpoint: Option<Endpoint>) -> Self {
+        self.kafka_endpoint = kafka_endpoint;
+        self
     }
 
     pub fn spu(&self) -> SpuId {
         self.spu
     }
+
+    pub fn kafka_endpoint(&self) -> Option<&Endpoint> {
+        self.kafka_endpoint.as_ref()
+    }
 }
 
 // -----------------------------------
@@ -7,6 +7,7 @@ use dataplane::derive::Decode;
 use dataplane::derive::Encode;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use fluvio_controlplane_metadata::partition::ReplicaStatus;
+use fluvio_types::SpuId;
 
 use crate::InternalScKey;
 
@@ -17,6 +18,8 @@ pub struct UpdateLrsRequest {
     pub id: ReplicaKey,
     pub leader: ReplicaStatus,
     pub replicas: Vec<ReplicaStatus>,
+    /// leader and followers in sync with it
+    pub in_sync_replicas: Vec<SpuId>,
 }
 
 impl fmt::Display for UpdateLrsRequest {
@@ -31,8 +34,14 @@ impl UpdateLrsRequest {
             id,
             leader,
             replicas,
+            in_sync_replicas: vec![],
         }
     }
+
+    pub fn with_in_sync_replicas(mut self, in_sync_replicas: Vec<SpuId>) -> Self {
+        self.in_sync_replicas = in_sync_replicas;
+        self
+    }
 }
 
 impl Request for UpdateLrsRequest {
@@ -1,6 +1,9 @@
 use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Cursor;
 use std::mem::size_of;
 use std::fmt::Debug;
+use std::convert::TryFrom;
 
 use log::trace;
 
@@ -16,6 +19,8 @@ use crate::derive::Encode;
 use crate::Offset;
 use crate::Size;
 use crate::record::DefaultRecord;
+use crate::compression::Compression;
+use crate::compression::COMPRESSION_CODEC_MASK;
 
 pub type DefaultBatchRecords = Vec<DefaultRecord>;
 pub type DefaultBatch = Batch<DefaultBatchRecords>;
@@ -29,11 +34,17 @@ pub trait BatchRecords: Default + Debug + Encoder + Decoder {
 
 impl BatchRecords for DefaultBatchRecords {}
 
+/// size of the record count which precedes the records
+const RECORD_COUNT_SIZE: usize = size_of::<i32>();
+
+/// max size of records after they are uncompressed
+pub const MAX_UNCOMPRESSED_RECORDS_SIZE: usize = 64 * 1024 * 1024;
+
 /// size of the offset and length
 pub const BATCH_PREAMBLE_SIZE: usize = size_of::<Offset>()     // Offset
         + size_of::<i32>(); // i32
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Batch<R>
 where
     R: BatchRecords,
@@ -41,7 +52,11 @@ where
     pub base_offset: Offset,
     pub batch_len: i32, // only for decoding
     pub header: BatchHeader,
-    pub records: R,
+    /// records of decoded or compressed batch must be changed with `records_mut`
+    records: R,
+    /// records compressed with codec, as received or by `compress`.
+    /// encoded instead of records, so batch is not compressed again
+    compressed_records: Option<(Compression, Vec<u8>)>,
 }
 
 impl<R> Batch<R>
@@ -56,6 +71,32 @@ where
         &self.header
     }
 
+    pub fn records(&self) -> &R {
+        &self.records
+    }
+
+    pub fn into_records(self) -> R {
+        self.records
+    }
+
+    /// records which may be changed, compressed records are dropped
+    pub fn records_mut(&mut self) -> &mut R {
+        self.compressed_records = None;
+        &mut self.records
+    }
+
+    /// compress records with codec of header, so they are compressed only once
+    /// no matter how many times batch is encoded
+    pub fn compress(&mut self, version: Version) -> Result<(), Error> {
+        self.compressed_records = None;
+        let compression = self.header.get_compression()?;
+        if compression != Compression::None {
+            let records = self.encode_records(version)?;
+            self.compressed_records = Some((compression, records));
+        }
+        Ok(())
+    }
+
     pub fn get_base_offset(&self) -> Offset {
         self.base_offset
     }
@@ -94,6 +135,87 @@ where
         self.header.decode(src, version)?;
         Ok(())
     }
+
+    /// decode records, uncompressing them if batch is compressed.
+    /// src must contain exactly records of this batch
+    pub fn decode_records<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        let compression = self.header.get_compression()?;
+        if compression == Compression::None {
+            return self.records.decode(src, version);
+        }
+
+        let records_len = usize::try_from(self.batch_len)
+            .ok()
+            .and_then(|batch_len| batch_len.checked_sub(BATCH_HEADER_SIZE))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid batch len: {}", self.batch_len),
+                )
+            })?;
+        if src.remaining() < records_len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "not enough bytes for compressed records, expected: {}, remaining: {}",
+                    records_len,
+                    src.remaining()
+                ),
+            ));
+        }
+
+        let mut compressed = vec![0u8; records_len];
+        src.copy_to_slice(&mut compressed);
+        let uncompressed = uncompress_records(&compressed, compression)?;
+        self.records
+            .decode(&mut Cursor::new(uncompressed), version)?;
+        self.compressed_records = Some((compression, compressed));
+        Ok(())
+    }
+
+    /// compressed records if they are still valid for codec of header
+    fn cached_records(&self) -> Option<&[u8]> {
+        match (&self.compressed_records, self.header.get_compression()) {
+            (Some((cached, records)), Ok(compression)) if *cached == compression => Some(records),
+            _ => None,
+        }
+    }
+
+    /// encode records, compressing them if compression is set in the header
+    fn encode_records(&self, version: Version) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::with_capacity(self.records.write_size(version));
+        self.records.encode(&mut out, version)?;
+        match self.header.get_compression()? {
+            Compression::None => Ok(out),
+            compression => compress_records(&out, compression),
+        }
+    }
+}
+
+/// compress encoded records.
+/// same as Kafka, record count is not compressed, only records following it.
+fn compress_records(records: &[u8], compression: Compression) -> Result<Vec<u8>, Error> {
+    if records.len() < RECORD_COUNT_SIZE {
+        return Ok(records.to_vec());
+    }
+    let (count, records) = records.split_at(RECORD_COUNT_SIZE);
+    let mut out = count.to_vec();
+    out.extend(compression.compress(records)?);
+    Ok(out)
+}
+
+/// reverse of `compress_records`
+fn uncompress_records(records: &[u8], compression: Compression) -> Result<Vec<u8>, Error> {
+    if records.len() < RECORD_COUNT_SIZE {
+        return Ok(records.to_vec());
+    }
+    let (count, records) = records.split_at(RECORD_COUNT_SIZE);
+    let mut out = count.to_vec();
+    out.extend(compression.uncompress(records, MAX_UNCOMPRESSED_RECORDS_SIZE)?);
+    Ok(out)
 }
 
 impl Batch<DefaultBatchRecords> {
@@ -106,7 +228,7 @@ impl Batch<DefaultBatchRecords> {
         };
         record.preamble.set_offset_delta(last_offset_delta);
         self.header.last_offset_delta = last_offset_delta as i32;
-        self.records.push(record)
+        self.records_mut().push(record)
     }
 }
 
@@ -120,7 +242,7 @@ where
     {
         trace!("decoding batch");
         self.decode_from_file_buf(src, version)?;
-        self.records.decode(src, version)?;
+        self.decode_records(src, version)?;
         Ok(())
     }
 }
@@ -131,7 +253,16 @@ where
     R: BatchRecords,
 {
     fn write_size(&self, version: Version) -> usize {
-        BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE + self.records.write_size(version)
+        let records_size = match (self.cached_records(), self.header.get_compression()) {
+            (Some(records), _) => records.len(),
+            (None, Ok(Compression::None)) | (None, Err(_)) => self.records.write_size(version),
+            // compressed size is only known after compressing
+            (None, Ok(_)) => self
+                .encode_records(version)
+                .map(|records| records.len())
+                .unwrap_or_else(|_| self.records.write_size(version)),
+        };
+        BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE + records_size
     }
 
     fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
@@ -139,8 +270,16 @@ where
         T: BufMut,
     {
         trace!("Encoding Batch");
+        let encoded;
+        let records = match self.cached_records() {
+            Some(records) => records,
+            None => {
+                encoded = self.encode_records(version)?;
+                &encoded
+            }
+        };
         self.base_offset.encode(dest, version)?;
-        let batch_len: i32 = (BATCH_HEADER_SIZE + self.records.write_size(version)) as i32;
+        let batch_len: i32 = (BATCH_HEADER_SIZE + records.len()) as i32;
         batch_len.encode(dest, version)?;
 
         // encode parts of header
@@ -156,7 +295,7 @@ where
         self.header.producer_id.encode(buf, version)?;
         self.header.producer_epoch.encode(buf, version)?;
         self.header.first_sequence.encode(buf, version)?;
-        self.records.encode(buf, version)?;
+        buf.extend_from_slice(records);
 
         let crc = crc32c::crc32c(&out);
         crc.encode(dest, version)?;
@@ -165,7 +304,7 @@ where
     }
 }
 
-#[derive(Debug, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode)]
 pub struct BatchHeader {
     pub partition_leader_epoch: i32,
     pub magic: i8,
@@ -179,6 +318,33 @@ pub struct BatchHeader {
     pub first_sequence: i32,
 }
 
+impl BatchHeader {
+    /// compression codec of records in the batch
+    pub fn get_compression(&self) -> Result<Compression, Error> {
+        Compression::try_from((self.attributes & COMPRESSION_CODEC_MASK) as i8)
+    }
+
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.attributes = (self.attributes & !COMPRESSION_CODEC_MASK)
+            | (compression as i16 & COMPRESSION_CODEC_MASK);
+    }
+
+    /// sequence number of last record in the batch
+    pub fn last_sequence(&self) -> i32 {
+        increment_sequence(self.first_sequence, self.last_offset_delta)
+    }
+}
+
+/// add increment to sequence number of producer.
+/// as in Kafka, sequence wraps around to 0 after i32::MAX
+pub fn increment_sequence(sequence: i32, increment: i32) -> i32 {
+    if sequence > i32::MAX - increment {
+        increment - (i32::MAX - sequence) - 1
+    } else {
+        sequence + increment
+    }
+}
+
 impl Default for BatchHeader {
     fn default() -> Self {
         BatchHeader {
@@ -213,13 +379,31 @@ mod test {
 
     use std::io::Cursor;
     use std::io::Error as IoError;
+    use std::io::ErrorKind;
 
     use crate::core::Decoder;
     use crate::core::Encoder;
     use crate::record::DefaultRecord;
     use crate::batch::DefaultBatch;
+    use crate::compression::Compression;
     use super::BatchHeader;
     use super::BATCH_HEADER_SIZE;
+    use super::increment_sequence;
+
+    #[test]
+    fn test_increment_sequence() {
+        assert_eq!(increment_sequence(0, 5), 5);
+        assert_eq!(increment_sequence(i32::MAX - 5, 5), i32::MAX);
+        assert_eq!(increment_sequence(i32::MAX, 1), 0);
+        assert_eq!(increment_sequence(i32::MAX - 1, 5), 3);
+
+        let header = BatchHeader {
+            first_sequence: i32::MAX - 1,
+            last_offset_delta: 2,
+            ..Default::default()
+        };
+        assert_eq!(header.last_sequence(), 0);
+    }
 
     #[test]
     fn test_batch_size() {
@@ -301,4 +485,102 @@ mod test {
         );
         assert_eq!(batch.get_last_offset_delta(), 2);
     }
+
+    #[test]
+    fn test_compression_attributes() {
+        let mut header = BatchHeader {
+            attributes: 0x10,
+            ..Default::default()
+        };
+        assert_eq!(header.get_compression().expect("codec"), Compression::None);
+        header.set_compression(Compression::Zstd);
+        assert_eq!(header.attributes, 0x14);
+        assert_eq!(header.get_compression().expect("codec"), Compression::Zstd);
+        header.set_compression(Compression::None);
+        assert_eq!(header.attributes, 0x10);
+    }
+
+    #[test]
+    fn test_encode_and_decode_compressed_batch() -> Result<(), IoError> {
+        for compression in &[
+            Compression::Gzip,
+            Compression::Snappy,
+            Compression::Lz4,
+            Compression::Zstd,
+        ] {
+            let mut batch = DefaultBatch::default();
+            for i in 0..20 {
+                batch.add_record(DefaultRecord::from(format!("log line number {}", i)));
+            }
+            let uncompressed_size = batch.write_size(0);
+            batch.get_mut_header().set_compression(*compression);
+
+            let bytes = batch.as_bytes(0)?;
+            assert_eq!(bytes.len(), batch.write_size(0));
+            assert!(bytes.len() < uncompressed_size);
+
+            let mut decoded = DefaultBatch::decode_from(&mut Cursor::new(bytes.clone()), 0)?;
+            assert_eq!(decoded.get_header().get_compression()?, *compression);
+            assert_eq!(decoded.records.len(), 20);
+            assert_eq!(decoded.get_last_offset_delta(), 19);
+            assert_eq!(
+                decoded.records[7].value.inner_value_ref().as_deref(),
+                Some("log line number 7".as_bytes())
+            );
+
+            // received records are encoded as they are, not compressed again
+            let received =
+                bytes[bytes.len() - decoded.cached_records().expect("cached").len()..].to_vec();
+            decoded.header.first_timestamp = 10;
+            let encoded = decoded.as_bytes(0)?;
+            assert!(encoded.ends_with(&received));
+
+            // changed records are compressed again
+            decoded.records_mut().truncate(10);
+            assert!(decoded.cached_records().is_none());
+            let encoded = decoded.as_bytes(0)?;
+            let reencoded = DefaultBatch::decode_from(&mut Cursor::new(encoded), 0)?;
+            assert_eq!(reencoded.records.len(), 10);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_invalid_batch_len() -> Result<(), IoError> {
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from("log line".to_owned()));
+        batch.get_mut_header().set_compression(Compression::Gzip);
+        let mut bytes = batch.as_bytes(0)?.to_vec();
+
+        // batch len is right after base offset
+        for batch_len in &[-1i32, BATCH_HEADER_SIZE as i32 - 1] {
+            bytes[8..12].copy_from_slice(&batch_len.to_be_bytes());
+            let err = DefaultBatch::decode_from(&mut Cursor::new(bytes.clone()), 0)
+                .expect_err("invalid batch len");
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_once() -> Result<(), IoError> {
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from("log line".to_owned()));
+        batch.get_mut_header().set_compression(Compression::Gzip);
+        batch.compress(0)?;
+        let compressed = batch.cached_records().expect("compressed").to_vec();
+
+        let bytes = batch.as_bytes(0)?;
+        assert_eq!(bytes.len(), batch.write_size(0));
+        assert!(bytes.ends_with(&compressed));
+
+        // cache is not used once codec is changed
+        batch.get_mut_header().set_compression(Compression::Lz4);
+        assert!(batch.cached_records().is_none());
+        let decoded = DefaultBatch::decode_from(&mut Cursor::new(batch.as_bytes(0)?), 0)?;
+        assert_eq!(decoded.get_header().get_compression()?, Compression::Lz4);
+        assert_eq!(decoded.records.len(), 1);
+        Ok(())
+    }
 }
@@ -0,0 +1,271 @@
+//!
+//! # Batch Compression
+//!
+//! Codec used to compress the records of a batch.  Codec is stored in the lowest
+//! 3 bits of the batch attributes, using same values as Kafka.
+//!
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// mask of the compression codec in the batch attributes
+pub const COMPRESSION_CODEC_MASK: i16 = 0x07;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i8)]
+pub enum Compression {
+    None = 0,
+    Gzip = 1,
+    Snappy = 2,
+    Lz4 = 3,
+    Zstd = 4,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::None
+    }
+}
+
+impl TryFrom<i8> for Compression {
+    type Error = Error;
+
+    fn try_from(value: i8) -> Result<Self, Error> {
+        match value {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Gzip),
+            2 => Ok(Compression::Snappy),
+            3 => Ok(Compression::Lz4),
+            4 => Ok(Compression::Zstd),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown compression codec: {}", value),
+            )),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Compression::None),
+            "gzip" => Ok(Compression::Gzip),
+            "snappy" => Ok(Compression::Snappy),
+            "lz4" => Ok(Compression::Lz4),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown compression: {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Snappy => "snappy",
+            Compression::Lz4 => "lz4",
+            Compression::Zstd => "zstd",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Compression {
+    /// compress bytes
+    pub fn compress(&self, src: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Compression::None => Ok(src.to_vec()),
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(src)?;
+                encoder.finish()
+            }
+            // kafka clients expect raw snappy block, not snappy frame format
+            Compression::Snappy => Ok(snap::raw::Encoder::new().compress_vec(src)?),
+            Compression::Lz4 => {
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+                encoder.write_all(src)?;
+                encoder
+                    .finish()
+                    .map_err(|err| Error::new(ErrorKind::Other, err.to_string()))
+            }
+            Compression::Zstd => zstd::stream::encode_all(src, zstd::DEFAULT_COMPRESSION_LEVEL),
+        }
+    }
+
+    /// uncompress bytes, fails with `InvalidData` if they would be larger than max size,
+    /// so small compressed input can't exhaust memory
+    pub fn uncompress(&self, src: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+        match self {
+            Compression::None => read_limited(src, max_size),
+            Compression::Gzip => read_limited(flate2::read::GzDecoder::new(src), max_size),
+            Compression::Snappy => uncompress_snappy(src, max_size),
+            Compression::Lz4 => read_limited(lz4_flex::frame::FrameDecoder::new(src), max_size),
+            Compression::Zstd => read_limited(zstd::stream::read::Decoder::new(src)?, max_size),
+        }
+    }
+}
+
+/// read until end, but no more than max size
+fn read_limited<R: Read>(reader: R, max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut out = vec![];
+    reader.take(max_size as u64 + 1).read_to_end(&mut out)?;
+    if out.len() > max_size {
+        return Err(size_exceeded(max_size));
+    }
+    Ok(out)
+}
+
+fn size_exceeded(max_size: usize) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("uncompressed size exceeds max: {}", max_size),
+    )
+}
+
+/// header of xerial snappy framing used by java kafka clients: magic, version and compatible version
+const XERIAL_MAGIC: &[u8] = b"\x82SNAPPY\0";
+const XERIAL_HEADER_SIZE: usize = 16;
+
+/// uncompress raw snappy block, or blocks of xerial framing
+fn uncompress_snappy(src: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut out = vec![];
+    if !src.starts_with(XERIAL_MAGIC) || src.len() < XERIAL_HEADER_SIZE {
+        uncompress_snappy_block(src, max_size, &mut out)?;
+        return Ok(out);
+    }
+
+    let mut chunks = &src[XERIAL_HEADER_SIZE..];
+    while !chunks.is_empty() {
+        if chunks.len() < 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "truncated xerial snappy chunk length",
+            ));
+        }
+        let (len, rest) = chunks.split_at(4);
+        let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        if rest.len() < len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "truncated xerial snappy chunk",
+            ));
+        }
+        let (block, rest) = rest.split_at(len);
+        uncompress_snappy_block(block, max_size, &mut out)?;
+        chunks = rest;
+    }
+    Ok(out)
+}
+
+/// append uncompressed block to out, size is known from block header,
+/// so it is checked before uncompressing
+fn uncompress_snappy_block(block: &[u8], max_size: usize, out: &mut Vec<u8>) -> Result<(), Error> {
+    let len = snap::raw::decompress_len(block)?;
+    if out.len() + len > max_size {
+        return Err(size_exceeded(max_size));
+    }
+    let start = out.len();
+    out.resize(start + len, 0);
+    snap::raw::Decoder::new().decompress(block, &mut out[start..])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::convert::TryFrom;
+    use std::io::ErrorKind;
+
+    use super::Compression;
+
+    const CODECS: [Compression; 5] = [
+        Compression::None,
+        Compression::Gzip,
+        Compression::Snappy,
+        Compression::Lz4,
+        Compression::Zstd,
+    ];
+
+    #[test]
+    fn test_compress_uncompress() {
+        let data = b"hello world, hello world, hello world, hello world".repeat(10);
+        for codec in &CODECS {
+            let compressed = codec.compress(&data).expect("compress");
+            if *codec != Compression::None {
+                assert!(compressed.len() < data.len(), "{} not compressed", codec);
+            }
+            assert_eq!(
+                codec
+                    .uncompress(&compressed, data.len())
+                    .expect("uncompress"),
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn test_uncompress_limit() {
+        let data = vec![0u8; 1_000_000];
+        for codec in &CODECS {
+            let compressed = codec.compress(&data).expect("compress");
+            let err = codec
+                .uncompress(&compressed, data.len() - 1)
+                .expect_err("limit");
+            assert_eq!(err.kind(), ErrorKind::InvalidData, "{}", codec);
+        }
+    }
+
+    #[test]
+    fn test_uncompress_snappy_xerial() {
+        let data = b"hello world, hello world, hello world, hello world".repeat(10);
+        let mut framed = b"\x82SNAPPY\0".to_vec();
+        framed.extend_from_slice(&1i32.to_be_bytes());
+        framed.extend_from_slice(&1i32.to_be_bytes());
+        for chunk in data.chunks(200) {
+            let block = snap::raw::Encoder::new()
+                .compress_vec(chunk)
+                .expect("compress");
+            framed.extend_from_slice(&(block.len() as u32).to_be_bytes());
+            framed.extend_from_slice(&block);
+        }
+
+        assert_eq!(
+            Compression::Snappy
+                .uncompress(&framed, data.len())
+                .expect("uncompress"),
+            data
+        );
+        let err = Compression::Snappy
+            .uncompress(&framed, data.len() - 1)
+            .expect_err("limit");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(Compression::Snappy
+            .uncompress(&framed[..framed.len() - 1], data.len())
+            .is_err());
+    }
+
+    #[test]
+    fn test_codec_conversion() {
+        for codec in &CODECS {
+            assert_eq!(Compression::try_from(*codec as i8).expect("codec"), *codec);
+            assert_eq!(
+                codec.to_string().parse::<Compression>().expect("parse"),
+                *codec
+            );
+        }
+        assert!(Compression::try_from(5).is_err());
+        assert!("brotli".parse::<Compression>().is_err());
+    }
+}
@@ -22,9 +22,22 @@ pub enum ErrorCode {
     None = 0,
 
     OffsetOutOfRange = 1,
+    UnknownTopicOrPartition = 3,
+    LeaderNotAvailable = 5,
     NotLeaderForPartition = 6,
+    RequestTimedOut = 7,
     PermissionDenied = 13,
+    CoordinatorNotAvailable = 15,
+    NotEnoughReplicas = 19,
+    InvalidGroupId = 24,
+    TopicAuthorizationFailed = 29,
+    UnsupportedVersion = 35,
+    InvalidRequest = 42,
+    OutOfOrderSequenceNumber = 45,
+    DuplicateSequenceNumber = 46,
+    InvalidProducerEpoch = 47,
     StorageError = 56,
+    UnknownProducerId = 59,
 
     // Spu errors
     SpuError = 1000,
@@ -43,6 +56,14 @@ pub enum ErrorCode {
     // Partition errors
     PartitionPendingInitialization = 3000,
     PartitionNotLeader = 3001,
+    PartitionError = 3002,
+
+    // Transform errors
+    TransformModuleNotFound = 4000,
+    TransformInvalidModule = 4001,
+    TransformFailed = 4002,
+    TransformRequiresStreamFetch = 4003,
+    TransformClientModuleDisabled = 4004,
 }
 
 impl Default for ErrorCode {
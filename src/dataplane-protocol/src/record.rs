@@ -34,7 +34,7 @@ pub trait AsyncBuffer {
 
 pub trait Records {}
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct DefaultAsyncBuffer(Option<Vec<u8>>);
 
 impl DefaultAsyncBuffer {
@@ -252,7 +252,7 @@ impl Encoder for RecordSet {
     }
 }
 
-#[derive(Decode, Encode, Default, Debug)]
+#[derive(Decode, Encode, Default, Debug, Clone)]
 pub struct RecordHeader {
     attributes: i8,
     #[varint]
@@ -265,9 +265,80 @@ impl RecordHeader {
     pub fn set_offset_delta(&mut self, delta: Offset) {
         self.offset_delta = delta;
     }
+
+    /// timestamp relative to first timestamp of batch
+    pub fn get_timestamp_delta(&self) -> i64 {
+        self.timestamp_delta
+    }
+}
+
+/// Key/value metadata attached to a record, such as trace id or content type
+///
+/// Encoded as in the Kafka record v2 format: varint length prefixed
+/// key followed by varint length prefixed value.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Header {
+    pub key: String,
+    pub value: Vec<u8>,
+}
+
+impl Header {
+    pub fn new<K, V>(key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<Vec<u8>>,
+    {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl Encoder for Header {
+    fn write_size(&self, _version: Version) -> usize {
+        let key_len = self.key.len() as i64;
+        let value_len = self.value.len() as i64;
+        key_len.var_write_size() + self.key.len() + value_len.var_write_size() + self.value.len()
+    }
+
+    fn encode<T>(&self, dest: &mut T, _version: Version) -> Result<(), Error>
+    where
+        T: BufMut,
+    {
+        let key_len = self.key.len() as i64;
+        key_len.encode_varint(dest)?;
+        dest.put_slice(self.key.as_bytes());
+        let value_len = self.value.len() as i64;
+        value_len.encode_varint(dest)?;
+        dest.put_slice(&self.value);
+        Ok(())
+    }
 }
 
-#[derive(Default)]
+impl Decoder for Header {
+    fn decode<T>(&mut self, src: &mut T, _version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        let mut key: Vec<u8> = vec![];
+        key.decode_varint(src)?;
+        self.key = String::from_utf8(key).map_err(|err| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("record header key is not utf8: {}", err),
+            )
+        })?;
+
+        // null value is allowed by kafka, treat it as empty
+        let mut value: Option<Vec<u8>> = None;
+        value.decode_varint(src)?;
+        self.value = value.unwrap_or_default();
+        Ok(())
+    }
+}
+
+#[derive(Default, Clone)]
 pub struct Record<B>
 where
     B: Default,
@@ -275,7 +346,7 @@ where
     pub preamble: RecordHeader,
     pub key: B,
     pub value: B,
-    pub headers: i64,
+    pub headers: Vec<Header>,
 }
 
 impl<B> Record<B>
@@ -293,6 +364,26 @@ where
     pub fn value(self) -> B {
         self.value
     }
+
+    pub fn get_key(&self) -> &B {
+        &self.key
+    }
+
+    pub fn get_headers(&self) -> &[Header] {
+        &self.headers
+    }
+
+    /// find value of first header with key
+    pub fn header(&self, key: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|header| header.key == key)
+            .map(|header| header.value.as_slice())
+    }
+
+    pub fn add_header(&mut self, header: Header) {
+        self.headers.push(header);
+    }
 }
 
 impl<B> Debug for Record<B>
@@ -345,10 +436,16 @@ where
     B: Encoder + Default,
 {
     fn write_size(&self, version: Version) -> usize {
+        let header_count = self.headers.len() as i64;
         let inner_size = self.preamble.write_size(version)
             + self.key.write_size(version)
             + self.value.write_size(version)
-            + self.headers.var_write_size();
+            + header_count.var_write_size()
+            + self
+                .headers
+                .iter()
+                .map(|header| header.write_size(version))
+                .sum::<usize>();
         let len: i64 = inner_size as i64;
         len.var_write_size() + inner_size
     }
@@ -361,7 +458,11 @@ where
         self.preamble.encode(&mut out, version)?;
         self.key.encode(&mut out, version)?;
         self.value.encode(&mut out, version)?;
-        self.headers.encode_varint(&mut out)?;
+        let header_count = self.headers.len() as i64;
+        header_count.encode_varint(&mut out)?;
+        for header in &self.headers {
+            header.encode(&mut out, version)?;
+        }
         let len: i64 = out.len() as i64;
         trace!("record encode as {} bytes", len);
         len.encode_varint(dest)?;
@@ -394,7 +495,16 @@ where
         trace!("offset delta: {}", self.preamble.offset_delta);
         self.key.decode(src, version)?;
         self.value.decode(src, version)?;
-        self.headers.decode_varint(src)?;
+
+        let mut header_count: i64 = 0;
+        header_count.decode_varint(src)?;
+        trace!("record headers: {}", header_count);
+        self.headers.clear();
+        for _ in 0..header_count.max(0) {
+            let mut header = Header::default();
+            header.decode(src, version)?;
+            self.headers.push(header);
+        }
 
         Ok(())
     }
@@ -437,6 +547,53 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_decode_encode_record_headers() -> Result<(), IoError> {
+        use super::Header;
+
+        let data = [
+            0x22, // record length of 17
+            0x00, // attributes
+            0xea, 0x0e, // timestamp
+            0x02, // offset delta, 1
+            0x01, // key
+            0x06, 0x64, 0x6f, 0x67, // value, 3 bytes len (dog)
+            0x02, // 1 header
+            0x04, 0x69, 0x64, // header key, 2 bytes len (id)
+            0x06, 0x61, 0x62, 0x63, // header value, 3 bytes len (abc)
+        ];
+
+        let record = DefaultRecord::decode_from(&mut Cursor::new(&data), 0)?;
+        assert_eq!(record.get_headers(), &[Header::new("id", "abc")]);
+        assert_eq!(record.header("id"), Some(&b"abc"[..]));
+        assert_eq!(record.header("trace"), None);
+
+        assert_eq!(record.write_size(0), data.len());
+        assert_eq!(record.as_bytes(0)?.as_ref(), &data[..]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_null_header_value() -> Result<(), IoError> {
+        let data = [
+            0x14, // record length of 10
+            0x00, // attributes
+            0x00, // timestamp
+            0x00, // offset delta
+            0x01, // key
+            0x01, // value
+            0x02, // 1 header
+            0x04, 0x69, 0x64, // header key, 2 bytes len (id)
+            0x01, // null header value
+        ];
+
+        let record = DefaultRecord::decode_from(&mut Cursor::new(&data), 0)?;
+        assert_eq!(record.get_headers().len(), 1);
+        assert_eq!(record.header("id"), Some(&b""[..]));
+        Ok(())
+    }
+
     /// test decoding of records when one of the batch was truncated
     #[test]
     fn test_decode_batch_truncation() {
@@ -447,7 +604,7 @@ mod test {
         fn create_batch() -> DefaultBatch {
             let record: DefaultRecord = vec![0x74, 0x65, 0x73, 0x74].into();
             let mut batch = DefaultBatch::default();
-            batch.records.push(record);
+            batch.records_mut().push(record);
             batch
         }
 
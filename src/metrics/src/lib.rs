@@ -0,0 +1,14 @@
+//!
+//! # Fluvio Metrics
+//!
+//! Counters, gauges and histograms registered in a `Registry`
+//! and exported over HTTP in Prometheus text format.
+//!
+mod metric;
+mod registry;
+mod server;
+
+pub use metric::{Counter, Gauge, GaugeGuard, Histogram, Family, Metric, MetricType};
+pub use metric::DEFAULT_BUCKETS;
+pub use registry::Registry;
+pub use server::start_metrics_server;
@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// buckets in seconds, same as Prometheus client defaults
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+impl std::fmt::Display for MetricType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// metric which can be written in Prometheus text format
+pub trait Metric: Send + Sync {
+    fn metric_type(&self) -> MetricType;
+
+    /// write samples of metric, labels are already formatted as `name="value",..`
+    fn encode(&self, name: &str, labels: &str, out: &mut String);
+}
+
+/// monotonically increasing value
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Metric for Counter {
+    fn metric_type(&self) -> MetricType {
+        MetricType::Counter
+    }
+
+    fn encode(&self, name: &str, labels: &str, out: &mut String) {
+        write_sample(out, name, labels, self.get());
+    }
+}
+
+/// value which can go up and down
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// increment now and decrement when guard is dropped,
+    /// useful to track number of active connections
+    pub fn track(self: &Arc<Self>) -> GaugeGuard {
+        self.inc();
+        GaugeGuard(self.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct GaugeGuard(Arc<Gauge>);
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+impl Metric for Gauge {
+    fn metric_type(&self) -> MetricType {
+        MetricType::Gauge
+    }
+
+    fn encode(&self, name: &str, labels: &str, out: &mut String) {
+        write_sample(out, name, labels, self.get());
+    }
+}
+
+/// distribution of observed values in buckets
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// bits of f64 sum
+    sum: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUCKETS)
+    }
+}
+
+impl Histogram {
+    /// bounds must be sorted
+    pub fn new(bounds: &[f64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        if let Some(index) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        let mut current = self.sum.load(Ordering::Relaxed);
+        loop {
+            let new = (f64::from_bits(current) + value).to_bits();
+            match self
+                .sum
+                .compare_exchange_weak(current, new, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// observe duration in seconds
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum.load(Ordering::Relaxed))
+    }
+}
+
+impl Metric for Histogram {
+    fn metric_type(&self) -> MetricType {
+        MetricType::Histogram
+    }
+
+    fn encode(&self, name: &str, labels: &str, out: &mut String) {
+        let bucket_name = format!("{}_bucket", name);
+        let separator = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, bucket) in self.bounds.iter().zip(self.buckets.iter()) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let bucket_labels = format!("{}{}le=\"{}\"", labels, separator, bound);
+            write_sample(out, &bucket_name, &bucket_labels, cumulative);
+        }
+        let count = self.count();
+        let inf_labels = format!("{}{}le=\"+Inf\"", labels, separator);
+        write_sample(out, &bucket_name, &inf_labels, count);
+        write_sample(out, &format!("{}_sum", name), labels, self.sum());
+        write_sample(out, &format!("{}_count", name), labels, count);
+    }
+}
+
+/// metrics of same name distinguished by label values
+pub struct Family<M> {
+    label_names: &'static [&'static str],
+    metric_type: MetricType,
+    metrics: RwLock<BTreeMap<Vec<String>, Arc<M>>>,
+}
+
+impl<M> std::fmt::Debug for Family<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Family({:?})", self.label_names)
+    }
+}
+
+impl<M> Family<M>
+where
+    M: Metric + Default,
+{
+    pub fn new(label_names: &'static [&'static str]) -> Self {
+        Self {
+            label_names,
+            metric_type: M::default().metric_type(),
+            metrics: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// metric for label values, created if not exists
+    pub fn with_labels(&self, values: &[&str]) -> Arc<M> {
+        let key: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        if let Some(metric) = self.metrics.read().unwrap().get(&key) {
+            return metric.clone();
+        }
+        self.metrics
+            .write()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(M::default()))
+            .clone()
+    }
+
+    /// stop exporting metric for label values
+    pub fn remove(&self, values: &[&str]) {
+        let key: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        self.metrics.write().unwrap().remove(&key);
+    }
+}
+
+impl<M> Metric for Family<M>
+where
+    M: Metric,
+{
+    fn metric_type(&self) -> MetricType {
+        self.metric_type
+    }
+
+    fn encode(&self, name: &str, labels: &str, out: &mut String) {
+        for (values, metric) in self.metrics.read().unwrap().iter() {
+            let mut all_labels = labels.to_owned();
+            for (label, value) in self.label_names.iter().zip(values.iter()) {
+                if !all_labels.is_empty() {
+                    all_labels.push(',');
+                }
+                let _ = write!(all_labels, "{}=\"{}\"", label, escape_label(value));
+            }
+            metric.encode(name, &all_labels, out);
+        }
+    }
+}
+
+fn write_sample<V: std::fmt::Display>(out: &mut String, name: &str, labels: &str, value: V) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{} {}", name, value);
+    } else {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_histogram_encode() {
+        let histogram = Histogram::new(&[0.1, 1.0]);
+        histogram.observe(0.05);
+        histogram.observe(0.5);
+        histogram.observe(2.0);
+
+        let mut out = String::new();
+        histogram.encode("latency", "", &mut out);
+        assert_eq!(
+            out,
+            "latency_bucket{le=\"0.1\"} 1\n\
+             latency_bucket{le=\"1\"} 2\n\
+             latency_bucket{le=\"+Inf\"} 3\n\
+             latency_sum 2.55\n\
+             latency_count 3\n"
+        );
+    }
+
+    #[test]
+    fn test_family_encode() {
+        let family: Family<Gauge> = Family::new(&["topic", "partition"]);
+        family.with_labels(&["test", "0"]).set(10);
+        family.with_labels(&["test", "1"]).set(5);
+        family.with_labels(&["test", "0"]).inc();
+        family.remove(&["test", "1"]);
+
+        let mut out = String::new();
+        family.encode("leo", "", &mut out);
+        assert_eq!(out, "leo{topic=\"test\",partition=\"0\"} 11\n");
+    }
+}
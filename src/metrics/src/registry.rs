@@ -0,0 +1,73 @@
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use crate::metric::Metric;
+
+struct Entry {
+    name: String,
+    help: String,
+    metric: Arc<dyn Metric>,
+}
+
+/// collection of metrics exported together
+#[derive(Default)]
+pub struct Registry {
+    entries: RwLock<Vec<Entry>>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self.entries.read().unwrap();
+        f.debug_list()
+            .entries(entries.iter().map(|entry| &entry.name))
+            .finish()
+    }
+}
+
+impl Registry {
+    /// add metric under name, returned handle is used to update it
+    pub fn register<M>(&self, name: &str, help: &str, metric: M) -> Arc<M>
+    where
+        M: Metric + 'static,
+    {
+        let metric = Arc::new(metric);
+        self.entries.write().unwrap().push(Entry {
+            name: name.to_owned(),
+            help: help.to_owned(),
+            metric: metric.clone(),
+        });
+        metric
+    }
+
+    /// all metrics in Prometheus text format
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        for entry in self.entries.read().unwrap().iter() {
+            let _ = writeln!(out, "# HELP {} {}", entry.name, entry.help);
+            let _ = writeln!(out, "# TYPE {} {}", entry.name, entry.metric.metric_type());
+            entry.metric.encode(&entry.name, "", &mut out);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::Counter;
+    use super::Registry;
+
+    #[test]
+    fn test_registry_encode() {
+        let registry = Registry::default();
+        let requests = registry.register("requests_total", "Requests", Counter::default());
+        requests.inc_by(3);
+
+        assert_eq!(
+            registry.encode(),
+            "# HELP requests_total Requests\n# TYPE requests_total counter\nrequests_total 3\n"
+        );
+    }
+}
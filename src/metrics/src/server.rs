@@ -0,0 +1,101 @@
+use std::io::Error as IoError;
+use std::sync::Arc;
+
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+use futures_util::stream::StreamExt;
+use http_types::{Method, Request, Response, StatusCode};
+
+use fluvio_future::net::TcpListener;
+use fluvio_future::task::spawn;
+
+use crate::Registry;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// serve metrics of registry at `/metrics` on addr
+pub fn start_metrics_server(addr: String, registry: Arc<Registry>) {
+    spawn(async move {
+        if let Err(err) = run(&addr, registry).await {
+            error!("metrics server at: {} failed: {}", addr, err);
+        }
+    });
+}
+
+async fn run(addr: &str, registry: Arc<Registry>) -> Result<(), IoError> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("metrics server started at: {}", addr);
+
+    let mut incoming = listener.incoming();
+    while let Some(stream) = incoming.next().await {
+        let stream = stream?;
+        let registry = registry.clone();
+        spawn(async move {
+            if let Err(err) =
+                async_h1::accept(stream, |request| respond(request, registry.clone())).await
+            {
+                debug!("metrics connection error: {}", err);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn respond(request: Request, registry: Arc<Registry>) -> http_types::Result<Response> {
+    if request.method() != Method::Get || request.url().path() != "/metrics" {
+        return Ok(Response::new(StatusCode::NotFound));
+    }
+
+    let mut response = Response::new(StatusCode::Ok);
+    response.insert_header("Content-Type", CONTENT_TYPE);
+    response.set_body(registry.encode());
+    Ok(response)
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use http_types::{Method, Request, StatusCode, Url};
+
+    use fluvio_future::net::TcpStream;
+    use fluvio_future::test_async;
+    use fluvio_future::timer::sleep;
+
+    use crate::{Counter, Registry};
+    use super::start_metrics_server;
+
+    const ADDR: &str = "127.0.0.1:9290";
+
+    async fn get(path: &str) -> (StatusCode, String) {
+        let stream = TcpStream::connect(ADDR).await.expect("connect");
+        let url = Url::parse(&format!("http://{}{}", ADDR, path)).expect("url");
+        let mut response = async_h1::connect(stream, Request::new(Method::Get, url))
+            .await
+            .expect("response");
+        let body = response.body_string().await.expect("body");
+        (response.status(), body)
+    }
+
+    #[test_async]
+    async fn test_metrics_endpoint() -> Result<(), ()> {
+        let registry = Arc::new(Registry::default());
+        let counter = registry.register("requests_total", "Requests", Counter::default());
+        counter.inc();
+        start_metrics_server(ADDR.to_owned(), registry);
+        sleep(Duration::from_millis(100)).await;
+
+        let (status, body) = get("/metrics").await;
+        assert_eq!(status, StatusCode::Ok);
+        assert!(body.contains("requests_total 1\n"));
+
+        let (status, _) = get("/other").await;
+        assert_eq!(status, StatusCode::NotFound);
+
+        Ok(())
+    }
+}
@@ -18,6 +18,9 @@ pub enum AdminPublicApiKey {
     Delete = 1002,
     List = 1003,
     Watch = 1004,
+    Update = 1005,
+    ReassignPartitions = 1006,
+    ElectLeaders = 1007,
 }
 
 impl Default for AdminPublicApiKey {
@@ -0,0 +1,116 @@
+//!
+//! # Update object
+//!
+//! Change spec of existing object
+//!
+
+use std::io::Error;
+use std::io::ErrorKind;
+
+use tracing::trace;
+
+use dataplane::core::Encoder;
+use dataplane::core::Decoder;
+use dataplane::core::Version;
+use dataplane::bytes::{Buf, BufMut};
+use dataplane::derive::{Decode, Encode};
+use dataplane::api::Request;
+use fluvio_controlplane_metadata::topic::TopicSpec;
+use fluvio_controlplane_metadata::core::Spec;
+use fluvio_types::PartitionCount;
+
+use crate::Status;
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct UpdateRequest {
+    pub name: String,
+    pub spec: AllUpdatableSpec,
+}
+
+impl AdminRequest for UpdateRequest {}
+
+impl Request for UpdateRequest {
+    const API_KEY: u16 = AdminPublicApiKey::Update as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = Status;
+}
+
+/// Changes to topic.  Partitions can only be added, never removed.
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+pub struct TopicUpdate {
+    /// new partition count, must be greater than current one
+    pub partitions: PartitionCount,
+}
+
+/// enum of changes that can be applied to existing object
+#[derive(Debug)]
+pub enum AllUpdatableSpec {
+    Topic(TopicUpdate),
+}
+
+impl Default for AllUpdatableSpec {
+    fn default() -> Self {
+        Self::Topic(TopicUpdate::default())
+    }
+}
+
+impl AllUpdatableSpec {
+    /// type represent as string
+    fn type_string(&self) -> &'static str {
+        match self {
+            Self::Topic(_) => TopicSpec::LABEL,
+        }
+    }
+}
+
+impl Encoder for AllUpdatableSpec {
+    fn write_size(&self, version: Version) -> usize {
+        let type_size = self.type_string().to_owned().write_size(version);
+
+        type_size
+            + match self {
+                Self::Topic(s) => s.write_size(version),
+            }
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: BufMut,
+    {
+        self.type_string().to_owned().encode(dest, version)?;
+
+        match self {
+            Self::Topic(s) => s.encode(dest, version)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Decoder for AllUpdatableSpec {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        let mut typ = "".to_owned();
+        typ.decode(src, version)?;
+        trace!("decoded type: {}", typ);
+
+        match typ.as_ref() {
+            TopicSpec::LABEL => {
+                let mut response = TopicUpdate::default();
+                response.decode(src, version)?;
+                *self = Self::Topic(response);
+                Ok(())
+            }
+
+            // Unexpected type
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid spec type {}", typ),
+            )),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Elect Leaders
+//!
+//! Move leadership of partitions back to preferred replicas.
+//!
+
+use dataplane::derive::{Decode, Encode};
+use dataplane::api::Request;
+use dataplane::ErrorCode;
+use dataplane::ReplicaKey;
+use fluvio_types::SpuId;
+
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct ElectLeadersRequest {
+    /// partitions to elect preferred leader for, all partitions if empty
+    pub partitions: Vec<ReplicaKey>,
+}
+
+impl Request for ElectLeadersRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ElectLeaders as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ElectLeadersResponse;
+}
+
+impl AdminRequest for ElectLeadersRequest {}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct ElectLeadersResponse {
+    pub results: Vec<LeaderElectionResult>,
+}
+
+/// Result of election for single partition
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+pub struct LeaderElectionResult {
+    pub partition: ReplicaKey,
+    /// leader after election
+    pub leader: SpuId,
+    pub error_code: ErrorCode,
+    pub error_message: Option<String>,
+}
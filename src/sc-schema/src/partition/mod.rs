@@ -1,4 +1,9 @@
 pub use fluvio_controlplane_metadata::partition::*;
+pub use reassign::*;
+pub use elect::*;
+
+mod reassign;
+mod elect;
 
 mod convert {
 
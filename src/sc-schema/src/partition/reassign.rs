@@ -0,0 +1,59 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Reassign Partitions
+//!
+//! Move replicas of partitions to other SPUs.
+//!
+
+use dataplane::derive::{Decode, Encode};
+use dataplane::api::Request;
+use dataplane::ErrorCode;
+use fluvio_types::SpuId;
+
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+/// Target replicas of single partition, first replica is preferred leader
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitionAssignment {
+    pub topic: String,
+    pub partition: i32,
+    pub replicas: Vec<SpuId>,
+}
+
+/// Set of partition moves
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReassignmentPlan {
+    pub partitions: Vec<PartitionAssignment>,
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct ReassignPartitionsRequest {
+    /// topics to generate balanced plan for, only used if plan is empty
+    pub topics: Vec<String>,
+    /// partition moves to execute
+    pub plan: ReassignmentPlan,
+    /// only generate or validate plan, partitions are not moved
+    pub dry_run: bool,
+    /// max bytes per second copied to new replicas, 0 is unlimited
+    pub throttle: u32,
+}
+
+impl Request for ReassignPartitionsRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ReassignPartitions as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ReassignPartitionsResponse;
+}
+
+impl AdminRequest for ReassignPartitionsRequest {}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct ReassignPartitionsResponse {
+    pub error_code: ErrorCode,
+    pub error_message: Option<String>,
+    /// plan that has been generated or executed
+    pub plan: ReassignmentPlan,
+}
@@ -19,6 +19,8 @@ use dataplane::derive::{Encode};
 
 use super::versions::ApiVersionsRequest;
 use super::objects::*;
+use super::partition::ReassignPartitionsRequest;
+use super::partition::ElectLeadersRequest;
 use super::AdminPublicApiKey;
 
 #[derive(Debug, Encode)]
@@ -29,6 +31,9 @@ pub enum AdminPublicRequest {
     DeleteRequest(RequestMessage<DeleteRequest>),
     ListRequest(RequestMessage<ListRequest>),
     WatchRequest(RequestMessage<WatchRequest>),
+    UpdateRequest(RequestMessage<UpdateRequest>),
+    ReassignPartitionsRequest(RequestMessage<ReassignPartitionsRequest>),
+    ElectLeadersRequest(RequestMessage<ElectLeadersRequest>),
 }
 
 impl Default for AdminPublicRequest {
@@ -59,6 +64,13 @@ impl ApiMessage for AdminPublicRequest {
             AdminPublicApiKey::Delete => api_decode!(Self, DeleteRequest, src, header),
             AdminPublicApiKey::List => api_decode!(Self, ListRequest, src, header),
             AdminPublicApiKey::Watch => api_decode!(Self, WatchRequest, src, header),
+            AdminPublicApiKey::Update => api_decode!(Self, UpdateRequest, src, header),
+            AdminPublicApiKey::ReassignPartitions => {
+                api_decode!(Self, ReassignPartitionsRequest, src, header)
+            }
+            AdminPublicApiKey::ElectLeaders => {
+                api_decode!(Self, ElectLeadersRequest, src, header)
+            }
         }
     }
 }
@@ -15,6 +15,12 @@ mod convert {
         }
     }
 
+    impl From<TopicUpdate> for AllUpdatableSpec {
+        fn from(update: TopicUpdate) -> Self {
+            Self::Topic(update)
+        }
+    }
+
     impl DeleteSpec for TopicSpec {
         fn into_request<K>(key: K) -> DeleteRequest
         where
@@ -1,7 +1,7 @@
 use structopt::StructOpt;
 
 use fluvio_sc::cli::ScOpt;
-use fluvio_sc::k8::main_k8_loop as main_loop;
+use fluvio_sc::main_loop;
 
 fn main() {
     fluvio_future::subscriber::init_tracer(None);
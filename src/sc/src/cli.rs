@@ -21,13 +21,15 @@ use fluvio_types::print_cli_err;
 use k8_client::K8Config;
 use fluvio_future::rust_tls::TlsAcceptor;
 use fluvio_future::rust_tls::AcceptorBuilder;
+use fluvio_auth::basic::BasicRbacPolicy;
 
-use crate::services::auth::basic::BasicRbacPolicy;
 use crate::error::ScError;
 use crate::config::ScConfig;
 
 type Config = (ScConfig, Option<BasicRbacPolicy>);
 
+const DEFAULT_NAMESPACE: &str = "default";
+
 /// cli options
 #[derive(Debug, StructOpt, Default)]
 #[structopt(name = "sc-server", about = "Streaming Controller")]
@@ -60,9 +62,44 @@ pub struct ScOpt {
         env
     )]
     auth_policy: Option<PathBuf>,
+
+    /// Keep metadata in local directory instead of Kubernetes
+    #[structopt(long = "metadata-dir", value_name = "metadata directory path", env)]
+    metadata_dir: Option<PathBuf>,
+
+    /// Address of prometheus metrics endpoint, disabled if not specified
+    #[structopt(
+        long = "metrics-addr",
+        value_name = "host:port",
+        env = "FLV_METRICS_ADDR"
+    )]
+    metrics_addr: Option<String>,
+
+    /// Move leaders back to preferred replicas when percentage of partitions
+    /// not led by preferred spu is above threshold, disabled if not specified
+    #[structopt(
+        long = "leader-imbalance-threshold",
+        value_name = "percent",
+        env = "FLV_LEADER_IMBALANCE_THRESHOLD",
+        parse(try_from_str = parse_percent)
+    )]
+    leader_imbalance_threshold: Option<u8>,
+}
+
+/// percentage from 0 to 100
+fn parse_percent(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(percent) if percent <= 100 => Ok(percent),
+        _ => Err(format!("invalid percent: {}, must be 0 to 100", value)),
+    }
 }
 
 impl ScOpt {
+    /// directory of local metadata, Kubernetes is used if not specified
+    pub fn metadata_dir(&self) -> Option<PathBuf> {
+        self.metadata_dir.clone()
+    }
+
     #[allow(clippy::type_complexity)]
     fn get_sc_and_k8_config(
         mut self,
@@ -97,6 +134,8 @@ impl ScOpt {
 
         config.namespace = self.namespace.unwrap();
         config.x509_auth_scopes = self.x509_auth_scopes;
+        config.metrics_endpoint = self.metrics_addr;
+        config.leader_imbalance_threshold = self.leader_imbalance_threshold;
 
         // Set Configuration Authorzation Policy
         let policy = match self.auth_policy {
@@ -126,6 +165,21 @@ impl ScOpt {
         }
     }
 
+    /// sc configuration without Kubernetes, namespace defaults to "default"
+    pub fn parse_local_cli_or_exit(mut self) -> (Config, Option<(String, TlsConfig)>) {
+        if self.namespace.is_none() {
+            self.namespace = Some(DEFAULT_NAMESPACE.to_owned());
+        }
+
+        match self.as_sc_config() {
+            Err(err) => {
+                print_cli_err!(err);
+                process::exit(-1);
+            }
+            Ok(config) => config,
+        }
+    }
+
     pub fn parse_cli_or_exit(self) -> (Config, K8Config, Option<(String, TlsConfig)>) {
         match self.get_sc_and_k8_config() {
             Err(err) => {
@@ -193,3 +247,25 @@ impl TlsConfig {
         Ok(builder.build())
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use structopt::StructOpt;
+
+    use super::ScOpt;
+
+    #[test]
+    fn test_leader_imbalance_threshold() {
+        let opt = ScOpt::from_iter_safe(&["sc-server", "--leader-imbalance-threshold", "100"])
+            .expect("parse");
+        assert_eq!(opt.leader_imbalance_threshold, Some(100));
+
+        assert!(
+            ScOpt::from_iter_safe(&["sc-server", "--leader-imbalance-threshold", "101"]).is_err()
+        );
+        assert!(
+            ScOpt::from_iter_safe(&["sc-server", "--leader-imbalance-threshold", "-1"]).is_err()
+        );
+    }
+}
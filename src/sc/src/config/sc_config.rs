@@ -24,6 +24,10 @@ pub struct ScConfig {
     pub run_k8_dispatchers: bool,
     pub namespace: String,
     pub x509_auth_scopes: Option<PathBuf>,
+    pub metrics_endpoint: Option<String>,
+    /// percentage of partitions not led by preferred spu above which leaders are moved back,
+    /// leaders are not rebalanced if not set
+    pub leader_imbalance_threshold: Option<u8>,
 }
 
 impl ::std::default::Default for ScConfig {
@@ -34,6 +38,8 @@ impl ::std::default::Default for ScConfig {
             run_k8_dispatchers: true,
             namespace: "default".to_owned(),
             x509_auth_scopes: None,
+            metrics_endpoint: None,
+            leader_imbalance_threshold: None,
         }
     }
 }
@@ -2,18 +2,22 @@
 //! # Auth Controller
 //!
 
+use std::sync::Arc;
+use std::time::Instant;
+
 use tracing::debug;
 
 use fluvio_future::task::spawn;
 
 use crate::core::SharedContext;
+use crate::core::ScMetrics;
 use crate::stores::*;
 use crate::stores::partition::*;
 use crate::stores::spu::*;
 
 use super::reducer::*;
 
-/// Handles Partition election
+/// Handles Partition election and reassignment
 #[derive(Debug)]
 pub struct PartitionController {
     partitions: StoreContext<PartitionSpec>,
@@ -21,6 +25,7 @@ pub struct PartitionController {
     spus: StoreContext<SpuSpec>,
     spu_epoch: Epoch,
     reducer: PartitionReducer,
+    metrics: Arc<ScMetrics>,
 }
 
 impl PartitionController {
@@ -39,6 +44,7 @@ impl PartitionController {
                 ctx.partitions().store().clone(),
                 ctx.spus().store().clone(),
             ),
+            metrics: ctx.metrics_owned(),
         };
 
         spawn(controller.dispatch_loop());
@@ -48,12 +54,19 @@ impl PartitionController {
         use tokio::select;
 
         self.sync_spu_changes().await;
+        self.sync_partition_changes().await;
 
         loop {
             select! {
 
                 _ = self.spus.status_listen() => {
                     self.sync_spu_changes().await;
+                },
+                _ = self.partitions.spec_listen() => {
+                    self.sync_partition_changes().await;
+                },
+                _ = self.partitions.status_listen() => {
+                    self.sync_partition_changes().await;
                 }
             }
         }
@@ -64,8 +77,9 @@ impl PartitionController {
     /// sync spu states to partition
     /// check to make sure
     async fn sync_spu_changes(&mut self) {
+        let start = Instant::now();
         let read_guard = self.spus.store().read().await;
-        let changes = read_guard.changes_since(self.partition_epoch);
+        let changes = read_guard.changes_since(self.spu_epoch);
         drop(read_guard);
         self.spu_epoch = changes.epoch;
         let (updates, deletes) = changes.parts();
@@ -85,5 +99,29 @@ impl PartitionController {
         for action in actions.into_iter() {
             self.partitions.send_action(action).await;
         }
+
+        self.metrics
+            .observe_reconciliation("partition", start.elapsed());
+    }
+
+    /// move partitions which are being reassigned
+    async fn sync_partition_changes(&mut self) {
+        let read_guard = self.partitions.store().read().await;
+        let changes = read_guard.changes_since(self.partition_epoch);
+        drop(read_guard);
+        self.partition_epoch = changes.epoch;
+        let (updates, _) = changes.parts();
+        debug!(
+            "received partition epoch: {}, updates: {}",
+            self.partition_epoch,
+            updates.len()
+        );
+
+        let actions = self.reducer.update_reassignments(updates);
+
+        debug!("there were reassignment actions: {}", actions.len());
+        for action in actions.into_iter() {
+            self.partitions.send_action(action).await;
+        }
     }
 }
@@ -1,7 +1,10 @@
 mod controller;
+mod rebalance;
 mod reducer;
 
 pub use self::controller::*;
+pub use self::rebalance::*;
+pub use self::reducer::{PreferredElection, preferred_leader_election};
 pub use common::*;
 
 mod common {
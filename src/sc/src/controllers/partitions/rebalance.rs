@@ -0,0 +1,66 @@
+//!
+//! # Leader Rebalance Controller
+//!
+//! Leadership moves away from spu when it goes offline and never comes back.
+//! This periodically moves leaders back to preferred replicas.
+//!
+
+use std::time::Duration;
+
+use tracing::debug;
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+
+use crate::core::SharedContext;
+use crate::stores::*;
+use crate::stores::partition::*;
+
+use super::reducer::*;
+
+/// how often leader imbalance is checked
+const LEADER_REBALANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Moves leaders back to preferred replicas when imbalance of spu is above threshold
+#[derive(Debug)]
+pub struct LeaderRebalanceController {
+    partitions: StoreContext<PartitionSpec>,
+    reducer: PartitionReducer,
+    threshold: u8,
+}
+
+impl LeaderRebalanceController {
+    pub fn start(ctx: SharedContext, threshold: u8) {
+        let controller = Self {
+            partitions: ctx.partitions().clone(),
+            reducer: PartitionReducer::new(
+                ctx.partitions().store().clone(),
+                ctx.spus().store().clone(),
+            ),
+            threshold,
+        };
+
+        spawn(controller.dispatch_loop());
+    }
+
+    async fn dispatch_loop(self) {
+        debug!(
+            "starting leader rebalance controller, threshold: {}%",
+            self.threshold
+        );
+
+        loop {
+            sleep(LEADER_REBALANCE_INTERVAL).await;
+            self.rebalance().await;
+        }
+    }
+
+    async fn rebalance(&self) {
+        let actions = self.reducer.rebalance_leaders(self.threshold).await;
+
+        debug!("there were leader rebalance actions: {}", actions.len());
+        for action in actions.into_iter() {
+            self.partitions.send_action(action).await;
+        }
+    }
+}
@@ -3,11 +3,14 @@
 //!
 //! Partition metadata information on cached in the local Controller.
 //!
+use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use tracing::debug;
 use tracing::warn;
 
+use fluvio_types::SpuId;
 use fluvio_controlplane_metadata::partition::*;
 
 use crate::stores::partition::*;
@@ -144,6 +147,7 @@ impl PartitionReducer {
                     warn!("no suitable leader has found: {}", partition_kv.key());
                     let mut part_kv_change = partition_kv.clone();
                     part_kv_change.status.resolution = PartitionResolution::LeaderOffline;
+                    part_kv_change.status.in_sync_replicas.clear();
                     actions.push(PartitionWSAction::UpdateStatus((
                         part_kv_change.key_owned(),
                         part_kv_change.status,
@@ -198,6 +202,218 @@ impl PartitionReducer {
     }
 }
 
+impl PartitionReducer {
+    ///
+    /// move partitions which are being reassigned to next step
+    ///
+    pub fn update_reassignments(
+        &self,
+        partitions: Vec<PartitionAdminMd>,
+    ) -> Vec<PartitionWSAction> {
+        let mut actions = vec![];
+        for partition in partitions.iter() {
+            next_reassignment_step(partition, &mut actions);
+        }
+        actions
+    }
+}
+
+impl PartitionReducer {
+    ///
+    /// move leaders back to preferred replicas of spus whose leadership imbalance
+    /// is above threshold
+    ///
+    pub async fn rebalance_leaders(&self, threshold: u8) -> Vec<PartitionWSAction> {
+        let online = self.spu_store.online_status().await;
+        let partitions: Vec<PartitionAdminMd> = self
+            .partition_store
+            .read()
+            .await
+            .values()
+            .map(|partition| partition.inner().clone())
+            .collect();
+
+        rebalance_leaders(&partitions, &online, threshold)
+    }
+}
+
+/// Outcome of moving leadership to preferred replica
+#[derive(Debug, PartialEq)]
+pub enum PreferredElection {
+    /// leader can be moved to preferred replica
+    Elect(SpuId),
+    /// preferred replica is already leader
+    NotNeeded,
+    /// preferred replica can't become leader now
+    NotReady(String),
+}
+
+/// Preferred replica can take over leadership only if it is online and in sync with current leader
+pub fn preferred_leader_election(
+    partition: &PartitionAdminMd,
+    online: &HashSet<SpuId>,
+) -> PreferredElection {
+    let preferred = match partition.spec.preferred_leader() {
+        Some(preferred) => preferred,
+        None if partition.spec.is_reassigning() => {
+            return PreferredElection::NotReady("partition is being reassigned".to_owned())
+        }
+        None => return PreferredElection::NotReady("partition has no replicas".to_owned()),
+    };
+
+    if preferred == partition.spec.leader {
+        return PreferredElection::NotNeeded;
+    }
+
+    if !online.contains(&preferred) {
+        return PreferredElection::NotReady(format!("preferred leader: {} is offline", preferred));
+    }
+
+    if partition.status.is_offline() || partition.status.leader.spu != partition.spec.leader {
+        return PreferredElection::NotReady("current leader is not online".to_owned());
+    }
+
+    if !partition.status.is_caught_up(preferred) {
+        return PreferredElection::NotReady(format!(
+            "preferred leader: {} is not in sync",
+            preferred
+        ));
+    }
+
+    PreferredElection::Elect(preferred)
+}
+
+/// Imbalance of spu is percentage of partitions preferring spu as leader which are led by other spu.
+/// For spus above threshold, leadership is moved back to them where possible.
+fn rebalance_leaders(
+    partitions: &[PartitionAdminMd],
+    online: &HashSet<SpuId>,
+    threshold: u8,
+) -> Vec<PartitionWSAction> {
+    let mut preferred_by_spu: BTreeMap<SpuId, Vec<&PartitionAdminMd>> = BTreeMap::new();
+    for partition in partitions {
+        if let Some(preferred) = partition.spec.preferred_leader() {
+            preferred_by_spu
+                .entry(preferred)
+                .or_default()
+                .push(partition);
+        }
+    }
+
+    let mut actions = vec![];
+    for (spu, preferred) in preferred_by_spu {
+        let not_led: Vec<&PartitionAdminMd> = preferred
+            .iter()
+            .filter(|partition| partition.spec.leader != spu)
+            .copied()
+            .collect();
+        let imbalance = not_led.len() * 100 / preferred.len();
+        if imbalance <= threshold as usize {
+            continue;
+        }
+
+        debug!(
+            "spu: {} leader imbalance: {}% is above threshold: {}%",
+            spu, imbalance, threshold
+        );
+        for partition in not_led {
+            if let PreferredElection::Elect(leader) = preferred_leader_election(partition, online) {
+                debug!(
+                    "partition: {} moving leader back to: {}",
+                    partition.key(),
+                    leader
+                );
+                let mut spec = partition.spec.clone();
+                spec.leader = leader;
+                actions.push(PartitionWSAction::UpdateSpec((partition.key_owned(), spec)));
+            }
+        }
+    }
+
+    actions
+}
+
+/// Reassignment is done in steps, each step is taken when partition is changed:
+/// 1. target replicas are added to replicas, so new followers start copying from leader
+/// 2. once all target replicas have caught up, leader is moved to first target replica
+///    if current leader is not one of target replicas
+/// 3. once leader is target replica, old replicas are dropped and reassignment is done
+fn next_reassignment_step(partition: &PartitionAdminMd, actions: &mut Vec<PartitionWSAction>) {
+    let reassignment = match &partition.spec.reassignment {
+        Some(reassignment) => reassignment,
+        None => return,
+    };
+    let target_replicas = &reassignment.target_replicas;
+
+    let moving_replicas = reassignment.moving_replicas(&partition.spec.replicas);
+    if moving_replicas != partition.spec.replicas {
+        debug!(
+            "partition: {} adding replicas: {:?}",
+            partition.key(),
+            moving_replicas
+        );
+        let mut spec = partition.spec.clone();
+        spec.replicas = moving_replicas;
+        actions.push(PartitionWSAction::UpdateSpec((partition.key_owned(), spec)));
+        return;
+    }
+
+    // wait until leader has reported status
+    if partition.status.is_offline() || partition.status.leader.spu != partition.spec.leader {
+        debug!("partition: {} waiting for leader", partition.key());
+        return;
+    }
+
+    if !target_replicas
+        .iter()
+        .all(|spu| partition.status.is_caught_up(*spu))
+    {
+        debug!(
+            "partition: {} waiting for replicas to catch up",
+            partition.key()
+        );
+        return;
+    }
+
+    let mut spec = partition.spec.clone();
+    if !target_replicas.contains(&spec.leader) {
+        if let Some(leader) = target_replicas.first() {
+            debug!(
+                "partition: {} moving leader to: {}",
+                partition.key(),
+                leader
+            );
+            spec.leader = *leader;
+            actions.push(PartitionWSAction::UpdateSpec((partition.key_owned(), spec)));
+        }
+        return;
+    }
+
+    debug!(
+        "partition: {} reassignment done, replicas: {:?}",
+        partition.key(),
+        target_replicas
+    );
+    spec.replicas = target_replicas.clone();
+    spec.reassignment = None;
+    actions.push(PartitionWSAction::UpdateSpec((partition.key_owned(), spec)));
+
+    // forget status of dropped replicas, so they are not elected as leader
+    let mut status = partition.status.clone();
+    status
+        .replicas
+        .retain(|replica| target_replicas.contains(&replica.spu));
+    status
+        .in_sync_replicas
+        .retain(|spu| target_replicas.contains(spu));
+    if status != partition.status {
+        actions.push(PartitionWSAction::UpdateStatus((
+            partition.key_owned(),
+            status,
+        )));
+    }
+}
+
 struct SimplePolicy {}
 
 impl SimplePolicy {
@@ -230,6 +446,185 @@ impl ElectionPolicy for SimplePolicy {
 #[cfg(test)]
 pub mod test {
 
+    use fluvio_controlplane_metadata::partition::PartitionReassignment;
+
+    use super::*;
+
+    fn moving_partition(
+        spec: PartitionSpec,
+        status: PartitionStatus,
+        target_replicas: Vec<i32>,
+    ) -> PartitionAdminMd {
+        let mut spec = spec;
+        spec.reassignment = Some(PartitionReassignment::new(target_replicas, 0));
+        PartitionAdminMd::new(("topic1", 0), spec, status)
+    }
+
+    fn spec_of(action: &PartitionWSAction) -> &PartitionSpec {
+        match action {
+            PartitionWSAction::UpdateSpec((_, spec)) => spec,
+            _ => panic!("expected spec update: {}", action),
+        }
+    }
+
+    #[test]
+    fn test_reassignment_steps() {
+        let reducer = PartitionReducer::default();
+        let online = |leader: (i32, i64), replicas: Vec<(i32, i64)>| {
+            PartitionStatus::new2(
+                (leader.0, leader.1, leader.1),
+                replicas
+                    .into_iter()
+                    .map(|(spu, leo)| (spu, leo, leo).into())
+                    .collect(),
+                PartitionResolution::Online,
+            )
+        };
+
+        // move partition from [0, 1] to [2, 3]
+        // step 1: target replicas are added
+        let partition = moving_partition(
+            PartitionSpec::new(0, vec![0, 1]),
+            online((0, 10), vec![(1, 10)]),
+            vec![2, 3],
+        );
+        let actions = reducer.update_reassignments(vec![partition]);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(spec_of(&actions[0]).replicas, vec![0, 1, 2, 3]);
+
+        // new replicas are still catching up
+        let partition = moving_partition(
+            PartitionSpec::new(0, vec![0, 1, 2, 3]),
+            online((0, 10), vec![(1, 10), (2, 10), (3, 4)]),
+            vec![2, 3],
+        );
+        assert!(reducer.update_reassignments(vec![partition]).is_empty());
+
+        // step 2: all caught up, leader is moved
+        let partition = moving_partition(
+            PartitionSpec::new(0, vec![0, 1, 2, 3]),
+            online((0, 10), vec![(1, 10), (2, 10), (3, 10)]),
+            vec![2, 3],
+        );
+        let actions = reducer.update_reassignments(vec![partition]);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(spec_of(&actions[0]).leader, 2);
+
+        // new leader hasn't reported yet
+        let partition = moving_partition(
+            PartitionSpec::new(2, vec![0, 1, 2, 3]),
+            online((0, 10), vec![(1, 10), (2, 10), (3, 10)]),
+            vec![2, 3],
+        );
+        assert!(reducer.update_reassignments(vec![partition]).is_empty());
+
+        // step 3: old replicas are dropped
+        let partition = moving_partition(
+            PartitionSpec::new(2, vec![0, 1, 2, 3]),
+            online((2, 10), vec![(0, 10), (1, 10), (3, 10)]),
+            vec![2, 3],
+        );
+        let actions = reducer.update_reassignments(vec![partition]);
+        assert_eq!(actions.len(), 2);
+        let spec = spec_of(&actions[0]);
+        assert_eq!(spec.replicas, vec![2, 3]);
+        assert_eq!(spec.reassignment, None);
+        match &actions[1] {
+            PartitionWSAction::UpdateStatus((_, status)) => {
+                assert_eq!(status.live_replicas(), vec![3]);
+            }
+            _ => panic!("expected status update"),
+        }
+
+        // partitions without reassignment are ignored
+        let partition = PartitionAdminMd::new(
+            ("topic1", 1),
+            PartitionSpec::new(0, vec![0, 1]),
+            online((0, 10), vec![(1, 10)]),
+        );
+        assert!(reducer.update_reassignments(vec![partition]).is_empty());
+    }
+
+    #[test]
+    fn test_rebalance_leaders() {
+        let status = |leader: i32, replicas: Vec<(i32, i64)>| {
+            PartitionStatus::new2(
+                (leader, 10, 10),
+                replicas
+                    .into_iter()
+                    .map(|(spu, leo)| (spu, leo, leo).into())
+                    .collect(),
+                PartitionResolution::Online,
+            )
+        };
+        let online: HashSet<i32> = vec![0, 1].into_iter().collect();
+
+        // spu 0 prefers 4 partitions but leads only 1 after restart, spu 1 leads its own
+        let partitions = vec![
+            PartitionAdminMd::new(
+                ("topic1", 0),
+                PartitionSpec::new(0, vec![0, 1]),
+                status(0, vec![(1, 10)]),
+            ),
+            PartitionAdminMd::new(
+                ("topic1", 1),
+                PartitionSpec::new(1, vec![0, 1]),
+                status(1, vec![(0, 10)]),
+            ),
+            PartitionAdminMd::new(
+                ("topic1", 2),
+                PartitionSpec::new(1, vec![0, 1]),
+                status(1, vec![(0, 8)]),
+            ),
+            PartitionAdminMd::new(
+                ("topic1", 3),
+                PartitionSpec::new(1, vec![0, 1]),
+                status(1, vec![(0, 10)]),
+            ),
+            PartitionAdminMd::new(
+                ("topic1", 4),
+                PartitionSpec::new(1, vec![1, 0]),
+                status(1, vec![(0, 10)]),
+            ),
+        ];
+
+        // imbalance of spu 0 is 75%, lagging partition stays
+        let actions = rebalance_leaders(&partitions, &online, 10);
+        assert_eq!(actions.len(), 2);
+        for action in &actions {
+            match action {
+                PartitionWSAction::UpdateSpec((key, spec)) => {
+                    assert!(key.partition == 1 || key.partition == 3);
+                    assert_eq!(spec.leader, 0);
+                }
+                _ => panic!("expected spec update"),
+            }
+        }
+
+        // lagging preferred leader is elected once leader reports it in sync
+        let mut written = partitions[2].clone();
+        written.status.in_sync_replicas = vec![1, 0];
+        assert_eq!(
+            preferred_leader_election(&written, &online),
+            PreferredElection::Elect(0)
+        );
+
+        // below threshold
+        assert!(rebalance_leaders(&partitions, &online, 80).is_empty());
+
+        // preferred leader offline
+        let online: HashSet<i32> = vec![1].into_iter().collect();
+        assert!(rebalance_leaders(&partitions, &online, 10).is_empty());
+        assert_eq!(
+            preferred_leader_election(&partitions[1], &online),
+            PreferredElection::NotReady("preferred leader: 0 is offline".to_owned())
+        );
+        assert_eq!(
+            preferred_leader_election(&partitions[4], &online),
+            PreferredElection::NotNeeded
+        );
+    }
+
     /*
     #[test_async]
     async fn test_process_partition_actions_without_partitions() -> Result<(), ()> {
@@ -2,6 +2,7 @@
 //! # Spu Controller
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 use std::time::Duration;
 
@@ -17,6 +18,7 @@ use fluvio_types::SpuId;
 
 use crate::stores::actions::WSAction;
 use crate::core::SharedContext;
+use crate::core::ScMetrics;
 use crate::stores::StoreContext;
 use crate::stores::spu::*;
 
@@ -43,6 +45,7 @@ pub struct SpuController {
     spus: StoreContext<SpuSpec>,
     health_receiver: Receiver<SpuAction>,
     status: HashMap<SpuId, SpuOnlineStatus>,
+    metrics: Arc<ScMetrics>,
 }
 
 impl SpuController {
@@ -51,6 +54,7 @@ impl SpuController {
             spus: ctx.spus().clone(),
             health_receiver: ctx.health().receiver(),
             status: HashMap::new(),
+            metrics: ctx.metrics_owned(),
         };
 
         spawn(async move {
@@ -114,6 +118,8 @@ impl SpuController {
     async fn sync_store(&mut self) {
         use std::collections::HashSet;
 
+        let start = Instant::now();
+
         // check if we need to sync spu and our health check cache
         if self.spus.store().count().await as usize != self.status.len() {
             let keys = self.spus.store().spu_ids().await;
@@ -168,6 +174,8 @@ impl SpuController {
                 debug!("set spu: {} to offline", spu_name);
             }
         }
+
+        self.metrics.observe_reconciliation("spu", start.elapsed());
     }
 
     async fn send_spu_status(&mut self, action: SpuAction) {
@@ -3,11 +3,15 @@
 //!
 //! Reconcile Topics
 
+use std::sync::Arc;
+use std::time::Instant;
+
 use tracing::debug;
 
 use fluvio_future::task::spawn;
 
 use crate::core::SharedContext;
+use crate::core::ScMetrics;
 use crate::stores::topic::*;
 use crate::stores::spu::*;
 use crate::stores::partition::*;
@@ -22,6 +26,7 @@ pub struct TopicController {
     spus: StoreContext<SpuSpec>,
     topic_epoch: Epoch,
     reducer: TopicReducer,
+    metrics: Arc<ScMetrics>,
 }
 
 impl TopicController {
@@ -41,6 +46,7 @@ impl TopicController {
             partitions,
             topic_epoch,
             spus: ctx.spus().clone(),
+            metrics: ctx.metrics_owned(),
         };
 
         spawn(controller.dispatch_loop());
@@ -78,6 +84,7 @@ impl TopicController {
     /// get list of topics we need to check
     async fn sync_topics(&mut self) {
         debug!("syncing topics");
+        let start = Instant::now();
         let read_guard = self.topics.store().read().await;
         let changes = read_guard.changes_since(self.topic_epoch);
         self.topic_epoch = changes.epoch;
@@ -104,5 +111,8 @@ impl TopicController {
                 self.partitions.send_action(action).await;
             }
         }
+
+        self.metrics
+            .observe_reconciliation("topic", start.elapsed());
     }
 }
@@ -34,6 +34,11 @@ pub fn validate_computed_topic_parameters(param: &TopicReplicaParam) -> TopicNex
         TopicStatus::next_resolution_invalid_config(&err.to_string()).into()
     } else if let Err(err) = TopicSpec::valid_replication_factor(&param.replication_factor) {
         TopicStatus::next_resolution_invalid_config(&err.to_string()).into()
+    } else if let Err(err) = TopicSpec::valid_min_in_sync_replicas(
+        &param.min_in_sync_replicas,
+        &param.replication_factor,
+    ) {
+        TopicStatus::next_resolution_invalid_config(&err.to_string()).into()
     } else {
         TopicStatus::next_resolution_pending().into()
     }
@@ -197,7 +202,20 @@ impl TopicNextState {
                     );
                     let mut next_state = TopicNextState::same_next_state(topic);
                     if next_state.resolution == TopicResolution::Provisioned {
-                        next_state.partitions = topic.create_new_partitions(partition_store).await;
+                        if topic.status.replica_map_cnt() < param.partitions {
+                            // partitions has been added to topic
+                            let replica_map =
+                                extend_replica_map(spu_store, param, &topic.status.replica_map)
+                                    .await;
+                            let mut updated_topic = topic.clone();
+                            updated_topic.status.set_replica_map(replica_map.clone());
+                            next_state.partitions =
+                                updated_topic.create_new_partitions(partition_store).await;
+                            next_state.replica_map = replica_map;
+                        } else {
+                            next_state.partitions =
+                                topic.create_new_partitions(partition_store).await;
+                        }
                         next_state
                     } else {
                         next_state
@@ -238,6 +256,32 @@ impl TopicNextState {
     }
 }
 
+///
+/// Place partitions added to topic, existing partitions are kept where they are.
+/// If there are not enough SPUs for replication factor, replica map is unchanged
+/// and placement is tried again on next pass.
+///
+pub async fn extend_replica_map(
+    spus: &SpuAdminStore,
+    param: &TopicReplicaParam,
+    replica_map: &ReplicaMap,
+) -> ReplicaMap {
+    let mut extended_map = replica_map.clone();
+    let spu_count = spus.count().await;
+    if spu_count < param.replication_factor {
+        debug!(
+            "R-MAP needs {:?} online spus to add partitions, found {:?}",
+            param.replication_factor, spu_count
+        );
+        return extended_map;
+    }
+
+    for (idx, replicas) in generate_replica_map_for_topic(spus, param, None).await {
+        extended_map.entry(idx).or_insert(replicas);
+    }
+    extended_map
+}
+
 ///
 /// Generate replica map for a specific topic
 ///
@@ -582,4 +626,37 @@ pub mod replica_map_test {
         assert_eq!(computed, expected);
         Ok(())
     }
+
+    #[test_async]
+    async fn test_add_partitions_to_provisioned_topic() -> Result<(), ()> {
+        let spus = SpuAdminStore::quick(vec![(0, true, None), (1, true, None), (2, true, None)]);
+        let partitions = PartitionAdminStore::default();
+
+        let topic = TopicAdminMd::new(
+            "topic1",
+            (4, 2).into(),
+            TopicStatus::new(
+                TopicResolution::Provisioned,
+                vec![vec![0, 1], vec![1, 2]],
+                "",
+            ),
+        );
+
+        let next_state = TopicNextState::compute_next_state(&topic, &spus, &partitions).await;
+        assert_eq!(next_state.resolution, TopicResolution::Provisioned);
+
+        // existing partitions are kept, new partitions are placed on spus
+        let replica_map = &next_state.replica_map;
+        assert_eq!(replica_map.len(), 4);
+        assert_eq!(replica_map.get(&0), Some(&vec![0, 1]));
+        assert_eq!(replica_map.get(&1), Some(&vec![1, 2]));
+        for idx in 2..4 {
+            assert_eq!(
+                replica_map.get(&idx).map(|replicas| replicas.len()),
+                Some(2)
+            );
+        }
+        assert_eq!(next_state.partitions.len(), 4);
+        Ok(())
+    }
 }
@@ -12,6 +12,7 @@ use crate::stores::topic::*;
 use crate::stores::spg::*;
 use crate::stores::*;
 use crate::controllers::spus::SpuStatusChannel;
+use super::ScMetrics;
 
 pub type SharedContext = Arc<Context>;
 
@@ -25,6 +26,7 @@ pub struct Context {
     spgs: StoreContext<SpuGroupSpec>,
     health: SpuStatusChannel,
     config: ScConfig,
+    metrics: Arc<ScMetrics>,
 }
 
 // -----------------------------------
@@ -45,6 +47,7 @@ impl Context {
             spgs: StoreContext::new(),
             health: SpuStatusChannel::new(),
             config,
+            metrics: Arc::new(ScMetrics::default()),
         }
     }
 
@@ -81,6 +84,14 @@ impl Context {
         &self.config
     }
 
+    pub fn metrics(&self) -> &ScMetrics {
+        &self.metrics
+    }
+
+    pub fn metrics_owned(&self) -> Arc<ScMetrics> {
+        self.metrics.clone()
+    }
+
     pub fn namespace(&self) -> &str {
         &self.config.namespace
     }
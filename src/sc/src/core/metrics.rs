@@ -0,0 +1,63 @@
+//!
+//! # SC Metrics
+//!
+//! Metrics exported at `/metrics` when SC is started with metrics address.
+//!
+use std::sync::Arc;
+use std::time::Duration;
+
+use fluvio_metrics::{Counter, Family, Gauge, Histogram, Registry};
+
+const CONTROLLER_LABELS: &[&str] = &["controller"];
+
+#[derive(Debug)]
+pub struct ScMetrics {
+    registry: Arc<Registry>,
+    pub public_connections: Arc<Gauge>,
+    pub spu_connections: Arc<Gauge>,
+    reconciliations: Arc<Family<Counter>>,
+    reconciliation_latency: Arc<Family<Histogram>>,
+}
+
+impl Default for ScMetrics {
+    fn default() -> Self {
+        let registry = Registry::default();
+        Self {
+            public_connections: registry.register(
+                "fluvio_sc_public_connections",
+                "Open client connections",
+                Gauge::default(),
+            ),
+            spu_connections: registry.register(
+                "fluvio_sc_spu_connections",
+                "Registered SPUs connected to SC",
+                Gauge::default(),
+            ),
+            reconciliations: registry.register(
+                "fluvio_sc_reconciliations_total",
+                "Reconciliation loops run by controller",
+                Family::new(CONTROLLER_LABELS),
+            ),
+            reconciliation_latency: registry.register(
+                "fluvio_sc_reconciliation_duration_seconds",
+                "Time of reconciliation loop by controller",
+                Family::new(CONTROLLER_LABELS),
+            ),
+            registry: Arc::new(registry),
+        }
+    }
+}
+
+impl ScMetrics {
+    pub fn registry(&self) -> Arc<Registry> {
+        self.registry.clone()
+    }
+
+    /// record reconciliation loop of controller
+    pub fn observe_reconciliation(&self, controller: &str, duration: Duration) {
+        self.reconciliations.with_labels(&[controller]).inc();
+        self.reconciliation_latency
+            .with_labels(&[controller])
+            .observe_duration(duration);
+    }
+}
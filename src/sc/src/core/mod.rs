@@ -1,3 +1,5 @@
 mod context;
+mod metrics;
 pub mod common;
 pub use self::context::*;
+pub use self::metrics::ScMetrics;
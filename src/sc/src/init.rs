@@ -7,16 +7,27 @@
 
 use k8_metadata_client::SharedClient;
 use k8_metadata_client::MetadataClient;
+use fluvio_auth::basic::BasicRbacPolicy;
+use fluvio_metrics::start_metrics_server;
 
 use crate::core::Context;
 use crate::core::SharedContext;
 use crate::controllers::spus::SpuController;
 use crate::controllers::topics::TopicController;
-use crate::controllers::partitions::PartitionController;
+use crate::controllers::partitions::{PartitionController, LeaderRebalanceController};
 use crate::config::ScConfig;
 use crate::services::start_internal_server;
 use crate::dispatcher::dispatcher::K8ClusterStateDispatcher;
-use crate::services::auth::basic::BasicRbacPolicy;
+use crate::cli::ScOpt;
+
+/// start SC with local metadata if metadata directory is specified, otherwise with Kubernetes
+pub fn main_loop(opt: ScOpt) {
+    if let Some(metadata_dir) = opt.metadata_dir() {
+        crate::local::main_local_loop(opt, metadata_dir);
+    } else {
+        crate::k8::main_k8_loop(opt);
+    }
+}
 
 /// start the main loop
 pub async fn start_main_loop<C>(
@@ -63,9 +74,16 @@ where
     SpuController::start(ctx.clone());
     TopicController::start(ctx.clone());
     PartitionController::start(ctx.clone());
+    if let Some(threshold) = ctx.config().leader_imbalance_threshold {
+        LeaderRebalanceController::start(ctx.clone(), threshold);
+    }
 
     start_internal_server(ctx.clone());
 
+    if let Some(metrics_addr) = &ctx.config().metrics_endpoint {
+        start_metrics_server(metrics_addr.to_owned(), ctx.metrics().registry());
+    }
+
     pub_server::start(ctx.clone(), auth_policy);
 
     mod pub_server {
@@ -77,7 +95,7 @@ where
         use crate::core::SharedContext;
 
         use crate::services::auth::{AuthGlobalContext, RootAuthorization};
-        use crate::services::auth::basic::{BasicAuthorization, BasicRbacPolicy};
+        use fluvio_auth::basic::{BasicAuthorization, BasicRbacPolicy};
 
         pub fn start(ctx: SharedContext, auth_policy_option: Option<BasicRbacPolicy>) {
             if let Some(policy) = auth_policy_option {
@@ -13,6 +13,7 @@ use k8_client::new_shared;
 use operator::run_k8_operators;
 
 use crate::cli::ScOpt;
+use crate::proxy;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -56,36 +57,3 @@ pub fn main_k8_loop(opt: ScOpt) {
         }
     });
 }
-
-mod proxy {
-    use std::process;
-    use log::info;
-
-    use fluvio_types::print_cli_err;
-    pub use fluvio_future::rust_tls::TlsAcceptor;
-
-    use fluvio_auth::x509::X509Authenticator;
-    use flv_tls_proxy::{
-        start as proxy_start, start_with_authenticator as proxy_start_with_authenticator,
-    };
-
-    use crate::config::ScConfig;
-
-    pub async fn start_proxy(config: ScConfig, acceptor: (TlsAcceptor, String)) {
-        let (tls_acceptor, proxy_addr) = acceptor;
-        let target = config.public_endpoint;
-        info!("starting TLS proxy: {}", proxy_addr);
-
-        let result = if let Some(x509_auth_scopes) = config.x509_auth_scopes {
-            let authenticator = Box::new(X509Authenticator::new(&x509_auth_scopes));
-            proxy_start_with_authenticator(&proxy_addr, tls_acceptor, target, authenticator).await
-        } else {
-            proxy_start(&proxy_addr, tls_acceptor, target).await
-        };
-
-        if let Err(err) = result {
-            print_cli_err!(err);
-            process::exit(-1);
-        }
-    }
-}
@@ -283,6 +283,7 @@ impl SpgOperator {
                 encryption: spu_private_ep.encryption,
             },
             rack: None,
+            kafka_endpoint: None,
         };
 
         let owner_ref = k8_metadata.make_owner_reference::<K8SpuGroupSpec>();
@@ -2,6 +2,7 @@
 
 #[cfg(any(feature = "k8", feature = "k8_rustls"))]
 pub mod k8;
+pub mod local;
 pub mod cli;
 pub mod core;
 pub mod config;
@@ -10,8 +11,10 @@ mod init;
 mod error;
 mod services;
 mod controllers;
+mod proxy;
 
 pub use init::start_main_loop;
+pub use init::main_loop;
 
 pub mod dispatcher {
     pub use fluvio_stream_dispatcher::*;
@@ -0,0 +1,774 @@
+//!
+//! # Local Metadata Client
+//!
+//! Implementation of metadata client which keeps objects in a local directory instead of Kubernetes.
+//! Each object is stored as JSON file: `<dir>/<plural>.<group>/<namespace>/<name>.json`.
+//! Files are written to temporary file first and renamed, so a crash never leaves partial object.
+//!
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_lock::Lock;
+use async_trait::async_trait;
+use event_listener::Event;
+use futures_util::stream::BoxStream;
+use futures_util::stream::StreamExt;
+use serde_json::json;
+use serde_json::Value;
+use serde_json::Error as SerdeJsonError;
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_future::fs::create_dir_all;
+use fluvio_future::fs::remove_file;
+use fluvio_future::fs::rename;
+use fluvio_future::fs::write;
+use k8_diff::DiffError;
+use k8_metadata_client::ListArg;
+use k8_metadata_client::MetadataClient;
+use k8_metadata_client::MetadataClientError;
+use k8_metadata_client::NameSpace;
+use k8_metadata_client::TokenStreamResult;
+use k8_obj_metadata::InputK8Obj;
+use k8_obj_metadata::K8List;
+use k8_obj_metadata::K8Meta;
+use k8_obj_metadata::K8Obj;
+use k8_obj_metadata::K8Status;
+use k8_obj_metadata::ListMetadata;
+use k8_obj_metadata::ObjectMeta;
+use k8_obj_metadata::Spec;
+use k8_obj_metadata::StatusEnum;
+use k8_obj_metadata::UpdateK8ObjStatus;
+
+/// number of change events kept for watchers that are behind
+const MAX_EVENTS: usize = 10000;
+
+const OBJECT_EXTENSION: &str = "json";
+
+#[derive(Debug)]
+pub enum LocalMetadataError {
+    IoError(IoError),
+    DiffError(DiffError),
+    JsonError(SerdeJsonError),
+    PatchError,
+    NotFound(String),
+    AlreadyExists(String),
+}
+
+impl From<IoError> for LocalMetadataError {
+    fn from(error: IoError) -> Self {
+        Self::IoError(error)
+    }
+}
+
+impl From<SerdeJsonError> for LocalMetadataError {
+    fn from(error: SerdeJsonError) -> Self {
+        Self::JsonError(error)
+    }
+}
+
+impl From<DiffError> for LocalMetadataError {
+    fn from(error: DiffError) -> Self {
+        Self::DiffError(error)
+    }
+}
+
+impl fmt::Display for LocalMetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "io: {}", err),
+            Self::DiffError(err) => write!(f, "{:#?}", err),
+            Self::JsonError(err) => write!(f, "{}", err),
+            Self::PatchError => write!(f, "patch error"),
+            Self::NotFound(name) => write!(f, "{} not found", name),
+            Self::AlreadyExists(name) => write!(f, "{} already exists", name),
+        }
+    }
+}
+
+impl MetadataClientError for LocalMetadataError {
+    fn patch_error() -> Self {
+        Self::PatchError
+    }
+
+    fn not_founded(&self) -> bool {
+        matches!(self, Self::NotFound(_))
+    }
+}
+
+/// identify object across all kinds
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ObjectKey {
+    kind: String,
+    namespace: String,
+    name: String,
+}
+
+impl ObjectKey {
+    fn new<S: Spec>(namespace: &str, name: &str) -> Self {
+        Self {
+            kind: kind_key::<S>(),
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    fn in_namespace(&self, namespace: &NameSpace) -> bool {
+        match namespace {
+            NameSpace::All => true,
+            NameSpace::Named(name) => &self.namespace == name,
+        }
+    }
+}
+
+impl fmt::Display for ObjectKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}/{}", self.kind, self.namespace, self.name)
+    }
+}
+
+/// directory name of kind
+fn kind_key<S: Spec>() -> String {
+    let crd = S::metadata();
+    format!("{}.{}", crd.names.plural, crd.group)
+}
+
+#[derive(Debug)]
+struct WatchEvent {
+    version: u64,
+    key: ObjectKey,
+    event_type: &'static str,
+    object: Value,
+}
+
+#[derive(Debug, Default)]
+struct LocalState {
+    /// last resource version assigned
+    version: u64,
+    /// events up to this version are no longer available
+    compacted_version: u64,
+    objects: BTreeMap<ObjectKey, Value>,
+    events: VecDeque<WatchEvent>,
+}
+
+impl LocalState {
+    fn next_version(&mut self) -> u64 {
+        self.version += 1;
+        self.version
+    }
+
+    fn push_event(&mut self, key: ObjectKey, event_type: &'static str, object: Value) {
+        let version = self.version;
+        self.events.push_back(WatchEvent {
+            version,
+            key,
+            event_type,
+            object,
+        });
+        while self.events.len() > MAX_EVENTS {
+            if let Some(event) = self.events.pop_front() {
+                self.compacted_version = event.version;
+            }
+        }
+    }
+}
+
+/// Metadata client backed by local directory
+#[derive(Debug)]
+pub struct LocalMetadataClient {
+    dir: PathBuf,
+    state: Lock<LocalState>,
+    event: Event,
+}
+
+impl LocalMetadataClient {
+    /// load all objects from directory, directory is created if it doesn't exist
+    pub fn load(dir: impl Into<PathBuf>) -> Result<Self, LocalMetadataError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut state = LocalState::default();
+        for kind_entry in fs::read_dir(&dir)? {
+            let kind_path = kind_entry?.path();
+            if !kind_path.is_dir() {
+                continue;
+            }
+            for ns_entry in fs::read_dir(&kind_path)? {
+                let ns_path = ns_entry?.path();
+                if !ns_path.is_dir() {
+                    continue;
+                }
+                for object_entry in fs::read_dir(&ns_path)? {
+                    let object_path = object_entry?.path();
+                    if object_path.extension().and_then(|ext| ext.to_str())
+                        != Some(OBJECT_EXTENSION)
+                    {
+                        continue;
+                    }
+
+                    let object: Value = serde_json::from_slice(&fs::read(&object_path)?)?;
+                    let key = ObjectKey {
+                        kind: file_name(&kind_path),
+                        namespace: file_name(&ns_path),
+                        name: object["metadata"]["name"]
+                            .as_str()
+                            .map(|name| name.to_owned())
+                            .unwrap_or_else(|| file_stem(&object_path)),
+                    };
+                    let version = resource_version(&object);
+                    if version > state.version {
+                        state.version = version;
+                    }
+                    trace!("loaded object: {}", key);
+                    state.objects.insert(key, object);
+                }
+            }
+        }
+
+        // changes before start are not known
+        state.compacted_version = state.version;
+
+        debug!(
+            "loaded {} objects from: {}, version: {}",
+            state.objects.len(),
+            dir.display(),
+            state.version
+        );
+
+        Ok(Self {
+            dir,
+            state: Lock::new(state),
+            event: Event::new(),
+        })
+    }
+
+    fn object_path(&self, key: &ObjectKey) -> PathBuf {
+        self.dir
+            .join(&key.kind)
+            .join(&key.namespace)
+            .join(format!("{}.{}", key.name, OBJECT_EXTENSION))
+    }
+
+    /// write object atomically
+    async fn persist(&self, key: &ObjectKey, object: &Value) -> Result<(), LocalMetadataError> {
+        let path = self.object_path(key);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).await?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        write(&tmp_path, serde_json::to_vec_pretty(object)?).await?;
+        rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, key: &ObjectKey) -> Result<(), LocalMetadataError> {
+        match remove_file(self.object_path(key)).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// store object with new resource version and notify watchers
+    async fn store(
+        &self,
+        state: &mut LocalState,
+        key: ObjectKey,
+        mut object: Value,
+        event_type: &'static str,
+    ) -> Result<Value, LocalMetadataError> {
+        let version = state.next_version();
+        object["metadata"]["resourceVersion"] = json!(version.to_string());
+        self.persist(&key, &object).await?;
+        state.objects.insert(key.clone(), object.clone());
+        state.push_event(key, event_type, object.clone());
+        self.event.notify(usize::MAX);
+        Ok(object)
+    }
+
+    /// delete object and objects owned by it
+    async fn delete_cascade(
+        &self,
+        state: &mut LocalState,
+        key: ObjectKey,
+    ) -> Result<(), LocalMetadataError> {
+        let mut pending = vec![key];
+        while let Some(key) = pending.pop() {
+            let object = match state.objects.remove(&key) {
+                Some(object) => object,
+                None => continue,
+            };
+            self.remove(&key).await?;
+            debug!("deleted: {}", key);
+            state.next_version();
+            let uid = object["metadata"]["uid"].as_str().unwrap_or("").to_owned();
+            state.push_event(key, "DELETED", object);
+
+            if !uid.is_empty() {
+                pending.extend(
+                    state
+                        .objects
+                        .iter()
+                        .filter(|(_, child)| is_owned_by(child, &uid))
+                        .map(|(child_key, _)| child_key.clone()),
+                );
+            }
+        }
+        self.event.notify(usize::MAX);
+        Ok(())
+    }
+
+    /// events for kind in namespace after version, `None` if events are no longer available
+    async fn events_since(
+        &self,
+        kind: &str,
+        namespace: &NameSpace,
+        since: Option<u64>,
+    ) -> Option<(u64, Vec<(&'static str, Value)>)> {
+        let state = self.state.lock().await;
+        let since = since.unwrap_or(state.version);
+        if since < state.compacted_version {
+            debug!(
+                "watch version: {} is older than: {}",
+                since, state.compacted_version
+            );
+            return None;
+        }
+
+        let events = state
+            .events
+            .iter()
+            .filter(|event| {
+                event.version > since && event.key.kind == kind && event.key.in_namespace(namespace)
+            })
+            .map(|event| (event.event_type, event.object.clone()))
+            .collect();
+
+        Some((state.version, events))
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn resource_version(object: &Value) -> u64 {
+    object["metadata"]["resourceVersion"]
+        .as_str()
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(0)
+}
+
+fn is_owned_by(object: &Value, uid: &str) -> bool {
+    object["metadata"]["ownerReferences"]
+        .as_array()
+        .map(|owners| owners.iter().any(|owner| owner["uid"] == uid))
+        .unwrap_or(false)
+}
+
+fn new_uid() -> String {
+    let uid: u128 = rand::random();
+    let hex = format!("{:032x}", uid);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// apply json merge patch (RFC 7386)
+fn merge_patch(target: &mut Value, patch: &Value) {
+    if let Value::Object(patch_map) = patch {
+        if !target.is_object() {
+            *target = Value::Object(serde_json::Map::new());
+        }
+        if let Value::Object(target_map) = target {
+            for (key, value) in patch_map {
+                if value.is_null() {
+                    target_map.remove(key);
+                } else {
+                    merge_patch(target_map.entry(key.clone()).or_insert(Value::Null), value);
+                }
+            }
+        }
+    } else {
+        *target = patch.clone();
+    }
+}
+
+fn to_k8_obj<S>(object: Value) -> Result<K8Obj<S>, LocalMetadataError>
+where
+    S: Spec,
+{
+    Ok(serde_json::from_value(object)?)
+}
+
+#[async_trait]
+impl MetadataClient for LocalMetadataClient {
+    type MetadataClientError = LocalMetadataError;
+
+    async fn retrieve_item<S, M>(&self, metadata: &M) -> Result<K8Obj<S>, Self::MetadataClientError>
+    where
+        S: Spec,
+        M: K8Meta + Send + Sync,
+    {
+        let key = ObjectKey::new::<S>(metadata.namespace(), metadata.name());
+        let state = self.state.lock().await;
+        match state.objects.get(&key) {
+            Some(object) => to_k8_obj(object.clone()),
+            None => Err(LocalMetadataError::NotFound(key.to_string())),
+        }
+    }
+
+    async fn retrieve_items_with_option<S, N>(
+        &self,
+        namespace: N,
+        _option: Option<ListArg>,
+    ) -> Result<K8List<S>, Self::MetadataClientError>
+    where
+        S: Spec,
+        N: Into<NameSpace> + Send + Sync,
+    {
+        let namespace = namespace.into();
+        let kind = kind_key::<S>();
+        let state = self.state.lock().await;
+
+        let items = state
+            .objects
+            .iter()
+            .filter(|(key, _)| key.kind == kind && key.in_namespace(&namespace))
+            .map(|(_, object)| to_k8_obj(object.clone()))
+            .collect::<Result<Vec<K8Obj<S>>, _>>()?;
+
+        Ok(K8List {
+            api_version: S::api_version(),
+            items,
+            kind: S::kind(),
+            metadata: ListMetadata {
+                _continue: None,
+                resource_version: state.version.to_string(),
+                self_link: "".to_owned(),
+            },
+        })
+    }
+
+    fn retrieve_items_in_chunks<'a, S, N>(
+        self: Arc<Self>,
+        namespace: N,
+        _limit: u32,
+        option: Option<ListArg>,
+    ) -> BoxStream<'a, K8List<S>>
+    where
+        S: Spec + 'static,
+        N: Into<NameSpace> + Send + Sync + 'static,
+    {
+        // all objects are in memory, so single chunk is returned
+        futures_util::stream::once(async move {
+            match self.retrieve_items_with_option(namespace, option).await {
+                Ok(list) => list,
+                Err(err) => {
+                    tracing::error!("error retrieving items: {}", err);
+                    K8List::new()
+                }
+            }
+        })
+        .boxed()
+    }
+
+    async fn delete_item<S, M>(&self, metadata: &M) -> Result<K8Status, Self::MetadataClientError>
+    where
+        S: Spec,
+        M: K8Meta + Send + Sync,
+    {
+        let key = ObjectKey::new::<S>(metadata.namespace(), metadata.name());
+        let mut state = self.state.lock().await;
+        if !state.objects.contains_key(&key) {
+            return Err(LocalMetadataError::NotFound(key.to_string()));
+        }
+
+        self.delete_cascade(&mut state, key).await?;
+
+        Ok(K8Status {
+            api_version: "v1".to_owned(),
+            code: None,
+            details: None,
+            kind: "Status".to_owned(),
+            message: None,
+            reason: None,
+            status: StatusEnum::SUCCESS,
+        })
+    }
+
+    async fn create_item<S>(
+        &self,
+        value: InputK8Obj<S>,
+    ) -> Result<K8Obj<S>, Self::MetadataClientError>
+    where
+        S: Spec,
+    {
+        let input_metadata = value.metadata;
+        let key = ObjectKey::new::<S>(&input_metadata.namespace, &input_metadata.name);
+        let mut state = self.state.lock().await;
+        if state.objects.contains_key(&key) {
+            return Err(LocalMetadataError::AlreadyExists(key.to_string()));
+        }
+
+        let object = K8Obj::<S> {
+            api_version: S::api_version(),
+            kind: S::kind(),
+            metadata: ObjectMeta {
+                name: input_metadata.name,
+                namespace: input_metadata.namespace,
+                uid: new_uid(),
+                labels: input_metadata.labels,
+                owner_references: input_metadata.owner_references,
+                ..Default::default()
+            },
+            spec: value.spec,
+            ..Default::default()
+        };
+
+        debug!("creating: {}", key);
+        let object = self
+            .store(&mut state, key, serde_json::to_value(object)?, "ADDED")
+            .await?;
+        to_k8_obj(object)
+    }
+
+    async fn update_status<S>(
+        &self,
+        value: &UpdateK8ObjStatus<S>,
+    ) -> Result<K8Obj<S>, Self::MetadataClientError>
+    where
+        S: Spec,
+    {
+        let key = ObjectKey::new::<S>(&value.metadata.namespace, &value.metadata.name);
+        let mut state = self.state.lock().await;
+        let mut object = match state.objects.get(&key) {
+            Some(object) => object.clone(),
+            None => return Err(LocalMetadataError::NotFound(key.to_string())),
+        };
+
+        trace!("updating status: {}", key);
+        object["status"] = serde_json::to_value(&value.status)?;
+        let object = self.store(&mut state, key, object, "MODIFIED").await?;
+        to_k8_obj(object)
+    }
+
+    async fn patch_spec<S, M>(
+        &self,
+        metadata: &M,
+        patch: &Value,
+    ) -> Result<K8Obj<S>, Self::MetadataClientError>
+    where
+        S: Spec,
+        M: K8Meta + fmt::Display + Send + Sync,
+    {
+        let key = ObjectKey::new::<S>(metadata.namespace(), metadata.name());
+        let mut state = self.state.lock().await;
+        let mut object = match state.objects.get(&key) {
+            Some(object) => object.clone(),
+            None => return Err(LocalMetadataError::NotFound(key.to_string())),
+        };
+
+        trace!("patching: {} with: {:#?}", key, patch);
+        merge_patch(&mut object, patch);
+        // make sure patched object is still valid
+        to_k8_obj::<S>(object.clone())?;
+        let object = self.store(&mut state, key, object, "MODIFIED").await?;
+        to_k8_obj(object)
+    }
+
+    fn watch_stream_since<S, N>(
+        &self,
+        namespace: N,
+        resource_version: Option<String>,
+    ) -> BoxStream<'_, TokenStreamResult<S, Self::MetadataClientError>>
+    where
+        S: Spec + 'static,
+        N: Into<NameSpace>,
+    {
+        let namespace = namespace.into();
+        let kind = kind_key::<S>();
+        let since = resource_version.and_then(|version| version.parse::<u64>().ok());
+
+        futures_util::stream::unfold(since, move |mut since| {
+            let namespace = namespace.clone();
+            let kind = kind.clone();
+            async move {
+                loop {
+                    // listen before reading events so no change is missed
+                    let listener = self.event.listen();
+                    // stream ends if events were compacted, so dispatcher re-syncs
+                    let (version, events) = self.events_since(&kind, &namespace, since).await?;
+
+                    if !events.is_empty() {
+                        let watch_events = events
+                            .into_iter()
+                            .map(|(event_type, object)| {
+                                serde_json::from_value(json!({
+                                    "type": event_type,
+                                    "object": object
+                                }))
+                                .map_err(|err| err.into())
+                            })
+                            .collect();
+                        return Some((Ok(watch_events), Some(version)));
+                    }
+
+                    since = Some(version);
+                    listener.await;
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+/// create shared local client
+pub fn new_shared(dir: impl Into<PathBuf>) -> Result<Arc<LocalMetadataClient>, LocalMetadataError> {
+    Ok(Arc::new(LocalMetadataClient::load(dir)?))
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::env::temp_dir;
+
+    use futures_util::stream::StreamExt;
+    use serde_json::json;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_future::test_async;
+    use k8_metadata_client::MetadataClient;
+    use k8_metadata_client::MetadataClientError;
+    use k8_obj_metadata::InputK8Obj;
+    use k8_obj_metadata::InputObjectMeta;
+    use k8_obj_metadata::ObjectMeta;
+    use k8_obj_metadata::Spec;
+
+    use crate::stores::topic::TopicSpec;
+    use crate::stores::partition::PartitionSpec;
+
+    use super::LocalMetadataClient;
+    use super::LocalMetadataError;
+
+    fn input_topic(name: &str) -> InputK8Obj<TopicSpec> {
+        InputK8Obj {
+            api_version: TopicSpec::api_version(),
+            kind: TopicSpec::kind(),
+            metadata: InputObjectMeta {
+                name: name.to_owned(),
+                namespace: "default".to_owned(),
+                ..Default::default()
+            },
+            spec: TopicSpec::new_computed(2, 1, None),
+            ..Default::default()
+        }
+    }
+
+    #[test_async]
+    async fn test_local_metadata_crud() -> Result<(), LocalMetadataError> {
+        let dir = temp_dir().join("local-metadata-crud");
+        ensure_clean_dir(&dir);
+
+        let client = LocalMetadataClient::load(&dir)?;
+        let topic = client.create_item(input_topic("test")).await?;
+        assert!(!topic.metadata.uid.is_empty());
+        assert_eq!(topic.metadata.resource_version, "1");
+        assert!(client.create_item(input_topic("test")).await.is_err());
+
+        // partition is owned by topic
+        let partition = client
+            .create_item(InputK8Obj {
+                api_version: PartitionSpec::api_version(),
+                kind: PartitionSpec::kind(),
+                metadata: InputObjectMeta {
+                    name: "test-0".to_owned(),
+                    namespace: "default".to_owned(),
+                    owner_references: vec![topic.metadata.make_owner_reference::<TopicSpec>()],
+                    ..Default::default()
+                },
+                spec: PartitionSpec::default(),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(partition.metadata.resource_version, "2");
+
+        let patched = client
+            .patch_spec::<TopicSpec, _>(
+                &InputObjectMeta {
+                    name: "test".to_owned(),
+                    namespace: "default".to_owned(),
+                    ..Default::default()
+                },
+                &json!({ "spec": { "partitions": 3 } }),
+            )
+            .await?;
+        assert_eq!(patched.metadata.resource_version, "3");
+        assert_eq!(patched.spec.partitions(), Some(3));
+
+        let topics = client.retrieve_items::<TopicSpec, _>("default").await?;
+        assert_eq!(topics.items.len(), 1);
+        assert_eq!(topics.metadata.resource_version, "3");
+
+        // deleting topic removes partition
+        client
+            .delete_item::<TopicSpec, _>(&ObjectMeta::new("test", "default"))
+            .await?;
+        let err = client
+            .retrieve_item::<PartitionSpec, _>(&ObjectMeta::new("test-0", "default"))
+            .await
+            .expect_err("partition should be deleted");
+        assert!(err.not_founded());
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_local_metadata_reload() -> Result<(), LocalMetadataError> {
+        let dir = temp_dir().join("local-metadata-reload");
+        ensure_clean_dir(&dir);
+
+        {
+            let client = LocalMetadataClient::load(&dir)?;
+            client.create_item(input_topic("test1")).await?;
+            client.create_item(input_topic("test2")).await?;
+        }
+
+        let client = LocalMetadataClient::load(&dir)?;
+        let topics = client.retrieve_items::<TopicSpec, _>("default").await?;
+        assert_eq!(topics.items.len(), 2);
+        assert_eq!(topics.metadata.resource_version, "2");
+
+        // watch from loaded version receives new changes only
+        let mut stream = client.watch_stream_since::<TopicSpec, _>("default", Some("2".to_owned()));
+        client.create_item(input_topic("test3")).await?;
+        let events = stream.next().await.expect("watch event").expect("events");
+        assert_eq!(events.len(), 1);
+
+        // events before loaded version are no longer available
+        let mut stream = client.watch_stream_since::<TopicSpec, _>("default", Some("1".to_owned()));
+        assert!(stream.next().await.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//!
+//! # Initialization routines for Streaming Coordinator (SC) without Kubernetes
+//!
+//! Metadata is kept in local directory instead of Kubernetes.
+//! SPUs are not managed, they must be registered as custom SPUs.
+//!
+
+mod metadata;
+
+pub use metadata::LocalMetadataClient;
+pub use metadata::LocalMetadataError;
+
+use std::path::PathBuf;
+
+use crate::cli::ScOpt;
+use crate::proxy;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn main_local_loop(opt: ScOpt, metadata_dir: PathBuf) {
+    use std::time::Duration;
+
+    use fluvio_future::task::run_block_on;
+    use fluvio_future::timer::sleep;
+
+    use crate::init::start_main_loop;
+    // parse configuration (program exits on error)
+    let ((sc_config, auth_policy), tls_option) = opt.parse_local_cli_or_exit();
+
+    println!(
+        "starting sc server with local metadata: {}, version: {}",
+        metadata_dir.display(),
+        VERSION
+    );
+
+    run_block_on(async move {
+        let metadata_client =
+            metadata::new_shared(metadata_dir).expect("problem loading local metadata");
+        start_main_loop((sc_config.clone(), auth_policy), metadata_client).await;
+
+        if let Some((proxy_port, tls_config)) = tls_option {
+            let tls_acceptor = tls_config
+                .try_build_tls_acceptor()
+                .expect("can't build tls acceptor");
+            proxy::start_proxy(sc_config, (tls_acceptor, proxy_port)).await;
+        }
+
+        println!("Streaming Controller started successfully");
+
+        // do inifinite loop
+        loop {
+            sleep(Duration::from_secs(60)).await;
+        }
+    });
+}
@@ -1,5 +1,3 @@
-pub mod basic;
-
 pub use common::*;
 
 mod common {
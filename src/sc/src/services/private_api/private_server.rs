@@ -14,6 +14,7 @@ use fluvio_types::SpuId;
 use fluvio_future::net::TcpStream;
 use dataplane::api::RequestMessage;
 use fluvio_controlplane_metadata::store::Epoch;
+use fluvio_controlplane_metadata::spu::Endpoint;
 use fluvio_controlplane_metadata::spu::store::SpuLocalStorePolicy;
 use fluvio_service::FlvService;
 use fluvio_service::wait_for_request;
@@ -73,11 +74,14 @@ impl FlvService<TcpStream> for ScInternalService {
                     return Ok(())
                 }
 
+                update_kafka_endpoint(&context, spu_id, req_msg.request.kafka_endpoint()).await;
+
                 spu_id
             }
         );
 
         debug!("beginning SPU loop: {}", spu_id);
+        let _connection = context.metrics().spu_connections.track();
         let health_sender = context.health().sender();
 
         health_sender
@@ -209,6 +213,32 @@ async fn dispatch_loop(
     Ok(())
 }
 
+/// record kafka endpoint advertised by spu in spu spec
+async fn update_kafka_endpoint(
+    ctx: &SharedContext,
+    spu_id: SpuId,
+    kafka_endpoint: Option<&Endpoint>,
+) {
+    let spu = match ctx.spus().store().get_by_id(spu_id).await {
+        Some(spu) => spu,
+        None => return,
+    };
+
+    if spu.spec.kafka_endpoint.as_ref() == kafka_endpoint {
+        return;
+    }
+
+    debug!(
+        "spu: {} kafka endpoint changed to: {:?}",
+        spu_id, kafka_endpoint
+    );
+    let mut spec = spu.spec;
+    spec.kafka_endpoint = kafka_endpoint.cloned();
+    ctx.spus()
+        .send_action(WSAction::UpdateSpec((spu.key, spec)))
+        .await;
+}
+
 /// send lrs update to metadata stores
 async fn send_lrs_update(ctx: &SharedContext, lrs_req: UpdateLrsRequest) {
     let read_guard = ctx.partitions().store().read().await;
@@ -221,6 +251,7 @@ async fn send_lrs_update(ctx: &SharedContext, lrs_req: UpdateLrsRequest) {
             PartitionResolution::Online,
         );
         current_status.merge(new_status);
+        current_status.in_sync_replicas = lrs_req.in_sync_replicas;
 
         WSAction::UpdateStatus::<PartitionSpec>((key, current_status))
     } else {
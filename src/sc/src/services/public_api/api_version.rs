@@ -7,6 +7,7 @@ use fluvio_sc_schema::versions::ApiVersionKey;
 use fluvio_sc_schema::versions::{ApiVersionsRequest, ApiVersionsResponse};
 use fluvio_sc_schema::AdminPublicApiKey;
 use fluvio_sc_schema::objects::*;
+use fluvio_sc_schema::partition::{ReassignPartitionsRequest, ElectLeadersRequest};
 
 pub async fn handle_api_versions_request(
     request: RequestMessage<ApiVersionsRequest>,
@@ -34,6 +35,21 @@ pub async fn handle_api_versions_request(
         WatchRequest::DEFAULT_API_VERSION,
         WatchRequest::DEFAULT_API_VERSION,
     ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::Update,
+        UpdateRequest::DEFAULT_API_VERSION,
+        UpdateRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ReassignPartitions,
+        ReassignPartitionsRequest::DEFAULT_API_VERSION,
+        ReassignPartitionsRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ElectLeaders,
+        ElectLeadersRequest::DEFAULT_API_VERSION,
+        ElectLeadersRequest::DEFAULT_API_VERSION,
+    ));
 
     trace!("flv api versions response: {:#?}", response);
 
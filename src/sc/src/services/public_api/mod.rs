@@ -7,6 +7,7 @@ mod api_version;
 mod create;
 mod delete;
 mod list;
+mod update;
 mod watch;
 
 pub use server::start_public_server;
@@ -0,0 +1,127 @@
+//!
+//! # Elect Leaders Request
+//!
+//! Move leadership of partitions back to preferred replicas.
+//! Preferred replica must be online and in sync with current leader.
+//!
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+
+use tracing::{debug, trace};
+
+use dataplane::ErrorCode;
+use dataplane::api::{RequestMessage, ResponseMessage};
+use fluvio_sc_schema::partition::*;
+use fluvio_sc_schema::topic::TopicSpec;
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_controlplane_metadata::spu::store::SpuLocalStorePolicy;
+use fluvio_auth::{AuthContext, InstanceAction};
+
+use crate::controllers::partitions::PartitionWSAction;
+use crate::controllers::partitions::{PreferredElection, preferred_leader_election};
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for elect leaders request
+pub async fn handle_elect_leaders_request<AC: AuthContext>(
+    request: RequestMessage<ElectLeadersRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<ElectLeadersResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    debug!(
+        "api request: elect leaders, partitions: {:?}",
+        req.partitions
+    );
+
+    let ctx = &auth_ctx.global_ctx;
+
+    let keys: Vec<ReplicaKey> = if req.partitions.is_empty() {
+        ctx.partitions()
+            .store()
+            .read()
+            .await
+            .values()
+            .map(|partition| partition.key_owned())
+            .collect()
+    } else {
+        req.partitions
+    };
+
+    let online = ctx.spus().store().online_status().await;
+    let mut authorized_topics: HashMap<String, bool> = HashMap::new();
+    let mut response = ElectLeadersResponse::default();
+
+    for key in keys {
+        let authorized = match authorized_topics.get(&key.topic) {
+            Some(authorized) => *authorized,
+            None => {
+                let authorized = auth_ctx
+                    .auth
+                    .allow_instance_action(
+                        TopicSpec::OBJECT_TYPE,
+                        InstanceAction::Update,
+                        &key.topic,
+                    )
+                    .await
+                    .map_err(|_| Error::new(ErrorKind::Interrupted, "authorization io error"))?;
+                authorized_topics.insert(key.topic.clone(), authorized);
+                authorized
+            }
+        };
+
+        if !authorized {
+            trace!("authorization failed");
+            response.results.push(LeaderElectionResult {
+                partition: key,
+                error_code: ErrorCode::PermissionDenied,
+                error_message: Some(String::from("permission denied")),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let partition = match ctx.partitions().store().value(&key).await {
+            Some(partition) => partition.inner_owned(),
+            None => {
+                response.results.push(LeaderElectionResult {
+                    partition: key,
+                    error_code: ErrorCode::PartitionError,
+                    error_message: Some("partition not found".to_owned()),
+                    ..Default::default()
+                });
+                continue;
+            }
+        };
+
+        let result = match preferred_leader_election(&partition, &online) {
+            PreferredElection::Elect(leader) => {
+                debug!("partition: {} moving leader to: {}", key, leader);
+                let mut spec = partition.spec;
+                spec.leader = leader;
+                ctx.partitions()
+                    .send_action(PartitionWSAction::UpdateSpec((key.clone(), spec)))
+                    .await;
+                LeaderElectionResult {
+                    partition: key,
+                    leader,
+                    ..Default::default()
+                }
+            }
+            PreferredElection::NotNeeded => LeaderElectionResult {
+                partition: key,
+                leader: partition.spec.leader,
+                ..Default::default()
+            },
+            PreferredElection::NotReady(reason) => LeaderElectionResult {
+                partition: key,
+                leader: partition.spec.leader,
+                error_code: ErrorCode::PartitionError,
+                error_message: Some(reason),
+            },
+        };
+        response.results.push(result);
+    }
+
+    trace!("elect leaders resp {:#?}", response);
+
+    Ok(ResponseMessage::from_header(&header, response))
+}
@@ -1,11 +1,18 @@
+mod reassign;
+mod elect;
+
+pub use reassign::*;
+pub use elect::*;
+
 use std::io::{Error, ErrorKind};
 
 use tracing::{trace, debug};
 
 use fluvio_sc_schema::objects::{ListResponse, Metadata};
 use fluvio_sc_schema::partition::{PartitionSpec};
+use fluvio_sc_schema::topic::TopicSpec;
 use fluvio_controlplane_metadata::extended::SpecExt;
-use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 
 use crate::services::auth::AuthServiceContext;
 
@@ -28,16 +35,30 @@ pub async fn handle_fetch_request<AC: AuthContext>(
         return Err(Error::new(ErrorKind::Interrupted, "authorization io error"));
     }
 
-    let partitions: Vec<Metadata<PartitionSpec>> = auth_ctx
+    let candidates: Vec<(String, Metadata<PartitionSpec>)> = auth_ctx
         .global_ctx
         .partitions()
         .store()
         .read()
         .await
         .values()
-        .map(|value| value.inner().clone().into())
+        .map(|value| (value.key().topic.clone(), value.inner().clone().into()))
         .collect();
 
+    // only partitions of topics which can be read
+    let mut partitions = vec![];
+    for (topic, partition) in candidates {
+        match auth_ctx
+            .auth
+            .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Read, &topic)
+            .await
+        {
+            Ok(true) => partitions.push(partition),
+            Ok(false) => trace!("partition: {} is not authorized", partition.name),
+            Err(_) => return Err(Error::new(ErrorKind::Interrupted, "authorization io error")),
+        }
+    }
+
     debug!("flv fetch partitions resp: {} items", partitions.len());
     trace!("flv fetch partitions resp {:#?}", partitions);
 
@@ -0,0 +1,378 @@
+//!
+//! # Reassign Partitions Request
+//!
+//! Generate balanced plan of partition replicas or start moving partitions.
+//! Partitions are moved by partition controller, this only validates and stores
+//! target replicas in partition spec.
+//!
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind};
+
+use tracing::{debug, trace};
+
+use dataplane::ErrorCode;
+use dataplane::api::{RequestMessage, ResponseMessage};
+use fluvio_sc_schema::partition::*;
+use fluvio_sc_schema::topic::{TopicSpec, TopicReplicaParam};
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_controlplane_metadata::spu::store::SpuLocalStorePolicy;
+use fluvio_auth::{AuthContext, InstanceAction};
+
+use crate::core::Context;
+use crate::controllers::topics::TopicWSAction;
+use crate::controllers::topics::generate_replica_map;
+use crate::controllers::partitions::PartitionWSAction;
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for reassign partitions request
+pub async fn handle_reassign_partitions_request<AC: AuthContext>(
+    request: RequestMessage<ReassignPartitionsRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<ReassignPartitionsResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    debug!(
+        "api request: reassign partitions, topics: {:?}, dry run: {}",
+        req.topics, req.dry_run
+    );
+
+    let response = match reassign_partitions(req, auth_ctx).await? {
+        Ok(plan) => ReassignPartitionsResponse {
+            plan,
+            ..Default::default()
+        },
+        Err((error_code, message)) => ReassignPartitionsResponse {
+            error_code,
+            error_message: Some(message),
+            ..Default::default()
+        },
+    };
+
+    trace!("reassign partitions resp {:#?}", response);
+
+    Ok(ResponseMessage::from_header(&header, response))
+}
+
+type PlanResult = Result<ReassignmentPlan, (ErrorCode, String)>;
+
+async fn reassign_partitions<AC: AuthContext>(
+    req: ReassignPartitionsRequest,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<PlanResult, Error> {
+    let ctx = &auth_ctx.global_ctx;
+
+    let plan = if req.plan.partitions.is_empty() {
+        match generate_plan(&req.topics, ctx).await {
+            Ok(plan) => plan,
+            Err(err) => return Ok(Err(err)),
+        }
+    } else {
+        req.plan
+    };
+
+    // reassignment changes topic
+    let topics: HashSet<&String> = plan
+        .partitions
+        .iter()
+        .map(|assignment| &assignment.topic)
+        .collect();
+    for topic in topics {
+        match auth_ctx
+            .auth
+            .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Update, topic)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                trace!("authorization failed");
+                return Ok(Err((
+                    ErrorCode::PermissionDenied,
+                    String::from("permission denied"),
+                )));
+            }
+            Err(_) => return Err(Error::new(ErrorKind::Interrupted, "authorization io error")),
+        }
+    }
+
+    if let Err(err) = validate_plan(&plan, ctx).await {
+        return Ok(Err(err));
+    }
+
+    if !req.dry_run {
+        execute_plan(&plan, req.throttle, ctx).await;
+    }
+
+    Ok(Ok(plan))
+}
+
+/// generate balanced replicas for all partitions of topics using topic replica placement
+async fn generate_plan(topics: &[String], ctx: &Context) -> PlanResult {
+    if topics.is_empty() {
+        return Err((
+            ErrorCode::PartitionError,
+            "plan or topics must be provided".to_owned(),
+        ));
+    }
+
+    let mut plan = ReassignmentPlan::default();
+    for name in topics {
+        let topic = match ctx.topics().store().value(name).await {
+            Some(topic) => topic.inner_owned(),
+            None => {
+                return Err((
+                    ErrorCode::TopicNotFound,
+                    format!("topic '{}' not found", name),
+                ))
+            }
+        };
+
+        let replica_map = &topic.status.replica_map;
+        let replication_factor = replica_map
+            .values()
+            .next()
+            .map(|replicas| replicas.len() as i32)
+            .unwrap_or(0);
+        let ignore_rack_assignment = match &topic.spec {
+            TopicSpec::Computed(param) => param.ignore_rack_assignment,
+            TopicSpec::Assigned(_) => false,
+        };
+        let param = TopicReplicaParam::new(
+            replica_map.len() as i32,
+            replication_factor,
+            ignore_rack_assignment,
+        );
+
+        let next_state = generate_replica_map(ctx.spus().store(), &param).await;
+        if next_state.resolution.no_resource() {
+            return Err((
+                ErrorCode::PartitionError,
+                format!("topic '{}': {}", name, next_state.reason),
+            ));
+        }
+
+        for (partition, replicas) in next_state.replica_map {
+            plan.partitions.push(PartitionAssignment {
+                topic: name.to_owned(),
+                partition,
+                replicas,
+            });
+        }
+    }
+
+    Ok(plan)
+}
+
+/// partitions must exist and not be moving, replicas must be registered spus
+async fn validate_plan(plan: &ReassignmentPlan, ctx: &Context) -> Result<(), (ErrorCode, String)> {
+    let spu_ids = ctx.spus().store().spu_ids().await;
+
+    for assignment in &plan.partitions {
+        let key = ReplicaKey::new(assignment.topic.clone(), assignment.partition);
+        let partition = match ctx.partitions().store().value(&key).await {
+            Some(partition) => partition.inner_owned(),
+            None => {
+                return Err((
+                    ErrorCode::PartitionError,
+                    format!("partition: {} not found", key),
+                ))
+            }
+        };
+
+        if partition.spec.is_reassigning() {
+            return Err((
+                ErrorCode::PartitionError,
+                format!("partition: {} is already being reassigned", key),
+            ));
+        }
+
+        if assignment.replicas.is_empty() {
+            return Err((
+                ErrorCode::PartitionError,
+                format!("partition: {} has no replicas", key),
+            ));
+        }
+
+        let unique: HashSet<&i32> = assignment.replicas.iter().collect();
+        if unique.len() != assignment.replicas.len() {
+            return Err((
+                ErrorCode::PartitionError,
+                format!("partition: {} has duplicated replicas", key),
+            ));
+        }
+
+        if let Some(spu) = assignment
+            .replicas
+            .iter()
+            .find(|spu| !spu_ids.contains(spu))
+        {
+            return Err((
+                ErrorCode::PartitionError,
+                format!("partition: {}, invalid spu id: {}", key, spu),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// store target replicas in partitions, partition controller does the move
+async fn execute_plan(plan: &ReassignmentPlan, throttle: u32, ctx: &Context) {
+    let mut topic_maps: BTreeMap<&String, Vec<&PartitionAssignment>> = BTreeMap::new();
+
+    for assignment in &plan.partitions {
+        let key = ReplicaKey::new(assignment.topic.clone(), assignment.partition);
+        let mut spec = match ctx.partitions().store().value(&key).await {
+            Some(partition) => partition.inner_owned().spec,
+            None => continue,
+        };
+
+        if spec.replicas == assignment.replicas {
+            debug!("partition: {} already has replicas, skipping", key);
+            continue;
+        }
+
+        debug!(
+            "partition: {} moving replicas from: {:?} to: {:?}",
+            key, spec.replicas, assignment.replicas
+        );
+        spec.reassignment = Some(PartitionReassignment::new(
+            assignment.replicas.clone(),
+            throttle,
+        ));
+        ctx.partitions()
+            .send_action(PartitionWSAction::UpdateSpec((key, spec)))
+            .await;
+
+        topic_maps
+            .entry(&assignment.topic)
+            .or_default()
+            .push(assignment);
+    }
+
+    // topic replica map shows where partitions are going
+    for (name, assignments) in topic_maps {
+        if let Some(topic) = ctx.topics().store().value(name).await {
+            let mut status = topic.inner_owned().status;
+            for assignment in assignments {
+                status
+                    .replica_map
+                    .insert(assignment.partition, assignment.replicas.clone());
+            }
+            ctx.topics()
+                .send_action(TopicWSAction::UpdateStatus((name.to_owned(), status)))
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_future::test_async;
+    use fluvio_controlplane_metadata::spu::SpuSpec;
+
+    use crate::config::ScConfig;
+    use crate::services::auth::RootAuthContext;
+    use crate::stores::partition::PartitionAdminMd;
+    use crate::stores::spu::SpuAdminMd;
+    use crate::stores::actions::WSAction;
+
+    use super::*;
+
+    async fn auth_ctx() -> AuthServiceContext<RootAuthContext> {
+        let ctx = Context::shared_metadata(ScConfig::default());
+        ctx.spus()
+            .store()
+            .sync_all(
+                (5000..5003)
+                    .map(|id| SpuAdminMd::with_spec(format!("spu-{}", id), SpuSpec::new(id)))
+                    .collect(),
+            )
+            .await;
+        ctx.partitions()
+            .store()
+            .sync_all(vec![PartitionAdminMd::with_spec(
+                ("topic1", 0),
+                PartitionSpec::new(5000, vec![5000, 5001]),
+            )])
+            .await;
+        AuthServiceContext::new(ctx, RootAuthContext {})
+    }
+
+    fn reassign_request(
+        replicas: Vec<i32>,
+        dry_run: bool,
+    ) -> RequestMessage<ReassignPartitionsRequest> {
+        RequestMessage::new_request(ReassignPartitionsRequest {
+            plan: ReassignmentPlan {
+                partitions: vec![PartitionAssignment {
+                    topic: "topic1".to_owned(),
+                    partition: 0,
+                    replicas,
+                }],
+            },
+            dry_run,
+            throttle: 1000,
+            ..Default::default()
+        })
+    }
+
+    #[test_async]
+    async fn test_reassign_partitions() -> Result<(), ()> {
+        let auth_ctx = auth_ctx().await;
+        let receiver = auth_ctx.global_ctx.partitions().receiver();
+
+        // unknown spu
+        let response = handle_reassign_partitions_request(
+            reassign_request(vec![5001, 5009], false),
+            &auth_ctx,
+        )
+        .await
+        .expect("response")
+        .response;
+        assert_eq!(response.error_code, ErrorCode::PartitionError);
+        assert!(receiver.is_empty());
+
+        // duplicated replicas
+        let response = handle_reassign_partitions_request(
+            reassign_request(vec![5001, 5001], false),
+            &auth_ctx,
+        )
+        .await
+        .expect("response")
+        .response;
+        assert_eq!(response.error_code, ErrorCode::PartitionError);
+
+        // dry run returns plan without moving partition
+        let response =
+            handle_reassign_partitions_request(reassign_request(vec![5001, 5002], true), &auth_ctx)
+                .await
+                .expect("response")
+                .response;
+        assert_eq!(response.error_code, ErrorCode::None);
+        assert_eq!(response.plan.partitions[0].replicas, vec![5001, 5002]);
+        assert!(receiver.is_empty());
+
+        // target replicas are stored in partition spec
+        let response = handle_reassign_partitions_request(
+            reassign_request(vec![5001, 5002], false),
+            &auth_ctx,
+        )
+        .await
+        .expect("response")
+        .response;
+        assert_eq!(response.error_code, ErrorCode::None);
+        match receiver.try_recv().expect("partition action") {
+            WSAction::UpdateSpec((key, spec)) => {
+                assert_eq!(key, ReplicaKey::new("topic1", 0));
+                assert_eq!(spec.replicas, vec![5000, 5001]);
+                let reassignment = spec.reassignment.expect("reassignment");
+                assert_eq!(reassignment.target_replicas, vec![5001, 5002]);
+                assert_eq!(reassignment.throttle, 1000);
+            }
+            _ => panic!("expected spec update"),
+        }
+
+        Ok(())
+    }
+}
@@ -68,6 +68,8 @@ where
             auth_context,
         ));
 
+        let _connection = ctx.global_ctx.metrics().public_connections.track();
+
         let (sink, mut stream) = socket.split();
         let mut api_stream = stream.api_stream::<AdminPublicRequest, AdminPublicApiKey>();
         let mut shared_sink = sink.as_shared();
@@ -104,6 +106,24 @@ where
                 shared_sink,
                 "list handler"
             ),
+            AdminPublicRequest::UpdateRequest(request) => call_service!(
+                request,
+                super::update::handle_update_request(request, &service_context),
+                shared_sink,
+                "update handler"
+            ),
+            AdminPublicRequest::ReassignPartitionsRequest(request) => call_service!(
+                request,
+                super::partition::handle_reassign_partitions_request(request, &service_context),
+                shared_sink,
+                "reassign partitions handler"
+            ),
+            AdminPublicRequest::ElectLeadersRequest(request) => call_service!(
+                request,
+                super::partition::handle_elect_leaders_request(request, &service_context),
+                shared_sink,
+                "elect leaders handler"
+            ),
             AdminPublicRequest::WatchRequest(request) =>
 
                 super::watch::handle_watch_request(
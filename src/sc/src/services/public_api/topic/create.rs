@@ -17,7 +17,7 @@ use dataplane::ErrorCode;
 
 use fluvio_sc_schema::Status;
 use fluvio_controlplane_metadata::topic::TopicSpec;
-use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_auth::{AuthContext, InstanceAction};
 use fluvio_controlplane_metadata::extended::SpecExt;
 
 use crate::core::Context;
@@ -38,7 +38,7 @@ pub async fn handle_create_topics_request<AC: AuthContext>(
 
     if let Ok(authorized) = auth_ctx
         .auth
-        .allow_type_action(TopicSpec::OBJECT_TYPE, TypeAction::Create)
+        .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Create, &name)
         .await
     {
         if !authorized {
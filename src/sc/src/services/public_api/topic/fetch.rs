@@ -4,7 +4,7 @@ use std::io::{Error, ErrorKind};
 use fluvio_controlplane_metadata::store::KeyFilter;
 use fluvio_sc_schema::objects::{ListResponse, Metadata};
 use fluvio_sc_schema::topic::TopicSpec;
-use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 use fluvio_controlplane_metadata::extended::SpecExt;
 
 use crate::services::auth::AuthServiceContext;
@@ -28,7 +28,7 @@ pub async fn handle_fetch_topics_request<AC: AuthContext>(
         return Err(Error::new(ErrorKind::Interrupted, "authorization io error"));
     }
 
-    let topics: Vec<Metadata<TopicSpec>> = auth_ctx
+    let candidates: Vec<Metadata<TopicSpec>> = auth_ctx
         .global_ctx
         .topics()
         .store()
@@ -44,6 +44,20 @@ pub async fn handle_fetch_topics_request<AC: AuthContext>(
         })
         .collect();
 
+    // only topics which can be read
+    let mut topics = vec![];
+    for topic in candidates {
+        match auth_ctx
+            .auth
+            .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Read, &topic.name)
+            .await
+        {
+            Ok(true) => topics.push(topic),
+            Ok(false) => trace!("topic: {} is not authorized", topic.name),
+            Err(_) => return Err(Error::new(ErrorKind::Interrupted, "authorization io error")),
+        }
+    }
+
     debug!("flv fetch topics resp: {} items", topics.len());
     trace!("flv fetch topics resp {:#?}", topics);
 
@@ -0,0 +1,98 @@
+//!
+//! # Update Topic Request
+//!
+//! Update topic request handler. Partitions can only be added to computed topics.
+//! New spec is sent to KV store, topic controller places new partitions on SPUs.
+//!
+use tracing::{debug, trace};
+use std::io::{Error, ErrorKind};
+
+use dataplane::ErrorCode;
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::objects::TopicUpdate;
+use fluvio_controlplane_metadata::topic::TopicSpec;
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::controllers::topics::TopicWSAction;
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for update topic request
+pub async fn handle_update_topic<AC: AuthContext>(
+    topic_name: String,
+    update: TopicUpdate,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<Status, Error> {
+    debug!("api request: update topic '{}': {:?}", topic_name, update);
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Update, &topic_name)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                topic_name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(Error::new(ErrorKind::Interrupted, "authorization io error"));
+    }
+
+    let topic = match auth_ctx
+        .global_ctx
+        .topics()
+        .store()
+        .value(&topic_name)
+        .await
+    {
+        Some(topic) => topic.inner_owned(),
+        None => {
+            return Ok(Status::new(
+                topic_name.clone(),
+                ErrorCode::TopicNotFound,
+                Some("not found".to_owned()),
+            ))
+        }
+    };
+
+    let mut param = match topic.spec {
+        TopicSpec::Computed(param) => param,
+        TopicSpec::Assigned(_) => {
+            return Ok(Status::new(
+                topic_name.clone(),
+                ErrorCode::TopicError,
+                Some("partitions of assigned topic can't be changed".to_owned()),
+            ))
+        }
+    };
+
+    if update.partitions <= param.partitions {
+        return Ok(Status::new(
+            topic_name.clone(),
+            ErrorCode::TopicError,
+            Some(format!(
+                "partitions can only be increased, topic has {} partitions",
+                param.partitions
+            )),
+        ));
+    }
+
+    param.partitions = update.partitions;
+    auth_ctx
+        .global_ctx
+        .topics()
+        .send_action(TopicWSAction::UpdateSpec((
+            topic_name.clone(),
+            TopicSpec::Computed(param),
+        )))
+        .await;
+
+    let status = Status::new_ok(topic_name);
+    trace!("flv update topic resp {:#?}", status);
+
+    Ok(status)
+}
@@ -0,0 +1,32 @@
+//!
+//! # Update Request
+//!
+//! Update request handler, dispatches change to handler of object type.
+//!
+use tracing::trace;
+use std::io::Error;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::objects::{UpdateRequest, AllUpdatableSpec};
+use fluvio_auth::{AuthContext};
+
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for update request
+pub async fn handle_update_request<AC: AuthContext>(
+    request: RequestMessage<UpdateRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<Status>, Error> {
+    let (header, req) = request.get_header_request();
+
+    let status = match req.spec {
+        AllUpdatableSpec::Topic(update) => {
+            super::topic::handle_update_topic(req.name, update, auth_ctx).await?
+        }
+    };
+
+    trace!("flv update resp {:#?}", status);
+
+    Ok(ResponseMessage::from_header(&header, status))
+}
@@ -0,0 +1,149 @@
+use tracing::trace;
+use std::convert::TryInto;
+use std::io::Error as IoError;
+
+use dataplane::bytes::Buf;
+use dataplane::derive::{Decode, Encode};
+use dataplane::api::Request;
+use dataplane::api::ApiMessage;
+use dataplane::api::api_decode;
+use dataplane::api::RequestHeader;
+use dataplane::api::RequestMessage;
+use dataplane::ErrorCode;
+
+use super::KafkaApiKey;
+use super::versions::KafkaApiVersionsRequest;
+use super::metadata::MetadataRequest;
+use super::list_offsets::ListOffsetsRequest;
+use super::produce::KafkaProduceRequest;
+use super::fetch::KafkaFetchRequest;
+use super::find_coordinator::FindCoordinatorRequest;
+use super::offset_commit::OffsetCommitRequest;
+use super::offset_fetch::OffsetFetchRequest;
+
+/// Request from Kafka client
+#[derive(Debug, Encode)]
+pub enum KafkaRequest {
+    KafkaApiVersionsRequest(RequestMessage<KafkaApiVersionsRequest>),
+    MetadataRequest(RequestMessage<MetadataRequest>),
+    ListOffsetsRequest(RequestMessage<ListOffsetsRequest>),
+    KafkaProduceRequest(RequestMessage<KafkaProduceRequest>),
+    KafkaFetchRequest(RequestMessage<KafkaFetchRequest>),
+    FindCoordinatorRequest(RequestMessage<FindCoordinatorRequest>),
+    OffsetCommitRequest(RequestMessage<OffsetCommitRequest>),
+    OffsetFetchRequest(RequestMessage<OffsetFetchRequest>),
+    /// api key or version which is not supported, body is not decoded
+    Unsupported(RequestHeader),
+}
+
+/// Response to unsupported request, Kafka clients only read error code
+#[derive(Encode, Decode, Default, Debug)]
+pub struct UnsupportedResponse {
+    pub error_code: ErrorCode,
+}
+
+impl Default for KafkaRequest {
+    fn default() -> Self {
+        Self::KafkaApiVersionsRequest(RequestMessage::<KafkaApiVersionsRequest>::default())
+    }
+}
+
+impl ApiMessage for KafkaRequest {
+    type ApiKey = KafkaApiKey;
+
+    fn decode_with_header<T>(src: &mut T, header: RequestHeader) -> Result<Self, IoError>
+    where
+        Self: Default + Sized,
+        Self::ApiKey: Sized,
+        T: Buf,
+    {
+        trace!("decoding kafka request with header: {:#?}", header);
+        let api_key: KafkaApiKey = match header.api_key().try_into() {
+            Ok(api_key) => api_key,
+            Err(_) => return Ok(Self::Unsupported(header)),
+        };
+        match api_key {
+            KafkaApiKey::ApiVersions => {
+                // body of newer versions is not needed, client retries with version we support
+                let request = KafkaApiVersionsRequest::default();
+                Ok(Self::KafkaApiVersionsRequest(RequestMessage::new(
+                    header, request,
+                )))
+            }
+            KafkaApiKey::Metadata if is_supported::<MetadataRequest>(&header) => {
+                api_decode!(Self, MetadataRequest, src, header)
+            }
+            KafkaApiKey::ListOffsets if is_supported::<ListOffsetsRequest>(&header) => {
+                api_decode!(Self, ListOffsetsRequest, src, header)
+            }
+            KafkaApiKey::Produce if is_supported::<KafkaProduceRequest>(&header) => {
+                api_decode!(Self, KafkaProduceRequest, src, header)
+            }
+            KafkaApiKey::Fetch if is_supported::<KafkaFetchRequest>(&header) => {
+                api_decode!(Self, KafkaFetchRequest, src, header)
+            }
+            KafkaApiKey::FindCoordinator if is_supported::<FindCoordinatorRequest>(&header) => {
+                api_decode!(Self, FindCoordinatorRequest, src, header)
+            }
+            KafkaApiKey::OffsetCommit if is_supported::<OffsetCommitRequest>(&header) => {
+                api_decode!(Self, OffsetCommitRequest, src, header)
+            }
+            KafkaApiKey::OffsetFetch if is_supported::<OffsetFetchRequest>(&header) => {
+                api_decode!(Self, OffsetFetchRequest, src, header)
+            }
+            _ => Ok(Self::Unsupported(header)),
+        }
+    }
+}
+
+fn is_supported<R: Request>(header: &RequestHeader) -> bool {
+    (R::MIN_API_VERSION..=R::MAX_API_VERSION).contains(&header.api_version())
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::io::Cursor;
+
+    use dataplane::core::Encoder;
+    use dataplane::api::ApiMessage;
+    use dataplane::api::RequestHeader;
+
+    use super::KafkaRequest;
+    use super::KafkaApiKey;
+
+    fn decode(api_key: u16, api_version: i16) -> KafkaRequest {
+        let mut header = RequestHeader::new(api_key);
+        header.set_api_version(api_version);
+        header.set_correlation_id(5);
+        let mut src = vec![];
+        header.encode(&mut src, 0).expect("encode");
+        // body of unsupported request is ignored
+        src.extend_from_slice(&[0xff; 8]);
+        KafkaRequest::decode_from(&mut Cursor::new(&src)).expect("decode")
+    }
+
+    #[test]
+    fn test_decode_unsupported() {
+        // join group
+        match decode(11, 2) {
+            KafkaRequest::Unsupported(header) => {
+                assert_eq!(header.api_key(), 11);
+                assert_eq!(header.correlation_id(), 5);
+            }
+            request => panic!("expected unsupported: {:?}", request),
+        }
+
+        // fetch versions before 4 don't have isolation level
+        assert!(matches!(
+            decode(KafkaApiKey::Fetch as u16, 3),
+            KafkaRequest::Unsupported(_)
+        ));
+
+        // newer api versions request is answered with versions we support
+        assert!(matches!(
+            decode(KafkaApiKey::ApiVersions as u16, 5),
+            KafkaRequest::KafkaApiVersionsRequest(_)
+        ));
+    }
+}
@@ -0,0 +1,23 @@
+use dataplane::derive::Encode;
+use dataplane::derive::Decode;
+
+/// Api Key for Kafka APIs supported by SPU
+#[fluvio(encode_discriminant)]
+#[derive(PartialEq, Debug, Encode, Decode, Clone, Copy)]
+#[repr(u16)]
+pub enum KafkaApiKey {
+    Produce = 0,
+    Fetch = 1,
+    ListOffsets = 2,
+    Metadata = 3,
+    OffsetCommit = 8,
+    OffsetFetch = 9,
+    FindCoordinator = 10,
+    ApiVersions = 18,
+}
+
+impl Default for KafkaApiKey {
+    fn default() -> Self {
+        Self::ApiVersions
+    }
+}
@@ -0,0 +1,214 @@
+//!
+//! # Kafka Fetch
+//!
+//! Records are sent from log files with zero copy, same as SPU fetch.
+//! Fetch sessions are not supported, every request is full fetch.
+//!
+use std::fmt::Debug;
+use std::io::Error as IoError;
+use std::marker::PhantomData;
+
+use tracing::trace;
+use bytes::BytesMut;
+
+use dataplane::api::Request;
+use dataplane::core::{Decoder, Encoder, Version};
+use dataplane::derive::{Decode, Encode, FluvioDefault};
+use dataplane::record::FileRecordSet;
+use dataplane::store::{FileWrite, StoreValue};
+use dataplane::ErrorCode;
+use dataplane::Isolation;
+
+use super::KafkaApiKey;
+
+pub type KafkaFileFetchResponse = KafkaFetchResponse<FileRecordSet>;
+pub type KafkaFileTopicResponse = KafkaFetchTopicResponse<FileRecordSet>;
+pub type KafkaFilePartitionResponse = KafkaFetchPartitionResponse<FileRecordSet>;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaFetchRequest {
+    /// The broker id of the follower, or -1 if this request is from a consumer.
+    pub replica_id: i32,
+
+    /// The maximum time in milliseconds to wait for the response.
+    pub max_wait_ms: i32,
+
+    /// The minimum bytes to accumulate in the response.
+    pub min_bytes: i32,
+
+    #[fluvio(min_version = 3)]
+    pub max_bytes: i32,
+
+    #[fluvio(min_version = 4)]
+    pub isolation_level: Isolation,
+
+    #[fluvio(min_version = 7)]
+    pub session_id: i32,
+
+    #[fluvio(min_version = 7)]
+    pub session_epoch: i32,
+
+    pub topics: Vec<KafkaFetchTopic>,
+
+    /// In an incremental fetch request, the partitions to remove.
+    #[fluvio(min_version = 7)]
+    pub forgotten_topics: Vec<KafkaForgottenTopic>,
+
+    #[fluvio(min_version = 11)]
+    pub rack_id: String,
+}
+
+impl Request for KafkaFetchRequest {
+    const API_KEY: u16 = KafkaApiKey::Fetch as u16;
+    const MIN_API_VERSION: i16 = 4;
+    const MAX_API_VERSION: i16 = 11;
+    const DEFAULT_API_VERSION: i16 = 11;
+    type Response = KafkaFileFetchResponse;
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaFetchTopic {
+    pub topic: String,
+    pub partitions: Vec<KafkaFetchPartition>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaFetchPartition {
+    pub partition: i32,
+
+    #[fluvio(min_version = 9)]
+    pub current_leader_epoch: i32,
+
+    pub fetch_offset: i64,
+
+    /// only used by followers
+    #[fluvio(min_version = 5)]
+    pub log_start_offset: i64,
+
+    pub partition_max_bytes: i32,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaForgottenTopic {
+    pub topic: String,
+    pub partitions: Vec<i32>,
+}
+
+#[derive(Encode, Decode, FluvioDefault, Debug)]
+pub struct KafkaFetchResponse<R>
+where
+    R: Encoder + Decoder + Default + Debug,
+{
+    #[fluvio(min_version = 1)]
+    pub throttle_time_ms: i32,
+
+    #[fluvio(min_version = 7)]
+    pub error_code: ErrorCode,
+
+    #[fluvio(min_version = 7)]
+    pub session_id: i32,
+
+    pub topics: Vec<KafkaFetchTopicResponse<R>>,
+}
+
+impl FileWrite for KafkaFileFetchResponse {
+    fn file_encode(
+        &self,
+        src: &mut BytesMut,
+        data: &mut Vec<StoreValue>,
+        version: Version,
+    ) -> Result<(), IoError> {
+        trace!("file encoding kafka fetch response");
+        if version >= 1 {
+            self.throttle_time_ms.encode(src, version)?;
+        }
+        if version >= 7 {
+            self.error_code.encode(src, version)?;
+            self.session_id.encode(src, version)?;
+        }
+        self.topics.file_encode(src, data, version)?;
+        Ok(())
+    }
+}
+
+#[derive(Encode, Decode, FluvioDefault, Debug)]
+pub struct KafkaFetchTopicResponse<R>
+where
+    R: Encoder + Decoder + Default + Debug,
+{
+    pub name: String,
+    pub partitions: Vec<KafkaFetchPartitionResponse<R>>,
+    pub data: PhantomData<R>,
+}
+
+impl FileWrite for KafkaFileTopicResponse {
+    fn file_encode(
+        &self,
+        src: &mut BytesMut,
+        data: &mut Vec<StoreValue>,
+        version: Version,
+    ) -> Result<(), IoError> {
+        self.name.encode(src, version)?;
+        self.partitions.file_encode(src, data, version)?;
+        Ok(())
+    }
+}
+
+#[derive(Encode, Decode, FluvioDefault, Debug)]
+pub struct KafkaFetchPartitionResponse<R>
+where
+    R: Encoder + Decoder + Default + Debug,
+{
+    pub partition_index: i32,
+    pub error_code: ErrorCode,
+    pub high_watermark: i64,
+
+    #[fluvio(min_version = 4)]
+    pub last_stable_offset: i64,
+
+    #[fluvio(min_version = 5)]
+    pub log_start_offset: i64,
+
+    /// transactions are not supported, always empty
+    #[fluvio(min_version = 4)]
+    pub aborted_transactions: Vec<KafkaAbortedTransaction>,
+
+    /// replica consumer should fetch from, -1 for leader
+    #[fluvio(min_version = 11)]
+    pub preferred_read_replica: i32,
+
+    pub records: R,
+}
+
+impl FileWrite for KafkaFilePartitionResponse {
+    fn file_encode(
+        &self,
+        src: &mut BytesMut,
+        data: &mut Vec<StoreValue>,
+        version: Version,
+    ) -> Result<(), IoError> {
+        self.partition_index.encode(src, version)?;
+        self.error_code.encode(src, version)?;
+        self.high_watermark.encode(src, version)?;
+        if version >= 4 {
+            self.last_stable_offset.encode(src, version)?;
+        }
+        if version >= 5 {
+            self.log_start_offset.encode(src, version)?;
+        }
+        if version >= 4 {
+            self.aborted_transactions.encode(src, version)?;
+        }
+        if version >= 11 {
+            self.preferred_read_replica.encode(src, version)?;
+        }
+        self.records.file_encode(src, data, version)?;
+        Ok(())
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaAbortedTransaction {
+    pub producer_id: i64,
+    pub first_offset: i64,
+}
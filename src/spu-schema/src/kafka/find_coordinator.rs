@@ -0,0 +1,46 @@
+//!
+//! # Kafka Find Coordinator
+//!
+//! Broker which stores offsets committed by consumer group.
+//! Every SPU with kafka listener is coordinator of any group.
+//!
+use dataplane::api::Request;
+use dataplane::derive::{Decode, Encode};
+use dataplane::ErrorCode;
+
+use super::KafkaApiKey;
+
+/// coordinator of consumer group
+pub const GROUP_KEY_TYPE: i8 = 0;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct FindCoordinatorRequest {
+    /// group id or transactional id
+    pub key: String,
+
+    #[fluvio(min_version = 1)]
+    pub key_type: i8,
+}
+
+impl Request for FindCoordinatorRequest {
+    const API_KEY: u16 = KafkaApiKey::FindCoordinator as u16;
+    const MIN_API_VERSION: i16 = 0;
+    const MAX_API_VERSION: i16 = 2;
+    const DEFAULT_API_VERSION: i16 = 2;
+    type Response = FindCoordinatorResponse;
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct FindCoordinatorResponse {
+    #[fluvio(min_version = 1)]
+    pub throttle_time_ms: i32,
+
+    pub error_code: ErrorCode,
+
+    #[fluvio(min_version = 1)]
+    pub error_message: String,
+
+    pub node_id: i32,
+    pub host: String,
+    pub port: i32,
+}
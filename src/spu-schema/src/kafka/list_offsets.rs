@@ -0,0 +1,80 @@
+//!
+//! # Kafka List Offsets
+//!
+//! Offset of earliest or latest record of partition or first offset at or after timestamp.
+//!
+use dataplane::api::Request;
+use dataplane::derive::{Decode, Encode};
+use dataplane::ErrorCode;
+use dataplane::Isolation;
+
+use super::KafkaApiKey;
+
+/// timestamp to request offset of next record to be written
+pub const LATEST_TIMESTAMP: i64 = -1;
+
+/// timestamp to request offset of first record in partition
+pub const EARLIEST_TIMESTAMP: i64 = -2;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct ListOffsetsRequest {
+    /// The broker id of the requestor, or -1 if this request is being made by a normal consumer
+    pub replica_id: i32,
+
+    #[fluvio(min_version = 2)]
+    pub isolation_level: Isolation,
+
+    pub topics: Vec<ListOffsetsTopic>,
+}
+
+impl Request for ListOffsetsRequest {
+    const API_KEY: u16 = KafkaApiKey::ListOffsets as u16;
+    const MIN_API_VERSION: i16 = 1;
+    const MAX_API_VERSION: i16 = 5;
+    const DEFAULT_API_VERSION: i16 = 5;
+    type Response = ListOffsetsResponse;
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct ListOffsetsTopic {
+    pub name: String,
+    pub partitions: Vec<ListOffsetsPartition>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct ListOffsetsPartition {
+    pub partition_index: i32,
+
+    #[fluvio(min_version = 4)]
+    pub current_leader_epoch: i32,
+
+    /// timestamp in millis, or one of the special timestamps
+    pub timestamp: i64,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct ListOffsetsResponse {
+    #[fluvio(min_version = 2)]
+    pub throttle_time_ms: i32,
+
+    pub topics: Vec<ListOffsetsTopicResponse>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct ListOffsetsTopicResponse {
+    pub name: String,
+    pub partitions: Vec<ListOffsetsPartitionResponse>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct ListOffsetsPartitionResponse {
+    pub partition_index: i32,
+    pub error_code: ErrorCode,
+
+    /// timestamp of returned offset, always -1
+    pub timestamp: i64,
+    pub offset: i64,
+
+    #[fluvio(min_version = 4)]
+    pub leader_epoch: i32,
+}
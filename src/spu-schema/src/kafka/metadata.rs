@@ -0,0 +1,241 @@
+//!
+//! # Kafka Metadata
+//!
+//! Brokers and partition leaders of topics.
+//! Every SPU with kafka listener is a broker, broker id is SPU id.
+//!
+use std::io::Error as IoError;
+
+use dataplane::api::Request;
+use dataplane::bytes::{Buf, BufMut};
+use dataplane::core::{Decoder, Encoder, Version};
+use dataplane::derive::{Decode, Encode};
+use dataplane::ErrorCode;
+
+use super::KafkaApiKey;
+
+/// authorized operations are not reported
+pub const AUTHORIZED_OPERATIONS_OMITTED: i32 = i32::MIN;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct MetadataRequest {
+    /// The topics to fetch metadata for
+    pub topics: MetadataRequestTopics,
+
+    /// Topics are never created by metadata request
+    #[fluvio(min_version = 4)]
+    pub allow_auto_topic_creation: bool,
+
+    #[fluvio(min_version = 8)]
+    pub include_cluster_authorized_operations: bool,
+
+    #[fluvio(min_version = 8)]
+    pub include_topic_authorized_operations: bool,
+}
+
+impl Request for MetadataRequest {
+    const API_KEY: u16 = KafkaApiKey::Metadata as u16;
+    const MIN_API_VERSION: i16 = 0;
+    const MAX_API_VERSION: i16 = 8;
+    const DEFAULT_API_VERSION: i16 = 8;
+    type Response = MetadataResponse;
+}
+
+impl MetadataRequest {
+    /// names of requested topics, none if all topics are requested
+    pub fn topic_names(&self) -> Option<Vec<&str>> {
+        self.topics
+            .0
+            .as_ref()
+            .map(|topics| topics.iter().map(|topic| topic.name.as_str()).collect())
+    }
+}
+
+/// Null array requests all topics. In version 0, empty array requests all topics.
+#[derive(Default, Debug)]
+pub struct MetadataRequestTopics(pub Option<Vec<MetadataRequestTopic>>);
+
+impl Encoder for MetadataRequestTopics {
+    fn write_size(&self, version: Version) -> usize {
+        match &self.0 {
+            Some(topics) => topics.write_size(version),
+            None => 4,
+        }
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: BufMut,
+    {
+        match &self.0 {
+            Some(topics) => topics.encode(dest, version),
+            None => (-1i32).encode(dest, version),
+        }
+    }
+}
+
+impl Decoder for MetadataRequestTopics {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: Buf,
+    {
+        let mut len: i32 = 0;
+        len.decode(src, version)?;
+        if len < 0 || (len == 0 && version == 0) {
+            self.0 = None;
+            return Ok(());
+        }
+
+        let mut topics = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            topics.push(MetadataRequestTopic::decode_from(src, version)?);
+        }
+        self.0 = Some(topics);
+        Ok(())
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct MetadataRequestTopic {
+    pub name: String,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct MetadataResponse {
+    #[fluvio(min_version = 3)]
+    pub throttle_time_ms: i32,
+
+    pub brokers: Vec<MetadataResponseBroker>,
+
+    #[fluvio(min_version = 2)]
+    pub cluster_id: String,
+
+    #[fluvio(min_version = 1)]
+    pub controller_id: i32,
+
+    pub topics: Vec<MetadataResponseTopic>,
+
+    #[fluvio(min_version = 8)]
+    pub cluster_authorized_operations: i32,
+}
+
+#[derive(Decode, Encode, Default, Debug, PartialEq)]
+pub struct MetadataResponseBroker {
+    pub node_id: i32,
+    pub host: String,
+    pub port: i32,
+
+    #[fluvio(min_version = 1)]
+    pub rack: String,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct MetadataResponseTopic {
+    pub error_code: ErrorCode,
+    pub name: String,
+
+    #[fluvio(min_version = 1)]
+    pub is_internal: bool,
+
+    pub partitions: Vec<MetadataResponsePartition>,
+
+    #[fluvio(min_version = 8)]
+    pub topic_authorized_operations: i32,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct MetadataResponsePartition {
+    pub error_code: ErrorCode,
+    pub partition_index: i32,
+    pub leader_id: i32,
+
+    /// Leader epoch is not tracked, always -1
+    #[fluvio(min_version = 7)]
+    pub leader_epoch: i32,
+
+    pub replica_nodes: Vec<i32>,
+    pub isr_nodes: Vec<i32>,
+
+    #[fluvio(min_version = 5)]
+    pub offline_replicas: Vec<i32>,
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::io::Cursor;
+
+    use dataplane::core::{Decoder, Encoder};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_metadata_request_topics() {
+        // null array
+        let src: Vec<u8> = vec![0xff, 0xff, 0xff, 0xff, 0x00];
+        let request = MetadataRequest::decode_from(&mut Cursor::new(&src), 4).expect("decode");
+        assert_eq!(request.topic_names(), None);
+
+        // empty array in version 0
+        let src: Vec<u8> = vec![0x00, 0x00, 0x00, 0x00];
+        let request = MetadataRequest::decode_from(&mut Cursor::new(&src), 0).expect("decode");
+        assert_eq!(request.topic_names(), None);
+
+        // empty array in version 1
+        let request = MetadataRequest::decode_from(&mut Cursor::new(&src), 1).expect("decode");
+        assert_eq!(request.topic_names(), Some(vec![]));
+
+        let src: Vec<u8> = vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x74, 0x31, 0x01];
+        let request = MetadataRequest::decode_from(&mut Cursor::new(&src), 4).expect("decode");
+        assert_eq!(request.topic_names(), Some(vec!["t1"]));
+        assert!(request.allow_auto_topic_creation);
+    }
+
+    #[test]
+    fn test_encode_metadata_response() {
+        let response = MetadataResponse {
+            brokers: vec![MetadataResponseBroker {
+                node_id: 5001,
+                host: "localhost".to_owned(),
+                port: 9092,
+                rack: "".to_owned(),
+            }],
+            controller_id: 5001,
+            topics: vec![MetadataResponseTopic {
+                name: "t1".to_owned(),
+                partitions: vec![MetadataResponsePartition {
+                    partition_index: 0,
+                    leader_id: 5001,
+                    leader_epoch: -1,
+                    replica_nodes: vec![5001],
+                    isr_nodes: vec![5001],
+                    ..Default::default()
+                }],
+                topic_authorized_operations: AUTHORIZED_OPERATIONS_OMITTED,
+                ..Default::default()
+            }],
+            cluster_authorized_operations: AUTHORIZED_OPERATIONS_OMITTED,
+            ..Default::default()
+        };
+
+        for version in 0..=MetadataRequest::MAX_API_VERSION {
+            let mut dest = vec![];
+            response.encode(&mut dest, version).expect("encode");
+            assert_eq!(dest.len(), response.write_size(version));
+
+            let decoded =
+                MetadataResponse::decode_from(&mut Cursor::new(&dest), version).expect("decode");
+            assert_eq!(decoded.brokers, response.brokers);
+            assert_eq!(decoded.topics[0].name, "t1");
+            assert_eq!(decoded.topics[0].partitions[0].isr_nodes, vec![5001]);
+        }
+
+        // version 0 has only brokers and topics
+        let mut dest = vec![];
+        response.encode(&mut dest, 0).expect("encode");
+        assert_eq!(
+            dest.len(),
+            4 + (4 + 2 + 9 + 4) + 4 + (2 + 2 + 2 + 4 + (2 + 4 + 4 + 4 + 4 + 4 + 4))
+        );
+    }
+}
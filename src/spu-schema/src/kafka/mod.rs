@@ -0,0 +1,21 @@
+//!
+//! # Kafka Wire Protocol
+//!
+//! Subset of Kafka APIs served by the SPU kafka compatible listener,
+//! so standard Kafka clients can produce to and consume from Fluvio topics.
+//! Consumer groups are not managed, only consumers which assign partitions can commit offsets.
+//! Only non flexible versions are supported.
+//!
+mod api_key;
+mod api;
+pub mod versions;
+pub mod metadata;
+pub mod list_offsets;
+pub mod produce;
+pub mod fetch;
+pub mod find_coordinator;
+pub mod offset_commit;
+pub mod offset_fetch;
+
+pub use self::api_key::*;
+pub use self::api::{KafkaRequest, UnsupportedResponse};
@@ -0,0 +1,70 @@
+//!
+//! # Kafka Offset Commit
+//!
+//! Offsets committed by consumer group, stored as consumer offsets of partitions.
+//! Groups are not managed, so generation and member are not checked.
+//! Versions with retention time, which are before group instances, are supported.
+//!
+use dataplane::api::Request;
+use dataplane::derive::{Decode, Encode};
+use dataplane::ErrorCode;
+
+use super::KafkaApiKey;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetCommitRequest {
+    pub group_id: String,
+
+    /// -1 for consumers which are not members of group
+    pub generation_id: i32,
+
+    pub member_id: String,
+
+    /// offsets are kept with partition, retention is ignored
+    pub retention_time_ms: i64,
+
+    pub topics: Vec<OffsetCommitRequestTopic>,
+}
+
+impl Request for OffsetCommitRequest {
+    const API_KEY: u16 = KafkaApiKey::OffsetCommit as u16;
+    const MIN_API_VERSION: i16 = 2;
+    const MAX_API_VERSION: i16 = 4;
+    const DEFAULT_API_VERSION: i16 = 4;
+    type Response = OffsetCommitResponse;
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetCommitRequestTopic {
+    pub name: String,
+    pub partitions: Vec<OffsetCommitRequestPartition>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetCommitRequestPartition {
+    pub partition_index: i32,
+    pub committed_offset: i64,
+
+    /// metadata is not stored
+    pub committed_metadata: String,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetCommitResponse {
+    #[fluvio(min_version = 3)]
+    pub throttle_time_ms: i32,
+
+    pub topics: Vec<OffsetCommitResponseTopic>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetCommitResponseTopic {
+    pub name: String,
+    pub partitions: Vec<OffsetCommitResponsePartition>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetCommitResponsePartition {
+    pub partition_index: i32,
+    pub error_code: ErrorCode,
+}
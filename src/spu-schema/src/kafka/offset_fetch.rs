@@ -0,0 +1,136 @@
+//!
+//! # Kafka Offset Fetch
+//!
+//! Offsets committed by consumer group, -1 if group has not committed offset for partition.
+//!
+use std::io::Error as IoError;
+
+use dataplane::api::Request;
+use dataplane::bytes::{Buf, BufMut};
+use dataplane::core::{Decoder, Encoder, Version};
+use dataplane::derive::{Decode, Encode};
+use dataplane::ErrorCode;
+
+use super::KafkaApiKey;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetFetchRequest {
+    pub group_id: String,
+    pub topics: OffsetFetchRequestTopics,
+}
+
+impl Request for OffsetFetchRequest {
+    const API_KEY: u16 = KafkaApiKey::OffsetFetch as u16;
+    const MIN_API_VERSION: i16 = 1;
+    const MAX_API_VERSION: i16 = 5;
+    const DEFAULT_API_VERSION: i16 = 5;
+    type Response = OffsetFetchResponse;
+}
+
+/// Null array requests all partitions with offset committed by group
+#[derive(Default, Debug)]
+pub struct OffsetFetchRequestTopics(pub Option<Vec<OffsetFetchRequestTopic>>);
+
+impl Encoder for OffsetFetchRequestTopics {
+    fn write_size(&self, version: Version) -> usize {
+        match &self.0 {
+            Some(topics) => topics.write_size(version),
+            None => 4,
+        }
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: BufMut,
+    {
+        match &self.0 {
+            Some(topics) => topics.encode(dest, version),
+            None => (-1i32).encode(dest, version),
+        }
+    }
+}
+
+impl Decoder for OffsetFetchRequestTopics {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: Buf,
+    {
+        let mut len: i32 = 0;
+        len.decode(src, version)?;
+        if len < 0 {
+            self.0 = None;
+            return Ok(());
+        }
+
+        let mut topics = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            topics.push(OffsetFetchRequestTopic::decode_from(src, version)?);
+        }
+        self.0 = Some(topics);
+        Ok(())
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetFetchRequestTopic {
+    pub name: String,
+    pub partition_indexes: Vec<i32>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetFetchResponse {
+    #[fluvio(min_version = 3)]
+    pub throttle_time_ms: i32,
+
+    pub topics: Vec<OffsetFetchResponseTopic>,
+
+    #[fluvio(min_version = 2)]
+    pub error_code: ErrorCode,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetFetchResponseTopic {
+    pub name: String,
+    pub partitions: Vec<OffsetFetchResponsePartition>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct OffsetFetchResponsePartition {
+    pub partition_index: i32,
+    pub committed_offset: i64,
+
+    /// Leader epoch is not tracked, always -1
+    #[fluvio(min_version = 5)]
+    pub committed_leader_epoch: i32,
+
+    pub metadata: String,
+    pub error_code: ErrorCode,
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::io::Cursor;
+
+    use dataplane::core::Decoder;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_offset_fetch_request_topics() {
+        // null array
+        let src: Vec<u8> = vec![0x00, 0x01, 0x67, 0xff, 0xff, 0xff, 0xff];
+        let request = OffsetFetchRequest::decode_from(&mut Cursor::new(&src), 5).expect("decode");
+        assert_eq!(request.group_id, "g");
+        assert!(request.topics.0.is_none());
+
+        let src: Vec<u8> = vec![
+            0x00, 0x01, 0x67, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x74, 0x31, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x03,
+        ];
+        let request = OffsetFetchRequest::decode_from(&mut Cursor::new(&src), 5).expect("decode");
+        let topics = request.topics.0.expect("topics");
+        assert_eq!(topics[0].name, "t1");
+        assert_eq!(topics[0].partition_indexes, vec![3]);
+    }
+}
@@ -0,0 +1,47 @@
+//!
+//! # Kafka Produce
+//!
+//! Same as SPU produce request, except that transactional id is Kafka nullable string.
+//! Response is SPU produce response.
+//!
+use std::marker::PhantomData;
+
+use dataplane::api::Request;
+use dataplane::derive::{Decode, Encode};
+use dataplane::produce::{DefaultProduceRequest, DefaultTopicRequest, ProduceResponse};
+
+use super::KafkaApiKey;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaProduceRequest {
+    /// The transactional ID, or empty if the producer is not transactional.
+    #[fluvio(min_version = 3)]
+    pub transactional_id: String,
+
+    /// 0 for no acknowledgments, 1 for only the leader and -1 for the full ISR.
+    pub acks: i16,
+
+    pub timeout_ms: i32,
+
+    pub topics: Vec<DefaultTopicRequest>,
+}
+
+impl Request for KafkaProduceRequest {
+    const API_KEY: u16 = KafkaApiKey::Produce as u16;
+    const MIN_API_VERSION: i16 = 3;
+    const MAX_API_VERSION: i16 = 7;
+    const DEFAULT_API_VERSION: i16 = 7;
+    type Response = ProduceResponse;
+}
+
+impl From<KafkaProduceRequest> for DefaultProduceRequest {
+    fn from(request: KafkaProduceRequest) -> Self {
+        Self {
+            transactional_id: None,
+            acks: request.acks,
+            timeout_ms: request.timeout_ms,
+            topics: request.topics,
+            data: PhantomData,
+        }
+    }
+}
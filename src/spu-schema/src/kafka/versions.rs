@@ -0,0 +1,32 @@
+//!
+//! # Kafka API Versions
+//!
+//! First request sent by Kafka clients, lists Kafka APIs served by SPU and their versions.
+//!
+use dataplane::api::Request;
+use dataplane::derive::{Decode, Encode};
+use dataplane::ErrorCode;
+
+use crate::server::versions::ApiVersionKey;
+use super::KafkaApiKey;
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaApiVersionsRequest {}
+
+impl Request for KafkaApiVersionsRequest {
+    const API_KEY: u16 = KafkaApiKey::ApiVersions as u16;
+    const MIN_API_VERSION: i16 = 0;
+    const MAX_API_VERSION: i16 = 2;
+    const DEFAULT_API_VERSION: i16 = 2;
+    type Response = KafkaApiVersionsResponse;
+}
+
+/// For unsupported version, client expects error in version 0 of response
+/// and retries with max version listed in it
+#[derive(Decode, Encode, Default, Debug)]
+pub struct KafkaApiVersionsResponse {
+    pub error_code: ErrorCode,
+    pub api_keys: Vec<ApiVersionKey>,
+    #[fluvio(min_version = 1)]
+    pub throttle_time_ms: i32,
+}
@@ -2,6 +2,7 @@
 
 pub mod server;
 pub mod client;
+pub mod kafka;
 pub mod errors {
     pub use dataplane::ErrorCode;
 }
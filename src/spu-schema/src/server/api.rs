@@ -19,9 +19,13 @@ use dataplane::fetch::FileFetchRequest;
 
 use super::SpuServerApiKey;
 use super::fetch_offset::FetchOffsetsRequest;
+use super::consumer_offset::CommitConsumerOffsetsRequest;
+use super::consumer_offset::FetchConsumerOffsetsRequest;
+use super::producer_id::InitProducerIdRequest;
 use super::versions::ApiVersionsRequest;
 use super::register_replica::RegisterSyncReplicaRequest;
 use super::stream_fetch::FileStreamFetchRequest;
+use super::stream_fetch::StreamFetchAckRequest;
 
 /// Request to Spu Server
 #[derive(Debug, Encode)]
@@ -35,6 +39,10 @@ pub enum SpuServerRequest {
     FetchOffsetsRequest(RequestMessage<FetchOffsetsRequest>),
     FileStreamFetchRequest(RequestMessage<FileStreamFetchRequest>),
     RegisterSyncReplicaRequest(RequestMessage<RegisterSyncReplicaRequest>),
+    CommitConsumerOffsetsRequest(RequestMessage<CommitConsumerOffsetsRequest>),
+    FetchConsumerOffsetsRequest(RequestMessage<FetchConsumerOffsetsRequest>),
+    StreamFetchAckRequest(RequestMessage<StreamFetchAckRequest>),
+    InitProducerIdRequest(RequestMessage<InitProducerIdRequest>),
 }
 
 impl Default for SpuServerRequest {
@@ -67,6 +75,18 @@ impl ApiMessage for SpuServerRequest {
                 api_decode!(Self, RegisterSyncReplicaRequest, src, header)
             }
             SpuServerApiKey::StreamFetch => api_decode!(Self, FileStreamFetchRequest, src, header),
+            SpuServerApiKey::CommitConsumerOffsets => {
+                api_decode!(Self, CommitConsumerOffsetsRequest, src, header)
+            }
+            SpuServerApiKey::FetchConsumerOffsets => {
+                api_decode!(Self, FetchConsumerOffsetsRequest, src, header)
+            }
+            SpuServerApiKey::StreamFetchAck => {
+                api_decode!(Self, StreamFetchAckRequest, src, header)
+            }
+            SpuServerApiKey::InitProducerId => {
+                api_decode!(Self, InitProducerIdRequest, src, header)
+            }
         }
     }
 }
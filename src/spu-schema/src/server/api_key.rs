@@ -16,6 +16,10 @@ pub enum SpuServerApiKey {
     FetchOffsets = 1002,
     StreamFetch = 1003,
     RegisterSyncReplicaRequest = 1004,
+    CommitConsumerOffsets = 1005,
+    FetchConsumerOffsets = 1006,
+    StreamFetchAck = 1007,
+    InitProducerId = 1008,
 }
 
 impl Default for SpuServerApiKey {
@@ -0,0 +1,208 @@
+//!
+//! # Consumer Group Offsets
+//!
+//! API that allows consumers to commit and fetch offsets of a consumer group.
+//! Committed offset is offset of next record the group will read.
+use dataplane::api::Request;
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+use dataplane::ReplicaKey;
+
+use crate::errors::ErrorCode;
+use super::SpuServerApiKey;
+
+// -----------------------------------
+// CommitConsumerOffsetsRequest
+// -----------------------------------
+
+/// Commit offsets of consumer group
+#[derive(Decode, Encode, Default, Debug)]
+pub struct CommitConsumerOffsetsRequest {
+    /// The consumer group
+    pub group: String,
+
+    /// Each topic in the request.
+    pub topics: Vec<CommitConsumerOffsetTopic>,
+}
+
+impl Request for CommitConsumerOffsetsRequest {
+    const API_KEY: u16 = SpuServerApiKey::CommitConsumerOffsets as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = CommitConsumerOffsetsResponse;
+}
+
+impl CommitConsumerOffsetsRequest {
+    /// create request with a single topic and partition
+    pub fn new(group: String, topic: String, partition: i32, offset: i64) -> Self {
+        Self {
+            group,
+            topics: vec![CommitConsumerOffsetTopic {
+                name: topic,
+                partitions: vec![CommitConsumerOffsetPartition {
+                    partition_index: partition,
+                    offset,
+                }],
+            }],
+        }
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct CommitConsumerOffsetTopic {
+    /// The topic name.
+    pub name: String,
+
+    /// Each partition in the request.
+    pub partitions: Vec<CommitConsumerOffsetPartition>,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct CommitConsumerOffsetPartition {
+    /// The partition index.
+    pub partition_index: i32,
+
+    /// Offset of next record to be read by the group
+    pub offset: i64,
+}
+
+// -----------------------------------
+// CommitConsumerOffsetsResponse
+// -----------------------------------
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct CommitConsumerOffsetsResponse {
+    /// Each topic in the response.
+    pub topics: Vec<CommitConsumerOffsetTopicResponse>,
+}
+
+impl CommitConsumerOffsetsResponse {
+    pub fn find_partition(
+        self,
+        replica: &ReplicaKey,
+    ) -> Option<CommitConsumerOffsetPartitionResponse> {
+        self.topics
+            .into_iter()
+            .filter(|topic_res| topic_res.name == replica.topic)
+            .flat_map(|topic_res| topic_res.partitions)
+            .find(|partition_res| partition_res.partition_index == replica.partition)
+    }
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct CommitConsumerOffsetTopicResponse {
+    /// The topic name
+    pub name: String,
+
+    /// Each partition in the response.
+    pub partitions: Vec<CommitConsumerOffsetPartitionResponse>,
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct CommitConsumerOffsetPartitionResponse {
+    /// The partition error code, None for no error
+    pub error_code: ErrorCode,
+
+    /// The partition index.
+    pub partition_index: i32,
+}
+
+// -----------------------------------
+// FetchConsumerOffsetsRequest
+// -----------------------------------
+
+/// Fetch offsets committed by consumer groups
+#[derive(Decode, Encode, Default, Debug)]
+pub struct FetchConsumerOffsetsRequest {
+    /// Groups to fetch, empty for all groups
+    pub groups: Vec<String>,
+
+    /// Each topic in the request.
+    pub topics: Vec<FetchConsumerOffsetTopic>,
+}
+
+impl Request for FetchConsumerOffsetsRequest {
+    const API_KEY: u16 = SpuServerApiKey::FetchConsumerOffsets as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = FetchConsumerOffsetsResponse;
+}
+
+impl FetchConsumerOffsetsRequest {
+    /// create request with a single topic and partition
+    pub fn new(groups: Vec<String>, topic: String, partition: i32) -> Self {
+        Self {
+            groups,
+            topics: vec![FetchConsumerOffsetTopic {
+                name: topic,
+                partitions: vec![partition],
+            }],
+        }
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct FetchConsumerOffsetTopic {
+    /// The topic name.
+    pub name: String,
+
+    /// The partition indexes.
+    pub partitions: Vec<i32>,
+}
+
+// -----------------------------------
+// FetchConsumerOffsetsResponse
+// -----------------------------------
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct FetchConsumerOffsetsResponse {
+    /// Each topic in the response.
+    pub topics: Vec<FetchConsumerOffsetTopicResponse>,
+}
+
+impl FetchConsumerOffsetsResponse {
+    pub fn find_partition(
+        self,
+        replica: &ReplicaKey,
+    ) -> Option<FetchConsumerOffsetPartitionResponse> {
+        self.topics
+            .into_iter()
+            .filter(|topic_res| topic_res.name == replica.topic)
+            .flat_map(|topic_res| topic_res.partitions)
+            .find(|partition_res| partition_res.partition_index == replica.partition)
+    }
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct FetchConsumerOffsetTopicResponse {
+    /// The topic name
+    pub name: String,
+
+    /// Each partition in the response.
+    pub partitions: Vec<FetchConsumerOffsetPartitionResponse>,
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct FetchConsumerOffsetPartitionResponse {
+    /// The partition error code, None for no error
+    pub error_code: ErrorCode,
+
+    /// The partition index.
+    pub partition_index: i32,
+
+    /// First readable offset.
+    pub start_offset: i64,
+
+    /// Last readable offset
+    pub last_stable_offset: i64,
+
+    /// Offsets committed by requested groups, ordered by group
+    pub offsets: Vec<ConsumerOffset>,
+}
+
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+pub struct ConsumerOffset {
+    /// The consumer group
+    pub group: String,
+
+    /// Offset of next record to be read by the group
+    pub offset: i64,
+}
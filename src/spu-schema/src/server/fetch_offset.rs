@@ -26,7 +26,7 @@ pub struct FetchOffsetsRequest {
 
 impl Request for FetchOffsetsRequest {
     const API_KEY: u16 = SpuServerApiKey::FetchOffsets as u16;
-    const DEFAULT_API_VERSION: i16 = 0;
+    const DEFAULT_API_VERSION: i16 = 1;
     type Response = FetchOffsetsResponse;
 }
 
@@ -38,6 +38,21 @@ impl FetchOffsetsRequest {
                 name: topic,
                 partitions: vec![FetchOffsetPartition {
                     partition_index: partition,
+                    timestamp: None,
+                }],
+            }],
+        }
+    }
+
+    /// create request with a single topic and partition,
+    /// which also looks up offset of timestamp (millis)
+    pub fn with_timestamp(topic: String, partition: i32, timestamp: i64) -> Self {
+        Self {
+            topics: vec![FetchOffsetTopic {
+                name: topic,
+                partitions: vec![FetchOffsetPartition {
+                    partition_index: partition,
+                    timestamp: Some(timestamp),
                 }],
             }],
         }
@@ -57,6 +72,10 @@ pub struct FetchOffsetTopic {
 pub struct FetchOffsetPartition {
     /// The partition index.
     pub partition_index: i32,
+
+    /// Find first offset with timestamp (millis) at or after this
+    #[fluvio(min_version = 1)]
+    pub timestamp: Option<i64>,
 }
 
 // -----------------------------------
@@ -107,6 +126,11 @@ pub struct FetchOffsetPartitionResponse {
 
     /// Last readable offset
     pub last_stable_offset: i64,
+
+    /// First offset with timestamp at or after requested timestamp,
+    /// last readable offset if all records are older
+    #[fluvio(min_version = 1)]
+    pub timestamp_offset: Option<i64>,
 }
 
 impl fmt::Display for FetchOffsetPartitionResponse {
@@ -2,6 +2,8 @@ mod api_key;
 mod api;
 pub mod versions;
 pub mod fetch_offset;
+pub mod consumer_offset;
+pub mod producer_id;
 pub mod register_replica;
 pub mod stream_fetch;
 
@@ -0,0 +1,41 @@
+//!
+//! # Init Producer Id
+//!
+//! API that assigns producer id to idempotent producer.
+//! Producer ids are unique across SPUs, so the same id is used for all partitions.
+use dataplane::api::Request;
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+
+use crate::errors::ErrorCode;
+use super::SpuServerApiKey;
+
+// -----------------------------------
+// InitProducerIdRequest
+// -----------------------------------
+
+/// Assign new producer id
+#[derive(Decode, Encode, Default, Debug)]
+pub struct InitProducerIdRequest {}
+
+impl Request for InitProducerIdRequest {
+    const API_KEY: u16 = SpuServerApiKey::InitProducerId as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = InitProducerIdResponse;
+}
+
+// -----------------------------------
+// InitProducerIdResponse
+// -----------------------------------
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct InitProducerIdResponse {
+    /// The error code, None for no error
+    pub error_code: ErrorCode,
+
+    /// The assigned producer id
+    pub producer_id: i64,
+
+    /// The epoch of producer id
+    pub producer_epoch: i16,
+}
@@ -41,6 +41,17 @@ where
     pub fetch_offset: i64,
     pub max_bytes: i32,
     pub isolation: Isolation,
+    /// id of stream in connection, assigned by client.
+    /// if not 0, records are not sent until previous response is acknowledged
+    /// with `StreamFetchAckRequest`
+    #[fluvio(min_version = 11)]
+    pub stream_id: i32,
+    /// only records matching filter are sent back
+    #[fluvio(min_version = 12)]
+    pub filter: Option<RecordFilter>,
+    /// wasm module run over each record before it is sent back, after topic transform
+    #[fluvio(min_version = 13)]
+    pub wasm_module: Option<Vec<u8>>,
     pub data: PhantomData<R>,
 }
 
@@ -49,17 +60,73 @@ where
     R: Debug + Decoder + Encoder,
 {
     const API_KEY: u16 = SpuServerApiKey::StreamFetch as u16;
-    const DEFAULT_API_VERSION: i16 = 10;
+    const MIN_API_VERSION: i16 = 10;
+    const DEFAULT_API_VERSION: i16 = 13;
     type Response = StreamFetchResponse<R>;
 }
 
-#[derive(Encode, Decode, Default, Debug)]
+/// Condition records must meet to be sent back, all conditions which are set must match
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+pub struct RecordFilter {
+    /// key starts with prefix
+    pub key_prefix: Option<Vec<u8>>,
+    /// record has header with same value
+    pub header: Option<HeaderFilter>,
+    /// value matches regular expression
+    pub value_regex: Option<String>,
+}
+
+impl RecordFilter {
+    pub fn with_key_prefix(mut self, prefix: impl Into<Vec<u8>>) -> Self {
+        self.key_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.header = Some(HeaderFilter {
+            key: key.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    pub fn with_value_regex(mut self, regex: impl Into<String>) -> Self {
+        self.value_regex = Some(regex.into());
+        self
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+pub struct HeaderFilter {
+    pub key: String,
+    pub value: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Debug)]
 pub struct StreamFetchResponse<R>
 where
     R: Encoder + Decoder + Default + Debug,
 {
     pub topic: String,
     pub partition: FetchablePartitionResponse<R>,
+    /// offset to continue stream from when records are filtered or transformed,
+    /// records before it which are not in response didn't match filter or were dropped.
+    /// -1 if records are not processed
+    #[fluvio(min_version = 12)]
+    pub next_offset: i64,
+}
+
+impl<R> Default for StreamFetchResponse<R>
+where
+    R: Encoder + Decoder + Default + Debug,
+{
+    fn default() -> Self {
+        Self {
+            topic: String::default(),
+            partition: FetchablePartitionResponse::default(),
+            next_offset: -1,
+        }
+    }
 }
 
 impl FileWrite for StreamFetchResponse<FileRecordSet> {
@@ -73,6 +140,26 @@ impl FileWrite for StreamFetchResponse<FileRecordSet> {
         trace!("topic {}", self.topic);
         self.topic.encode(src, version)?;
         self.partition.file_encode(src, data, version)?;
+        if version >= 12 {
+            self.next_offset.encode(src, version)?;
+        }
         Ok(())
     }
 }
+
+/// Acknowledge records received from stream, SPU continues streaming from offset
+#[derive(Decode, Encode, Default, Debug)]
+pub struct StreamFetchAckRequest {
+    pub stream_id: i32,
+    /// offset of next record consumer will read
+    pub offset: i64,
+}
+
+impl Request for StreamFetchAckRequest {
+    const API_KEY: u16 = SpuServerApiKey::StreamFetchAck as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = StreamFetchAckResponse;
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct StreamFetchAckResponse {}
@@ -7,6 +7,9 @@
 use std::io::Error as IoError;
 use std::process;
 use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::convert::TryFrom;
 
 use tracing::debug;
 use tracing::info;
@@ -16,6 +19,9 @@ use fluvio_types::print_cli_err;
 use fluvio_types::SpuId;
 use fluvio_future::rust_tls::TlsAcceptor;
 use fluvio_future::rust_tls::AcceptorBuilder;
+use fluvio_auth::basic::BasicRbacPolicy;
+use fluvio_controlplane_metadata::spu::Endpoint;
+use flv_util::socket_helpers::ServerAddress;
 
 use super::SpuConfig;
 
@@ -51,6 +57,36 @@ pub struct SpuOpt {
     #[structopt(long, value_name = "integer", env = "FLV_LOG_INDEX_MAX_INTERVAL_BYTES")]
     pub index_max_interval_bytes: Option<u32>,
 
+    /// keep logs of deleted partitions in trash for seconds, deleted right away if not set.
+    /// Orphan logs found on startup are always moved to trash
+    #[structopt(long, value_name = "seconds", env = "FLV_LOG_DELETE_GRACE_SECS")]
+    pub log_delete_grace_secs: Option<u64>,
+
+    /// min replicas in sync with leader to accept produce, topic setting overrides it
+    #[structopt(long, value_name = "integer", env = "FLV_MIN_IN_SYNC_REPLICAS")]
+    pub min_in_sync_replicas: Option<u16>,
+
+    /// directory of wasm modules which can be set as topic transform
+    #[structopt(long, value_name = "dir", env = "FLV_TRANSFORM_DIR")]
+    pub transform_dir: Option<PathBuf>,
+
+    /// fuel for transforming single record with wasm module
+    #[structopt(long, value_name = "integer", env = "FLV_TRANSFORM_FUEL")]
+    pub transform_fuel: Option<u64>,
+
+    /// max 64KiB pages of memory for wasm transform
+    #[structopt(long, value_name = "integer", env = "FLV_TRANSFORM_MEMORY_PAGES")]
+    pub transform_memory_pages: Option<u32>,
+
+    /// max bytes of wasm transform module sent by client
+    #[structopt(long, value_name = "integer", env = "FLV_TRANSFORM_MAX_MODULE_BYTES")]
+    pub transform_max_module_bytes: Option<usize>,
+
+    /// allow clients to send their own wasm module to transform stream,
+    /// otherwise only topic transforms from transform dir are run
+    #[structopt(long)]
+    pub allow_client_transforms: bool,
+
     /// max bytes to transfer between leader and follower
     #[structopt(
         long,
@@ -62,6 +98,45 @@ pub struct SpuOpt {
 
     #[structopt(flatten)]
     tls: TlsConfig,
+
+    /// scopes of client certificate principals, same as in the SC
+    #[structopt(
+        long = "authorization-scopes",
+        value_name = "authorization scopes path",
+        env
+    )]
+    x509_auth_scopes: Option<PathBuf>,
+
+    /// policy for produce and consume, same as in the SC.
+    /// requires authorization scopes
+    #[structopt(
+        long = "authorization-policy",
+        value_name = "authorization policy path",
+        env
+    )]
+    auth_policy: Option<PathBuf>,
+
+    /// Address of prometheus metrics endpoint, disabled if not specified
+    #[structopt(
+        long = "metrics-addr",
+        value_name = "host:port",
+        env = "FLV_METRICS_ADDR"
+    )]
+    metrics_addr: Option<String>,
+
+    /// Address of kafka compatible listener, disabled if not specified.
+    /// Consumer groups are not managed, only consumers which assign partitions are supported
+    #[structopt(long = "kafka-addr", value_name = "host:port", env = "FLV_KAFKA_ADDR")]
+    kafka_addr: Option<String>,
+
+    /// Address of kafka compatible listener advertised to kafka clients, kafka addr if not specified
+    #[structopt(
+        long = "kafka-advertised-addr",
+        value_name = "host:port",
+        env = "FLV_KAFKA_ADVERTISED_ADDR",
+        requires = "kafka-addr"
+    )]
+    kafka_advertised_addr: Option<String>,
 }
 
 impl SpuOpt {
@@ -79,8 +154,6 @@ impl SpuOpt {
 
     #[allow(clippy::wrong_self_convention)]
     fn as_spu_config(self) -> Result<(SpuConfig, Option<String>), IoError> {
-        use std::path::PathBuf;
-
         let mut config = SpuConfig::default();
 
         config.id = match self.id {
@@ -116,6 +189,44 @@ impl SpuOpt {
             config.log.index_max_interval_bytes = index_max_interval_bytes;
         }
 
+        if let Some(grace_secs) = self.log_delete_grace_secs {
+            info!("keeping deleted logs for seconds: {}", grace_secs);
+            config.log.delete_grace_period = Some(Duration::from_secs(grace_secs));
+        }
+
+        if let Some(min_in_sync_replicas) = self.min_in_sync_replicas {
+            info!("overriding min in sync replicas: {}", min_in_sync_replicas);
+            config.replication.min_in_sync_replicas = min_in_sync_replicas;
+        }
+
+        if let Some(transform_dir) = self.transform_dir {
+            info!("using transform dir: {:?}", transform_dir);
+            config.transform.module_dir = Some(transform_dir);
+        }
+
+        if let Some(transform_fuel) = self.transform_fuel {
+            info!("overriding transform fuel: {}", transform_fuel);
+            config.transform.fuel = transform_fuel;
+        }
+
+        if let Some(memory_pages) = self.transform_memory_pages {
+            info!("overriding transform memory pages: {}", memory_pages);
+            config.transform.memory_pages = memory_pages;
+        }
+
+        if let Some(max_module_bytes) = self.transform_max_module_bytes {
+            info!(
+                "overriding transform max module bytes: {}",
+                max_module_bytes
+            );
+            config.transform.max_module_bytes = max_module_bytes;
+        }
+
+        if self.allow_client_transforms {
+            info!("allowing client transforms");
+            config.transform.allow_client_modules = true;
+        }
+
         if let Some(public_addr) = self.bind_public {
             info!("overriding public addr: {}", public_addr);
             config.public_endpoint = public_addr;
@@ -142,6 +253,53 @@ impl SpuOpt {
 
         config.peer_max_bytes = self.peer_max_bytes;
 
+        if let Some(metrics_addr) = self.metrics_addr {
+            info!("using metrics addr: {}", metrics_addr);
+            config.metrics_endpoint = Some(metrics_addr);
+        }
+
+        if let Some(kafka_addr) = self.kafka_addr {
+            // kafka clients are not authenticated, so topics can't be protected by policy
+            if self.auth_policy.is_some() || self.x509_auth_scopes.is_some() {
+                return Err(IoError::new(
+                    ErrorKind::InvalidInput,
+                    "kafka listener can't be used with authorization",
+                ));
+            }
+            let advertised_addr = self
+                .kafka_advertised_addr
+                .unwrap_or_else(|| kafka_addr.clone());
+            info!(
+                "using kafka addr: {}, advertised as: {}",
+                kafka_addr, advertised_addr
+            );
+            let advertised_addr = ServerAddress::try_from(advertised_addr)?;
+            config.kafka_endpoint = Some(kafka_addr);
+            config.kafka_advertised_endpoint = Some(Endpoint::from(advertised_addr));
+        }
+
+        // identity of client is only known when TLS proxy authenticates with scopes
+        if let Some(x509_auth_scopes) = self.x509_auth_scopes {
+            if !self.tls.tls {
+                return Err(IoError::new(
+                    ErrorKind::InvalidInput,
+                    "authorization scopes require tls",
+                ));
+            }
+            info!("using authorization scopes: {:?}", x509_auth_scopes);
+            config.auth_policy = Some(match self.auth_policy {
+                Some(path) => BasicRbacPolicy::try_from(path)?,
+                // only root role is permitted
+                None => BasicRbacPolicy::default(),
+            });
+            config.x509_auth_scopes = Some(x509_auth_scopes);
+        } else if self.auth_policy.is_some() {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "authorization policy requires authorization scopes",
+            ));
+        }
+
         Ok((config, tls_port))
     }
 
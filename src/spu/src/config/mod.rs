@@ -5,3 +5,4 @@ pub use self::cli::SpuOpt;
 
 pub use self::spu_config::SpuConfig;
 pub use self::spu_config::Log;
+pub use self::spu_config::Transform;
@@ -10,6 +10,7 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
 // defaults values
 use fluvio_types::defaults::SPU_PUBLIC_PORT;
@@ -25,10 +26,15 @@ use fluvio_types::defaults::SPU_RETRY_SC_TIMEOUT_MS;
 // environment variables
 
 use fluvio_types::defaults::SPU_MIN_IN_SYNC_REPLICAS;
+use fluvio_types::defaults::SPU_TRANSFORM_FUEL;
+use fluvio_types::defaults::SPU_TRANSFORM_MEMORY_PAGES;
+use fluvio_types::defaults::SPU_TRANSFORM_MAX_MODULE_BYTES;
 use fluvio_types::defaults::FLV_LOG_BASE_DIR;
 use fluvio_types::defaults::FLV_LOG_SIZE;
 use fluvio_types::SpuId;
 use fluvio_storage::ConfigOption;
+use fluvio_controlplane_metadata::spu::Endpoint;
+use fluvio_auth::basic::BasicRbacPolicy;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Replication {
@@ -43,6 +49,32 @@ impl Default for Replication {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transform {
+    /// directory of wasm modules named by topic transform setting
+    pub module_dir: Option<PathBuf>,
+    /// fuel for transforming single record
+    pub fuel: u64,
+    /// max 64KiB pages of memory of module instance
+    pub memory_pages: u32,
+    /// max size of module sent by client
+    pub max_module_bytes: usize,
+    /// clients may send their own module to transform stream
+    pub allow_client_modules: bool,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            module_dir: None,
+            fuel: SPU_TRANSFORM_FUEL,
+            memory_pages: SPU_TRANSFORM_MEMORY_PAGES,
+            max_module_bytes: SPU_TRANSFORM_MAX_MODULE_BYTES,
+            allow_client_modules: false,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Log {
     pub base_dir: PathBuf,
@@ -50,6 +82,9 @@ pub struct Log {
     pub index_max_bytes: u32,
     pub index_max_interval_bytes: u32,
     pub segment_max_bytes: u32,
+    /// removed replicas are kept in trash for grace period, deleted right away if not set.
+    /// orphan replicas are always kept in trash, it is only purged if set
+    pub delete_grace_period: Option<Duration>,
 }
 
 impl Default for Log {
@@ -62,6 +97,7 @@ impl Default for Log {
             index_max_bytes: SPU_LOG_INDEX_MAX_BYTES,
             index_max_interval_bytes: SPU_LOG_INDEX_MAX_INTERVAL_BYTES,
             segment_max_bytes: SPU_LOG_SEGMENT_MAX_BYTES,
+            delete_grace_period: None,
         }
     }
 }
@@ -96,8 +132,20 @@ pub struct SpuConfig {
     // parameters
     pub replication: Replication,
     pub log: Log,
+    pub transform: Transform,
 
     pub peer_max_bytes: u32,
+
+    // authorization of public requests
+    pub x509_auth_scopes: Option<PathBuf>,
+    pub auth_policy: Option<BasicRbacPolicy>,
+
+    // address of prometheus metrics endpoint
+    pub metrics_endpoint: Option<String>,
+
+    // kafka compatible listener and address advertised to kafka clients
+    pub kafka_endpoint: Option<String>,
+    pub kafka_advertised_endpoint: Option<Endpoint>,
 }
 
 impl Default for SpuConfig {
@@ -111,7 +159,13 @@ impl Default for SpuConfig {
             replication: Replication::default(),
             sc_retry_ms: SPU_RETRY_SC_TIMEOUT_MS,
             log: Log::default(),
+            transform: Transform::default(),
             peer_max_bytes: fluvio_storage::FileReplica::PREFER_MAX_LEN,
+            x509_auth_scopes: None,
+            auth_policy: None,
+            metrics_endpoint: None,
+            kafka_endpoint: None,
+            kafka_advertised_endpoint: None,
         }
     }
 }
@@ -1,4 +1,6 @@
+use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use tracing::trace;
 use tracing::error;
@@ -25,6 +27,8 @@ use crate::controllers::leader_replica::UpdateOffsetRequest;
 use crate::services::internal::FetchStreamRequest;
 use crate::core::spus::SharedSpuLocalStore;
 use crate::core::SharedSpuConfig;
+use crate::core::SpuMetrics;
+use crate::core::storage::retention_config;
 
 use super::FollowerReplicaControllerCommand;
 use super::FollowerReplicaState;
@@ -44,6 +48,7 @@ pub struct ReplicaFollowerController<S> {
     followers_state: SharedFollowersState<S>,
     receiver: Receiver<FollowerReplicaControllerCommand>,
     config: SharedSpuConfig,
+    metrics: Arc<SpuMetrics>,
 }
 
 impl<S> ReplicaFollowerController<S> {
@@ -53,6 +58,7 @@ impl<S> ReplicaFollowerController<S> {
         spu_localstore: SharedSpuLocalStore,
         followers_state: SharedFollowersState<S>,
         config: SharedSpuConfig,
+        metrics: Arc<SpuMetrics>,
     ) -> Self {
         Self {
             leader_id,
@@ -60,6 +66,7 @@ impl<S> ReplicaFollowerController<S> {
             receiver,
             followers_state,
             config,
+            metrics,
         }
     }
 }
@@ -213,7 +220,12 @@ impl ReplicaFollowerController<FileReplica> {
     async fn write_to_follower_replica(&self, sink: &mut FlvSink, req: DefaultSyncRequest) {
         follower_debug!(self, "handling sync request from req {}", req);
 
+        let start = Instant::now();
         let offsets = self.followers_state.send_records(req).await;
+        self.metrics.follower_syncs.inc();
+        self.metrics
+            .follower_sync_latency
+            .observe_duration(start.elapsed());
         self.sync_offsets_to_leader(sink, offsets).await;
     }
 
@@ -311,7 +323,7 @@ impl ReplicaFollowerController<FileReplica> {
                 replica_key
             );
         } else {
-            let log = &self.config.storage().new_config();
+            let log = retention_config(self.config.storage().new_config(), &replica_msg.retention);
             match FollowerReplicaState::new(
                 self.config.id(),
                 replica_msg.leader,
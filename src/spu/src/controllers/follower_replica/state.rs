@@ -67,6 +67,12 @@ impl<S> FollowersState<S> {
         self.replicas.contains_key(key)
     }
 
+    /// keys of all follower replicas
+    pub fn replica_keys(&self) -> Vec<ReplicaKey> {
+        let keys_lock = self.replica_keys.read().unwrap();
+        keys_lock.values().flatten().cloned().collect()
+    }
+
     pub fn get_replica(
         &self,
         key: &ReplicaKey,
@@ -189,6 +195,20 @@ impl FollowersState<FileReplica> {
                                 "successfully written send to follower replica: {}",
                                 replica_key
                             );
+                            let consumer_offsets = partition_request
+                                .consumer_offsets
+                                .into_iter()
+                                .map(|consumer_offset| {
+                                    (consumer_offset.group, consumer_offset.offset)
+                                })
+                                .collect();
+                            if let Err(err) = replica
+                                .mut_storage()
+                                .commit_consumer_offsets(consumer_offsets)
+                                .await
+                            {
+                                error!("error writing replica consumer offsets: {}", err);
+                            }
                             let end_offset = replica.storage().get_leo();
                             let high_watermark = partition_request.high_watermark;
                             if end_offset == high_watermark {
@@ -314,6 +334,22 @@ impl FollowerReplicaState<FileReplica> {
 #[cfg(test)]
 mod test {
 
+    use std::env::temp_dir;
+    use std::io::Cursor;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_future::test_async;
+    use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
+    use fluvio_storage::ConfigOption;
+    use fluvio_storage::ReplicaStorage;
+    use dataplane::api::Request;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::core::{Decoder, Encoder};
+    use dataplane::record::{DefaultRecord, RecordSet};
+
+    use crate::controllers::follower_replica::DefaultSyncRequest;
+    use crate::controllers::follower_replica::sync::PeerFetchablePartitionResponse;
+    use crate::controllers::follower_replica::sync::PeerFetchableTopicResponse;
     use super::FollowerReplicaState;
     use super::FollowersState;
 
@@ -354,4 +390,59 @@ mod test {
         let old_state = states.remove_replica(&10, &k1).expect("old state exists");
         assert_eq!(old_state.leader, 10);
     }
+
+    #[test_async]
+    async fn test_sync_records_and_consumer_offsets() -> Result<(), ()> {
+        let option = ConfigOption {
+            base_dir: temp_dir().join("follower-sync-consumer-offsets"),
+            ..Default::default()
+        };
+        ensure_clean_dir(&option.base_dir);
+
+        let replica = ("topic", 0).into();
+        let states = FollowersState::new();
+        states.insert_replica(
+            FollowerReplicaState::new(5002, 5001, &replica, &option)
+                .await
+                .expect("replica"),
+        );
+
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from("hello".to_owned()));
+        let request = DefaultSyncRequest {
+            topics: vec![PeerFetchableTopicResponse {
+                name: "topic".to_owned(),
+                partitions: vec![PeerFetchablePartitionResponse {
+                    partition_index: 0,
+                    high_watermark: 1,
+                    last_stable_offset: 1,
+                    records: RecordSet::default().add(batch),
+                    consumer_offsets: vec![ConsumerOffset {
+                        group: "g1".to_owned(),
+                        offset: 1,
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        // offsets are sent as they are encoded by leader
+        let version = DefaultSyncRequest::DEFAULT_API_VERSION;
+        let mut bytes = vec![];
+        request.encode(&mut bytes, version).expect("encode");
+        let mut decoded = DefaultSyncRequest::default();
+        decoded
+            .decode(&mut Cursor::new(bytes), version)
+            .expect("decode");
+
+        let offsets = states.send_records(decoded).await;
+        assert_eq!(offsets.replicas.len(), 1);
+        assert_eq!(offsets.replicas[0].leo, 1);
+
+        let follower = states.get_replica(&replica).expect("follower");
+        assert_eq!(follower.storage().get_hw(), 1);
+        assert_eq!(follower.storage().consumer_offsets().get("g1"), Some(1));
+        Ok(())
+    }
 }
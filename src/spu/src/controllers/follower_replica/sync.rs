@@ -16,6 +16,7 @@ use dataplane::api::Request;
 use dataplane::ErrorCode;
 use dataplane::store::StoreValue;
 use dataplane::store::FileWrite;
+use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
 use fluvio_storage::SlicePartitionResponse;
 use fluvio_future::file_slice::AsyncFileSlice;
 
@@ -50,14 +51,14 @@ where
 }
 
 // Request trait
-// Note that DEFAULT_API_VERSION is 7 which is required in order to map all fields for file encoding
+// Note that DEFAULT_API_VERSION is 8 which is required in order to map all fields for file encoding
 // TODO: come up with unify encoding
 impl<R> Request for SyncRequest<R>
 where
     R: Encoder + Decoder + Debug,
 {
     const API_KEY: u16 = FollowerPeerApiEnum::SyncRecords as u16;
-    const DEFAULT_API_VERSION: i16 = 7;
+    const DEFAULT_API_VERSION: i16 = 8;
     type Response = SyncResponse;
 }
 
@@ -111,6 +112,9 @@ where
     pub high_watermark: i64,
     pub last_stable_offset: i64,
     pub records: R,
+    /// offsets committed by consumer groups on leader
+    #[fluvio(min_version = 8)]
+    pub consumer_offsets: Vec<ConsumerOffset>,
 }
 
 impl<R> fmt::Display for PeerFetchablePartitionResponse<R>
@@ -153,6 +157,9 @@ impl FileWrite for PeerFilePartitionResponse {
         self.high_watermark.encode(src, version)?;
         self.last_stable_offset.encode(src, version)?;
         self.records.file_encode(src, data, version)?;
+        if version >= 8 {
+            self.consumer_offsets.encode(src, version)?;
+        }
         Ok(())
     }
 }
@@ -12,6 +12,7 @@ use futures_util::stream::StreamExt;
 use fluvio_future::task::spawn;
 use fluvio_future::timer::sleep;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_controlplane_metadata::partition::Replica;
 use fluvio_storage::FileReplica;
 use fluvio_types::SpuId;
 use fluvio_socket::ExclusiveFlvSink;
@@ -27,6 +28,12 @@ use super::SharedReplicaLeadersState;
 /// time for complete re-sync with followers
 pub const FOLLOWER_RECONCILIATION_INTERVAL_SEC: u64 = 300; // 5 min
 
+/// time to retry sync with followers when sync has been throttled
+const THROTTLED_SYNC_INTERVAL_MS: u64 = 200;
+
+/// time to check if followers have fallen out of sync
+const IN_SYNC_CHECK_INTERVAL_MS: u64 = 2000;
+
 /// Controller for managing leader replica.
 /// Each leader replica controller is spawned and managed by master controller to ensure max parallism.
 pub struct ReplicaLeaderController<S> {
@@ -92,7 +99,9 @@ impl ReplicaLeaderController<FileReplica> {
         self.send_status_to_sc().await;
         self.sync_followers().await;
 
-        let mut timer = sleep(Duration::from_secs(FOLLOWER_RECONCILIATION_INTERVAL_SEC));
+        let mut timer = sleep(self.sync_interval());
+        let mut in_sync_replicas = self.in_sync_replicas();
+        let mut in_sync_timer = sleep(Duration::from_millis(IN_SYNC_CHECK_INTERVAL_MS));
         loop {
             leader_debug!(self, "waiting for next command");
 
@@ -101,6 +110,12 @@ impl ReplicaLeaderController<FileReplica> {
                 _ = &mut timer => {
 
                     self.sync_followers().await;
+                    timer = sleep(self.sync_interval());
+                },
+
+                _ = &mut in_sync_timer => {
+                    in_sync_replicas = self.check_in_sync_replicas(in_sync_replicas).await;
+                    in_sync_timer = sleep(Duration::from_millis(IN_SYNC_CHECK_INTERVAL_MS));
                 },
 
                 controller_req = self.controller_receiver.next() => {
@@ -118,8 +133,17 @@ impl ReplicaLeaderController<FileReplica> {
 
                             LeaderReplicaControllerCommand::UpdateReplicaFromSc(replica) => {
                                 leader_debug!(self,"update replica from sc: {}",replica.id);
+                                self.update_replica(replica).await;
+                            },
+
+                            LeaderReplicaControllerCommand::ConsumerOffsetsUpdated => {
+                                leader_debug!(self,"consumer offsets committed, sync all followers");
+                                self.sync_all_followers().await;
                             }
                         }
+                        if self.is_throttled() {
+                            timer = sleep(self.sync_interval());
+                        }
                     } else {
                         leader_debug!(
                             self,
@@ -131,11 +155,20 @@ impl ReplicaLeaderController<FileReplica> {
         }
     }
 
-    /// update the follower offsets
+    /// update the follower offsets, high watermark moves if in sync followers have caught up
     async fn update_follower_offsets(&self, offsets: FollowerOffsetUpdate) {
-        if let Some(mut leader_replica) = self.leaders_state.get_mut_replica(&self.id) {
+        let hw_updated = if let Some(mut leader_replica) =
+            self.leaders_state.get_mut_replica(&self.id)
+        {
             let follower_id = offsets.follower_id;
             let (update_status, sync_follower) = leader_replica.update_follower_offsets(offsets);
+            let hw_updated = match leader_replica.update_hw().await {
+                Ok(updated) => updated,
+                Err(err) => {
+                    error!("error updating high watermark: {}, {}", self.id, err);
+                    false
+                }
+            };
             join(
                 async {
                     if update_status {
@@ -156,11 +189,91 @@ impl ReplicaLeaderController<FileReplica> {
                 },
             )
             .await;
+            hw_updated
         } else {
             warn!(
                 "no replica is found: {} for update follower offsets",
                 self.id
             );
+            false
+        };
+
+        if hw_updated {
+            join3(
+                self.send_status_to_sc(),
+                self.sync_followers(),
+                self.update_offset_to_clients(),
+            )
+            .await;
+        }
+    }
+
+    /// apply followers and throttle from sc, new followers are synced
+    async fn update_replica(&self, replica: Replica) {
+        if let Some(mut leader_replica) = self.leaders_state.get_mut_replica(&self.id) {
+            leader_replica.update_replica(&replica);
+        } else {
+            leader_warn!(self, "update replica: no replica is found");
+            return;
+        }
+
+        join(self.send_status_to_sc(), self.sync_followers()).await;
+    }
+
+    fn in_sync_replicas(&self) -> Vec<SpuId> {
+        self.leaders_state
+            .get_replica(&self.id)
+            .map(|leader_replica| leader_replica.in_sync_replicas())
+            .unwrap_or_default()
+    }
+
+    /// followers fall out of sync without any offset update, report change of in sync replicas to sc.
+    /// high watermark may move once lagging follower is out of sync
+    async fn check_in_sync_replicas(&self, last: Vec<SpuId>) -> Vec<SpuId> {
+        let (in_sync_replicas, hw_updated) =
+            if let Some(mut leader_replica) = self.leaders_state.get_mut_replica(&self.id) {
+                let in_sync_replicas = leader_replica.in_sync_replicas();
+                if in_sync_replicas == last {
+                    return in_sync_replicas;
+                }
+                debug!(
+                    "replica: <{}> => in sync replicas changed: {:?} => {:?}",
+                    self.id, last, in_sync_replicas
+                );
+                let hw_updated = match leader_replica.update_hw().await {
+                    Ok(updated) => updated,
+                    Err(err) => {
+                        error!("error updating high watermark: {}, {}", self.id, err);
+                        false
+                    }
+                };
+                (in_sync_replicas, hw_updated)
+            } else {
+                leader_warn!(self, "check in sync replicas: no replica is found");
+                return last;
+            };
+
+        if hw_updated {
+            join(self.send_status_to_sc(), self.update_offset_to_clients()).await;
+        } else {
+            self.send_status_to_sc().await;
+        }
+        in_sync_replicas
+    }
+
+    fn is_throttled(&self) -> bool {
+        self.leaders_state
+            .get_replica(&self.id)
+            .map(|leader_replica| leader_replica.is_throttled())
+            .unwrap_or(false)
+    }
+
+    /// followers are synced sooner while sync is throttled
+    fn sync_interval(&self) -> Duration {
+        if self.is_throttled() {
+            Duration::from_millis(THROTTLED_SYNC_INTERVAL_MS)
+        } else {
+            Duration::from_secs(FOLLOWER_RECONCILIATION_INTERVAL_SEC)
         }
     }
 
@@ -175,6 +288,17 @@ impl ReplicaLeaderController<FileReplica> {
         }
     }
 
+    /// sync every follower, even if it has caught up
+    async fn sync_all_followers(&self) {
+        if let Some(leader_replica) = self.leaders_state.get_replica(&self.id) {
+            leader_replica
+                .sync_all_followers(&self.follower_sinks, self.max_bytes)
+                .await;
+        } else {
+            leader_warn!(self, "sync all followers: no replica is found");
+        }
+    }
+
     /// send status back to sc
     async fn send_status_to_sc(&self) {
         if let Some(leader_replica) = self.leaders_state.get_replica(&self.id) {
@@ -14,7 +14,9 @@ use tracing::error;
 
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use dataplane::record::RecordSet;
+use dataplane::batch::DefaultBatch;
 use fluvio_storage::FileReplica;
+use fluvio_storage::StorageError;
 use dataplane::fetch::FilePartitionResponse;
 use dataplane::{Offset, Isolation, ErrorCode};
 
@@ -122,6 +124,11 @@ impl<S> ReplicaLeadersState<S> {
     ) -> Option<Sender<LeaderReplicaControllerCommand>> {
         self.mailboxes.read().await.get(key).cloned()
     }
+
+    /// keys of all leader replicas
+    pub async fn replica_keys(&self) -> Vec<ReplicaKey> {
+        self.mailboxes.read().await.keys().cloned().collect()
+    }
 }
 
 impl ReplicaLeadersState<FileReplica> {
@@ -156,24 +163,60 @@ impl ReplicaLeadersState<FileReplica> {
         }
     }
 
+    /// read decoded batches with high watermark and end offset, none if replica is not found
+    pub async fn read_batches(
+        &self,
+        rep_id: &ReplicaKey,
+        offset: Offset,
+        max_len: u32,
+        isolation: Isolation,
+    ) -> Option<(Result<Vec<DefaultBatch>, StorageError>, Offset, Offset)> {
+        if let Some(leader_replica) = self.get_replica(rep_id) {
+            let batches = leader_replica
+                .read_batches(offset, max_len, isolation)
+                .await;
+            Some((batches, leader_replica.hw(), leader_replica.leo()))
+        } else {
+            warn!("no replica is found: {}", rep_id);
+            None
+        }
+    }
+
     /// write new record and notify the leader replica controller
+    /// return base offset of written records and end offset after write with error code of
+    /// sequence check, none if replica is not found
     /// TODO: may replica should be moved it's own map
     pub async fn send_records(
         &self,
         rep_id: &ReplicaKey,
         records: RecordSet,
         update_hw: bool,
-    ) -> Result<bool, InternalServerError> {
-        if let Some(mut leader_replica) = self.get_mut_replica(rep_id) {
-            leader_replica.send_records(records, update_hw).await?;
-            self.send_message(rep_id, LeaderReplicaControllerCommand::EndOffsetUpdated)
-                .await
-                .map_err(|err| err.into())
+    ) -> Result<Option<(Offset, Offset, ErrorCode)>, InternalServerError> {
+        let (base_offset, leo, error_code) = if let Some(mut leader_replica) =
+            self.get_mut_replica(rep_id)
+        {
+            let (base_offset, error_code) = leader_replica.send_records(records, update_hw).await?;
+            (base_offset, leader_replica.leo(), error_code)
         } else {
             warn!("no replica is found: {}", rep_id);
-            Ok(false)
+            return Ok(None);
+        };
+
+        if self
+            .send_message(rep_id, LeaderReplicaControllerCommand::EndOffsetUpdated)
+            .await?
+        {
+            Ok(Some((base_offset, leo, error_code)))
+        } else {
+            Ok(None)
         }
     }
+
+    /// high watermark of replica
+    pub fn hw(&self, rep_id: &ReplicaKey) -> Option<Offset> {
+        self.get_replica(rep_id)
+            .map(|leader_replica| leader_replica.hw())
+    }
 }
 
 #[cfg(test)]
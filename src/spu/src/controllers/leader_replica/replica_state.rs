@@ -1,4 +1,10 @@
 use std::collections::BTreeMap;
+use std::io::Error as IoError;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use tracing::debug;
 use tracing::trace;
@@ -7,12 +13,16 @@ use tracing::warn;
 
 use fluvio_socket::SinkPool;
 use dataplane::record::RecordSet;
-use dataplane::{Offset, Isolation};
+use dataplane::{ErrorCode, Offset, Isolation};
+use dataplane::batch::BatchHeader;
+use dataplane::batch::increment_sequence;
+use dataplane::batch::DefaultBatch;
 use dataplane::api::RequestMessage;
 
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use fluvio_controlplane_metadata::partition::Replica;
 use fluvio_controlplane::UpdateLrsRequest;
+use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
 use fluvio_storage::FileReplica;
 use fluvio_storage::ConfigOption;
 use fluvio_storage::StorageError;
@@ -23,11 +33,20 @@ use fluvio_storage::ReplicaStorage;
 use fluvio_socket::ExclusiveFlvSink;
 
 use crate::core::storage::create_replica_storage;
+use crate::core::storage::retention_config;
+use crate::core::ReplicaMetrics;
 use crate::controllers::follower_replica::FileSyncRequest;
 use crate::controllers::follower_replica::PeerFileTopicResponse;
 use crate::controllers::follower_replica::PeerFilePartitionResponse;
 
 use super::FollowerOffsetUpdate;
+use super::throttle::ReplicationThrottle;
+
+/// followers which have not caught up with leader end offset within this time are out of sync
+const REPLICA_MAX_LAG: Duration = Duration::from_secs(10);
+
+/// batches up to this far behind last sequence of producer are duplicates, further ones are gaps
+const MAX_SEQUENCE_DISTANCE: i64 = 1 << 30;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FollowerReplicaInfo {
@@ -77,7 +96,15 @@ pub struct LeaderReplicaState<S> {
     replica_id: ReplicaKey,
     leader_id: SpuId,
     followers: BTreeMap<SpuId, FollowerReplicaInfo>,
+    /// last time each follower was caught up with leader end offset
+    followers_caught_up: BTreeMap<SpuId, Instant>,
+    /// leader end offset when records were last sent to each follower
+    synced_leos: Mutex<BTreeMap<SpuId, Offset>>,
     storage: S,
+    metrics: Option<ReplicaMetrics>,
+    throttle: ReplicationThrottle,
+    /// produce is rejected when fewer replicas are in sync, leader included
+    min_in_sync_replicas: u16,
 }
 
 impl<S> LeaderReplicaState<S> {
@@ -90,7 +117,12 @@ impl<S> LeaderReplicaState<S> {
             replica_id: replica_id.into(),
             leader_id,
             followers: BTreeMap::new(),
+            followers_caught_up: BTreeMap::new(),
+            synced_leos: Mutex::new(BTreeMap::new()),
             storage,
+            metrics: None,
+            throttle: ReplicationThrottle::default(),
+            min_in_sync_replicas: 1,
         };
         state.add_follower_replica(follower_ids);
         state
@@ -100,6 +132,24 @@ impl<S> LeaderReplicaState<S> {
         &self.replica_id
     }
 
+    /// limit bytes per second copied to followers, 0 is unlimited
+    pub fn with_throttle(mut self, throttle: u32) -> Self {
+        self.throttle = ReplicationThrottle::new(throttle);
+        self
+    }
+
+    /// min replicas in sync, leader included, to accept produce
+    pub fn with_min_in_sync_replicas(mut self, min_in_sync_replicas: u16) -> Self {
+        self.min_in_sync_replicas = min_in_sync_replicas;
+        self
+    }
+
+    /// export offsets of this replica
+    pub fn with_metrics(mut self, metrics: ReplicaMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     pub fn storage(&self) -> &S {
         &self.storage
     }
@@ -109,12 +159,47 @@ impl<S> LeaderReplicaState<S> {
         &mut self.storage
     }
 
+    pub fn storage_owned(self) -> S {
+        self.storage
+    }
+
     /// probably only used in the test
     #[allow(dead_code)]
     pub(crate) fn followers(&self, spu: &SpuId) -> Option<FollowerReplicaInfo> {
         self.followers.get(spu).cloned()
     }
 
+    /// apply replica changes from sc.
+    /// followers added by reassignment must catch up before they are in sync
+    pub fn update_replica(&mut self, replica: &Replica) {
+        let leader_id = self.leader_id;
+        for id in replica.replicas.iter().filter(|id| **id != leader_id) {
+            if !self.followers.contains_key(id) {
+                debug!("replica: {}, adding follower: {}", self.replica_id, id);
+                self.followers.insert(*id, FollowerReplicaInfo::default());
+            }
+        }
+
+        let replica_id = &self.replica_id;
+        self.followers.retain(|id, _| {
+            let keep = replica.replicas.contains(id);
+            if !keep {
+                debug!("replica: {}, removing follower: {}", replica_id, id);
+            }
+            keep
+        });
+        self.followers_caught_up
+            .retain(|id, _| replica.replicas.contains(id));
+        self.synced_leos
+            .lock()
+            .unwrap()
+            .retain(|id, _| replica.replicas.contains(id));
+
+        if replica.throttle != self.throttle.rate() {
+            self.throttle = ReplicationThrottle::new(replica.throttle);
+        }
+    }
+
     /// if replica id's doesn't exists, then add, otherwise ignore it
     #[allow(clippy::map_entry)]
     fn add_follower_replica(&mut self, follower_ids: Vec<SpuId>) {
@@ -133,6 +218,8 @@ impl<S> LeaderReplicaState<S> {
                     id
                 );
                 self.followers.insert(id, FollowerReplicaInfo::default());
+                // new followers are given time to catch up before they are out of sync
+                self.followers_caught_up.insert(id, Instant::now());
             }
         }
     }
@@ -150,6 +237,13 @@ where
         self.storage.get_hw()
     }
 
+    /// update exported offsets
+    pub fn update_metrics(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.update(self.leo(), self.hw());
+        }
+    }
+
     /// update followers offset, return (status_needs_to_changed,follower to be synced)
     ///
     /// // case 1:  follower offset has same value as leader
@@ -194,6 +288,15 @@ where
                 false
             };
 
+        // under load, leader has usually written more by the time follower reports.
+        // follower is still caught up if it has all records leader had when it was last synced
+        let synced_leo = self.synced_leos.lock().unwrap().get(&follower_id).copied();
+        if follower_info.leo == leader_leo
+            || matches!(synced_leo, Some(synced_leo) if follower_info.leo >= synced_leo)
+        {
+            self.followers_caught_up.insert(follower_id, Instant::now());
+        }
+
         (
             changed,
             if leader_leo != follower_info.leo || leader_hw != follower_info.hw {
@@ -204,6 +307,130 @@ where
         )
     }
 
+    /// check sequence numbers of batches from idempotent producers.
+    /// batches which have already been written are removed from records.
+    /// return error if any batch must be rejected, or if all batches are duplicates
+    pub fn check_sequences(&self, records: &mut RecordSet) -> ErrorCode {
+        // batches earlier in same request are not in storage yet
+        let mut written: BTreeMap<i64, (i16, i32)> = BTreeMap::new();
+        let mut duplicates = 0;
+        let mut batches = Vec::with_capacity(records.batches.len());
+
+        for batch in records.batches.drain(..) {
+            let header = batch.get_header();
+            if header.producer_id < 0 {
+                batches.push(batch);
+                continue;
+            }
+
+            let last = written.get(&header.producer_id).cloned().or_else(|| {
+                self.storage
+                    .get_producer_state(header.producer_id)
+                    .map(|state| (state.epoch, state.last_sequence))
+            });
+            match check_sequence(last, header) {
+                ErrorCode::None => {
+                    written.insert(
+                        header.producer_id,
+                        (header.producer_epoch, header.last_sequence()),
+                    );
+                    batches.push(batch);
+                }
+                ErrorCode::DuplicateSequenceNumber => {
+                    debug!(
+                        "replica: {}, skipping duplicate batch of producer: {}, sequence: {}",
+                        self.replica_id, header.producer_id, header.first_sequence
+                    );
+                    duplicates += 1;
+                }
+                error_code => {
+                    debug!(
+                        "replica: {}, rejecting batch of producer: {}, sequence: {}, {:?}",
+                        self.replica_id, header.producer_id, header.first_sequence, error_code
+                    );
+                    return error_code;
+                }
+            }
+        }
+
+        records.batches = batches;
+        if duplicates > 0 && records.batches.is_empty() {
+            ErrorCode::DuplicateSequenceNumber
+        } else {
+            ErrorCode::None
+        }
+    }
+
+    /// base offset of batches which are all duplicates, if they are retry of last batches
+    /// written by their producer.  -1 if original offset is no longer known
+    pub fn duplicate_base_offset(&self, records: &RecordSet) -> Offset {
+        let last = match records.batches.last() {
+            Some(batch) => batch.get_header(),
+            None => return -1,
+        };
+        let same_producer = records
+            .batches
+            .iter()
+            .all(|batch| batch.get_header().producer_id == last.producer_id);
+        match self.storage.get_producer_state(last.producer_id) {
+            Some(state)
+                if same_producer
+                    && state.epoch == last.producer_epoch
+                    && state.last_sequence == last.last_sequence() =>
+            {
+                let records_count: Offset = records
+                    .batches
+                    .iter()
+                    .map(|batch| batch.get_last_offset_delta() as Offset + 1)
+                    .sum();
+                state.last_offset + 1 - records_count
+            }
+            _ => -1,
+        }
+    }
+
+    /// true if some followers are behind and sync is limited by throttle
+    pub fn is_throttled(&self) -> bool {
+        self.throttle.is_limited() && !self.need_follower_updates().is_empty()
+    }
+
+    /// followers which are at leader end offset or have caught up with it recently
+    pub fn in_sync_followers(&self) -> Vec<SpuId> {
+        let leo = self.leo();
+        self.followers
+            .iter()
+            .filter(|(follower_id, follower_info)| {
+                (follower_info.is_valid() && follower_info.leo == leo)
+                    || self
+                        .followers_caught_up
+                        .get(follower_id)
+                        .map(|caught_up| caught_up.elapsed() < REPLICA_MAX_LAG)
+                        .unwrap_or(false)
+            })
+            .map(|(follower_id, _)| *follower_id)
+            .collect()
+    }
+
+    /// leader followed by in sync followers
+    pub fn in_sync_replicas(&self) -> Vec<SpuId> {
+        let mut replicas = vec![self.leader_id];
+        replicas.extend(self.in_sync_followers());
+        replicas
+    }
+
+    /// true if there are enough replicas in sync to accept produce
+    pub fn has_min_in_sync_replicas(&self) -> bool {
+        self.in_sync_replicas().len() >= self.min_in_sync_replicas as usize
+    }
+
+    /// high watermark is smallest end offset among leader and in sync followers
+    fn compute_hw(&self) -> Offset {
+        self.in_sync_followers()
+            .iter()
+            .filter_map(|follower_id| self.followers.get(follower_id))
+            .fold(self.leo(), |hw, follower_info| hw.min(follower_info.leo))
+    }
+
     /// compute list of followers that need to be sync
     /// this is done by checking diff of end offset and high watermark
     fn need_follower_updates(&self) -> Vec<(SpuId, FollowerReplicaInfo)> {
@@ -249,6 +476,7 @@ where
             .collect();
 
         UpdateLrsRequest::new(self.replica_id.clone(), leader, replicas)
+            .with_in_sync_replicas(self.in_sync_replicas())
     }
 
     pub async fn send_status_to_sc(&self, sc_sink: &ExclusiveFlvSink) {
@@ -277,14 +505,34 @@ impl LeaderReplicaState<FileReplica> {
             leader
         );
 
+        let config = retention_config(config.clone(), &leader.retention);
         let storage = create_replica_storage(leader.leader, &leader.id, &config).await?;
 
-        Ok(Self::new(
-            leader.id,
-            leader.leader,
-            storage,
-            leader.replicas,
-        ))
+        Ok(
+            Self::new(leader.id, leader.leader, storage, leader.replicas)
+                .with_throttle(leader.throttle),
+        )
+    }
+
+    /// advance high watermark to offset replicated by all in sync followers.
+    /// return true if high watermark has changed
+    pub async fn update_hw(&mut self) -> Result<bool, IoError> {
+        let hw = self.compute_hw();
+        if hw > self.hw() {
+            debug!(
+                "replica: {}, advancing high watermark: {} => {}",
+                self.replica_id,
+                self.hw(),
+                hw
+            );
+            self.storage.update_high_watermark(hw).await?;
+            self.update_metrics();
+            Ok(true)
+        } else {
+            // end offset may still have changed
+            self.update_metrics();
+            Ok(false)
+        }
     }
 
     /// sync specific follower
@@ -300,22 +548,13 @@ impl LeaderReplicaState<FileReplica> {
                 "sink is found for follower: {}, ready to build sync records",
                 follower_id
             );
-            let mut sync_request = FileSyncRequest::default();
-            let mut topic_response = PeerFileTopicResponse::default();
-            topic_response.name = self.replica_id.topic.to_owned();
-            let mut partition_response = PeerFilePartitionResponse::default();
-            partition_response.partition_index = self.replica_id.partition;
-            self.read_records(
-                follower_info.leo,
-                max_bytes,
-                Isolation::ReadUncommitted,
-                &mut partition_response,
-            )
-            .await;
-            partition_response.last_stable_offset = self.leo();
-            partition_response.high_watermark = self.hw();
-            topic_response.partitions.push(partition_response);
-            sync_request.topics.push(topic_response);
+            let sync_request = match self
+                .follower_sync_request(follower_id, follower_info, max_bytes)
+                .await
+            {
+                Some(sync_request) => sync_request,
+                None => return,
+            };
 
             let request = RequestMessage::new_request(sync_request).set_client_id(format!(
                 "leader: {}, replica: {}",
@@ -336,6 +575,60 @@ impl LeaderReplicaState<FileReplica> {
         }
     }
 
+    /// build request with records follower doesn't have, none if sync is throttled.
+    /// follower only writes whole batches, so records are not cut in middle of batch
+    async fn follower_sync_request(
+        &self,
+        follower_id: SpuId,
+        follower_info: &FollowerReplicaInfo,
+        max_bytes: u32,
+    ) -> Option<FileSyncRequest> {
+        let max_bytes = match self.throttle.available() {
+            Some(0) => {
+                debug!(
+                    "replica: {}, sync to follower: {} is throttled",
+                    self.replica_id, follower_id
+                );
+                return None;
+            }
+            Some(available) => available.min(max_bytes),
+            None => max_bytes,
+        };
+
+        let mut sync_request = FileSyncRequest::default();
+        let mut topic_response = PeerFileTopicResponse {
+            name: self.replica_id.topic.to_owned(),
+            ..Default::default()
+        };
+        let mut partition_response = PeerFilePartitionResponse {
+            partition_index: self.replica_id.partition,
+            ..Default::default()
+        };
+        self.storage
+            .read_whole_batches(follower_info.leo, max_bytes, &mut partition_response)
+            .await;
+        self.throttle.consume(partition_response.records.len());
+        self.synced_leos
+            .lock()
+            .unwrap()
+            .insert(follower_id, self.leo());
+        partition_response.last_stable_offset = self.leo();
+        partition_response.high_watermark = self.hw();
+        partition_response.consumer_offsets = self
+            .storage
+            .consumer_offsets()
+            .offsets()
+            .iter()
+            .map(|(group, offset)| ConsumerOffset {
+                group: group.clone(),
+                offset: *offset,
+            })
+            .collect();
+        topic_response.partitions.push(partition_response);
+        sync_request.topics.push(topic_response);
+        Some(sync_request)
+    }
+
     /// synchronize
     pub async fn sync_followers(&self, sinks: &SinkPool<SpuId>, max_bytes: u32) {
         let follower_sync = self.need_follower_updates();
@@ -346,6 +639,21 @@ impl LeaderReplicaState<FileReplica> {
         }
     }
 
+    /// sync all followers, including caught up ones, so they have latest consumer offsets
+    pub async fn sync_all_followers(&self, sinks: &SinkPool<SpuId>, max_bytes: u32) {
+        let followers: Vec<(SpuId, FollowerReplicaInfo)> = self
+            .followers
+            .iter()
+            .filter(|(_, follower_info)| follower_info.is_valid())
+            .map(|(follower_id, follower_info)| (*follower_id, follower_info.clone()))
+            .collect();
+
+        for (follower_id, follower_info) in followers {
+            self.sync_follower(sinks, follower_id, &follower_info, max_bytes)
+                .await;
+        }
+    }
+
     /// read records into partition response
     /// return hw and leo
     pub async fn read_records<P>(
@@ -374,19 +682,78 @@ impl LeaderReplicaState<FileReplica> {
         (self.hw(), self.leo())
     }
 
+    /// read decoded batches from offset, only batches before high watermark if committed
+    pub async fn read_batches(
+        &self,
+        offset: Offset,
+        max_len: u32,
+        isolation: Isolation,
+    ) -> Result<Vec<DefaultBatch>, StorageError> {
+        let max_offset = match isolation {
+            Isolation::ReadCommitted => Some(self.hw()),
+            Isolation::ReadUncommitted => None,
+        };
+        self.storage.read_batches(offset, max_offset, max_len).await
+    }
+
+    /// write records, if `update_hw` high watermark moves to end offset unless there are
+    /// in sync followers, then it only moves once followers have records.
+    /// return base offset of written records, or of original records if all are duplicates.
+    /// return error code if there are not enough replicas in sync or batches are rejected
+    /// by sequence check, nothing is written then and base offset is -1
     pub async fn send_records(
         &mut self,
-        records: RecordSet,
-        update_highwatermark: bool,
-    ) -> Result<(), StorageError> {
+        mut records: RecordSet,
+        update_hw: bool,
+    ) -> Result<(Offset, ErrorCode), StorageError> {
         trace!(
             "writing records to leader: {} replica: {}, ",
             self.leader_id,
             self.replica_id
         );
-        self.storage
-            .send_records(records, update_highwatermark)
-            .await
+        // followers at end offset only start lagging once records are written
+        let leo = self.leo();
+        for (follower_id, follower_info) in self.followers.iter() {
+            if follower_info.is_valid() && follower_info.leo == leo {
+                self.followers_caught_up
+                    .insert(*follower_id, Instant::now());
+            }
+        }
+        if !self.has_min_in_sync_replicas() {
+            warn!(
+                "replica: {}, in sync replicas: {:?} below min: {}, rejecting records",
+                self.replica_id,
+                self.in_sync_replicas(),
+                self.min_in_sync_replicas
+            );
+            return Ok((-1, ErrorCode::NotEnoughReplicas));
+        }
+        let duplicate_base_offset = self.duplicate_base_offset(&records);
+        match self.check_sequences(&mut records) {
+            ErrorCode::None => {}
+            ErrorCode::DuplicateSequenceNumber => {
+                return Ok((duplicate_base_offset, ErrorCode::DuplicateSequenceNumber))
+            }
+            error_code => return Ok((-1, error_code)),
+        }
+        // records are stored as compressed by producer, which applies codec of topic
+        // retention relies on batch timestamp, use append time if producer didn't set it
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        for batch in records.batches.iter_mut() {
+            let header = batch.get_mut_header();
+            if header.max_time_stamp <= 0 {
+                header.first_timestamp = now;
+                header.max_time_stamp = now;
+            }
+        }
+        let base_offset = self.leo();
+        let commit = update_hw && self.in_sync_followers().is_empty();
+        self.storage.send_records(records, commit).await?;
+        self.update_metrics();
+        Ok((base_offset, ErrorCode::None))
     }
 
     #[allow(dead_code)]
@@ -399,22 +766,77 @@ impl LeaderReplicaState<FileReplica> {
     }
 }
 
+/// check sequence of batch against last epoch and sequence written by producer
+fn check_sequence(last: Option<(i16, i32)>, header: &BatchHeader) -> ErrorCode {
+    let first_sequence = header.first_sequence;
+    if first_sequence < 0 {
+        return ErrorCode::OutOfOrderSequenceNumber;
+    }
+
+    match last {
+        // producer must start from beginning
+        None if first_sequence == 0 => ErrorCode::None,
+        None => ErrorCode::UnknownProducerId,
+        Some((epoch, _)) if header.producer_epoch < epoch => ErrorCode::InvalidProducerEpoch,
+        Some((epoch, _)) if header.producer_epoch > epoch => {
+            if first_sequence == 0 {
+                ErrorCode::None
+            } else {
+                ErrorCode::OutOfOrderSequenceNumber
+            }
+        }
+        Some((_, last_sequence)) if first_sequence == increment_sequence(last_sequence, 1) => {
+            ErrorCode::None
+        }
+        // batch ending at or before last sequence, sequences may have wrapped around in between
+        Some((_, last_sequence))
+            if sequence_distance(header.last_sequence(), last_sequence) < MAX_SEQUENCE_DISTANCE =>
+        {
+            ErrorCode::DuplicateSequenceNumber
+        }
+        Some(_) => ErrorCode::OutOfOrderSequenceNumber,
+    }
+}
+
+/// distance from one sequence number forward to another, counting wrap around after i32::MAX
+fn sequence_distance(from: i32, to: i32) -> i64 {
+    (to as i64 - from as i64).rem_euclid(i32::MAX as i64 + 1)
+}
+
 #[cfg(test)]
 mod test {
 
+    use std::collections::BTreeMap;
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_future::test_async;
+    use fluvio_controlplane_metadata::partition::Replica;
     use fluvio_storage::ReplicaStorage;
-    use dataplane::Offset;
+    use fluvio_storage::ProducerState;
+    use fluvio_storage::ConfigOption;
+    use dataplane::{ErrorCode, Offset};
+    use dataplane::batch::DefaultBatch;
+    use dataplane::core::Encoder;
+    use dataplane::record::{DefaultRecord, RecordSet};
 
     use super::LeaderReplicaState;
+    use super::FollowerReplicaInfo;
 
     struct MockReplica {
         hw: Offset,
         leo: Offset,
+        producers: BTreeMap<i64, ProducerState>,
     }
 
     impl MockReplica {
         fn new(leo: Offset, hw: Offset) -> Self {
-            MockReplica { hw, leo }
+            MockReplica {
+                hw,
+                leo,
+                producers: BTreeMap::new(),
+            }
         }
     }
 
@@ -426,6 +848,10 @@ mod test {
         fn get_leo(&self) -> Offset {
             self.leo
         }
+
+        fn get_producer_state(&self, producer_id: i64) -> Option<ProducerState> {
+            self.producers.get(&producer_id).cloned()
+        }
     }
 
     #[test]
@@ -497,4 +923,299 @@ mod test {
         );
         assert_eq!(replica_state.need_follower_updates().len(), 0);
     }
+
+    #[test]
+    fn test_hw_from_in_sync_followers() {
+        use std::time::Duration;
+        use std::time::Instant;
+
+        let mock_replica = MockReplica::new(20, 10); // eof, hw
+
+        let mut replica_state =
+            LeaderReplicaState::new(("test", 1), 5000, mock_replica, vec![5001, 5002]);
+        assert_eq!(replica_state.in_sync_followers(), vec![5001, 5002]);
+        // followers have not reported yet
+        assert_eq!(replica_state.compute_hw(), -1);
+
+        replica_state.update_follower_offsets((5001, 15, 10));
+        replica_state.update_follower_offsets((5002, 20, 10));
+        assert_eq!(replica_state.compute_hw(), 15);
+
+        replica_state.update_follower_offsets((5001, 20, 10));
+        assert_eq!(replica_state.compute_hw(), 20);
+
+        // follower which has not caught up for long time is out of sync
+        replica_state.mut_storage().leo = 30;
+        replica_state.update_follower_offsets((5002, 30, 20));
+        replica_state.followers_caught_up.insert(
+            5001,
+            Instant::now() - super::REPLICA_MAX_LAG - Duration::from_secs(1),
+        );
+        assert_eq!(replica_state.in_sync_followers(), vec![5002]);
+        assert_eq!(replica_state.compute_hw(), 30);
+    }
+
+    #[test]
+    fn test_follower_caught_up_under_load() {
+        let mock_replica = MockReplica::new(20, 10);
+        let mut replica_state =
+            LeaderReplicaState::new(("test", 1), 5000, mock_replica, vec![5001]);
+
+        // follower has fallen behind since it was added
+        replica_state.followers_caught_up.remove(&5001);
+
+        // leader has written more since follower was synced at 20
+        replica_state.synced_leos.lock().unwrap().insert(5001, 20);
+        replica_state.mut_storage().leo = 25;
+        replica_state.update_follower_offsets((5001, 18, 10));
+        assert!(!replica_state.followers_caught_up.contains_key(&5001));
+
+        replica_state.update_follower_offsets((5001, 20, 10));
+        assert!(replica_state.followers_caught_up.contains_key(&5001));
+        assert_eq!(replica_state.in_sync_followers(), vec![5001]);
+    }
+
+    #[test]
+    fn test_update_replica_followers() {
+        use fluvio_controlplane_metadata::partition::Replica;
+
+        let mock_replica = MockReplica::new(20, 10); // eof, hw
+
+        let mut replica_state =
+            LeaderReplicaState::new(("test", 1), 5000, mock_replica, vec![5000, 5001]);
+        replica_state.update_follower_offsets((5001, 20, 10));
+
+        // follower 5002 is added by reassignment, it is not in sync until it catches up
+        let mut replica = Replica::new(("test", 1).into(), 5000, vec![5000, 5001, 5002]);
+        replica.throttle = 100;
+        replica_state.update_replica(&replica);
+        assert!(replica_state.followers(&5002).is_some());
+        assert_eq!(replica_state.in_sync_followers(), vec![5001]);
+        assert_eq!(replica_state.compute_hw(), 20);
+        assert_eq!(replica_state.throttle.rate(), 100);
+
+        replica_state.update_follower_offsets((5002, 5, 5));
+        assert!(replica_state.is_throttled());
+
+        // follower 5001 is dropped
+        let replica = Replica::new(("test", 1).into(), 5000, vec![5000, 5002]);
+        replica_state.update_replica(&replica);
+        assert!(replica_state.followers(&5001).is_none());
+        assert!(replica_state.in_sync_followers().is_empty());
+    }
+
+    #[test]
+    fn test_in_sync_replicas() {
+        use std::time::Duration;
+        use std::time::Instant;
+
+        let mock_replica = MockReplica::new(20, 20); // eof, hw
+
+        let mut replica_state =
+            LeaderReplicaState::new(("test", 1), 5000, mock_replica, vec![5000, 5001, 5002])
+                .with_min_in_sync_replicas(2);
+        replica_state.update_follower_offsets((5001, 20, 20));
+        replica_state.update_follower_offsets((5002, 10, 10));
+
+        // follower at end offset stays in sync while there are no new records
+        let lagged = Instant::now() - super::REPLICA_MAX_LAG - Duration::from_secs(1);
+        replica_state.followers_caught_up.insert(5001, lagged);
+        replica_state.followers_caught_up.insert(5002, lagged);
+        assert_eq!(replica_state.in_sync_replicas(), vec![5000, 5001]);
+        assert!(replica_state.has_min_in_sync_replicas());
+
+        replica_state.mut_storage().leo = 30;
+        assert_eq!(replica_state.in_sync_replicas(), vec![5000]);
+        assert!(!replica_state.has_min_in_sync_replicas());
+
+        replica_state.update_follower_offsets((5002, 30, 20));
+        assert_eq!(replica_state.in_sync_replicas(), vec![5000, 5002]);
+        assert!(replica_state.has_min_in_sync_replicas());
+    }
+
+    /// batch of idempotent producer with records
+    fn producer_batch(
+        producer_id: i64,
+        epoch: i16,
+        first_sequence: i32,
+        records: i32,
+    ) -> DefaultBatch {
+        let mut batch = DefaultBatch::default();
+        let header = batch.get_mut_header();
+        header.producer_id = producer_id;
+        header.producer_epoch = epoch;
+        header.first_sequence = first_sequence;
+        header.last_offset_delta = records - 1;
+        batch
+    }
+
+    fn check(
+        replica_state: &LeaderReplicaState<MockReplica>,
+        batches: Vec<DefaultBatch>,
+    ) -> (ErrorCode, usize) {
+        let mut records = RecordSet { batches };
+        let error_code = replica_state.check_sequences(&mut records);
+        (error_code, records.batches.len())
+    }
+
+    #[test]
+    fn test_check_sequences() {
+        let mut mock_replica = MockReplica::new(20, 20);
+        mock_replica.producers.insert(
+            1,
+            ProducerState {
+                epoch: 0,
+                last_sequence: 9,
+                last_offset: 19,
+            },
+        );
+        let replica_state = LeaderReplicaState::new(("test", 1), 5000, mock_replica, vec![]);
+
+        // batches without producer are not checked
+        assert_eq!(
+            check(&replica_state, vec![DefaultBatch::default()]),
+            (ErrorCode::None, 1)
+        );
+
+        // next sequence
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 0, 10, 2)]),
+            (ErrorCode::None, 1)
+        );
+        // consecutive batches in same request
+        assert_eq!(
+            check(
+                &replica_state,
+                vec![producer_batch(1, 0, 10, 2), producer_batch(1, 0, 12, 1)]
+            ),
+            (ErrorCode::None, 2)
+        );
+        // retried batch is removed
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 0, 8, 2)]),
+            (ErrorCode::DuplicateSequenceNumber, 0)
+        );
+        // offset of retried batch is known only if it was last batch of producer
+        let retried = |batches| replica_state.duplicate_base_offset(&RecordSet { batches });
+        assert_eq!(retried(vec![producer_batch(1, 0, 8, 2)]), 18);
+        assert_eq!(
+            retried(vec![producer_batch(1, 0, 5, 3), producer_batch(1, 0, 8, 2)]),
+            15
+        );
+        assert_eq!(retried(vec![producer_batch(1, 0, 6, 2)]), -1);
+        assert_eq!(
+            check(
+                &replica_state,
+                vec![producer_batch(1, 0, 8, 2), producer_batch(1, 0, 10, 1)]
+            ),
+            (ErrorCode::None, 1)
+        );
+        // gap in sequence
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 0, 12, 1)]),
+            (ErrorCode::OutOfOrderSequenceNumber, 0)
+        );
+        // partially written
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 0, 9, 2)]),
+            (ErrorCode::OutOfOrderSequenceNumber, 0)
+        );
+        // fenced epoch
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, -1, 10, 1)]),
+            (ErrorCode::InvalidProducerEpoch, 0)
+        );
+        // new epoch starts from 0
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 1, 0, 1)]),
+            (ErrorCode::None, 1)
+        );
+        // new producer
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(2, 0, 0, 1)]),
+            (ErrorCode::None, 1)
+        );
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(2, 0, 5, 1)]),
+            (ErrorCode::UnknownProducerId, 0)
+        );
+    }
+
+    #[test_async]
+    async fn test_throttled_sync_sends_whole_batch() -> Result<(), ()> {
+        let base_dir = temp_dir().join("throttled-sync");
+        ensure_clean_dir(&base_dir);
+        let config = ConfigOption {
+            base_dir,
+            ..Default::default()
+        };
+        let mut replica = Replica::new(("test", 0).into(), 5000, vec![5000, 5001]);
+        replica.throttle = 100;
+        let mut replica_state = LeaderReplicaState::create_file_replica(replica, &config)
+            .await
+            .expect("replica");
+
+        // batch is bigger than bytes follower may get per second
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from(vec![0u8; 200]));
+        let batch_len = batch.write_size(0);
+        replica_state
+            .send_records(
+                RecordSet {
+                    batches: vec![batch],
+                },
+                true,
+            )
+            .await
+            .expect("write");
+
+        let follower_info = FollowerReplicaInfo::new(0, 0);
+        let request = replica_state
+            .follower_sync_request(5001, &follower_info, 1_000_000)
+            .await
+            .expect("sync request");
+        assert_eq!(request.topics[0].partitions[0].records.len(), batch_len);
+
+        // bytes over limit are paid off before next sync
+        assert_eq!(replica_state.throttle.available(), Some(0));
+        assert!(replica_state
+            .follower_sync_request(5001, &follower_info, 1_000_000)
+            .await
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_sequences_wrap_around() {
+        let mut mock_replica = MockReplica::new(20, 20);
+        mock_replica.producers.insert(
+            1,
+            ProducerState {
+                epoch: 0,
+                last_sequence: i32::MAX - 1,
+                last_offset: 19,
+            },
+        );
+        let replica_state = LeaderReplicaState::new(("test", 1), 5000, mock_replica, vec![]);
+
+        // batch crossing i32::MAX continues from 0
+        assert_eq!(
+            check(
+                &replica_state,
+                vec![
+                    producer_batch(1, 0, i32::MAX, 3),
+                    producer_batch(1, 0, 2, 1)
+                ]
+            ),
+            (ErrorCode::None, 2)
+        );
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 0, i32::MAX - 2, 2)]),
+            (ErrorCode::DuplicateSequenceNumber, 0)
+        );
+        assert_eq!(
+            check(&replica_state, vec![producer_batch(1, 0, 0, 1)]),
+            (ErrorCode::OutOfOrderSequenceNumber, 0)
+        );
+    }
 }
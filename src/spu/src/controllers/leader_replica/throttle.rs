@@ -0,0 +1,104 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+/// length of window in which bytes are counted
+const THROTTLE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Limits bytes leader copies to followers, used while partition is reassigned.
+///
+/// Bytes are counted in windows of one second. Followers are sent whole batches,
+/// so bytes sent in a window may go over the limit. Bytes over the limit are carried
+/// into following windows, followers are not synced until they are used up.
+#[derive(Debug)]
+pub struct ReplicationThrottle {
+    /// bytes per second, 0 is unlimited
+    rate: u32,
+    /// start of current window and bytes sent in it
+    window: Mutex<(Instant, u64)>,
+}
+
+impl Default for ReplicationThrottle {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl ReplicationThrottle {
+    pub fn new(rate: u32) -> Self {
+        Self {
+            rate,
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.rate > 0
+    }
+
+    /// bytes that can be sent now, none if there is no limit
+    pub fn available(&self) -> Option<u32> {
+        if !self.is_limited() {
+            return None;
+        }
+
+        let mut window = self.window.lock().unwrap();
+        let elapsed_windows = (window.0.elapsed().as_millis() / THROTTLE_WINDOW.as_millis()) as u32;
+        if elapsed_windows > 0 {
+            window.0 += THROTTLE_WINDOW * elapsed_windows;
+            window.1 = window
+                .1
+                .saturating_sub(self.rate as u64 * elapsed_windows as u64);
+        }
+        Some((self.rate as u64).saturating_sub(window.1) as u32)
+    }
+
+    /// record bytes that have been sent
+    pub fn consume(&self, bytes: usize) {
+        if self.is_limited() {
+            let mut window = self.window.lock().unwrap();
+            window.1 += bytes as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ReplicationThrottle;
+    use super::THROTTLE_WINDOW;
+
+    #[test]
+    fn test_throttle() {
+        let unlimited = ReplicationThrottle::default();
+        assert_eq!(unlimited.available(), None);
+
+        let throttle = ReplicationThrottle::new(100);
+        assert_eq!(throttle.available(), Some(100));
+        throttle.consume(60);
+        assert_eq!(throttle.available(), Some(40));
+        throttle.consume(60);
+        assert_eq!(throttle.available(), Some(0));
+    }
+
+    #[test]
+    fn test_throttle_carries_excess_bytes() {
+        let throttle = ReplicationThrottle::new(100);
+        // batch bigger than limit is sent whole
+        throttle.consume(250);
+        assert_eq!(throttle.available(), Some(0));
+
+        throttle.window.lock().unwrap().0 -= THROTTLE_WINDOW;
+        assert_eq!(throttle.available(), Some(0));
+
+        throttle.window.lock().unwrap().0 -= THROTTLE_WINDOW;
+        assert_eq!(throttle.available(), Some(50));
+
+        throttle.window.lock().unwrap().0 -= THROTTLE_WINDOW * 3;
+        assert_eq!(throttle.available(), Some(100));
+    }
+}
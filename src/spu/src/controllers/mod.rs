@@ -1,3 +1,4 @@
 pub(crate) mod follower_replica;
 pub(crate) mod leader_replica;
 pub(crate) mod sc;
+pub(crate) mod retention;
@@ -0,0 +1,174 @@
+//!
+//! # Retention Cleaner
+//!
+//! Periodically deletes segments of leader and follower replicas
+//! which are past retention time or exceed maximum partition size of the topic.
+//! Segments of compacted topics are rewritten to keep only latest record of each key,
+//! once new segments have been rolled over since last compaction.
+//! Removed replicas in trash are deleted once delete grace period is over.
+//!
+use std::time::Duration;
+
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+
+use dataplane::{Offset, ReplicaKey};
+use fluvio_storage::{Compaction, CompactedSegments, ExpiredSegments, FileReplica, StorageError};
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::core::storage::purge_replica_trash;
+
+/// how often retention is checked
+const RETENTION_CHECK_INTERVAL_SEC: u64 = 60;
+
+pub struct RetentionCleaner {
+    ctx: DefaultSharedGlobalContext,
+    interval: Duration,
+}
+
+impl RetentionCleaner {
+    pub fn new(ctx: DefaultSharedGlobalContext) -> Self {
+        Self {
+            ctx,
+            interval: Duration::from_secs(RETENTION_CHECK_INTERVAL_SEC),
+        }
+    }
+
+    pub fn run(self) {
+        spawn(self.dispatch_loop());
+    }
+
+    async fn dispatch_loop(self) {
+        info!(
+            "starting retention cleaner, interval: {} secs",
+            self.interval.as_secs()
+        );
+        self.purge_trash().await;
+        loop {
+            sleep(self.interval).await;
+            self.clean().await;
+            self.purge_trash().await;
+        }
+    }
+
+    /// delete removed replicas which are past grace period
+    async fn purge_trash(&self) {
+        let local_id = self.ctx.local_spu_id();
+        match purge_replica_trash(local_id, self.ctx.config().storage()).await {
+            Ok(purged) => {
+                for path in purged {
+                    info!("purged replica from trash: {}", path.display());
+                }
+            }
+            Err(err) => error!("error purging trash: {}", err),
+        }
+    }
+
+    /// enforce retention and compaction on all replicas of this spu.
+    /// segments are deleted and rewritten without lock on replica, which is only held
+    /// to detach expired segments and to swap in compacted ones
+    async fn clean(&self) {
+        debug!("checking retention");
+
+        for key in self.ctx.leaders_state().replica_keys().await {
+            let expired = match self.ctx.leaders_state().get_mut_replica(&key) {
+                Some(mut replica) => detach_expired_segments(replica.mut_storage()).await,
+                None => continue,
+            };
+            remove_expired_segments("leader", &key, expired).await;
+
+            let compaction = self
+                .ctx
+                .leaders_state()
+                .get_replica(&key)
+                .and_then(|replica| replica.storage().prepare_compaction());
+            if let Some(compacted) = run_compaction("leader", &key, compaction).await {
+                if let Some(mut replica) = self.ctx.leaders_state().get_mut_replica(&key) {
+                    let applied = replica.mut_storage().apply_compaction(compacted).await;
+                    log_compaction("leader", &key, applied);
+                }
+            }
+        }
+
+        for key in self.ctx.followers_state().replica_keys() {
+            let expired = match self.ctx.followers_state().get_mut_replica(&key) {
+                Some(mut replica) => detach_expired_segments(replica.mut_storage()).await,
+                None => continue,
+            };
+            remove_expired_segments("follower", &key, expired).await;
+
+            let compaction = self
+                .ctx
+                .followers_state()
+                .get_replica(&key)
+                .and_then(|replica| replica.storage().prepare_compaction());
+            if let Some(compacted) = run_compaction("follower", &key, compaction).await {
+                if let Some(mut replica) = self.ctx.followers_state().get_mut_replica(&key) {
+                    let applied = replica.mut_storage().apply_compaction(compacted).await;
+                    log_compaction("follower", &key, applied);
+                }
+            }
+        }
+    }
+}
+
+/// detach expired segments, with log start offset after they are detached
+async fn detach_expired_segments(
+    storage: &mut FileReplica,
+) -> Result<(ExpiredSegments, Offset), StorageError> {
+    let expired = storage.detach_expired_segments().await?;
+    Ok((expired, storage.get_log_start_offset()))
+}
+
+async fn remove_expired_segments(
+    role: &str,
+    key: &ReplicaKey,
+    expired: Result<(ExpiredSegments, Offset), StorageError>,
+) {
+    let (expired, log_start_offset) = match expired {
+        Ok(expired) => expired,
+        Err(err) => {
+            error!("error enforcing retention on {}: {}, {}", role, key, err);
+            return;
+        }
+    };
+    if expired.is_empty() {
+        return;
+    }
+    let removed = expired.len();
+    match expired.remove().await {
+        Ok(()) => info!(
+            "{} replica: {} removed {} segments, log start offset: {}",
+            role, key, removed, log_start_offset
+        ),
+        Err(err) => error!("error removing segments of {}: {}, {}", role, key, err),
+    }
+}
+
+async fn run_compaction(
+    role: &str,
+    key: &ReplicaKey,
+    compaction: Option<Compaction>,
+) -> Option<CompactedSegments> {
+    match compaction?.run().await {
+        Ok(compacted) => Some(compacted),
+        Err(err) => {
+            error!("error compacting {}: {}, {}", role, key, err);
+            None
+        }
+    }
+}
+
+fn log_compaction(role: &str, key: &ReplicaKey, applied: Result<usize, StorageError>) {
+    match applied {
+        Ok(compacted) if compacted > 0 => {
+            info!("{} replica: {} compacted {} segments", role, key, compacted)
+        }
+        Ok(_) => {}
+        Err(err) => error!("error compacting {}: {}, {}", role, key, err),
+    }
+}
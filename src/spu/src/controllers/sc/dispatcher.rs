@@ -34,6 +34,9 @@ use fluvio_types::log_on_err;
 use flv_util::actions::Actions;
 
 use crate::core::SharedGlobalContext;
+use crate::core::storage::remove_replica_storage;
+use crate::core::storage::remove_orphan_replicas;
+use fluvio_types::SpuId;
 use crate::core::SpecChange;
 use crate::controllers::follower_replica::ReplicaFollowerController;
 use crate::controllers::follower_replica::FollowerReplicaControllerCommand;
@@ -57,6 +60,8 @@ pub struct ScDispatcher<S> {
     supervisor_command_sender: Sender<SupervisorCommand>,
     ctx: SharedGlobalContext<S>,
     max_bytes: u32,
+    /// orphan replicas are moved to trash once, after first replica update from sc
+    orphans_removed: bool,
 }
 
 impl<S> ScDispatcher<S> {
@@ -69,6 +74,7 @@ impl<S> ScDispatcher<S> {
             supervisor_command_sender,
             ctx,
             max_bytes,
+            orphans_removed: false,
         }
     }
 }
@@ -195,7 +201,8 @@ impl ScDispatcher<FileReplica> {
 
         debug!("sending spu '{}' registration request", local_spu_id);
 
-        let register_req = RegisterSpuRequest::new(local_spu_id);
+        let register_req = RegisterSpuRequest::new(local_spu_id)
+            .with_kafka_endpoint(self.ctx.config().kafka_advertised_endpoint.clone());
         let mut message = RequestMessage::new_request(register_req);
         message
             .get_mut_header()
@@ -291,9 +298,36 @@ impl ScDispatcher<FileReplica> {
         };
 
         self.apply_replica_actions(actions, shared_sc_sink).await;
+
+        // first update from sc has all replicas, even if it has none and looks like empty changes
+        if !self.orphans_removed {
+            self.remove_orphan_replicas().await;
+        }
         Ok(())
     }
 
+    /// move replica directories left from partitions deleted while spu was down to trash
+    async fn remove_orphan_replicas(&mut self) {
+        let local_id = self.ctx.local_spu_id();
+        let replicas: Vec<ReplicaKey> = self
+            .ctx
+            .replica_localstore()
+            .all_values()
+            .into_iter()
+            .filter(|replica| replica.replicas.contains(&local_id))
+            .map(|replica| replica.id)
+            .collect();
+        match remove_orphan_replicas(local_id, &replicas, self.ctx.config().storage()).await {
+            Ok(orphans) => {
+                for orphan in orphans {
+                    info!("moved orphan replica to trash: {}", orphan.display());
+                }
+                self.orphans_removed = true;
+            }
+            Err(err) => error!("failed to remove orphan replicas: {}", err),
+        }
+    }
+
     ///
     /// Follower Update Handler sent by a peer Spu
     ///
@@ -347,6 +381,11 @@ impl ScDispatcher<FileReplica> {
         for replica_action in actions.into_iter() {
             trace!("applying action: {:#?}", replica_action);
 
+            let replica_action = match local_replica_change(local_id, replica_action) {
+                Some(replica_action) => replica_action,
+                None => continue,
+            };
+
             match replica_action {
                 SpecChange::Add(new_replica) => {
                     if new_replica.leader == local_id {
@@ -357,10 +396,14 @@ impl ScDispatcher<FileReplica> {
                     }
                 }
                 SpecChange::Delete(deleted_replica) => {
-                    if deleted_replica.leader == local_id {
-                        self.remove_leader_replica(&deleted_replica.id).await;
+                    let id = deleted_replica.id.clone();
+                    let storage = if deleted_replica.leader == local_id {
+                        self.remove_leader_replica(&id).await
                     } else {
-                        self.remove_follower_replica(deleted_replica);
+                        self.remove_follower_replica(deleted_replica)
+                    };
+                    if let Some(storage) = storage {
+                        self.delete_replica_storage(&id, storage).await;
                     }
                 }
                 SpecChange::Mod(new_replica, old_replica) => {
@@ -407,9 +450,11 @@ impl ScDispatcher<FileReplica> {
 
         let storage_log = self.ctx.config().storage().new_config();
         let replica_id = replica.id.clone();
+        let min_in_sync_replicas = self.min_in_sync_replicas(&replica);
 
         match LeaderReplicaState::create_file_replica(replica, &storage_log).await {
             Ok(leader_replica) => {
+                let leader_replica = leader_replica.with_min_in_sync_replicas(min_in_sync_replicas);
                 debug!("file replica for leader is created: {}", storage_log);
                 self.spawn_leader_controller(replica_id, leader_replica, shared_sc_sink)
                     .await;
@@ -478,6 +523,9 @@ impl ScDispatcher<FileReplica> {
 
         let (sender, receiver) = bounded(10);
 
+        let leader_state = leader_state.with_metrics(self.ctx.metrics().replica(&replica_id));
+        leader_state.update_metrics();
+
         if let Some(old_replica) = self
             .ctx
             .leaders_state()
@@ -507,12 +555,29 @@ impl ScDispatcher<FileReplica> {
         skip(self, id),
         fields(replica_id = &*format!("{}", id))
     )]
-    pub async fn remove_leader_replica(&self, id: &ReplicaKey) {
+    pub async fn remove_leader_replica(&self, id: &ReplicaKey) -> Option<FileReplica> {
         debug!("removing leader replica");
 
-        if self.ctx.leaders_state().remove_replica(id).await.is_none() {
+        let leader_state = self.ctx.leaders_state().remove_replica(id).await;
+        if leader_state.is_none() {
             error!("failed to find leader replica when removing");
         }
+        self.ctx.metrics().remove_replica(id);
+        leader_state.map(|state| state.storage_owned())
+    }
+
+    /// delete logs of replica which is no longer hosted by this spu
+    async fn delete_replica_storage(&self, id: &ReplicaKey, storage: FileReplica) {
+        debug!("deleting storage of replica: {}", id);
+        if let Err(err) = remove_replica_storage(
+            self.ctx.local_spu_id(),
+            storage,
+            self.ctx.config().storage(),
+        )
+        .await
+        {
+            error!("failed to delete storage of replica: {}, {}", id, err);
+        }
     }
 
     /// Promote follower replica as leader,
@@ -538,12 +603,15 @@ impl ScDispatcher<FileReplica> {
                 old_replica.id
             );
 
+            let min_in_sync_replicas = self.min_in_sync_replicas(&new_replica);
             let leader_state = LeaderReplicaState::new(
                 new_replica.id.clone(),
                 new_replica.leader,
                 follower_replica.storage_owned(),
                 new_replica.replicas,
-            );
+            )
+            .with_throttle(new_replica.throttle)
+            .with_min_in_sync_replicas(min_in_sync_replicas);
 
             self.spawn_leader_controller(new_replica.id, leader_state, shared_sc_sink)
                 .await;
@@ -551,7 +619,7 @@ impl ScDispatcher<FileReplica> {
     }
 
     /// Demote leader replica as follower.
-    /// This only happens on manual election
+    /// This only happens on manual or preferred leader election and reassignment
     pub async fn demote_replica(&self, replica: Replica) {
         debug!("demoting replica: {}", replica);
 
@@ -559,6 +627,7 @@ impl ScDispatcher<FileReplica> {
             self.ctx.leaders_state().remove_replica(&replica.id).await
         {
             drop(leader_replica_state);
+            self.ctx.metrics().remove_replica(&replica.id);
             // for now, we re-scan file replica
             self.add_follower_replica(replica).await;
         } else {
@@ -594,6 +663,7 @@ impl ScDispatcher<FileReplica> {
                 self.ctx.spu_localstore_owned(),
                 self.ctx.followers_state_owned(),
                 self.ctx.config_owned(),
+                self.ctx.metrics_owned(),
             );
             follower_controller.run();
             log_on_err!(
@@ -624,15 +694,105 @@ impl ScDispatcher<FileReplica> {
         }
     }
 
-    fn remove_follower_replica(&self, replica: Replica) {
+    fn remove_follower_replica(&self, replica: Replica) -> Option<FileReplica> {
         debug!("removing follower replica: {}", replica);
-        if self
+        let follower_state = self
             .ctx
             .followers_state()
-            .remove_replica(&replica.leader, &replica.id)
-            .is_none()
-        {
+            .remove_replica(&replica.leader, &replica.id);
+        if follower_state.is_none() {
             error!("there was no follower replica: {}", replica);
         }
+        follower_state.map(|state| state.storage_owned())
+    }
+
+    /// topic setting of replica, spu setting if topic doesn't set it
+    fn min_in_sync_replicas(&self, replica: &Replica) -> u16 {
+        replica
+            .min_in_sync_replicas
+            .unwrap_or(self.ctx.config().replication.min_in_sync_replicas)
+    }
+}
+
+/// SC sends changes of all replicas, convert them into changes of replicas hosted by this spu.
+/// replica moved to this spu is added, replica moved away from this spu is deleted
+fn local_replica_change(
+    local_id: SpuId,
+    change: SpecChange<Replica>,
+) -> Option<SpecChange<Replica>> {
+    match change {
+        SpecChange::Add(new_replica) => {
+            if new_replica.replicas.contains(&local_id) {
+                Some(SpecChange::Add(new_replica))
+            } else {
+                None
+            }
+        }
+        SpecChange::Delete(old_replica) => {
+            if old_replica.replicas.contains(&local_id) {
+                Some(SpecChange::Delete(old_replica))
+            } else {
+                None
+            }
+        }
+        SpecChange::Mod(new_replica, old_replica) => {
+            match (
+                new_replica.replicas.contains(&local_id),
+                old_replica.replicas.contains(&local_id),
+            ) {
+                (true, true) => Some(SpecChange::Mod(new_replica, old_replica)),
+                (true, false) => Some(SpecChange::Add(new_replica)),
+                (false, true) => Some(SpecChange::Delete(old_replica)),
+                (false, false) => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_controlplane_metadata::partition::Replica;
+
+    use super::local_replica_change;
+    use super::SpecChange;
+
+    fn replica(leader: i32, replicas: Vec<i32>) -> Replica {
+        Replica::new(("topic", 0).into(), leader, replicas)
+    }
+
+    #[test]
+    fn test_local_replica_change() {
+        // replica on other spus is ignored
+        assert!(local_replica_change(5001, SpecChange::Add(replica(5002, vec![5002]))).is_none());
+
+        // replica moved to this spu
+        assert!(matches!(
+            local_replica_change(
+                5001,
+                SpecChange::Mod(replica(5002, vec![5002, 5001]), replica(5002, vec![5002]))
+            ),
+            Some(SpecChange::Add(_))
+        ));
+
+        // replica moved away from this spu
+        assert!(matches!(
+            local_replica_change(
+                5001,
+                SpecChange::Mod(replica(5002, vec![5002]), replica(5002, vec![5002, 5001]))
+            ),
+            Some(SpecChange::Delete(_))
+        ));
+
+        assert!(matches!(
+            local_replica_change(
+                5001,
+                SpecChange::Mod(
+                    replica(5001, vec![5001, 5002]),
+                    replica(5002, vec![5002, 5001])
+                )
+            ),
+            Some(SpecChange::Mod(_, _))
+        ));
     }
 }
@@ -17,12 +17,15 @@ use crate::controllers::leader_replica::SharedReplicaLeadersState;
 use crate::controllers::follower_replica::FollowersState;
 use crate::controllers::follower_replica::SharedFollowersState;
 use crate::controllers::leader_replica::ReplicaLeadersState;
+use crate::services::public::TransformModules;
 use super::spus::SharedSpuLocalStore;
 use super::SharedReplicaLocalStore;
 use super::spus::SpuLocalStore;
 use super::replica::ReplicaStore;
 use super::SharedSpuConfig;
 use super::OffsetUpdateEvent;
+use super::SpuMetrics;
+use super::ProducerIdAllocator;
 
 #[derive(Debug)]
 pub struct GlobalContext<S> {
@@ -33,6 +36,9 @@ pub struct GlobalContext<S> {
     followers_state: SharedFollowersState<S>,
     follower_sinks: SharedSinkPool<SpuId>,
     offset_channel: Channel<OffsetUpdateEvent>,
+    metrics: Arc<SpuMetrics>,
+    producer_ids: ProducerIdAllocator,
+    transform_modules: TransformModules,
 }
 
 // -----------------------------------
@@ -48,6 +54,8 @@ where
     }
 
     pub fn new(spu_config: SpuConfig) -> Self {
+        let producer_ids = ProducerIdAllocator::new(spu_config.id, spu_config.log.base_dir.clone());
+        let transform_modules = TransformModules::new(spu_config.transform.clone());
         GlobalContext {
             spu_localstore: SpuLocalStore::new_shared(),
             replica_localstore: ReplicaStore::new_shared(),
@@ -56,6 +64,9 @@ where
             leaders_state: ReplicaLeadersState::new_shared(),
             followers_state: FollowersState::new_shared(),
             offset_channel: Channel::new(100),
+            metrics: Arc::new(SpuMetrics::default()),
+            producer_ids,
+            transform_modules,
         }
     }
 
@@ -110,4 +121,22 @@ where
     pub fn offset_channel(&self) -> &Channel<OffsetUpdateEvent> {
         &self.offset_channel
     }
+
+    pub fn metrics(&self) -> &SpuMetrics {
+        &self.metrics
+    }
+
+    pub fn metrics_owned(&self) -> Arc<SpuMetrics> {
+        self.metrics.clone()
+    }
+
+    /// assigns ids of idempotent producers
+    pub fn producer_ids(&self) -> &ProducerIdAllocator {
+        &self.producer_ids
+    }
+
+    /// wasm engine and compiled modules of topic transforms
+    pub(crate) fn transform_modules(&self) -> &TransformModules {
+        &self.transform_modules
+    }
 }
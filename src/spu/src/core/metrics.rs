@@ -0,0 +1,139 @@
+//!
+//! # SPU Metrics
+//!
+//! Metrics exported at `/metrics` when SPU is started with metrics address.
+//!
+use std::sync::Arc;
+
+use fluvio_metrics::{Counter, Family, Gauge, Histogram, Registry};
+use dataplane::Offset;
+use dataplane::ReplicaKey;
+
+const REPLICA_LABELS: &[&str] = &["topic", "partition"];
+
+#[derive(Debug)]
+pub struct SpuMetrics {
+    registry: Arc<Registry>,
+    pub public_connections: Arc<Gauge>,
+    pub produce_requests: Arc<Counter>,
+    pub produce_bytes: Arc<Counter>,
+    pub produce_latency: Arc<Histogram>,
+    pub fetch_requests: Arc<Counter>,
+    pub fetch_bytes: Arc<Counter>,
+    pub fetch_latency: Arc<Histogram>,
+    pub follower_syncs: Arc<Counter>,
+    pub follower_sync_latency: Arc<Histogram>,
+    replica_leo: Arc<Family<Gauge>>,
+    replica_hw: Arc<Family<Gauge>>,
+    replica_lag: Arc<Family<Gauge>>,
+}
+
+impl Default for SpuMetrics {
+    fn default() -> Self {
+        let registry = Registry::default();
+        Self {
+            public_connections: registry.register(
+                "fluvio_spu_public_connections",
+                "Open client connections",
+                Gauge::default(),
+            ),
+            produce_requests: registry.register(
+                "fluvio_spu_produce_requests_total",
+                "Produce requests handled",
+                Counter::default(),
+            ),
+            produce_bytes: registry.register(
+                "fluvio_spu_produce_bytes_total",
+                "Bytes of records received from producers",
+                Counter::default(),
+            ),
+            produce_latency: registry.register(
+                "fluvio_spu_produce_duration_seconds",
+                "Time to handle produce request",
+                Histogram::default(),
+            ),
+            fetch_requests: registry.register(
+                "fluvio_spu_fetch_requests_total",
+                "Fetch and stream fetch responses sent",
+                Counter::default(),
+            ),
+            fetch_bytes: registry.register(
+                "fluvio_spu_fetch_bytes_total",
+                "Bytes of records sent to consumers",
+                Counter::default(),
+            ),
+            fetch_latency: registry.register(
+                "fluvio_spu_fetch_duration_seconds",
+                "Time to handle fetch request",
+                Histogram::default(),
+            ),
+            follower_syncs: registry.register(
+                "fluvio_spu_follower_syncs_total",
+                "Record syncs received from leaders",
+                Counter::default(),
+            ),
+            follower_sync_latency: registry.register(
+                "fluvio_spu_follower_sync_duration_seconds",
+                "Time for follower to write records synced from leader",
+                Histogram::default(),
+            ),
+            replica_leo: registry.register(
+                "fluvio_spu_replica_leo",
+                "Log end offset of leader replica",
+                Family::new(REPLICA_LABELS),
+            ),
+            replica_hw: registry.register(
+                "fluvio_spu_replica_hw",
+                "High watermark of leader replica",
+                Family::new(REPLICA_LABELS),
+            ),
+            replica_lag: registry.register(
+                "fluvio_spu_replica_lag",
+                "Records of leader replica not yet committed",
+                Family::new(REPLICA_LABELS),
+            ),
+            registry: Arc::new(registry),
+        }
+    }
+}
+
+impl SpuMetrics {
+    pub fn registry(&self) -> Arc<Registry> {
+        self.registry.clone()
+    }
+
+    /// offset metrics of leader replica
+    pub fn replica(&self, replica: &ReplicaKey) -> ReplicaMetrics {
+        let partition = replica.partition.to_string();
+        let labels = [replica.topic.as_str(), partition.as_str()];
+        ReplicaMetrics {
+            leo: self.replica_leo.with_labels(&labels),
+            hw: self.replica_hw.with_labels(&labels),
+            lag: self.replica_lag.with_labels(&labels),
+        }
+    }
+
+    /// stop exporting replica which is no longer leader
+    pub fn remove_replica(&self, replica: &ReplicaKey) {
+        let partition = replica.partition.to_string();
+        let labels = [replica.topic.as_str(), partition.as_str()];
+        self.replica_leo.remove(&labels);
+        self.replica_hw.remove(&labels);
+        self.replica_lag.remove(&labels);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicaMetrics {
+    leo: Arc<Gauge>,
+    hw: Arc<Gauge>,
+    lag: Arc<Gauge>,
+}
+
+impl ReplicaMetrics {
+    pub fn update(&self, leo: Offset, hw: Offset) {
+        self.leo.set(leo);
+        self.hw.set(hw);
+        self.lag.set(leo - hw);
+    }
+}
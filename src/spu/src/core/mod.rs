@@ -1,5 +1,7 @@
 mod global_context;
 mod store;
+mod metrics;
+mod producer_id;
 pub(crate) mod storage;
 
 pub mod spus;
@@ -9,6 +11,9 @@ pub use self::global_context::GlobalContext;
 pub use self::store::Spec;
 pub use self::store::LocalStore;
 pub use self::store::SpecChange;
+pub use self::metrics::SpuMetrics;
+pub use self::metrics::ReplicaMetrics;
+pub use self::producer_id::ProducerIdAllocator;
 
 pub use self::spus::SpuLocalStore;
 pub use self::replica::SharedReplicaLocalStore;
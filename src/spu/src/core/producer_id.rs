@@ -0,0 +1,122 @@
+use std::convert::TryInto;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use tracing::debug;
+use async_rwlock::RwLock;
+
+use fluvio_future::fs::create_dir_all;
+use fluvio_future::fs::metadata;
+use fluvio_future::fs::read;
+use fluvio_future::fs::rename;
+use fluvio_future::fs::write;
+use fluvio_types::SpuId;
+
+/// file in log base directory where next producer id is stored
+const PRODUCER_ID_FILE: &str = "producer_id.chk";
+
+/// Assigns ids of idempotent producers.
+///
+/// Id has SPU id in upper 32 bits and counter in lower 32 bits, so ids
+/// assigned by different SPUs never collide.  Next counter is written to
+/// disk before id is handed out, so ids are not reused after restart.
+#[derive(Debug)]
+pub struct ProducerIdAllocator {
+    spu_id: SpuId,
+    base_dir: PathBuf,
+    next: RwLock<Option<i64>>,
+}
+
+impl ProducerIdAllocator {
+    pub fn new(spu_id: SpuId, base_dir: PathBuf) -> Self {
+        Self {
+            spu_id,
+            base_dir,
+            next: RwLock::new(None),
+        }
+    }
+
+    /// assign new producer id
+    pub async fn allocate(&self) -> Result<i64, IoError> {
+        let mut next = self.next.write().await;
+        let counter = match *next {
+            Some(counter) => counter,
+            None => self.read().await?,
+        };
+        if counter > u32::MAX as i64 {
+            return Err(IoError::new(
+                ErrorKind::Other,
+                "producer ids of spu are exhausted",
+            ));
+        }
+
+        self.write(counter + 1).await?;
+        *next = Some(counter + 1);
+
+        let producer_id = ((self.spu_id as i64) << 32) | counter;
+        debug!("allocated producer id: {}", producer_id);
+        Ok(producer_id)
+    }
+
+    async fn read(&self) -> Result<i64, IoError> {
+        let path = self.base_dir.join(PRODUCER_ID_FILE);
+        if metadata(&path).await.is_err() {
+            return Ok(0);
+        }
+
+        let contents = read(&path).await?;
+        let bytes: [u8; 8] = contents.as_slice().try_into().map_err(|_| {
+            IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "there should be exact 8 bytes but {} bytes available",
+                    contents.len()
+                ),
+            )
+        })?;
+        Ok(i64::from_be_bytes(bytes))
+    }
+
+    async fn write(&self, counter: i64) -> Result<(), IoError> {
+        create_dir_all(&self.base_dir).await?;
+        let path = self.base_dir.join(PRODUCER_ID_FILE);
+        let tmp_path = path.with_extension("tmp");
+        write(&tmp_path, counter.to_be_bytes()).await?;
+        rename(&tmp_path, &path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_clean_dir;
+
+    use super::ProducerIdAllocator;
+
+    #[test_async]
+    async fn test_allocate_producer_id() -> Result<(), std::io::Error> {
+        let base_dir = temp_dir().join("producer-id-allocator");
+        ensure_clean_dir(&base_dir);
+
+        let allocator = ProducerIdAllocator::new(5001, base_dir.clone());
+        assert_eq!(allocator.allocate().await?, 5001 << 32);
+        assert_eq!(allocator.allocate().await?, (5001 << 32) + 1);
+        drop(allocator);
+
+        // ids are not reused after restart
+        let allocator = ProducerIdAllocator::new(5001, base_dir);
+        assert_eq!(allocator.allocate().await?, (5001 << 32) + 2);
+
+        // other spu assigns different ids
+        let other_dir = temp_dir().join("producer-id-allocator-other");
+        ensure_clean_dir(&other_dir);
+        let other = ProducerIdAllocator::new(5002, other_dir);
+        assert_eq!(other.allocate().await?, 5002 << 32);
+
+        Ok(())
+    }
+}
@@ -19,3 +19,10 @@ impl Spec for Replica {
 }
 
 pub type ReplicaStore = LocalStore<Replica>;
+
+impl ReplicaStore {
+    /// records of replica with transform can only be read by stream fetch
+    pub fn has_transform(&self, replica: &ReplicaKey) -> bool {
+        matches!(self.spec(replica), Some(replica) if replica.transform.is_some())
+    }
+}
@@ -1,9 +1,18 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use tracing::debug;
+
 use fluvio_storage::ConfigOption;
 use fluvio_storage::FileReplica;
 use fluvio_storage::StorageError;
+use fluvio_storage::{find_orphan_replicas, purge_trash, move_to_trash};
 use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_controlplane_metadata::topic::RetentionPolicy;
 use fluvio_types::SpuId;
 
+use crate::config::Log;
+
 fn default_config(spu_id: SpuId, config: &ConfigOption) -> ConfigOption {
     let base_dir = config.base_dir.join(format!("spu-logs-{}", spu_id));
     let new_config = config.clone();
@@ -19,3 +28,133 @@ pub(crate) async fn create_replica_storage(
     let config = default_config(local_spu, base_config);
     FileReplica::create(replica.topic.clone(), replica.partition as u32, 0, &config).await
 }
+
+/// Delete storage of replica removed from this spu.
+/// If grace period is set, it is moved to trash and trash older than grace period is purged.
+pub(crate) async fn remove_replica_storage(
+    local_spu: SpuId,
+    storage: FileReplica,
+    log: &Log,
+) -> Result<(), StorageError> {
+    match log.delete_grace_period {
+        Some(_) => {
+            storage.move_to_trash().await?;
+            purge_replica_trash(local_spu, log).await?;
+        }
+        None => storage.remove().await?,
+    }
+    Ok(())
+}
+
+/// Move replica directories which none of replicas hosted by this spu refers to into trash.
+/// They are never deleted right away since sc may have left out live replica.
+/// Returns directories moved to trash
+pub(crate) async fn remove_orphan_replicas(
+    local_spu: SpuId,
+    replicas: &[ReplicaKey],
+    log: &Log,
+) -> Result<Vec<PathBuf>, StorageError> {
+    let config = default_config(local_spu, &log.new_config());
+    let replicas: HashSet<(String, u32)> = replicas
+        .iter()
+        .map(|replica| (replica.topic.clone(), replica.partition as u32))
+        .collect();
+
+    let mut trashed = vec![];
+    for orphan in find_orphan_replicas(&config.base_dir, &replicas)? {
+        debug!("moving orphan replica to trash: {}", orphan.display());
+        trashed.push(move_to_trash(&orphan).await?);
+    }
+
+    Ok(trashed)
+}
+
+/// Delete replicas in trash which are older than grace period.
+/// Trash is kept if grace period is not set
+pub(crate) async fn purge_replica_trash(
+    local_spu: SpuId,
+    log: &Log,
+) -> Result<Vec<PathBuf>, StorageError> {
+    match log.delete_grace_period {
+        Some(grace_period) => {
+            let config = default_config(local_spu, &log.new_config());
+            Ok(purge_trash(&config.base_dir, grace_period).await?)
+        }
+        None => Ok(vec![]),
+    }
+}
+
+/// apply retention policy of the topic to storage config
+pub(crate) fn retention_config(config: ConfigOption, retention: &RetentionPolicy) -> ConfigOption {
+    config
+        .retention_seconds(retention.max_age_seconds)
+        .max_partition_size(retention.max_partition_size.map(|size| size.max(0) as u64))
+        .compact(retention.compact)
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_future::test_async;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::{DefaultRecord, RecordSet};
+
+    use crate::config::Log;
+
+    use super::*;
+
+    #[test_async]
+    async fn test_orphan_replica_kept_in_trash() -> Result<(), ()> {
+        let base_dir = temp_dir().join("orphan-replica-trash");
+        ensure_clean_dir(&base_dir);
+        let log = Log {
+            base_dir,
+            ..Default::default()
+        };
+
+        let live = ReplicaKey::new("live", 0);
+        let mut storage = create_replica_storage(5001, &live, &log.new_config())
+            .await
+            .expect("storage");
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from(vec![1u8; 10]));
+        storage
+            .send_records(
+                RecordSet {
+                    batches: vec![batch],
+                },
+                true,
+            )
+            .await
+            .expect("write");
+        drop(storage);
+
+        // sc left out live replica, it is not deleted
+        let trashed = remove_orphan_replicas(5001, &[], &log)
+            .await
+            .expect("orphans");
+        assert_eq!(trashed.len(), 1);
+        assert!(trashed[0].join("00000000000000000000.log").exists());
+
+        // without grace period trash is kept
+        assert!(purge_replica_trash(5001, &log)
+            .await
+            .expect("purge")
+            .is_empty());
+        assert!(trashed[0].exists());
+
+        // once grace period is over it is purged
+        let log = Log {
+            delete_grace_period: Some(std::time::Duration::from_secs(0)),
+            ..log
+        };
+        assert_eq!(
+            purge_replica_trash(5001, &log).await.expect("purge"),
+            trashed
+        );
+        Ok(())
+    }
+}
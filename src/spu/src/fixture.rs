@@ -0,0 +1,254 @@
+//!
+//! # Test SPU
+//!
+//! SPU running in same process as test, without SC.
+//! Partitions are assigned directly, so clients can be tested against real SPU services.
+//!
+use std::env::temp_dir;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_channel::bounded;
+use event_listener::Event;
+use futures_util::stream::StreamExt;
+use tracing::debug;
+
+use flv_util::fixture::ensure_clean_dir;
+use fluvio_future::task::{spawn, spawn_blocking, run_block_on};
+use fluvio_future::timer::sleep;
+use fluvio_types::SpuId;
+use fluvio_auth::basic::BasicRbacPolicy;
+use fluvio_controlplane_metadata::partition::Replica;
+use dataplane::batch::DefaultBatch;
+use dataplane::record::RecordSet;
+use dataplane::{Isolation, Offset, ReplicaKey};
+
+use crate::config::SpuConfig;
+use crate::core::GlobalContext;
+use crate::core::DefaultSharedGlobalContext;
+use crate::core::OffsetUpdateEvent;
+use crate::controllers::leader_replica::LeaderReplicaState;
+use crate::controllers::leader_replica::LeaderReplicaControllerCommand;
+use crate::services::create_public_server;
+use crate::services::create_kafka_server;
+
+/// max bytes read back from replica
+const READ_MAX_BYTES: u32 = 1_000_000;
+
+pub struct TestSpu {
+    ctx: DefaultSharedGlobalContext,
+    shutdown: Arc<Event>,
+    kafka_shutdown: Option<Arc<Event>>,
+}
+
+impl TestSpu {
+    /// start public service of spu at port, log is stored in temp dir named after spu
+    pub async fn start(name: &str, spu_id: SpuId, port: u16) -> Self {
+        Self::start_with_policy(name, spu_id, port, None).await
+    }
+
+    /// start spu which authorizes requests of authenticated connections by policy
+    pub async fn start_with_policy(
+        name: &str,
+        spu_id: SpuId,
+        port: u16,
+        auth_policy: Option<BasicRbacPolicy>,
+    ) -> Self {
+        let config = SpuConfig {
+            auth_policy,
+            ..Self::config(spu_id, port)
+        };
+        Self::start_with_config(name, config).await
+    }
+
+    /// config of spu with public service at port
+    pub fn config(spu_id: SpuId, port: u16) -> SpuConfig {
+        SpuConfig {
+            id: spu_id,
+            public_endpoint: format!("127.0.0.1:{}", port),
+            ..Default::default()
+        }
+    }
+
+    /// start spu with config, log dir is replaced by temp dir named after spu
+    pub async fn start_with_config(name: &str, mut config: SpuConfig) -> Self {
+        config.log.base_dir = temp_dir().join(name);
+        ensure_clean_dir(&config.log.base_dir);
+
+        let ctx = GlobalContext::new_shared_context(config);
+        let shutdown =
+            create_public_server(ctx.config().public_socket_addr().to_owned(), ctx.clone()).run();
+        // wait for server to start
+        sleep(Duration::from_millis(100)).await;
+        Self {
+            ctx,
+            shutdown,
+            kafka_shutdown: None,
+        }
+    }
+
+    /// also serve kafka clients at port, returns kafka endpoint
+    pub async fn start_kafka(&mut self, port: u16) -> String {
+        let addr = format!("127.0.0.1:{}", port);
+        self.kafka_shutdown = Some(create_kafka_server(addr.clone(), self.ctx.clone()).run());
+        sleep(Duration::from_millis(100)).await;
+        addr
+    }
+
+    pub fn id(&self) -> SpuId {
+        self.ctx.local_spu_id()
+    }
+
+    pub fn public_endpoint(&self) -> &str {
+        self.ctx.config().public_socket_addr()
+    }
+
+    /// make this spu only replica and leader of partition
+    pub async fn add_leader(&self, topic: &str, partition: i32) {
+        self.add_replica(Replica::new(
+            ReplicaKey::new(topic, partition),
+            self.id(),
+            vec![],
+        ))
+        .await
+    }
+
+    /// make this spu leader of partition replicated to followers, which only sync when told by test
+    pub async fn add_leader_with_followers(
+        &self,
+        topic: &str,
+        partition: i32,
+        followers: &[SpuId],
+    ) {
+        let mut replicas = vec![self.id()];
+        replicas.extend_from_slice(followers);
+        self.add_replica(Replica::new(
+            ReplicaKey::new(topic, partition),
+            self.id(),
+            replicas,
+        ))
+        .await
+    }
+
+    /// add leader replica, replica is also stored as if it was sent by SC
+    pub async fn add_replica(&self, replica: Replica) {
+        let key = replica.id.clone();
+        let leader = LeaderReplicaState::create_file_replica(
+            replica.clone(),
+            &self.ctx.config().storage().new_config(),
+        )
+        .await
+        .expect("replica");
+        self.ctx.replica_localstore().insert(replica);
+
+        // stands in for leader controller, which would also sync followers and report to SC
+        let (sender, mut receiver) = bounded(10);
+        let ctx = self.ctx.clone();
+        spawn(async move {
+            while let Some(command) = receiver.next().await {
+                if let LeaderReplicaControllerCommand::EndOffsetUpdated = command {
+                    let offsets = blocking(&ctx, {
+                        let key = key.clone();
+                        move |ctx| {
+                            ctx.leaders_state()
+                                .get_replica(&key)
+                                .map(|leader| (leader.leo(), leader.hw()))
+                        }
+                    })
+                    .await;
+                    if let Some((leo, hw)) = offsets {
+                        let event = OffsetUpdateEvent {
+                            replica_id: key.clone(),
+                            leo,
+                            hw,
+                        };
+                        let _ = ctx.offset_channel().sender().send(event);
+                    }
+                }
+            }
+            debug!("test leader: {} removed", key);
+        });
+
+        self.ctx
+            .leaders_state()
+            .insert_replica(leader.replica_id().clone(), leader, sender)
+            .await;
+    }
+
+    /// stop being leader of partition, records are kept
+    pub async fn remove_leader(&self, topic: &str, partition: i32) {
+        let key = ReplicaKey::new(topic, partition);
+        self.ctx.leaders_state().remove_replica(&key).await;
+        self.ctx.replica_localstore().delete(&key);
+    }
+
+    /// record offsets of follower as if it had synced, high watermark moves once in sync followers have caught up
+    pub async fn update_follower(
+        &self,
+        topic: &str,
+        partition: i32,
+        follower_id: SpuId,
+        leo: Offset,
+        hw: Offset,
+    ) {
+        let key = ReplicaKey::new(topic, partition);
+        let event = blocking(&self.ctx, move |ctx| {
+            let mut leader = ctx.leaders_state().get_mut_replica(&key).expect("leader");
+            leader.update_follower_offsets((follower_id, leo, hw));
+            run_block_on(leader.update_hw()).expect("hw");
+            OffsetUpdateEvent {
+                replica_id: key.clone(),
+                leo: leader.leo(),
+                hw: leader.hw(),
+            }
+        })
+        .await;
+        let _ = self.ctx.offset_channel().sender().send(event);
+    }
+
+    /// write records as if they were produced, streams are notified
+    pub async fn send_records(&self, topic: &str, partition: i32, records: RecordSet) {
+        self.ctx
+            .leaders_state()
+            .send_records(&ReplicaKey::new(topic, partition), records, true)
+            .await
+            .expect("write")
+            .expect("leader");
+    }
+
+    /// all batches stored in partition
+    pub async fn read_batches(&self, topic: &str, partition: i32) -> Vec<DefaultBatch> {
+        let key = ReplicaKey::new(topic, partition);
+        let (batches, _, _) = blocking(&self.ctx, move |ctx| {
+            run_block_on(ctx.leaders_state().read_batches(
+                &key,
+                0,
+                READ_MAX_BYTES,
+                Isolation::ReadUncommitted,
+            ))
+        })
+        .await
+        .expect("leader");
+        batches.expect("batches")
+    }
+}
+
+/// access leaders on blocking thread.  services hold replica locks while writing,
+/// waiting for them on executor thread could keep writes from completing
+async fn blocking<F, T>(ctx: &DefaultSharedGlobalContext, f: F) -> T
+where
+    F: FnOnce(DefaultSharedGlobalContext) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let ctx = ctx.clone();
+    spawn_blocking(move || f(ctx)).await
+}
+
+impl Drop for TestSpu {
+    fn drop(&mut self) {
+        self.shutdown.notify(usize::MAX);
+        if let Some(kafka_shutdown) = &self.kafka_shutdown {
+            kafka_shutdown.notify(usize::MAX);
+        }
+    }
+}
@@ -8,6 +8,9 @@ mod core;
 mod services;
 mod controllers;
 
+#[cfg(any(test, feature = "fixture"))]
+pub mod fixture;
+
 //#[cfg(test)]
 //mod tests;
 
@@ -0,0 +1,80 @@
+use std::io::Error;
+use tracing::debug;
+
+use dataplane::api::{RequestMessage, ResponseMessage, Request};
+use dataplane::ErrorCode;
+use fluvio_spu_schema::server::versions::ApiVersionKey;
+use fluvio_spu_schema::kafka::KafkaApiKey;
+use fluvio_spu_schema::kafka::versions::KafkaApiVersionsRequest;
+use fluvio_spu_schema::kafka::versions::KafkaApiVersionsResponse;
+use fluvio_spu_schema::kafka::metadata::MetadataRequest;
+use fluvio_spu_schema::kafka::list_offsets::ListOffsetsRequest;
+use fluvio_spu_schema::kafka::produce::KafkaProduceRequest;
+use fluvio_spu_schema::kafka::fetch::KafkaFetchRequest;
+use fluvio_spu_schema::kafka::find_coordinator::FindCoordinatorRequest;
+use fluvio_spu_schema::kafka::offset_commit::OffsetCommitRequest;
+use fluvio_spu_schema::kafka::offset_fetch::OffsetFetchRequest;
+
+/// return response with version it must be encoded with
+pub fn handle_kafka_api_versions_request(
+    request: RequestMessage<KafkaApiVersionsRequest>,
+) -> Result<(ResponseMessage<KafkaApiVersionsResponse>, i16), Error> {
+    let version = request.header.api_version();
+    debug!("generating kafka api versions response: {}", version);
+
+    let mut response = KafkaApiVersionsResponse::default();
+    response
+        .api_keys
+        .push(make_version_key::<KafkaProduceRequest>(
+            KafkaApiKey::Produce,
+        ));
+    response
+        .api_keys
+        .push(make_version_key::<KafkaFetchRequest>(KafkaApiKey::Fetch));
+    response
+        .api_keys
+        .push(make_version_key::<ListOffsetsRequest>(
+            KafkaApiKey::ListOffsets,
+        ));
+    response
+        .api_keys
+        .push(make_version_key::<MetadataRequest>(KafkaApiKey::Metadata));
+    response
+        .api_keys
+        .push(make_version_key::<OffsetCommitRequest>(
+            KafkaApiKey::OffsetCommit,
+        ));
+    response
+        .api_keys
+        .push(make_version_key::<OffsetFetchRequest>(
+            KafkaApiKey::OffsetFetch,
+        ));
+    response
+        .api_keys
+        .push(make_version_key::<FindCoordinatorRequest>(
+            KafkaApiKey::FindCoordinator,
+        ));
+    response
+        .api_keys
+        .push(make_version_key::<KafkaApiVersionsRequest>(
+            KafkaApiKey::ApiVersions,
+        ));
+
+    let version = if version > KafkaApiVersionsRequest::MAX_API_VERSION {
+        response.error_code = ErrorCode::UnsupportedVersion;
+        0
+    } else {
+        version
+    };
+
+    Ok((request.new_response(response), version))
+}
+
+/// Build version key object
+fn make_version_key<R: Request>(key: KafkaApiKey) -> ApiVersionKey {
+    ApiVersionKey {
+        api_key: key as i16,
+        min_version: R::MIN_API_VERSION,
+        max_version: R::MAX_API_VERSION,
+    }
+}
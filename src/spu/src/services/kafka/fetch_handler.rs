@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use tracing::debug;
+use tracing::trace;
+use tokio::select;
+use tokio::sync::broadcast::Receiver;
+use futures_util::future::FutureExt;
+use futures_util::io::AsyncRead;
+use futures_util::io::AsyncWrite;
+
+use fluvio_future::timer::sleep;
+use fluvio_future::zero_copy::ZeroCopyWrite;
+use fluvio_socket::InnerFlvSink;
+use fluvio_socket::InnerExclusiveFlvSink;
+use fluvio_socket::FlvSocketError;
+use dataplane::api::RequestMessage;
+use dataplane::fetch::FilePartitionResponse;
+use dataplane::{ErrorCode, Isolation, Offset, ReplicaKey};
+use fluvio_spu_schema::kafka::fetch::*;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::core::OffsetUpdateEvent;
+use super::kafka_error_code;
+
+/// read committed records of partitions using zero copy write.
+/// if there are no records, wait up to max wait time for new records
+pub async fn handle_kafka_fetch_request<S>(
+    request: RequestMessage<KafkaFetchRequest>,
+    ctx: DefaultSharedGlobalContext,
+    sink: InnerExclusiveFlvSink<S>,
+) -> Result<(), FlvSocketError>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+    InnerFlvSink<S>: ZeroCopyWrite,
+{
+    let start = Instant::now();
+    let (header, fetch_request) = request.get_header_request();
+    let deadline = start + Duration::from_millis(fetch_request.max_wait_ms.max(0) as u64);
+
+    // subscribe before reading so new records are not missed
+    let mut offset_receiver = ctx.offset_channel().receiver();
+
+    let mut fetch_response = read_records(&ctx, &fetch_request).await;
+    if fetch_bytes(&fetch_response) == 0 && Instant::now() < deadline {
+        let offsets = fetch_offsets(&fetch_request);
+        if wait_for_records(&mut offset_receiver, &offsets, deadline).await {
+            fetch_response = read_records(&ctx, &fetch_request).await;
+        }
+    }
+    let fetch_bytes = fetch_bytes(&fetch_response);
+
+    let response =
+        RequestMessage::<KafkaFetchRequest>::response_with_header(&header, fetch_response);
+    trace!("sending back kafka fetch response: {:#?}", response);
+    let mut inner = sink.lock().await;
+    inner
+        .encode_file_slices(&response, header.api_version())
+        .await?;
+    drop(inner);
+
+    let metrics = ctx.metrics();
+    metrics.fetch_requests.inc();
+    metrics.fetch_bytes.inc_by(fetch_bytes as u64);
+    metrics.fetch_latency.observe_duration(start.elapsed());
+
+    Ok(())
+}
+
+async fn read_records(
+    ctx: &DefaultSharedGlobalContext,
+    fetch_request: &KafkaFetchRequest,
+) -> KafkaFileFetchResponse {
+    let mut fetch_response = KafkaFileFetchResponse::default();
+
+    for topic_request in &fetch_request.topics {
+        let mut topic_response = KafkaFileTopicResponse {
+            name: topic_request.topic.clone(),
+            ..Default::default()
+        };
+
+        for partition_request in &topic_request.partitions {
+            debug!(
+                "kafka fetch log: {}-{}, offset: {}, max_bytes: {}",
+                topic_request.topic,
+                partition_request.partition,
+                partition_request.fetch_offset,
+                partition_request.partition_max_bytes
+            );
+            let rep_id = ReplicaKey::new(topic_request.topic.clone(), partition_request.partition);
+
+            // kafka consumers only see records up to high watermark
+            let mut partition_response = FilePartitionResponse::default();
+            if ctx.replica_localstore().has_transform(&rep_id) {
+                // records are sent as stored, transform only runs in stream fetch
+                partition_response.error_code = ErrorCode::TransformRequiresStreamFetch;
+            } else {
+                ctx.leaders_state()
+                    .read_records(
+                        &rep_id,
+                        partition_request.fetch_offset,
+                        partition_request.partition_max_bytes.max(0) as u32,
+                        Isolation::ReadCommitted,
+                        &mut partition_response,
+                    )
+                    .await;
+            }
+
+            topic_response.partitions.push(KafkaFilePartitionResponse {
+                partition_index: partition_request.partition,
+                error_code: kafka_error_code(partition_response.error_code),
+                high_watermark: partition_response.high_watermark,
+                last_stable_offset: partition_response.last_stable_offset,
+                log_start_offset: partition_response.log_start_offset,
+                preferred_read_replica: -1,
+                records: partition_response.records,
+                ..Default::default()
+            });
+        }
+
+        fetch_response.topics.push(topic_response);
+    }
+
+    fetch_response
+}
+
+fn fetch_bytes(fetch_response: &KafkaFileFetchResponse) -> usize {
+    fetch_response
+        .topics
+        .iter()
+        .flat_map(|topic| topic.partitions.iter())
+        .map(|partition| partition.records.len())
+        .sum()
+}
+
+fn fetch_offsets(fetch_request: &KafkaFetchRequest) -> HashMap<ReplicaKey, Offset> {
+    let mut offsets = HashMap::new();
+    for topic_request in &fetch_request.topics {
+        for partition_request in &topic_request.partitions {
+            offsets.insert(
+                ReplicaKey::new(topic_request.topic.clone(), partition_request.partition),
+                partition_request.fetch_offset,
+            );
+        }
+    }
+    offsets
+}
+
+/// wait until high watermark of any partition moves past fetch offset, false if timed out
+async fn wait_for_records(
+    receiver: &mut Receiver<OffsetUpdateEvent>,
+    offsets: &HashMap<ReplicaKey, Offset>,
+    deadline: Instant,
+) -> bool {
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+
+        select! {
+            _ = sleep(deadline - now).fuse() => return false,
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if let Some(offset) = offsets.get(&event.replica_id) {
+                        if event.hw > *offset {
+                            return true;
+                        }
+                    }
+                }
+                // updates may have been missed, read again
+                Err(_) => return true,
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::io::Error;
+
+use tracing::debug;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_spu_schema::kafka::find_coordinator::*;
+
+use crate::core::DefaultSharedGlobalContext;
+
+/// this spu is coordinator of every group, offsets are committed to partition leaders
+pub async fn handle_find_coordinator_request(
+    request: RequestMessage<FindCoordinatorRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<FindCoordinatorResponse>, Error> {
+    let find_request = &request.request;
+    debug!(
+        "handling kafka find coordinator request, key: {}, type: {}",
+        find_request.key, find_request.key_type
+    );
+
+    let mut response = FindCoordinatorResponse {
+        node_id: -1,
+        port: -1,
+        ..Default::default()
+    };
+    match &ctx.config().kafka_advertised_endpoint {
+        // transactions are not supported
+        Some(endpoint) if find_request.key_type == GROUP_KEY_TYPE => {
+            response.node_id = ctx.local_spu_id();
+            response.host = endpoint.host.clone();
+            response.port = endpoint.port as i32;
+        }
+        _ => {
+            response.error_code = ErrorCode::CoordinatorNotAvailable;
+            response.error_message = response.error_code.to_sentence();
+        }
+    }
+
+    Ok(request.new_response(response))
+}
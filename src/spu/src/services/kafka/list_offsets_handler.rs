@@ -0,0 +1,94 @@
+use std::io::Error;
+
+use tracing::debug;
+use tracing::trace;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_spu_schema::server::fetch_offset::{
+    FetchOffsetsRequest, FetchOffsetTopic, FetchOffsetPartition, FetchOffsetPartitionResponse,
+};
+use fluvio_spu_schema::kafka::list_offsets::*;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::services::public::auth::PublicAuthContext;
+use crate::services::public::offset_request::handle_offset_request;
+use super::kafka_error_code;
+
+/// offsets are looked up by SPU fetch offsets request
+pub async fn handle_list_offsets_request(
+    request: RequestMessage<ListOffsetsRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<ListOffsetsResponse>, Error> {
+    let list_request = &request.request;
+    debug!("handling kafka list offsets request");
+    trace!("kafka list offsets request: {:#?}", list_request);
+
+    let fetch_request = FetchOffsetsRequest {
+        topics: list_request
+            .topics
+            .iter()
+            .map(|topic| FetchOffsetTopic {
+                name: topic.name.clone(),
+                partitions: topic
+                    .partitions
+                    .iter()
+                    .map(|partition| FetchOffsetPartition {
+                        partition_index: partition.partition_index,
+                        timestamp: if partition.timestamp >= 0 {
+                            Some(partition.timestamp)
+                        } else {
+                            None
+                        },
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    let fetch_response = handle_offset_request(
+        RequestMessage::new_request(fetch_request),
+        ctx,
+        &PublicAuthContext::default(),
+    )
+    .await?
+    .response;
+
+    let mut response = ListOffsetsResponse::default();
+    for (topic, fetch_topic) in list_request.topics.iter().zip(fetch_response.topics) {
+        let mut topic_response = ListOffsetsTopicResponse {
+            name: fetch_topic.name,
+            ..Default::default()
+        };
+        for (partition, fetch_partition) in topic.partitions.iter().zip(fetch_topic.partitions) {
+            topic_response
+                .partitions
+                .push(ListOffsetsPartitionResponse {
+                    partition_index: fetch_partition.partition_index,
+                    error_code: kafka_error_code(fetch_partition.error_code),
+                    timestamp: -1,
+                    offset: partition_offset(partition.timestamp, &fetch_partition),
+                    leader_epoch: -1,
+                });
+        }
+        response.topics.push(topic_response);
+    }
+
+    trace!("kafka list offsets response: {:#?}", response);
+
+    Ok(request.new_response(response))
+}
+
+/// offset of special or real timestamp, -1 if there is none
+fn partition_offset(timestamp: i64, partition: &FetchOffsetPartitionResponse) -> i64 {
+    if partition.error_code != ErrorCode::None {
+        return -1;
+    }
+
+    match timestamp {
+        LATEST_TIMESTAMP => partition.last_stable_offset,
+        EARLIEST_TIMESTAMP => partition.start_offset,
+        timestamp if timestamp >= 0 => partition.timestamp_offset.unwrap_or(-1),
+        _ => -1,
+    }
+}
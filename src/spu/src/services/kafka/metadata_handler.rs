@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io::Error;
+
+use tracing::debug;
+use tracing::trace;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_controlplane_metadata::partition::Replica;
+use fluvio_spu_schema::kafka::metadata::*;
+
+use crate::core::DefaultSharedGlobalContext;
+
+const CLUSTER_ID: &str = "fluvio";
+
+/// brokers are spus with kafka listener, partitions are from replica store which has all replicas
+pub async fn handle_metadata_request(
+    request: RequestMessage<MetadataRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<MetadataResponse>, Error> {
+    let metadata_request = &request.request;
+    debug!(
+        "handling kafka metadata request, topics: {:?}",
+        metadata_request.topic_names()
+    );
+
+    let mut response = MetadataResponse {
+        cluster_id: CLUSTER_ID.to_owned(),
+        controller_id: ctx.local_spu_id(),
+        cluster_authorized_operations: AUTHORIZED_OPERATIONS_OMITTED,
+        ..Default::default()
+    };
+
+    for spu in ctx.spu_localstore().all_values() {
+        if let Some(endpoint) = &spu.kafka_endpoint {
+            response.brokers.push(MetadataResponseBroker {
+                node_id: spu.id,
+                host: endpoint.host.clone(),
+                port: endpoint.port as i32,
+                rack: spu.rack.clone().unwrap_or_default(),
+            });
+        }
+    }
+    response.brokers.sort_by_key(|broker| broker.node_id);
+    let brokers: HashSet<i32> = response.brokers.iter().map(|b| b.node_id).collect();
+
+    let mut topics: BTreeMap<String, Vec<Replica>> = BTreeMap::new();
+    for replica in ctx.replica_localstore().all_values() {
+        topics
+            .entry(replica.id.topic.clone())
+            .or_default()
+            .push(replica);
+    }
+
+    let names: Vec<String> = match metadata_request.topic_names() {
+        Some(names) => names.into_iter().map(|name| name.to_owned()).collect(),
+        None => topics.keys().cloned().collect(),
+    };
+
+    for name in names {
+        let mut topic_response = MetadataResponseTopic {
+            name,
+            topic_authorized_operations: AUTHORIZED_OPERATIONS_OMITTED,
+            ..Default::default()
+        };
+
+        match topics.get_mut(&topic_response.name) {
+            Some(replicas) => {
+                replicas.sort_by_key(|replica| replica.id.partition);
+                for replica in replicas.iter() {
+                    topic_response
+                        .partitions
+                        .push(partition_metadata(&ctx, replica, &brokers));
+                }
+            }
+            None => {
+                debug!("kafka metadata, topic not found: {}", topic_response.name);
+                topic_response.error_code = ErrorCode::UnknownTopicOrPartition;
+            }
+        }
+
+        response.topics.push(topic_response);
+    }
+
+    trace!("kafka metadata response: {:#?}", response);
+
+    Ok(request.new_response(response))
+}
+
+/// in sync replicas are only known for partitions led by this spu
+fn partition_metadata(
+    ctx: &DefaultSharedGlobalContext,
+    replica: &Replica,
+    brokers: &HashSet<i32>,
+) -> MetadataResponsePartition {
+    let isr_nodes = match ctx.leaders_state().get_replica(&replica.id) {
+        Some(leader) => leader.in_sync_replicas(),
+        None => replica.replicas.clone(),
+    };
+
+    MetadataResponsePartition {
+        error_code: if brokers.contains(&replica.leader) {
+            ErrorCode::None
+        } else {
+            ErrorCode::LeaderNotAvailable
+        },
+        partition_index: replica.id.partition,
+        leader_id: replica.leader,
+        leader_epoch: -1,
+        replica_nodes: replica.replicas.clone(),
+        isr_nodes,
+        offline_replicas: vec![],
+    }
+}
@@ -0,0 +1,394 @@
+//!
+//! # Kafka Compatible Listener
+//!
+//! Serves Kafka clients, so existing Kafka tools can produce to and consume from Fluvio topics.
+//! Kafka clients are not authenticated, so listener can't be used with authorization.
+//! Consumer groups are not managed, join group, sync group and heartbeat are unsupported,
+//! so only consumers which assign partitions themselves can be used.
+//! Such consumers can commit and fetch offsets, with this SPU as coordinator of their group.
+//!
+mod service_impl;
+mod api_versions;
+mod metadata_handler;
+mod list_offsets_handler;
+mod produce_handler;
+mod fetch_handler;
+mod find_coordinator_handler;
+mod offset_commit_handler;
+
+use tracing::info;
+
+use dataplane::ErrorCode;
+use fluvio_service::FlvApiServer;
+use fluvio_spu_schema::kafka::KafkaRequest;
+use fluvio_spu_schema::kafka::KafkaApiKey;
+use service_impl::KafkaService;
+
+use crate::core::DefaultSharedGlobalContext;
+
+pub(crate) type KafkaApiServer =
+    FlvApiServer<KafkaRequest, KafkaApiKey, DefaultSharedGlobalContext, KafkaService>;
+
+// start server
+pub fn create_kafka_server(addr: String, ctx: DefaultSharedGlobalContext) -> KafkaApiServer {
+    info!(
+        "starting SPU: {} kafka service at: {}",
+        ctx.local_spu_id(),
+        addr
+    );
+
+    FlvApiServer::new(addr, ctx, KafkaService::new())
+}
+
+/// Fluvio specific error codes are unknown to Kafka clients
+fn kafka_error_code(error_code: ErrorCode) -> ErrorCode {
+    match error_code {
+        ErrorCode::PartitionNotLeader => ErrorCode::NotLeaderForPartition,
+        ErrorCode::TopicNotFound => ErrorCode::UnknownTopicOrPartition,
+        ErrorCode::TransformRequiresStreamFetch => ErrorCode::InvalidRequest,
+        error_code if (error_code as i16) < 1000 => error_code,
+        _ => ErrorCode::UnknownServerError,
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::io::Cursor;
+
+    use bytes::BytesMut;
+    use futures_util::stream::StreamExt;
+
+    use fluvio_future::test_async;
+    use fluvio_socket::FlvSocket;
+    use fluvio_spu_schema::kafka::UnsupportedResponse;
+    use fluvio_spu_schema::kafka::produce::KafkaProduceRequest;
+    use fluvio_spu_schema::kafka::list_offsets::*;
+    use fluvio_spu_schema::kafka::fetch::*;
+    use fluvio_spu_schema::kafka::versions::KafkaApiVersionsRequest;
+    use fluvio_spu_schema::kafka::find_coordinator::*;
+    use fluvio_spu_schema::kafka::offset_commit::*;
+    use fluvio_spu_schema::kafka::offset_fetch::*;
+    use fluvio_controlplane_metadata::spu::Endpoint;
+    use dataplane::api::{Request, RequestHeader, RequestMessage, ResponseMessage};
+    use dataplane::batch::DefaultBatch;
+    use dataplane::produce::{DefaultPartitionRequest, DefaultTopicRequest};
+    use dataplane::record::{DefaultRecord, RecordSet};
+    use dataplane::{ErrorCode, Isolation};
+
+    use crate::config::SpuConfig;
+    use crate::fixture::TestSpu;
+    use super::kafka_error_code;
+
+    const TOPIC: &str = "test";
+
+    #[test]
+    fn test_kafka_error_code() {
+        assert_eq!(kafka_error_code(ErrorCode::None), ErrorCode::None);
+        assert_eq!(
+            kafka_error_code(ErrorCode::NotEnoughReplicas),
+            ErrorCode::NotEnoughReplicas
+        );
+        assert_eq!(
+            kafka_error_code(ErrorCode::PartitionNotLeader),
+            ErrorCode::NotLeaderForPartition
+        );
+        assert_eq!(
+            kafka_error_code(ErrorCode::TransformRequiresStreamFetch),
+            ErrorCode::InvalidRequest
+        );
+        assert_eq!(
+            kafka_error_code(ErrorCode::SpuError),
+            ErrorCode::UnknownServerError
+        );
+    }
+
+    /// send request and read back response bytes, for responses which can't be decoded by request type
+    async fn send_raw<R: Request>(socket: &mut FlvSocket, request: &RequestMessage<R>) -> BytesMut {
+        socket
+            .get_mut_sink()
+            .send_request(request)
+            .await
+            .expect("send");
+        socket
+            .get_mut_stream()
+            .get_mut_tcp_stream()
+            .next()
+            .await
+            .expect("response")
+            .expect("bytes")
+    }
+
+    fn produce_request(values: &[&str]) -> KafkaProduceRequest {
+        let mut batch = DefaultBatch::default();
+        for value in values {
+            batch.add_record(DefaultRecord::from(value.to_string()));
+        }
+        let mut partition_request = DefaultPartitionRequest::default();
+        partition_request.records.batches.push(batch);
+        KafkaProduceRequest {
+            acks: 1,
+            timeout_ms: 1000,
+            topics: vec![DefaultTopicRequest {
+                name: TOPIC.to_owned(),
+                partitions: vec![partition_request],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn list_offsets_request(topic: &str) -> ListOffsetsRequest {
+        ListOffsetsRequest {
+            replica_id: -1,
+            topics: vec![ListOffsetsTopic {
+                name: topic.to_owned(),
+                partitions: vec![
+                    ListOffsetsPartition {
+                        partition_index: 0,
+                        timestamp: EARLIEST_TIMESTAMP,
+                        ..Default::default()
+                    },
+                    ListOffsetsPartition {
+                        partition_index: 0,
+                        timestamp: LATEST_TIMESTAMP,
+                        ..Default::default()
+                    },
+                ],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test_async]
+    async fn test_kafka_listener() -> Result<(), ()> {
+        let mut spu = TestSpu::start("kafka-listener", 5001, 9233).await;
+        let addr = spu.start_kafka(9234).await;
+        spu.add_leader(TOPIC, 0).await;
+
+        let mut socket = FlvSocket::connect(&addr).await.expect("connect");
+
+        // join group is not supported, connection is kept
+        let mut header = RequestHeader::new(11);
+        header.set_api_version(2);
+        header.set_correlation_id(1);
+        let request = RequestMessage::new(header, KafkaApiVersionsRequest::default());
+        let bytes = send_raw(&mut socket, &request).await;
+        let response =
+            ResponseMessage::<UnsupportedResponse>::decode_from(&mut Cursor::new(&bytes), 0)
+                .expect("decode");
+        assert_eq!(response.correlation_id, 1);
+        assert_eq!(response.response.error_code, ErrorCode::UnsupportedVersion);
+
+        let response = socket
+            .send(&RequestMessage::new_request(
+                KafkaApiVersionsRequest::default(),
+            ))
+            .await
+            .expect("versions");
+        assert_eq!(response.response.error_code, ErrorCode::None);
+
+        // produce
+        for (values, base_offset) in [(vec!["a", "b"], 0), (vec!["c"], 2)] {
+            let response = socket
+                .send(&RequestMessage::new_request(produce_request(&values)))
+                .await
+                .expect("produce");
+            let partition = &response.response.responses[0].partitions[0];
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.base_offset, base_offset);
+        }
+
+        // list offsets
+        let response = socket
+            .send(&RequestMessage::new_request(list_offsets_request(TOPIC)))
+            .await
+            .expect("list offsets");
+        let partitions = &response.response.topics[0].partitions;
+        assert_eq!(partitions[0].error_code, ErrorCode::None);
+        assert_eq!(partitions[0].offset, 0);
+        assert_eq!(partitions[1].offset, 3);
+
+        // client refreshes metadata for partitions not led by this spu
+        let response = socket
+            .send(&RequestMessage::new_request(list_offsets_request(
+                "unknown",
+            )))
+            .await
+            .expect("list offsets");
+        assert_eq!(
+            response.response.topics[0].partitions[0].error_code,
+            ErrorCode::NotLeaderForPartition
+        );
+
+        // fetch
+        let request = RequestMessage::new_request(KafkaFetchRequest {
+            replica_id: -1,
+            max_wait_ms: 100,
+            max_bytes: 1_000_000,
+            isolation_level: Isolation::ReadCommitted,
+            topics: vec![KafkaFetchTopic {
+                topic: TOPIC.to_owned(),
+                partitions: vec![KafkaFetchPartition {
+                    partition: 0,
+                    fetch_offset: 1,
+                    partition_max_bytes: 1_000_000,
+                    ..Default::default()
+                }],
+            }],
+            ..Default::default()
+        });
+        let bytes = send_raw(&mut socket, &request).await;
+        let response = ResponseMessage::<KafkaFetchResponse<RecordSet>>::decode_from(
+            &mut Cursor::new(&bytes),
+            KafkaFetchRequest::DEFAULT_API_VERSION,
+        )
+        .expect("decode");
+        let partition = &response.response.topics[0].partitions[0];
+        assert_eq!(partition.error_code, ErrorCode::None);
+        assert_eq!(partition.high_watermark, 3);
+        // whole batches are sent, consumer skips records before fetch offset
+        let batches = &partition.records.batches;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].base_offset, 0);
+        assert_eq!(batches[1].records()[0].value.to_string(), "c");
+
+        Ok(())
+    }
+
+    fn offset_fetch_request(group: &str, partitions: Option<Vec<i32>>) -> OffsetFetchRequest {
+        OffsetFetchRequest {
+            group_id: group.to_owned(),
+            topics: OffsetFetchRequestTopics(partitions.map(|partition_indexes| {
+                vec![OffsetFetchRequestTopic {
+                    name: TOPIC.to_owned(),
+                    partition_indexes,
+                }]
+            })),
+        }
+    }
+
+    #[test_async]
+    async fn test_kafka_offset_commit() -> Result<(), ()> {
+        let config = SpuConfig {
+            kafka_advertised_endpoint: Some(Endpoint {
+                host: "127.0.0.1".to_owned(),
+                port: 9238,
+                ..Default::default()
+            }),
+            ..TestSpu::config(5001, 9237)
+        };
+        let mut spu = TestSpu::start_with_config("kafka-offset-commit", config).await;
+        let addr = spu.start_kafka(9238).await;
+        spu.add_leader(TOPIC, 0).await;
+
+        let mut socket = FlvSocket::connect(&addr).await.expect("connect");
+
+        // this spu is coordinator of group
+        let response = socket
+            .send(&RequestMessage::new_request(FindCoordinatorRequest {
+                key: "g1".to_owned(),
+                key_type: GROUP_KEY_TYPE,
+            }))
+            .await
+            .expect("find coordinator");
+        assert_eq!(response.response.error_code, ErrorCode::None);
+        assert_eq!(response.response.node_id, 5001);
+        assert_eq!(response.response.host, "127.0.0.1");
+        assert_eq!(response.response.port, 9238);
+
+        // transactions are not supported
+        let response = socket
+            .send(&RequestMessage::new_request(FindCoordinatorRequest {
+                key: "tx".to_owned(),
+                key_type: 1,
+            }))
+            .await
+            .expect("find coordinator");
+        assert_eq!(
+            response.response.error_code,
+            ErrorCode::CoordinatorNotAvailable
+        );
+
+        socket
+            .send(&RequestMessage::new_request(produce_request(&[
+                "a", "b", "c",
+            ])))
+            .await
+            .expect("produce");
+
+        // partition 1 is not led by this spu
+        let response = socket
+            .send(&RequestMessage::new_request(OffsetCommitRequest {
+                group_id: "g1".to_owned(),
+                generation_id: -1,
+                retention_time_ms: -1,
+                topics: vec![OffsetCommitRequestTopic {
+                    name: TOPIC.to_owned(),
+                    partitions: vec![
+                        OffsetCommitRequestPartition {
+                            partition_index: 0,
+                            committed_offset: 2,
+                            ..Default::default()
+                        },
+                        OffsetCommitRequestPartition {
+                            partition_index: 1,
+                            committed_offset: 2,
+                            ..Default::default()
+                        },
+                    ],
+                }],
+                ..Default::default()
+            }))
+            .await
+            .expect("offset commit");
+        let partitions = &response.response.topics[0].partitions;
+        assert_eq!(partitions[0].error_code, ErrorCode::None);
+        assert_eq!(partitions[1].error_code, ErrorCode::UnknownTopicOrPartition);
+
+        let response = socket
+            .send(&RequestMessage::new_request(offset_fetch_request(
+                "g1",
+                Some(vec![0, 1]),
+            )))
+            .await
+            .expect("offset fetch");
+        assert_eq!(response.response.error_code, ErrorCode::None);
+        let partitions = &response.response.topics[0].partitions;
+        assert_eq!(partitions[0].error_code, ErrorCode::None);
+        assert_eq!(partitions[0].committed_offset, 2);
+        assert_eq!(partitions[1].error_code, ErrorCode::UnknownTopicOrPartition);
+        assert_eq!(partitions[1].committed_offset, -1);
+
+        // all partitions with offset of group
+        for (group, offsets) in [("g1", vec![2]), ("g2", vec![])] {
+            let response = socket
+                .send(&RequestMessage::new_request(offset_fetch_request(
+                    group, None,
+                )))
+                .await
+                .expect("offset fetch");
+            let committed: Vec<i64> = response
+                .response
+                .topics
+                .iter()
+                .flat_map(|topic| topic.partitions.iter().map(|p| p.committed_offset))
+                .collect();
+            assert_eq!(committed, offsets);
+        }
+
+        // group without offset
+        let response = socket
+            .send(&RequestMessage::new_request(offset_fetch_request(
+                "g2",
+                Some(vec![0]),
+            )))
+            .await
+            .expect("offset fetch");
+        let partition = &response.response.topics[0].partitions[0];
+        assert_eq!(partition.error_code, ErrorCode::None);
+        assert_eq!(partition.committed_offset, -1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::io::Error;
+
+use tracing::debug;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_spu_schema::kafka::offset_commit::*;
+use fluvio_spu_schema::kafka::offset_fetch::*;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::services::public::consumer_offset::commit_offset;
+
+/// offsets are committed as consumer offsets of partition led by this spu,
+/// group membership is not checked since groups are not managed
+pub async fn handle_offset_commit_request(
+    request: RequestMessage<OffsetCommitRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<OffsetCommitResponse>, Error> {
+    let commit_request = &request.request;
+    debug!(
+        "handling kafka offset commit request, group: {}",
+        commit_request.group_id
+    );
+
+    let group = &commit_request.group_id;
+    let mut response = OffsetCommitResponse::default();
+
+    for topic_request in &commit_request.topics {
+        let mut topic_response = OffsetCommitResponseTopic {
+            name: topic_request.name.clone(),
+            ..Default::default()
+        };
+
+        for partition_request in &topic_request.partitions {
+            let rep_id = ReplicaKey::new(
+                topic_request.name.clone(),
+                partition_request.partition_index,
+            );
+            let error_code = if group.is_empty() {
+                ErrorCode::InvalidGroupId
+            } else {
+                commit_offset(&ctx, &rep_id, group, partition_request.committed_offset).await
+            };
+
+            topic_response
+                .partitions
+                .push(OffsetCommitResponsePartition {
+                    partition_index: partition_request.partition_index,
+                    error_code: offset_error_code(error_code),
+                });
+        }
+
+        response.topics.push(topic_response);
+    }
+
+    Ok(request.new_response(response))
+}
+
+/// without topics, offsets of group in all partitions led by this spu are returned
+pub async fn handle_offset_fetch_request(
+    request: RequestMessage<OffsetFetchRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<OffsetFetchResponse>, Error> {
+    let fetch_request = &request.request;
+    debug!(
+        "handling kafka offset fetch request, group: {}",
+        fetch_request.group_id
+    );
+
+    let group = &fetch_request.group_id;
+    let mut response = OffsetFetchResponse::default();
+    if group.is_empty() {
+        response.error_code = ErrorCode::InvalidGroupId;
+        return Ok(request.new_response(response));
+    }
+
+    let rep_ids: Vec<ReplicaKey> = match &fetch_request.topics.0 {
+        Some(topics) => topics
+            .iter()
+            .flat_map(|topic| {
+                topic
+                    .partition_indexes
+                    .iter()
+                    .map(move |partition| ReplicaKey::new(topic.name.clone(), *partition))
+            })
+            .collect(),
+        None => {
+            let mut rep_ids = ctx.leaders_state().replica_keys().await;
+            rep_ids.retain(
+                |rep_id| matches!(committed_offset(&ctx, rep_id, group), Ok(offset) if offset >= 0),
+            );
+            rep_ids.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+            rep_ids
+        }
+    };
+
+    for rep_id in rep_ids {
+        let mut partition_response = OffsetFetchResponsePartition {
+            partition_index: rep_id.partition,
+            committed_leader_epoch: -1,
+            ..Default::default()
+        };
+        match committed_offset(&ctx, &rep_id, group) {
+            Ok(offset) => partition_response.committed_offset = offset,
+            Err(error_code) => {
+                partition_response.committed_offset = -1;
+                partition_response.error_code = offset_error_code(error_code);
+            }
+        }
+
+        match response.topics.last_mut() {
+            Some(topic_response) if topic_response.name == rep_id.topic => {
+                topic_response.partitions.push(partition_response)
+            }
+            _ => response.topics.push(OffsetFetchResponseTopic {
+                name: rep_id.topic,
+                partitions: vec![partition_response],
+            }),
+        }
+    }
+
+    Ok(request.new_response(response))
+}
+
+/// offset committed by group, -1 if group has not committed, error if replica is not led by this spu
+fn committed_offset(
+    ctx: &DefaultSharedGlobalContext,
+    rep_id: &ReplicaKey,
+    group: &str,
+) -> Result<i64, ErrorCode> {
+    match ctx.leaders_state().get_replica(rep_id) {
+        Some(replica) => Ok(replica
+            .storage()
+            .consumer_offsets()
+            .get(group)
+            .unwrap_or(-1)),
+        None => Err(ErrorCode::PartitionNotLeader),
+    }
+}
+
+/// kafka clients treat offsets of partitions unknown to coordinator as retriable
+fn offset_error_code(error_code: ErrorCode) -> ErrorCode {
+    match error_code {
+        ErrorCode::PartitionNotLeader => ErrorCode::UnknownTopicOrPartition,
+        error_code => super::kafka_error_code(error_code),
+    }
+}
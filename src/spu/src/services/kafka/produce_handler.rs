@@ -0,0 +1,41 @@
+use std::io::Error;
+
+use tracing::trace;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::produce::{DefaultProduceRequest, ProduceResponse};
+use fluvio_spu_schema::kafka::produce::KafkaProduceRequest;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::services::public::auth::PublicAuthContext;
+use crate::services::public::produce_handler::handle_produce_request;
+use super::kafka_error_code;
+
+/// records are written same as SPU produce request
+pub async fn handle_kafka_produce_request(
+    request: RequestMessage<KafkaProduceRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<ProduceResponse>, Error> {
+    let (header, kafka_request) = request.get_header_request();
+    trace!(
+        "handling kafka produce request, acks: {}",
+        kafka_request.acks
+    );
+
+    let produce_request: DefaultProduceRequest = kafka_request.into();
+    let mut response = handle_produce_request(
+        RequestMessage::new(header, produce_request),
+        ctx,
+        &PublicAuthContext::default(),
+    )
+    .await?;
+
+    for topic_response in &mut response.response.responses {
+        for partition_response in &mut topic_response.partitions {
+            partition_response.error_code = kafka_error_code(partition_response.error_code);
+            partition_response.log_append_time_ms = -1;
+        }
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use tracing::debug;
+use tracing::trace;
+use async_trait::async_trait;
+use futures_util::stream::StreamExt;
+
+use fluvio_socket::InnerFlvSocket;
+use fluvio_socket::FlvSocketError;
+use fluvio_service::call_service;
+use fluvio_service::FlvService;
+use fluvio_spu_schema::kafka::KafkaApiKey;
+use fluvio_spu_schema::kafka::KafkaRequest;
+use fluvio_spu_schema::kafka::UnsupportedResponse;
+use fluvio_future::net::TcpStream;
+use dataplane::ErrorCode;
+use dataplane::api::ResponseMessage;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::services::public::produce_handler::ACKS_NONE;
+use super::api_versions::handle_kafka_api_versions_request;
+use super::metadata_handler::handle_metadata_request;
+use super::list_offsets_handler::handle_list_offsets_request;
+use super::produce_handler::handle_kafka_produce_request;
+use super::fetch_handler::handle_kafka_fetch_request;
+use super::find_coordinator_handler::handle_find_coordinator_request;
+use super::offset_commit_handler::handle_offset_commit_request;
+use super::offset_commit_handler::handle_offset_fetch_request;
+
+#[derive(Debug)]
+pub struct KafkaService {}
+
+impl KafkaService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl FlvService<TcpStream> for KafkaService {
+    type Context = DefaultSharedGlobalContext;
+    type Request = KafkaRequest;
+
+    async fn respond(
+        self: Arc<Self>,
+        context: DefaultSharedGlobalContext,
+        socket: InnerFlvSocket<TcpStream>,
+    ) -> Result<(), FlvSocketError> {
+        let _connection = context.metrics().public_connections.track();
+
+        let (sink, mut stream) = socket.split();
+
+        let mut s_sink = sink.as_shared();
+        let mut api_stream = stream.api_stream::<KafkaRequest, KafkaApiKey>();
+
+        while let Some(msg) = api_stream.next().await {
+            let req_message = match msg {
+                Ok(req_message) => req_message,
+                Err(err) => {
+                    debug!(
+                        "conn: {}, kafka msg can't be decoded: {}, ending connection",
+                        s_sink.id(),
+                        err
+                    );
+                    break;
+                }
+            };
+
+            trace!(
+                "conn: {}, received kafka request: {:#?}",
+                s_sink.id(),
+                req_message
+            );
+            match req_message {
+                KafkaRequest::KafkaApiVersionsRequest(request) => {
+                    let (response, version) = handle_kafka_api_versions_request(request)?;
+                    s_sink.send_response(&response, version).await?;
+                }
+                KafkaRequest::MetadataRequest(request) => call_service!(
+                    request,
+                    handle_metadata_request(request, context.clone()),
+                    s_sink,
+                    "kafka metadata handler"
+                ),
+                KafkaRequest::ListOffsetsRequest(request) => call_service!(
+                    request,
+                    handle_list_offsets_request(request, context.clone()),
+                    s_sink,
+                    "kafka list offsets handler"
+                ),
+                KafkaRequest::KafkaProduceRequest(request) => {
+                    let version = request.header.api_version();
+                    let acks = request.request.acks;
+                    let response = handle_kafka_produce_request(request, context.clone()).await?;
+                    if acks != ACKS_NONE {
+                        s_sink.send_response(&response, version).await?;
+                    }
+                }
+                KafkaRequest::KafkaFetchRequest(request) => {
+                    handle_kafka_fetch_request(request, context.clone(), s_sink.clone()).await?
+                }
+                KafkaRequest::FindCoordinatorRequest(request) => call_service!(
+                    request,
+                    handle_find_coordinator_request(request, context.clone()),
+                    s_sink,
+                    "kafka find coordinator handler"
+                ),
+                KafkaRequest::OffsetCommitRequest(request) => call_service!(
+                    request,
+                    handle_offset_commit_request(request, context.clone()),
+                    s_sink,
+                    "kafka offset commit handler"
+                ),
+                KafkaRequest::OffsetFetchRequest(request) => call_service!(
+                    request,
+                    handle_offset_fetch_request(request, context.clone()),
+                    s_sink,
+                    "kafka offset fetch handler"
+                ),
+                KafkaRequest::Unsupported(header) => {
+                    debug!(
+                        "conn: {}, unsupported kafka api: {}, version: {}",
+                        s_sink.id(),
+                        header.api_key(),
+                        header.api_version()
+                    );
+                    let response = ResponseMessage::from_header(
+                        &header,
+                        UnsupportedResponse {
+                            error_code: ErrorCode::UnsupportedVersion,
+                        },
+                    );
+                    s_sink.send_response(&response, 0).await?;
+                }
+            }
+        }
+
+        debug!("conn: {}, kafka connection terminated", s_sink.id());
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 pub(crate) mod public;
+pub(crate) mod kafka;
 
 pub mod internal;
 
 pub use self::internal::create_internal_server;
 pub use self::public::create_public_server;
+pub use self::kafka::create_kafka_server;
@@ -9,6 +9,11 @@ use fluvio_spu_schema::server::versions::ApiVersionKey;
 use fluvio_spu_schema::server::versions::ApiVersionsRequest;
 use fluvio_spu_schema::server::versions::ApiVersionsResponse;
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetsRequest;
+use fluvio_spu_schema::server::stream_fetch::FileStreamFetchRequest;
+use fluvio_spu_schema::server::stream_fetch::StreamFetchAckRequest;
+use fluvio_spu_schema::server::producer_id::InitProducerIdRequest;
 
 pub async fn handle_kf_lookup_version_request(
     request: RequestMessage<ApiVersionsRequest>,
@@ -33,6 +38,31 @@ pub async fn handle_kf_lookup_version_request(
         FetchOffsetsRequest::DEFAULT_API_VERSION,
         FetchOffsetsRequest::DEFAULT_API_VERSION,
     ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::CommitConsumerOffsets,
+        CommitConsumerOffsetsRequest::DEFAULT_API_VERSION,
+        CommitConsumerOffsetsRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::FetchConsumerOffsets,
+        FetchConsumerOffsetsRequest::DEFAULT_API_VERSION,
+        FetchConsumerOffsetsRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::StreamFetch,
+        FileStreamFetchRequest::MIN_API_VERSION,
+        FileStreamFetchRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::StreamFetchAck,
+        StreamFetchAckRequest::DEFAULT_API_VERSION,
+        StreamFetchAckRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::InitProducerId,
+        InitProducerIdRequest::DEFAULT_API_VERSION,
+        InitProducerIdRequest::DEFAULT_API_VERSION,
+    ));
 
     Ok(request.new_response(response))
 }
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use tracing::debug;
+use tracing::error;
+
+use fluvio_auth::AuthContext;
+use fluvio_auth::InstanceAction;
+use fluvio_auth::basic::BasicAuthContext;
+use fluvio_controlplane_metadata::extended::ObjectType;
+
+/// authorization context of public connection.
+/// if SPU has no authorization policy, every request is permitted
+#[derive(Debug, Clone, Default)]
+pub struct PublicAuthContext(Option<Arc<BasicAuthContext>>);
+
+impl PublicAuthContext {
+    pub fn new(auth: Option<BasicAuthContext>) -> Self {
+        Self(auth.map(Arc::new))
+    }
+
+    /// check if action on records of topic can be permitted
+    pub async fn allow_topic_action(&self, action: InstanceAction, topic: &str) -> bool {
+        let auth = match &self.0 {
+            Some(auth) => auth,
+            None => return true,
+        };
+
+        match auth
+            .allow_instance_action(ObjectType::Topic, action, topic)
+            .await
+        {
+            Ok(allowed) => {
+                if !allowed {
+                    debug!("topic: {} access denied", topic);
+                }
+                allowed
+            }
+            Err(err) => {
+                error!("topic: {} authorization failed: {}", topic, err);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::collections::HashMap;
+
+    use fluvio_future::test_async;
+    use fluvio_socket::FlvSocket;
+    use fluvio_auth::basic::{Action, BasicRbacPolicy, Permissions, Rule};
+    use fluvio_auth::x509::AuthRequest;
+    use fluvio_controlplane_metadata::extended::ObjectType;
+    use dataplane::api::RequestMessage;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::fetch::{DefaultFetchRequest, FetchableTopic, FetchPartition};
+    use dataplane::produce::{DefaultProduceRequest, DefaultPartitionRequest, DefaultTopicRequest};
+    use dataplane::record::{DefaultRecord, RecordSet};
+    use dataplane::ErrorCode;
+
+    use crate::fixture::TestSpu;
+
+    const TOPIC: &str = "test";
+    const SECRET_TOPIC: &str = "secret";
+
+    /// readers can read all topics except secret one, but can't write
+    fn reader_policy() -> BasicRbacPolicy {
+        let mut objects = HashMap::new();
+        objects.insert(
+            ObjectType::Topic,
+            Permissions::from(vec![
+                Rule::allow("*", vec![Action::Read]),
+                Rule::deny(SECRET_TOPIC, vec![Action::Read]),
+            ]),
+        );
+        let mut roles = HashMap::new();
+        roles.insert("reader".to_owned(), objects);
+        BasicRbacPolicy::from(roles)
+    }
+
+    async fn start_spu(name: &str, port: u16) -> (TestSpu, FlvSocket) {
+        let spu = TestSpu::start_with_policy(name, 5001, port, Some(reader_policy())).await;
+        for topic in &[TOPIC, SECRET_TOPIC] {
+            spu.add_leader(topic, 0).await;
+            let mut batch = DefaultBatch::default();
+            batch.add_record(DefaultRecord::from("hello".to_owned()));
+            spu.send_records(topic, 0, RecordSet::default().add(batch))
+                .await;
+        }
+
+        let mut socket = FlvSocket::connect(spu.public_endpoint())
+            .await
+            .expect("connect");
+        let request = AuthRequest::new("alice".to_owned(), vec!["reader".to_owned()]);
+        let response = socket
+            .send(&RequestMessage::new_request(request))
+            .await
+            .expect("authenticate");
+        assert!(response.response.success);
+        (spu, socket)
+    }
+
+    #[test_async]
+    async fn test_unauthorized_produce() -> Result<(), ()> {
+        let (spu, mut socket) = start_spu("auth-unauthorized-produce", 9229).await;
+
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from("denied".to_owned()));
+        let mut partition_request = DefaultPartitionRequest::default();
+        partition_request.records.batches.push(batch);
+        let topic_request = DefaultTopicRequest {
+            name: TOPIC.to_owned(),
+            partitions: vec![partition_request],
+            ..Default::default()
+        };
+        let request = DefaultProduceRequest {
+            acks: 1,
+            topics: vec![topic_request],
+            ..Default::default()
+        };
+
+        let response = socket
+            .send(&RequestMessage::new_request(request))
+            .await
+            .expect("produce");
+        let partition = response
+            .response
+            .find_partition_response(TOPIC, 0)
+            .expect("partition");
+        assert_eq!(partition.error_code, ErrorCode::TopicAuthorizationFailed);
+
+        // nothing is written
+        assert_eq!(spu.read_batches(TOPIC, 0).await.len(), 1);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_unauthorized_fetch() -> Result<(), ()> {
+        let (_spu, mut socket) = start_spu("auth-unauthorized-fetch", 9230).await;
+
+        let topic = |name: &str| FetchableTopic {
+            name: name.to_owned(),
+            fetch_partitions: vec![FetchPartition {
+                partition_index: 0,
+                fetch_offset: 0,
+                ..Default::default()
+            }],
+        };
+        let request = DefaultFetchRequest {
+            max_bytes: 10000,
+            topics: vec![topic(TOPIC), topic(SECRET_TOPIC)],
+            ..Default::default()
+        };
+
+        let response = socket
+            .send(&RequestMessage::new_request(request))
+            .await
+            .expect("fetch");
+        let topics = &response.response.topics;
+        assert_eq!(topics[0].partitions[0].error_code, ErrorCode::None);
+        assert_eq!(topics[0].partitions[0].records.batches.len(), 1);
+        assert_eq!(
+            topics[1].partitions[0].error_code,
+            ErrorCode::TopicAuthorizationFailed
+        );
+        assert!(topics[1].partitions[0].records.batches.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use std::io::Error as IoError;
+
+use tracing::trace;
+use tracing::error;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::{ErrorCode, Offset};
+use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetsResponse;
+use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetTopicResponse;
+use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetPartitionResponse;
+use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetsResponse;
+use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetTopicResponse;
+use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetPartitionResponse;
+use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
+use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_storage::ReplicaStorage;
+use fluvio_auth::InstanceAction;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::controllers::leader_replica::LeaderReplicaControllerCommand;
+use super::auth::PublicAuthContext;
+
+pub async fn handle_commit_consumer_offsets_request(
+    req_msg: RequestMessage<CommitConsumerOffsetsRequest>,
+    ctx: DefaultSharedGlobalContext,
+    auth: &PublicAuthContext,
+) -> Result<ResponseMessage<CommitConsumerOffsetsResponse>, IoError> {
+    let request = req_msg.request();
+    trace!("handling commit consumer offsets request: {:#?}", request);
+
+    let group = &request.group;
+    let mut response = CommitConsumerOffsetsResponse::default();
+
+    for topic_request in &request.topics {
+        let topic = &topic_request.name;
+
+        let mut topic_response = CommitConsumerOffsetTopicResponse {
+            name: topic.clone(),
+            ..Default::default()
+        };
+
+        let allowed = auth.allow_topic_action(InstanceAction::Read, topic).await;
+
+        for partition_req in &topic_request.partitions {
+            let rep_id = ReplicaKey::new(topic.clone(), partition_req.partition_index);
+
+            let error_code = if !allowed {
+                ErrorCode::TopicAuthorizationFailed
+            } else if group.is_empty() {
+                ErrorCode::InvalidGroupId
+            } else {
+                commit_offset(&ctx, &rep_id, group, partition_req.offset).await
+            };
+
+            topic_response
+                .partitions
+                .push(CommitConsumerOffsetPartitionResponse {
+                    error_code,
+                    partition_index: partition_req.partition_index,
+                });
+        }
+
+        response.topics.push(topic_response);
+    }
+
+    Ok(req_msg.new_response(response))
+}
+
+/// commit offset on leader replica, followers are synced with new offsets
+pub(crate) async fn commit_offset(
+    ctx: &DefaultSharedGlobalContext,
+    rep_id: &ReplicaKey,
+    group: &str,
+    offset: Offset,
+) -> ErrorCode {
+    let error_code = if let Some(mut replica) = ctx.leaders_state().get_mut_replica(rep_id) {
+        if offset < 0 || offset > replica.storage().get_leo() {
+            trace!("commit offset: {} out of range for: {}", offset, rep_id);
+            ErrorCode::OffsetOutOfRange
+        } else {
+            match replica
+                .mut_storage()
+                .commit_consumer_offset(group, offset)
+                .await
+            {
+                Ok(_) => ErrorCode::None,
+                Err(err) => {
+                    error!(
+                        "error: {:#?} committing group: {} offset to replica: {}",
+                        err, group, rep_id
+                    );
+                    ErrorCode::StorageError
+                }
+            }
+        }
+    } else {
+        trace!("commit offset replica is not found: {}", rep_id);
+        return ErrorCode::PartitionNotLeader;
+    };
+
+    if error_code == ErrorCode::None {
+        if let Err(err) = ctx
+            .leaders_state()
+            .send_message(
+                rep_id,
+                LeaderReplicaControllerCommand::ConsumerOffsetsUpdated,
+            )
+            .await
+        {
+            error!(
+                "error notifying leader: {} of consumer offsets: {}",
+                rep_id, err
+            );
+        }
+    }
+    error_code
+}
+
+pub async fn handle_fetch_consumer_offsets_request(
+    req_msg: RequestMessage<FetchConsumerOffsetsRequest>,
+    ctx: DefaultSharedGlobalContext,
+    auth: &PublicAuthContext,
+) -> Result<ResponseMessage<FetchConsumerOffsetsResponse>, IoError> {
+    let request = req_msg.request();
+    trace!("handling fetch consumer offsets request: {:#?}", request);
+
+    let mut response = FetchConsumerOffsetsResponse::default();
+
+    for topic_request in &request.topics {
+        let topic = &topic_request.name;
+
+        let mut topic_response = FetchConsumerOffsetTopicResponse {
+            name: topic.clone(),
+            ..Default::default()
+        };
+
+        let allowed = auth.allow_topic_action(InstanceAction::Read, topic).await;
+
+        for partition in &topic_request.partitions {
+            let mut partition_response = FetchConsumerOffsetPartitionResponse {
+                partition_index: *partition,
+                ..Default::default()
+            };
+            let rep_id = ReplicaKey::new(topic.clone(), *partition);
+            if !allowed {
+                partition_response.error_code = ErrorCode::TopicAuthorizationFailed;
+            } else if let Some(replica) = ctx.leaders_state().get_replica(&rep_id) {
+                let storage = replica.storage();
+                partition_response.error_code = ErrorCode::None;
+                partition_response.start_offset = storage.get_log_start_offset();
+                partition_response.last_stable_offset = storage.get_hw();
+                partition_response.offsets = storage
+                    .consumer_offsets()
+                    .offsets()
+                    .iter()
+                    .filter(|(group, _)| {
+                        request.groups.is_empty() || request.groups.contains(group)
+                    })
+                    .map(|(group, offset)| ConsumerOffset {
+                        group: group.clone(),
+                        offset: *offset,
+                    })
+                    .collect();
+            } else {
+                trace!("fetch consumer offsets replica is not found: {}", rep_id);
+                partition_response.error_code = ErrorCode::PartitionNotLeader;
+            }
+
+            topic_response.partitions.push(partition_response);
+        }
+
+        response.topics.push(topic_response);
+    }
+
+    Ok(req_msg.new_response(response))
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_future::test_async;
+    use fluvio_socket::FlvSocket;
+    use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetsRequest;
+    use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetTopic;
+    use fluvio_spu_schema::server::consumer_offset::CommitConsumerOffsetPartition;
+    use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetsRequest;
+    use fluvio_spu_schema::server::consumer_offset::FetchConsumerOffsetTopic;
+    use fluvio_spu_schema::server::consumer_offset::ConsumerOffset;
+    use dataplane::api::RequestMessage;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::{DefaultRecord, RecordSet};
+    use dataplane::ErrorCode;
+
+    use crate::fixture::TestSpu;
+
+    const TOPIC: &str = "test";
+
+    fn commit_request(group: &str, offsets: &[(i32, i64)]) -> CommitConsumerOffsetsRequest {
+        CommitConsumerOffsetsRequest {
+            group: group.to_owned(),
+            topics: vec![CommitConsumerOffsetTopic {
+                name: TOPIC.to_owned(),
+                partitions: offsets
+                    .iter()
+                    .map(|(partition_index, offset)| CommitConsumerOffsetPartition {
+                        partition_index: *partition_index,
+                        offset: *offset,
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    async fn commit(socket: &mut FlvSocket, group: &str, offsets: &[(i32, i64)]) -> Vec<ErrorCode> {
+        let response = socket
+            .send(&RequestMessage::new_request(commit_request(group, offsets)))
+            .await
+            .expect("commit");
+        response.response.topics[0]
+            .partitions
+            .iter()
+            .map(|partition| partition.error_code)
+            .collect()
+    }
+
+    #[test_async]
+    async fn test_commit_and_fetch_consumer_offsets() -> Result<(), ()> {
+        let spu = TestSpu::start("consumer-offsets-handler", 5001, 9226).await;
+        spu.add_leader(TOPIC, 0).await;
+        let mut batch = DefaultBatch::default();
+        for _ in 0..3 {
+            batch.add_record(DefaultRecord::from("hello".to_owned()));
+        }
+        spu.send_records(TOPIC, 0, RecordSet::default().add(batch))
+            .await;
+
+        let mut socket = FlvSocket::connect(spu.public_endpoint())
+            .await
+            .expect("connect");
+
+        assert_eq!(
+            commit(&mut socket, "g1", &[(0, 2), (1, 0)]).await,
+            vec![ErrorCode::None, ErrorCode::PartitionNotLeader]
+        );
+        assert_eq!(
+            commit(&mut socket, "g2", &[(0, 3)]).await,
+            vec![ErrorCode::None]
+        );
+        // offset can't be past end of partition
+        assert_eq!(
+            commit(&mut socket, "g2", &[(0, 4)]).await,
+            vec![ErrorCode::OffsetOutOfRange]
+        );
+        assert_eq!(
+            commit(&mut socket, "", &[(0, 1)]).await,
+            vec![ErrorCode::InvalidGroupId]
+        );
+
+        let fetch = |groups: Vec<String>| FetchConsumerOffsetsRequest {
+            groups,
+            topics: vec![FetchConsumerOffsetTopic {
+                name: TOPIC.to_owned(),
+                partitions: vec![0, 1],
+            }],
+        };
+
+        let response = socket
+            .send(&RequestMessage::new_request(fetch(vec![])))
+            .await
+            .expect("fetch");
+        let partitions = &response.response.topics[0].partitions;
+        assert_eq!(partitions[0].error_code, ErrorCode::None);
+        assert_eq!(partitions[0].last_stable_offset, 3);
+        assert_eq!(
+            partitions[0].offsets,
+            vec![
+                ConsumerOffset {
+                    group: "g1".to_owned(),
+                    offset: 2
+                },
+                ConsumerOffset {
+                    group: "g2".to_owned(),
+                    offset: 3
+                }
+            ]
+        );
+        assert_eq!(partitions[1].error_code, ErrorCode::PartitionNotLeader);
+
+        // only requested groups are returned
+        let response = socket
+            .send(&RequestMessage::new_request(fetch(vec!["g2".to_owned()])))
+            .await
+            .expect("fetch");
+        assert_eq!(
+            response.response.topics[0].partitions[0].offsets,
+            vec![ConsumerOffset {
+                group: "g2".to_owned(),
+                offset: 3
+            }]
+        );
+
+        Ok(())
+    }
+}
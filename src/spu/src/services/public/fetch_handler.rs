@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use tracing::trace;
 use tracing::debug;
 use futures_util::io::AsyncRead;
@@ -6,25 +8,31 @@ use futures_util::io::AsyncWrite;
 use fluvio_socket::InnerFlvSink;
 use fluvio_socket::InnerExclusiveFlvSink;
 use fluvio_socket::FlvSocketError;
+use dataplane::ErrorCode;
 use dataplane::api::RequestMessage;
 use dataplane::fetch::{FileFetchResponse, FileFetchRequest, FilePartitionResponse, FileTopicResponse};
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use fluvio_future::zero_copy::ZeroCopyWrite;
+use fluvio_auth::InstanceAction;
 
 use crate::core::DefaultSharedGlobalContext;
+use super::auth::PublicAuthContext;
 
 /// perform log fetch request using zero copy write
 pub async fn handle_fetch_request<S>(
     request: RequestMessage<FileFetchRequest>,
     ctx: DefaultSharedGlobalContext,
     sink: InnerExclusiveFlvSink<S>,
+    auth: &PublicAuthContext,
 ) -> Result<(), FlvSocketError>
 where
     S: AsyncRead + AsyncWrite + Unpin + Send,
     InnerFlvSink<S>: ZeroCopyWrite,
 {
+    let start = Instant::now();
     let (header, fetch_request) = request.get_header_request();
     let mut fetch_response = FileFetchResponse::default();
+    let mut fetch_bytes = 0;
 
     for topic_request in &fetch_request.topics {
         let topic = &topic_request.name;
@@ -32,6 +40,8 @@ where
         let mut topic_response = FileTopicResponse::default();
         topic_response.name = topic.clone();
 
+        let allowed = auth.allow_topic_action(InstanceAction::Read, topic).await;
+
         for partition_req in &topic_request.fetch_partitions {
             let partition = &partition_req.partition_index;
             debug!(
@@ -43,6 +53,19 @@ where
             let mut partition_response = FilePartitionResponse::default();
             partition_response.partition_index = *partition;
 
+            if !allowed {
+                partition_response.error_code = ErrorCode::TopicAuthorizationFailed;
+                topic_response.partitions.push(partition_response);
+                continue;
+            }
+
+            if ctx.replica_localstore().has_transform(&rep_id) {
+                // records are sent as stored, transform only runs in stream fetch
+                partition_response.error_code = ErrorCode::TransformRequiresStreamFetch;
+                topic_response.partitions.push(partition_response);
+                continue;
+            }
+
             ctx.leaders_state()
                 .read_records(
                     &rep_id,
@@ -52,6 +75,7 @@ where
                     &mut partition_response,
                 )
                 .await;
+            fetch_bytes += partition_response.records.len();
 
             topic_response.partitions.push(partition_response);
         }
@@ -69,5 +93,10 @@ where
     drop(inner);
     trace!("finish sending fetch response");
 
+    let metrics = ctx.metrics();
+    metrics.fetch_requests.inc();
+    metrics.fetch_bytes.inc_by(fetch_bytes as u64);
+    metrics.fetch_latency.observe_duration(start.elapsed());
+
     Ok(())
 }
@@ -1,13 +1,19 @@
+pub(crate) mod auth;
 mod api_versions;
 mod service_impl;
-mod produce_handler;
+pub(crate) mod produce_handler;
 mod fetch_handler;
-mod offset_request;
+pub(crate) mod offset_request;
+pub(crate) mod consumer_offset;
+mod producer_id;
 mod stream_fetch;
+mod record_filter;
+mod record_transform;
 
 use tracing::info;
 
 use fluvio_service::FlvApiServer;
+use fluvio_auth::basic::BasicAuthorization;
 use service_impl::PublicService;
 use fluvio_spu_schema::server::SpuServerRequest;
 use fluvio_spu_schema::server::SpuServerApiKey;
@@ -15,8 +21,14 @@ use dataplane::ReplicaKey;
 
 use crate::core::DefaultSharedGlobalContext;
 
+pub(crate) use record_transform::TransformModules;
+
 pub type OffsetReplicaList = std::collections::HashSet<ReplicaKey>;
 
+/// acknowledged offsets of flow controlled streams in connection, by stream id
+pub type StreamAckSenders =
+    std::collections::HashMap<i32, async_channel::Sender<dataplane::Offset>>;
+
 pub(crate) type PublicApiServer =
     FlvApiServer<SpuServerRequest, SpuServerApiKey, DefaultSharedGlobalContext, PublicService>;
 
@@ -28,5 +40,10 @@ pub fn create_public_server(addr: String, ctx: DefaultSharedGlobalContext) -> Pu
         addr
     );
 
-    FlvApiServer::new(addr, ctx, PublicService::new())
+    let authorization = ctx.config().auth_policy.clone().map(|policy| {
+        info!("using basic authorization");
+        BasicAuthorization::new(policy)
+    });
+
+    FlvApiServer::new(addr, ctx, PublicService::new(authorization))
 }
@@ -1,6 +1,7 @@
 use std::io::Error as IoError;
 
 use tracing::trace;
+use tracing::error;
 
 use dataplane::api::{RequestMessage, ResponseMessage};
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetsRequest;
@@ -10,12 +11,15 @@ use fluvio_spu_schema::server::fetch_offset::FetchOffsetPartitionResponse;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use dataplane::ErrorCode;
 use fluvio_storage::ReplicaStorage;
+use fluvio_auth::InstanceAction;
 
 use crate::core::DefaultSharedGlobalContext;
+use super::auth::PublicAuthContext;
 
 pub async fn handle_offset_request(
     req_msg: RequestMessage<FetchOffsetsRequest>,
     ctx: DefaultSharedGlobalContext,
+    auth: &PublicAuthContext,
 ) -> Result<ResponseMessage<FetchOffsetsResponse>, IoError> {
     let request = req_msg.request();
     trace!("handling flv fetch request: {:#?}", request);
@@ -28,17 +32,33 @@ pub async fn handle_offset_request(
         let mut topic_response = FetchOffsetTopicResponse::default();
         topic_response.name = topic.clone();
 
+        let allowed = auth.allow_topic_action(InstanceAction::Read, topic).await;
+
         for partition_req in &topic_request.partitions {
             let partition = &partition_req.partition_index;
             let mut partition_response = FetchOffsetPartitionResponse::default();
             partition_response.partition_index = *partition;
             let rep_id = ReplicaKey::new(topic.clone(), *partition);
-            if let Some(replica) = ctx.leaders_state().get_replica(&rep_id) {
+            if !allowed {
+                partition_response.error_code = ErrorCode::TopicAuthorizationFailed;
+            } else if let Some(replica) = ctx.leaders_state().get_replica(&rep_id) {
                 trace!("offset fetch request for replica found: {}", rep_id);
                 let storage = replica.storage();
                 partition_response.error_code = ErrorCode::None;
                 partition_response.start_offset = storage.get_log_start_offset();
                 partition_response.last_stable_offset = storage.get_hw();
+                if let Some(timestamp) = partition_req.timestamp {
+                    match storage.find_offset_by_timestamp(timestamp).await {
+                        // records which are not committed can't be read yet
+                        Ok(offset) => {
+                            partition_response.timestamp_offset = Some(offset.min(storage.get_hw()))
+                        }
+                        Err(err) => {
+                            error!("error finding offset of timestamp in: {}, {}", rep_id, err);
+                            partition_response.error_code = ErrorCode::StorageError;
+                        }
+                    }
+                }
             } else {
                 trace!("offset fetch request is not found: {}", rep_id);
                 partition_response.error_code = ErrorCode::PartitionNotLeader;
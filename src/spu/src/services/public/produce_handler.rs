@@ -1,29 +1,111 @@
 use std::io::Error;
+use std::time::Duration;
+use std::time::Instant;
 
 use tracing::warn;
 use tracing::trace;
 use tracing::error;
+use tracing::debug;
+use tokio::select;
+use tokio::sync::broadcast::Receiver;
+use futures_util::future::FutureExt;
 
+use fluvio_future::timer::sleep;
+use fluvio_auth::InstanceAction;
+use dataplane::core::Encoder;
 use dataplane::ErrorCode;
+use dataplane::Offset;
 use dataplane::produce::{
     DefaultProduceRequest, ProduceResponse, TopicProduceResponse, PartitionProduceResponse,
 };
+use dataplane::api::RequestHeader;
 use dataplane::api::RequestMessage;
 use dataplane::api::ResponseMessage;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 
 use crate::core::DefaultSharedGlobalContext;
+use crate::core::OffsetUpdateEvent;
+use super::auth::PublicAuthContext;
+
+/// producer waits until all in sync replicas have records
+const ACKS_ALL: i16 = -1;
+
+/// producer doesn't expect response
+pub(crate) const ACKS_NONE: i16 = 0;
 
 pub async fn handle_produce_request(
     request: RequestMessage<DefaultProduceRequest>,
     ctx: DefaultSharedGlobalContext,
+    auth: &PublicAuthContext,
 ) -> Result<ResponseMessage<ProduceResponse>, Error> {
+    let pending = write_produce_request(request, ctx, auth).await?;
+    Ok(pending.wait().await)
+}
+
+/// produce request whose records are written, response is completed by `wait`
+/// once in sync replicas have records
+pub struct PendingProduce {
+    ctx: DefaultSharedGlobalContext,
+    header: RequestHeader,
+    response: ProduceResponse,
+    start: Instant,
+    deadline: Instant,
+    offset_receiver: Option<Receiver<OffsetUpdateEvent>>,
+    /// topic and partition index in response with end offset to wait for
+    hw_waits: Vec<(usize, usize, ReplicaKey, Offset)>,
+}
+
+impl PendingProduce {
+    /// true if response has to wait for high watermark
+    pub fn is_waiting(&self) -> bool {
+        !self.hw_waits.is_empty()
+    }
+
+    pub async fn wait(mut self) -> ResponseMessage<ProduceResponse> {
+        if let Some(receiver) = self.offset_receiver.as_mut() {
+            for (topic_index, partition_index, rep_id, leo) in self.hw_waits.drain(..) {
+                let wait_error =
+                    wait_for_hw(&self.ctx, receiver, &rep_id, leo, self.deadline).await;
+                if wait_error.is_error() {
+                    self.response.responses[topic_index].partitions[partition_index].error_code =
+                        wait_error;
+                }
+            }
+        }
+
+        trace!("produce request completed");
+        self.ctx.metrics().produce_requests.inc();
+        self.ctx
+            .metrics()
+            .produce_latency
+            .observe_duration(self.start.elapsed());
+
+        RequestMessage::<DefaultProduceRequest>::response_with_header(&self.header, self.response)
+    }
+}
+
+/// write records of produce request to leader replicas
+pub async fn write_produce_request(
+    request: RequestMessage<DefaultProduceRequest>,
+    ctx: DefaultSharedGlobalContext,
+    auth: &PublicAuthContext,
+) -> Result<PendingProduce, Error> {
     let (header, produce_request) = request.get_header_request();
     trace!("handling produce request: {:#?}", produce_request);
 
+    let start = Instant::now();
     let mut response = ProduceResponse::default();
+    let mut hw_waits = vec![];
 
-    //let ack = produce_request.acks;
+    // with acks 0 or 1, response is sent once leader has written records
+    let acks = produce_request.acks;
+    let deadline = Instant::now() + Duration::from_millis(produce_request.timeout_ms.max(0) as u64);
+    // subscribe before writing so high watermark updates are not missed
+    let offset_receiver = if acks == ACKS_ALL {
+        Some(ctx.offset_channel().receiver())
+    } else {
+        None
+    };
 
     for topic_request in produce_request.topics {
         let topic = &topic_request.name;
@@ -32,6 +114,8 @@ pub async fn handle_produce_request(
         let mut topic_response = TopicProduceResponse::default();
         topic_response.name = topic.to_owned();
 
+        let allowed = auth.allow_topic_action(InstanceAction::Write, topic).await;
+
         for partition_request in topic_request.partitions {
             let rep_id = ReplicaKey::new(topic.clone(), partition_request.partition_index);
 
@@ -40,20 +124,43 @@ pub async fn handle_produce_request(
             let mut partition_response = PartitionProduceResponse::default();
             partition_response.partition_index = rep_id.partition;
 
+            if !allowed {
+                partition_response.error_code = ErrorCode::TopicAuthorizationFailed;
+                topic_response.partitions.push(partition_response);
+                continue;
+            }
+
+            ctx.metrics()
+                .produce_bytes
+                .inc_by(partition_request.records.write_size(header.api_version()) as u64);
             match ctx
                 .leaders_state()
                 .send_records(&rep_id, partition_request.records, true)
                 .await
             {
-                Ok(found_flag) => {
-                    if found_flag {
-                        trace!("records has successfull processed for: {}", rep_id);
-                        partition_response.error_code = ErrorCode::None;
-                    } else {
-                        warn!("no replica found: {}", rep_id);
-                        partition_response.error_code = ErrorCode::NotLeaderForPartition;
+                Ok(Some((base_offset, leo, error_code))) => {
+                    trace!("records has successfull processed for: {}", rep_id);
+                    partition_response.base_offset = base_offset;
+                    partition_response.error_code = error_code;
+                    // duplicate is acknowledged like original batch, once it is replicated
+                    if offset_receiver.is_some()
+                        && matches!(
+                            error_code,
+                            ErrorCode::None | ErrorCode::DuplicateSequenceNumber
+                        )
+                    {
+                        hw_waits.push((
+                            response.responses.len(),
+                            topic_response.partitions.len(),
+                            rep_id,
+                            leo,
+                        ));
                     }
                 }
+                Ok(None) => {
+                    warn!("no replica found: {}", rep_id);
+                    partition_response.error_code = ErrorCode::NotLeaderForPartition;
+                }
                 Err(err) => {
                     error!("error: {:#?} writing to replica: {}", err, rep_id);
                     partition_response.error_code = ErrorCode::StorageError;
@@ -66,7 +173,194 @@ pub async fn handle_produce_request(
         response.responses.push(topic_response);
     }
 
-    trace!("produce request completed");
+    Ok(PendingProduce {
+        ctx,
+        header,
+        response,
+        start,
+        deadline,
+        offset_receiver,
+        hw_waits,
+    })
+}
+
+/// wait until high watermark of replica reaches offset, time out at deadline
+async fn wait_for_hw(
+    ctx: &DefaultSharedGlobalContext,
+    receiver: &mut Receiver<OffsetUpdateEvent>,
+    rep_id: &ReplicaKey,
+    offset: Offset,
+    deadline: Instant,
+) -> ErrorCode {
+    loop {
+        match ctx.leaders_state().hw(rep_id) {
+            Some(hw) if hw >= offset => return ErrorCode::None,
+            Some(_) => {}
+            None => return ErrorCode::NotLeaderForPartition,
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            debug!(
+                "replica: {}, timed out waiting for high watermark: {}",
+                rep_id, offset
+            );
+            return ErrorCode::RequestTimedOut;
+        }
+
+        select! {
+            _ = sleep(deadline - now).fuse() => {},
+            event = receiver.recv() => {
+                // any event or lag means high watermark may have changed, check again
+                trace!("offset event while waiting for high watermark: {:?}", event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_future::test_async;
+    use fluvio_socket::FlvSocket;
+    use fluvio_spu_schema::server::versions::ApiVersionsRequest;
+    use dataplane::api::RequestMessage;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::produce::{DefaultProduceRequest, DefaultPartitionRequest, DefaultTopicRequest};
+    use dataplane::record::DefaultRecord;
+    use dataplane::ErrorCode;
+
+    use crate::fixture::TestSpu;
+    use super::ACKS_ALL;
+    use super::ACKS_NONE;
+
+    const TOPIC: &str = "test";
+
+    fn produce_request(acks: i16, value: &str) -> DefaultProduceRequest {
+        produce_records(acks, &[value])
+    }
+
+    fn produce_records(acks: i16, values: &[&str]) -> DefaultProduceRequest {
+        produce_batches(acks, vec![batch(values)])
+    }
+
+    fn batch(values: &[&str]) -> DefaultBatch {
+        let mut batch = DefaultBatch::default();
+        for value in values {
+            batch.add_record(DefaultRecord::from(value.to_string()));
+        }
+        batch
+    }
+
+    fn producer_batch(first_sequence: i32, values: &[&str]) -> DefaultBatch {
+        let mut batch = batch(values);
+        let header = batch.get_mut_header();
+        header.producer_id = 1;
+        header.producer_epoch = 0;
+        header.first_sequence = first_sequence;
+        batch
+    }
+
+    fn produce_batches(acks: i16, batches: Vec<DefaultBatch>) -> DefaultProduceRequest {
+        let mut partition_request = DefaultPartitionRequest::default();
+        partition_request.records.batches = batches;
+        let topic_request = DefaultTopicRequest {
+            name: TOPIC.to_owned(),
+            partitions: vec![partition_request],
+            ..Default::default()
+        };
+
+        DefaultProduceRequest {
+            acks,
+            timeout_ms: 1000,
+            topics: vec![topic_request],
+            ..Default::default()
+        }
+    }
+
+    #[test_async]
+    async fn test_produce_without_acks() -> Result<(), ()> {
+        let spu = TestSpu::start("produce-without-acks", 5001, 9228).await;
+        spu.add_leader(TOPIC, 0).await;
+
+        let mut socket = FlvSocket::connect(spu.public_endpoint())
+            .await
+            .expect("connect");
+        let mut request = RequestMessage::new_request(produce_request(ACKS_NONE, "hello"));
+        request.get_mut_header().set_correlation_id(1);
+        socket
+            .get_mut_sink()
+            .send_request(&request)
+            .await
+            .expect("produce");
+
+        // next response is for request sent after produce
+        let mut request = RequestMessage::new_request(ApiVersionsRequest::default());
+        request.get_mut_header().set_correlation_id(2);
+        let response = socket.send(&request).await.expect("versions");
+        assert_eq!(response.correlation_id, 2);
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].records()[0].value.to_string(), "hello");
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_produce_base_offset() -> Result<(), ()> {
+        let spu = TestSpu::start("produce-base-offset", 5001, 9232).await;
+        spu.add_leader(TOPIC, 0).await;
 
-    Ok(RequestMessage::<DefaultProduceRequest>::response_with_header(&header, response))
+        let mut socket = FlvSocket::connect(spu.public_endpoint())
+            .await
+            .expect("connect");
+        // base offset is offset of first record in batch
+        for (values, base_offset) in [(vec!["a", "b", "c"], 0), (vec!["d", "e"], 3)] {
+            let request = RequestMessage::new_request(produce_records(1, &values));
+            let response = socket.send(&request).await.expect("produce");
+            let partition = &response.response.responses[0].partitions[0];
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.base_offset, base_offset);
+        }
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].base_offset, 3);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_produce_duplicate_base_offset() -> Result<(), ()> {
+        let spu = TestSpu::start("produce-duplicate-base-offset", 5001, 9236).await;
+        spu.add_leader(TOPIC, 0).await;
+
+        let mut socket = FlvSocket::connect(spu.public_endpoint())
+            .await
+            .expect("connect");
+        // base offset is offset of first written batch, retry is acknowledged with original offset
+        for (batches, error_code, base_offset) in [
+            (vec![producer_batch(0, &["a", "b"])], ErrorCode::None, 0),
+            (
+                vec![producer_batch(0, &["a", "b"]), producer_batch(2, &["c"])],
+                ErrorCode::None,
+                2,
+            ),
+            (
+                vec![producer_batch(2, &["c"])],
+                ErrorCode::DuplicateSequenceNumber,
+                2,
+            ),
+        ] {
+            let request = RequestMessage::new_request(produce_batches(ACKS_ALL, batches));
+            let response = socket.send(&request).await.expect("produce");
+            let partition = &response.response.responses[0].partitions[0];
+            assert_eq!(partition.error_code, error_code);
+            assert_eq!(partition.base_offset, base_offset);
+        }
+
+        let batches = spu.read_batches(TOPIC, 0).await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].base_offset, 2);
+        Ok(())
+    }
 }
@@ -0,0 +1,34 @@
+use std::io::Error as IoError;
+
+use tracing::trace;
+use tracing::error;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_spu_schema::server::producer_id::InitProducerIdRequest;
+use fluvio_spu_schema::server::producer_id::InitProducerIdResponse;
+
+use crate::core::DefaultSharedGlobalContext;
+
+pub async fn handle_init_producer_id_request(
+    req_msg: RequestMessage<InitProducerIdRequest>,
+    ctx: DefaultSharedGlobalContext,
+) -> Result<ResponseMessage<InitProducerIdResponse>, IoError> {
+    trace!("handling init producer id request");
+
+    let mut response = InitProducerIdResponse::default();
+    match ctx.producer_ids().allocate().await {
+        Ok(producer_id) => {
+            response.producer_id = producer_id;
+            response.producer_epoch = 0;
+        }
+        Err(err) => {
+            error!("error allocating producer id: {}", err);
+            response.producer_id = -1;
+            response.producer_epoch = -1;
+            response.error_code = ErrorCode::StorageError;
+        }
+    }
+
+    Ok(req_msg.new_response(response))
+}
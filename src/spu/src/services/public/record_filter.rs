@@ -0,0 +1,145 @@
+//!
+//! # Record Filter
+//!
+//! Records are matched against filter of stream fetch request before they are sent back
+//!
+use regex::Regex;
+
+use dataplane::Offset;
+use dataplane::batch::DefaultBatch;
+use dataplane::record::DefaultRecord;
+use fluvio_spu_schema::server::stream_fetch::{RecordFilter, HeaderFilter};
+
+/// filter with regular expression compiled once per stream
+#[derive(Debug)]
+pub struct RecordMatcher {
+    key_prefix: Option<Vec<u8>>,
+    header: Option<HeaderFilter>,
+    value_regex: Option<Regex>,
+}
+
+impl RecordMatcher {
+    pub fn new(filter: RecordFilter) -> Result<Self, regex::Error> {
+        let value_regex = match filter.value_regex {
+            Some(regex) => Some(Regex::new(&regex)?),
+            None => None,
+        };
+        Ok(Self {
+            key_prefix: filter.key_prefix,
+            header: filter.header,
+            value_regex,
+        })
+    }
+
+    /// true if record meets all conditions of filter
+    pub fn matches(&self, record: &DefaultRecord) -> bool {
+        if let Some(prefix) = &self.key_prefix {
+            match record.get_key().inner_value_ref() {
+                Some(key) if key.starts_with(prefix) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(header) = &self.header {
+            if record.header(&header.key) != Some(header.value.as_slice()) {
+                return false;
+            }
+        }
+
+        if let Some(regex) = &self.value_regex {
+            let value = match record.get_value().inner_value_ref() {
+                Some(value) => String::from_utf8_lossy(value),
+                None => return false,
+            };
+            if !regex.is_match(&value) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// keep matching records at or after start offset, batches without any are dropped.
+    /// batches keep their base offset and records their offset delta
+    pub fn filter_batches(
+        &self,
+        batches: Vec<DefaultBatch>,
+        start_offset: Offset,
+    ) -> Vec<DefaultBatch> {
+        batches
+            .into_iter()
+            .filter_map(|mut batch| {
+                let base_offset = batch.get_base_offset();
+                batch.records_mut().retain(|record| {
+                    base_offset + record.get_offset_delta() >= start_offset && self.matches(record)
+                });
+                if batch.records().is_empty() {
+                    None
+                } else {
+                    Some(batch)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::{DefaultRecord, Header};
+    use fluvio_spu_schema::server::stream_fetch::RecordFilter;
+
+    use super::RecordMatcher;
+
+    fn record(key: &str, value: &str) -> DefaultRecord {
+        let mut record = DefaultRecord::from(value.to_owned());
+        record.key = key.to_owned().into();
+        record
+    }
+
+    #[test]
+    fn test_record_matcher() {
+        let matcher = RecordMatcher::new(
+            RecordFilter::default()
+                .with_key_prefix("user-")
+                .with_value_regex("^err"),
+        )
+        .expect("matcher");
+        assert!(matcher.matches(&record("user-1", "error")));
+        assert!(!matcher.matches(&record("user-1", "ok")));
+        assert!(!matcher.matches(&record("admin-1", "error")));
+        assert!(!matcher.matches(&DefaultRecord::from("error".to_owned())));
+
+        let matcher = RecordMatcher::new(RecordFilter::default().with_header("type", "alert"))
+            .expect("matcher");
+        let mut alert = record("a", "b");
+        alert.add_header(Header::new("type", "alert"));
+        assert!(matcher.matches(&alert));
+        assert!(!matcher.matches(&record("a", "b")));
+
+        assert!(RecordMatcher::new(RecordFilter::default())
+            .expect("matcher")
+            .matches(&record("a", "b")));
+        assert!(RecordMatcher::new(RecordFilter::default().with_value_regex("(")).is_err());
+    }
+
+    #[test]
+    fn test_filter_batches() {
+        let mut batch1 = DefaultBatch::default();
+        batch1.add_record(record("a", "1"));
+        batch1.add_record(record("b", "2"));
+        batch1.add_record(record("a", "3"));
+        let mut batch2 = DefaultBatch::default();
+        batch2.add_record(record("b", "4"));
+        batch2.set_base_offset(3);
+
+        let matcher =
+            RecordMatcher::new(RecordFilter::default().with_key_prefix("a")).expect("matcher");
+        let batches = matcher.filter_batches(vec![batch1, batch2], 1);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].records().len(), 1);
+        assert_eq!(batches[0].records()[0].get_offset_delta(), 2);
+        assert_eq!(batches[0].get_last_offset(), 2);
+    }
+}
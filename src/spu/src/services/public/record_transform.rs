@@ -0,0 +1,580 @@
+//!
+//! # Record Transform
+//!
+//! Records are run through wasm module before they are sent back.
+//! Module can't have imports and must export:
+//!
+//! - `memory`
+//! - `alloc(len: i32) -> i32`: pointer to `len` bytes where key and value are written
+//! - `transform(key_ptr: i32, key_len: i32, value_ptr: i32, value_len: i32) -> i64`:
+//!   new value as `ptr << 32 | len`, or negative if record is dropped
+//!
+//! Records of batch are transformed by same instance, which is refueled before each record,
+//! so no record can exhaust resources of SPU. Instance memory is limited, if record fails
+//! on instance which transformed earlier records, it is retried on new instance,
+//! so module which doesn't free memory it allocated still works. Module can't rely on state
+//! kept between records. Records without value are tombstones, they are not transformed.
+//!
+//! Modules sent by clients are only run if SPU allows client transforms.
+//!
+use std::fmt;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::collections::HashMap;
+
+use tracing::debug;
+use fluvio_future::task::spawn_blocking;
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, StoreLimitsBuilder, TypedFunc};
+
+use dataplane::ErrorCode;
+use dataplane::batch::DefaultBatch;
+use dataplane::record::DefaultRecord;
+
+use crate::config::Transform;
+
+const MEMORY: &str = "memory";
+const ALLOC_FN: &str = "alloc";
+const TRANSFORM_FN: &str = "transform";
+const MODULE_EXTENSION: &str = "wasm";
+
+#[derive(Debug)]
+pub enum TransformError {
+    /// module is not in transform directory of spu
+    ModuleNotFound(String),
+    /// module can't be compiled or doesn't export transform functions
+    InvalidModule(String),
+    /// module trapped, ran out of fuel or returned value outside of its memory
+    Failed(String),
+    /// client sent module, but spu only runs topic transforms
+    ClientModuleDisabled,
+}
+
+impl TransformError {
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::ModuleNotFound(_) => ErrorCode::TransformModuleNotFound,
+            Self::InvalidModule(_) => ErrorCode::TransformInvalidModule,
+            Self::Failed(_) => ErrorCode::TransformFailed,
+            Self::ClientModuleDisabled => ErrorCode::TransformClientModuleDisabled,
+        }
+    }
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ModuleNotFound(err) => write!(f, "transform module not found: {}", err),
+            Self::InvalidModule(err) => write!(f, "invalid transform module: {}", err),
+            Self::Failed(err) => write!(f, "transform failed: {}", err),
+            Self::ClientModuleDisabled => write!(f, "client transform modules are disabled"),
+        }
+    }
+}
+
+/// transform functions of module instance
+struct TransformInstance {
+    store: Store,
+    /// total fuel added to store, remaining fuel is what is not consumed of it
+    fuel_added: u64,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    transform: TypedFunc<(i32, i32, i32, i32), i64>,
+}
+
+impl TransformInstance {
+    /// top up fuel of store, so record can use all of it
+    fn refuel(&mut self, fuel: u64) -> Result<(), TransformError> {
+        let consumed = self.store.fuel_consumed().unwrap_or_default();
+        let remaining = self.fuel_added.saturating_sub(consumed);
+        if remaining < fuel {
+            self.store
+                .add_fuel(fuel - remaining)
+                .map_err(|err| TransformError::Failed(err.to_string()))?;
+            self.fuel_added += fuel - remaining;
+        }
+        Ok(())
+    }
+
+    /// new value of record, none if record is dropped
+    fn transform(
+        &mut self,
+        record: &DefaultRecord,
+        fuel: u64,
+    ) -> Result<Option<Vec<u8>>, TransformError> {
+        self.refuel(fuel)?;
+        let key = record.get_key().inner_value_ref().as_deref().unwrap_or(&[]);
+        let value = record
+            .get_value()
+            .inner_value_ref()
+            .as_deref()
+            .unwrap_or(&[]);
+        let (key_ptr, key_len) = self.write(key)?;
+        let (value_ptr, value_len) = self.write(value)?;
+
+        let result = self
+            .transform
+            .call((key_ptr, key_len, value_ptr, value_len))
+            .map_err(|err| TransformError::Failed(err.to_string()))?;
+        if result < 0 {
+            return Ok(None);
+        }
+
+        let ptr = (result as u64 >> 32) as usize;
+        let len = (result as u64 & 0xffff_ffff) as usize;
+        self.read(ptr, len).map(Some)
+    }
+
+    /// copy bytes into memory of instance
+    fn write(&self, bytes: &[u8]) -> Result<(i32, i32), TransformError> {
+        let len = bytes.len() as i32;
+        let ptr = self
+            .alloc
+            .call(len)
+            .map_err(|err| TransformError::Failed(err.to_string()))?;
+        self.memory
+            .write(ptr as u32 as usize, bytes)
+            .map_err(|err| TransformError::Failed(err.to_string()))?;
+        Ok((ptr, len))
+    }
+
+    /// copy bytes out of memory of instance
+    fn read(&self, ptr: usize, len: usize) -> Result<Vec<u8>, TransformError> {
+        if ptr.saturating_add(len) > self.memory.data_size() {
+            return Err(TransformError::Failed(format!(
+                "value at: {} len: {} is out of memory",
+                ptr, len
+            )));
+        }
+        let mut bytes = vec![0; len];
+        self.memory
+            .read(ptr, &mut bytes)
+            .map_err(|err| TransformError::Failed(err.to_string()))?;
+        Ok(bytes)
+    }
+}
+
+/// engine and topic modules shared by all streams of spu.
+/// topic module is compiled once and compiled again only if its file is modified
+pub struct TransformModules {
+    engine: Engine,
+    config: Transform,
+    topic_modules: Mutex<HashMap<String, (SystemTime, Module)>>,
+}
+
+impl fmt::Debug for TransformModules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TransformModules")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl TransformModules {
+    pub fn new(config: Transform) -> Self {
+        let mut engine_config = Config::new();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config).expect("wasm engine");
+        Self {
+            engine,
+            config,
+            topic_modules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// transform of module sent by client
+    pub async fn client_transform(
+        &self,
+        bytes: Vec<u8>,
+    ) -> Result<RecordTransform, TransformError> {
+        if !self.config.allow_client_modules {
+            return Err(TransformError::ClientModuleDisabled);
+        }
+        if bytes.len() > self.config.max_module_bytes {
+            return Err(TransformError::InvalidModule(format!(
+                "module size: {} exceeds max: {}",
+                bytes.len(),
+                self.config.max_module_bytes
+            )));
+        }
+        let module = self.compile(bytes).await?;
+        RecordTransform::new(self.engine.clone(), module, &self.config)
+    }
+
+    /// transform of module named by topic setting in transform directory
+    pub async fn topic_transform(&self, name: &str) -> Result<RecordTransform, TransformError> {
+        // name can't refer to file outside of directory
+        if name.is_empty() || name.starts_with('.') || name.contains(&['/', '\\'][..]) {
+            return Err(TransformError::ModuleNotFound(name.to_owned()));
+        }
+        let dir =
+            self.config.module_dir.as_ref().ok_or_else(|| {
+                TransformError::ModuleNotFound(format!("{}, no transform dir", name))
+            })?;
+
+        let path = dir.join(format!("{}.{}", name, MODULE_EXTENSION));
+        let modified = fluvio_future::fs::metadata(&path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .map_err(|err| {
+                TransformError::ModuleNotFound(format!("{}, {}", path.display(), err))
+            })?;
+
+        let cached = self
+            .topic_modules
+            .lock()
+            .unwrap()
+            .get(name)
+            .filter(|(cached_modified, _)| *cached_modified == modified)
+            .map(|(_, module)| module.clone());
+        let module = match cached {
+            Some(module) => module,
+            None => {
+                debug!("loading transform module: {}", path.display());
+                let bytes = fluvio_future::fs::read(&path).await.map_err(|err| {
+                    TransformError::ModuleNotFound(format!("{}, {}", path.display(), err))
+                })?;
+                let module = self.compile(bytes).await?;
+                self.topic_modules
+                    .lock()
+                    .unwrap()
+                    .insert(name.to_owned(), (modified, module.clone()));
+                module
+            }
+        };
+        RecordTransform::new(self.engine.clone(), module, &self.config)
+    }
+
+    /// compile on blocking pool, since large module can take a while
+    async fn compile(&self, bytes: Vec<u8>) -> Result<Module, TransformError> {
+        let engine = self.engine.clone();
+        spawn_blocking(move || Module::new(&engine, bytes))
+            .await
+            .map_err(|err| TransformError::InvalidModule(err.to_string()))
+    }
+}
+
+/// compiled module, instances are created per batch
+pub struct RecordTransform {
+    engine: Engine,
+    module: Module,
+    fuel: u64,
+    memory_pages: u32,
+}
+
+impl fmt::Debug for RecordTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecordTransform")
+            .field("fuel", &self.fuel)
+            .field("memory_pages", &self.memory_pages)
+            .finish()
+    }
+}
+
+impl RecordTransform {
+    fn new(engine: Engine, module: Module, config: &Transform) -> Result<Self, TransformError> {
+        let transform = Self {
+            engine,
+            module,
+            fuel: config.fuel,
+            memory_pages: config.memory_pages,
+        };
+
+        // exports and memory limit are checked up front rather than on first record
+        match transform.instantiate() {
+            Ok(_) => Ok(transform),
+            Err(TransformError::Failed(err)) => Err(TransformError::InvalidModule(err)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// run module over records, batches left without records are dropped.
+    /// batches keep their base offset and records their offset delta.
+    /// records without value are tombstones and are kept unchanged
+    pub fn transform_batches(
+        &self,
+        batches: Vec<DefaultBatch>,
+    ) -> Result<Vec<DefaultBatch>, TransformError> {
+        let mut transformed = Vec::with_capacity(batches.len());
+        for mut batch in batches {
+            let mut instance = None;
+            let records = batch.records_mut();
+            for mut record in std::mem::take(records) {
+                if record.get_value().inner_value_ref().is_none() {
+                    records.push(record);
+                } else if let Some(value) = self.transform_with(&mut instance, &record)? {
+                    record.value = value.into();
+                    records.push(record);
+                }
+            }
+            if !batch.records().is_empty() {
+                transformed.push(batch);
+            }
+        }
+        Ok(transformed)
+    }
+
+    /// new value of record, none if record is dropped.
+    /// transform record by instance of earlier records, or by new instance if there is none
+    /// or record fails on it
+    fn transform_with(
+        &self,
+        instance: &mut Option<TransformInstance>,
+        record: &DefaultRecord,
+    ) -> Result<Option<Vec<u8>>, TransformError> {
+        if let Some(used) = instance.as_mut() {
+            match used.transform(record, self.fuel) {
+                Err(TransformError::Failed(err)) => {
+                    debug!("transform failed on used instance, retrying: {}", err)
+                }
+                result => return result,
+            }
+        }
+        instance
+            .insert(self.instantiate()?)
+            .transform(record, self.fuel)
+    }
+
+    /// new instance with memory limit, it is fueled before each record.
+    /// store is not Send, so instance must not be held across await
+    fn instantiate(&self) -> Result<TransformInstance, TransformError> {
+        let store = Store::new_with_limits(
+            &self.engine,
+            StoreLimitsBuilder::new()
+                .memory_pages(self.memory_pages)
+                .build(),
+        );
+        store
+            .add_fuel(self.fuel)
+            .map_err(|err| TransformError::Failed(err.to_string()))?;
+        let instance = Instance::new(&store, &self.module, &[])
+            .map_err(|err| TransformError::Failed(err.to_string()))?;
+
+        let memory = instance
+            .get_memory(MEMORY)
+            .ok_or_else(|| TransformError::InvalidModule(format!("missing export: {}", MEMORY)))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(ALLOC_FN)
+            .map_err(|err| TransformError::InvalidModule(err.to_string()))?;
+        let transform = instance
+            .get_typed_func::<(i32, i32, i32, i32), i64>(TRANSFORM_FN)
+            .map_err(|err| TransformError::InvalidModule(err.to_string()))?;
+
+        Ok(TransformInstance {
+            store,
+            fuel_added: self.fuel,
+            memory,
+            alloc,
+            transform,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::env::temp_dir;
+    use std::fs;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_clean_dir;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::{DefaultAsyncBuffer, DefaultRecord};
+
+    use crate::config::Transform;
+    use super::{TransformModules, TransformError};
+
+    /// value is replaced by key, records with empty value are dropped
+    const KEY_AS_VALUE: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (global $next (mut i32) (i32.const 1024))
+            (func (export "alloc") (param $len i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $next))
+                (global.set $next (i32.add (global.get $next) (local.get $len)))
+                (local.get $ptr))
+            (func (export "transform")
+                (param $key_ptr i32) (param $key_len i32)
+                (param $value_ptr i32) (param $value_len i32)
+                (result i64)
+                (if (i32.eqz (local.get $value_len))
+                    (then (return (i64.const -1))))
+                (i64.or
+                    (i64.shl (i64.extend_i32_u (local.get $key_ptr)) (i64.const 32))
+                    (i64.extend_i32_u (local.get $key_len)))))
+    "#;
+
+    const ENDLESS_LOOP: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32)
+                (i32.const 0))
+            (func (export "transform") (param i32 i32 i32 i32) (result i64)
+                (loop $forever (br $forever))
+                (i64.const -1)))
+    "#;
+
+    fn client_modules() -> Transform {
+        Transform {
+            allow_client_modules: true,
+            ..Default::default()
+        }
+    }
+
+    fn record(key: &str, value: &str) -> DefaultRecord {
+        let mut record = DefaultRecord::from(value.to_owned());
+        record.key = key.to_owned().into();
+        record
+    }
+
+    #[test_async]
+    async fn test_transform_batches() -> Result<(), ()> {
+        let transform = TransformModules::new(client_modules())
+            .client_transform(KEY_AS_VALUE.as_bytes().to_vec())
+            .await
+            .expect("module");
+
+        let mut batch1 = DefaultBatch::default();
+        batch1.add_record(record("a", "1"));
+        batch1.add_record(record("b", ""));
+        batch1.add_record(record("c", "3"));
+        let mut tombstone = record("e", "");
+        tombstone.value = DefaultAsyncBuffer::new(None);
+        batch1.add_record(tombstone);
+        let mut batch2 = DefaultBatch::default();
+        batch2.add_record(record("d", ""));
+        batch2.set_base_offset(4);
+
+        let batches = transform
+            .transform_batches(vec![batch1, batch2])
+            .expect("transform");
+        assert_eq!(batches.len(), 1);
+        let records = batches[0].records();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].value.to_string(), "a");
+        assert_eq!(records[1].value.to_string(), "c");
+        assert_eq!(records[1].get_offset_delta(), 2);
+        assert!(records[2].get_value().inner_value_ref().is_none());
+        assert_eq!(records[2].get_offset_delta(), 3);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_transform_limits() -> Result<(), ()> {
+        let modules = TransformModules::new(client_modules());
+        let transform = modules
+            .client_transform(ENDLESS_LOOP.as_bytes().to_vec())
+            .await
+            .expect("module");
+        let mut batch = DefaultBatch::default();
+        batch.add_record(record("a", "1"));
+        assert!(matches!(
+            transform.transform_batches(vec![batch]),
+            Err(TransformError::Failed(_))
+        ));
+
+        assert!(matches!(
+            modules.client_transform(b"(module)".to_vec()).await,
+            Err(TransformError::InvalidModule(_))
+        ));
+        assert!(matches!(
+            modules.client_transform(b"not wasm".to_vec()).await,
+            Err(TransformError::InvalidModule(_))
+        ));
+
+        let modules = TransformModules::new(Transform {
+            memory_pages: 0,
+            ..client_modules()
+        });
+        assert!(matches!(
+            modules
+                .client_transform(KEY_AS_VALUE.as_bytes().to_vec())
+                .await,
+            Err(TransformError::InvalidModule(_))
+        ));
+
+        let modules = TransformModules::new(Transform {
+            max_module_bytes: 100,
+            ..client_modules()
+        });
+        assert!(matches!(
+            modules
+                .client_transform(KEY_AS_VALUE.as_bytes().to_vec())
+                .await,
+            Err(TransformError::InvalidModule(_))
+        ));
+
+        // only topic transforms are run unless client modules are allowed
+        assert!(matches!(
+            TransformModules::new(Transform::default())
+                .client_transform(KEY_AS_VALUE.as_bytes().to_vec())
+                .await,
+            Err(TransformError::ClientModuleDisabled)
+        ));
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_transform_batch_memory() -> Result<(), ()> {
+        let transform = TransformModules::new(client_modules())
+            .client_transform(KEY_AS_VALUE.as_bytes().to_vec())
+            .await
+            .expect("module");
+
+        // module never frees memory, so records of batch don't fit in memory of one instance
+        let value = "v".repeat(20_000);
+        let mut batch = DefaultBatch::default();
+        for key in &["a", "b", "c", "d", "e"] {
+            batch.add_record(record(key, &value));
+        }
+
+        let batches = transform.transform_batches(vec![batch]).expect("transform");
+        let values: Vec<String> = batches[0]
+            .records()
+            .iter()
+            .map(|record| record.value.to_string())
+            .collect();
+        assert_eq!(values, vec!["a", "b", "c", "d", "e"]);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_topic_transform() -> Result<(), ()> {
+        let dir = temp_dir().join("test-topic-transform");
+        ensure_clean_dir(&dir);
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(dir.join("key-as-value.wasm"), KEY_AS_VALUE).expect("write module");
+
+        let modules = TransformModules::new(Transform {
+            module_dir: Some(dir.clone()),
+            ..Default::default()
+        });
+        assert!(modules.topic_transform("key-as-value").await.is_ok());
+        assert!(modules
+            .topic_modules
+            .lock()
+            .unwrap()
+            .contains_key("key-as-value"));
+        // cached module is used while file is not modified
+        assert!(modules.topic_transform("key-as-value").await.is_ok());
+        assert_eq!(modules.topic_modules.lock().unwrap().len(), 1);
+
+        assert!(matches!(
+            modules.topic_transform("missing").await,
+            Err(TransformError::ModuleNotFound(_))
+        ));
+        assert!(matches!(
+            modules
+                .topic_transform("../test-topic-transform/key-as-value")
+                .await,
+            Err(TransformError::ModuleNotFound(_))
+        ));
+
+        let modules = TransformModules::new(Transform::default());
+        assert!(matches!(
+            modules.topic_transform("key-as-value").await,
+            Err(TransformError::ModuleNotFound(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,65 +1,87 @@
 use std::sync::Arc;
 use std::collections::HashSet;
+use std::collections::HashMap;
 
 use tracing::debug;
 use tracing::trace;
 use tracing::warn;
+use tracing::error;
 use async_trait::async_trait;
-use futures_util::io::AsyncRead;
-use futures_util::io::AsyncWrite;
 use futures_util::stream::StreamExt;
 use tokio::select;
 use event_listener::Event;
 
 use dataplane::api::RequestMessage;
 use fluvio_socket::InnerFlvSocket;
-use fluvio_socket::InnerFlvSink;
 use fluvio_socket::FlvSocketError;
 use fluvio_service::call_service;
 use fluvio_service::FlvService;
 use fluvio_spu_schema::server::SpuServerApiKey;
 use fluvio_spu_schema::server::SpuServerRequest;
-use fluvio_future::zero_copy::ZeroCopyWrite;
+use fluvio_future::net::TcpStream;
+use fluvio_future::task::spawn;
+use fluvio_auth::Authorization;
+use fluvio_auth::basic::BasicAuthorization;
 
 use crate::core::DefaultSharedGlobalContext;
 use super::api_versions::handle_kf_lookup_version_request;
-use super::produce_handler::handle_produce_request;
+use super::produce_handler::write_produce_request;
+use super::produce_handler::ACKS_NONE;
 use super::fetch_handler::handle_fetch_request;
 use super::offset_request::handle_offset_request;
+use super::consumer_offset::handle_commit_consumer_offsets_request;
+use super::consumer_offset::handle_fetch_consumer_offsets_request;
+use super::producer_id::handle_init_producer_id_request;
 use super::stream_fetch::StreamFetchHandler;
+use super::stream_fetch::handle_stream_fetch_ack;
 use super::OffsetReplicaList;
+use super::StreamAckSenders;
+use super::auth::PublicAuthContext;
 
 #[derive(Debug)]
-pub struct PublicService {}
+pub struct PublicService {
+    authorization: Option<BasicAuthorization>,
+}
 
 impl PublicService {
-    pub fn new() -> Self {
-        PublicService {}
+    /// without authorization, all requests are permitted
+    pub fn new(authorization: Option<BasicAuthorization>) -> Self {
+        PublicService { authorization }
     }
 }
 
 #[async_trait]
-impl<S> FlvService<S> for PublicService
-where
-    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
-{
+impl FlvService<TcpStream> for PublicService {
     type Context = DefaultSharedGlobalContext;
     type Request = SpuServerRequest;
 
     async fn respond(
         self: Arc<Self>,
         context: DefaultSharedGlobalContext,
-        socket: InnerFlvSocket<S>,
-    ) -> Result<(), FlvSocketError>
-    where
-        InnerFlvSink<S>: ZeroCopyWrite,
-    {
+        mut socket: InnerFlvSocket<TcpStream>,
+    ) -> Result<(), FlvSocketError> {
+        let auth = PublicAuthContext::new(match &self.authorization {
+            Some(authorization) => Some(
+                authorization
+                    .create_auth_context(&mut socket)
+                    .await
+                    .map_err(|err| {
+                        let io_error: std::io::Error = err.into();
+                        io_error
+                    })?,
+            ),
+            None => None,
+        });
+
+        let _connection = context.metrics().public_connections.track();
+
         let (sink, mut stream) = socket.split();
 
         let mut s_sink = sink.as_shared();
         let mut api_stream = stream.api_stream::<SpuServerRequest, SpuServerApiKey>();
 
         let mut offset_replica_list: OffsetReplicaList = HashSet::new();
+        let mut stream_acks: StreamAckSenders = HashMap::new();
 
         let mut receiver = context.offset_channel().receiver();
 
@@ -133,19 +155,53 @@ where
                                 ),
 
                                 // Kafka
-                                SpuServerRequest::ProduceRequest(request) => call_service!(
+                                SpuServerRequest::ProduceRequest(request) => {
+                                    // producer doesn't wait for response without acks
+                                    let version = request.header.api_version();
+                                    let acks = request.request.acks;
+                                    let pending = write_produce_request(request,context.clone(),&auth).await?;
+                                    if pending.is_waiting() {
+                                        // waiting for replication must not block other requests on connection
+                                        let mut sink = s_sink.clone();
+                                        spawn(async move {
+                                            let response = pending.wait().await;
+                                            if let Err(err) = sink.send_response(&response, version).await {
+                                                error!("error sending produce response: {:#?}", err);
+                                            }
+                                        });
+                                    } else {
+                                        let response = pending.wait().await;
+                                        if acks != ACKS_NONE {
+                                            s_sink.send_response(&response, version).await?;
+                                        }
+                                    }
+                                },
+                                SpuServerRequest::FileFetchRequest(request) => handle_fetch_request(request,context.clone(),s_sink.clone(),&auth).await?,
+
+                                SpuServerRequest::FetchOffsetsRequest(request) => call_service!(
                                     request,
-                                    handle_produce_request(request,context.clone()),
+                                    handle_offset_request(request,context.clone(),&auth),
                                     s_sink,
-                                    "ks produce request handler"
+                                    "handling offset fetch request"
                                 ),
-                                SpuServerRequest::FileFetchRequest(request) => handle_fetch_request(request,context.clone(),s_sink.clone()).await?,
 
-                                SpuServerRequest::FetchOffsetsRequest(request) => call_service!(
+                                SpuServerRequest::CommitConsumerOffsetsRequest(request) => call_service!(
                                     request,
-                                    handle_offset_request(request,context.clone()),
+                                    handle_commit_consumer_offsets_request(request,context.clone(),&auth),
                                     s_sink,
-                                    "handling offset fetch request"
+                                    "handling commit consumer offsets request"
+                                ),
+                                SpuServerRequest::FetchConsumerOffsetsRequest(request) => call_service!(
+                                    request,
+                                    handle_fetch_consumer_offsets_request(request,context.clone(),&auth),
+                                    s_sink,
+                                    "handling fetch consumer offsets request"
+                                ),
+                                SpuServerRequest::InitProducerIdRequest(request) => call_service!(
+                                    request,
+                                    handle_init_producer_id_request(request,context.clone()),
+                                    s_sink,
+                                    "handling init producer id request"
                                 ),
 
                                 SpuServerRequest::RegisterSyncReplicaRequest(request) => {
@@ -155,7 +211,13 @@ where
                                     debug!("registered offset sync request: {:#?}",sync_request);
                                     offset_replica_list = HashSet::from_iter(sync_request.leader_replicas);
                                 },
-                                SpuServerRequest::FileStreamFetchRequest(request) =>  StreamFetchHandler::handle_stream_fetch(request,context.clone(),s_sink.clone(),end_event.clone())
+                                SpuServerRequest::FileStreamFetchRequest(request) =>  StreamFetchHandler::handle_stream_fetch(request,context.clone(),s_sink.clone(),end_event.clone(),auth.clone(),&mut stream_acks),
+                                SpuServerRequest::StreamFetchAckRequest(request) => call_service!(
+                                    request,
+                                    handle_stream_fetch_ack(request,&mut stream_acks),
+                                    s_sink,
+                                    "handling stream fetch ack request"
+                                ),
 
                             }
                         } else {
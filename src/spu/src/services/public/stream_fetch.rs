@@ -1,27 +1,79 @@
+use std::io::Error as IoError;
 use std::sync::Arc;
 
 use tracing::debug;
 use tracing::trace;
 use tracing::warn;
-use tracing::error;
 use futures_util::io::AsyncRead;
 use futures_util::io::AsyncWrite;
 use tokio::select;
 use event_listener::Event;
 use tokio::sync::broadcast::RecvError;
+use async_channel::Receiver;
+use async_channel::unbounded;
+use futures_util::future::pending;
+use futures_util::stream::StreamExt;
 
 use fluvio_future::zero_copy::ZeroCopyWrite;
 use fluvio_future::task::spawn;
+use fluvio_future::task::spawn_blocking;
 use fluvio_socket::InnerFlvSink;
 use fluvio_socket::InnerExclusiveFlvSink;
 use fluvio_socket::FlvSocketError;
-use dataplane::api::{RequestMessage, RequestHeader};
-use dataplane::{Offset, Isolation, ReplicaKey};
+use dataplane::api::{RequestMessage, RequestHeader, ResponseMessage};
+use dataplane::core::Encoder;
+use dataplane::{ErrorCode, Offset, Isolation, ReplicaKey};
 use dataplane::fetch::FilePartitionResponse;
+use dataplane::fetch::FetchablePartitionResponse;
+use dataplane::batch::DefaultBatch;
+use dataplane::record::RecordSet;
+use fluvio_storage::StorageError;
 use fluvio_spu_schema::server::stream_fetch::FileStreamFetchRequest;
+use fluvio_spu_schema::server::stream_fetch::DefaultStreamFetchRequest;
+use fluvio_spu_schema::server::stream_fetch::RecordFilter;
 use fluvio_spu_schema::server::stream_fetch::StreamFetchResponse;
+use fluvio_spu_schema::server::stream_fetch::StreamFetchAckRequest;
+use fluvio_spu_schema::server::stream_fetch::StreamFetchAckResponse;
+use fluvio_auth::InstanceAction;
 
 use crate::core::DefaultSharedGlobalContext;
+use super::auth::PublicAuthContext;
+use super::record_filter::RecordMatcher;
+use super::record_transform::{RecordTransform, TransformError};
+use super::StreamAckSenders;
+
+/// forward offset acknowledged by client to its stream
+pub async fn handle_stream_fetch_ack(
+    request: RequestMessage<StreamFetchAckRequest>,
+    stream_acks: &mut StreamAckSenders,
+) -> Result<ResponseMessage<StreamFetchAckResponse>, IoError> {
+    let (header, ack) = request.get_header_request();
+    trace!("stream fetch ack: {:?}", ack);
+
+    if let Some(sender) = stream_acks.get(&ack.stream_id) {
+        if sender.send(ack.offset).await.is_err() {
+            debug!("stream: {} has been terminated", ack.stream_id);
+            stream_acks.remove(&ack.stream_id);
+        }
+    } else {
+        debug!("no stream: {} for ack", ack.stream_id);
+    }
+
+    Ok(
+        RequestMessage::<StreamFetchAckRequest>::response_with_header(
+            &header,
+            StreamFetchAckResponse {},
+        ),
+    )
+}
+
+/// wait for next acknowledged offset, never resolves if stream is not flow controlled
+async fn next_ack(acks: &mut Option<Receiver<Offset>>) -> Option<Offset> {
+    match acks {
+        Some(acks) => acks.next().await,
+        None => pending().await,
+    }
+}
 
 /// continuous fetch handler
 /// while client is active, it continuously send back new records
@@ -33,6 +85,49 @@ pub struct StreamFetchHandler<S> {
     header: RequestHeader,
     kf_sink: InnerExclusiveFlvSink<S>,
     end_event: Arc<Event>,
+    auth: PublicAuthContext,
+    /// acknowledged offsets from client, if stream is flow controlled
+    acks: Option<Receiver<Offset>>,
+    /// response has been sent but not yet acknowledged
+    in_flight: bool,
+    /// filter and transforms of stream, records are sent as stored if there are none
+    processor: Arc<BatchProcessor>,
+}
+
+/// filter and transforms run over decoded batches on blocking pool
+#[derive(Debug, Default)]
+struct BatchProcessor {
+    /// records are decoded and only matching ones are sent back
+    matcher: Option<RecordMatcher>,
+    /// transform of topic, run before records are matched
+    topic_transform: Option<RecordTransform>,
+    /// transform of request, run after records are matched
+    transform: Option<RecordTransform>,
+}
+
+impl BatchProcessor {
+    fn is_empty(&self) -> bool {
+        self.matcher.is_none() && self.topic_transform.is_none() && self.transform.is_none()
+    }
+
+    /// transform and filter batches read from offset
+    fn process_batches(
+        &self,
+        batches: Vec<DefaultBatch>,
+        offset: Offset,
+    ) -> Result<Vec<DefaultBatch>, TransformError> {
+        let mut batches = match &self.topic_transform {
+            Some(transform) => transform.transform_batches(batches)?,
+            None => batches,
+        };
+        if let Some(matcher) = &self.matcher {
+            batches = matcher.filter_batches(batches, offset);
+        }
+        match &self.transform {
+            Some(transform) => transform.transform_batches(batches),
+            None => Ok(batches),
+        }
+    }
 }
 
 impl<S> StreamFetchHandler<S>
@@ -46,12 +141,14 @@ where
         ctx: DefaultSharedGlobalContext,
         kf_sink: InnerExclusiveFlvSink<S>,
         end_event: Arc<Event>,
+        auth: PublicAuthContext,
+        stream_acks: &mut StreamAckSenders,
     ) {
-        // first get receiver to offset update channel to we don't missed events
-
         let (header, msg) = request.get_header_request();
 
         let current_offset = msg.fetch_offset;
+        let filter = msg.filter;
+        let wasm_module = msg.wasm_module;
         let isolation = msg.isolation;
         let replica = ReplicaKey::new(msg.topic, msg.partition);
         let max_bytes = msg.max_bytes as u32;
@@ -63,6 +160,21 @@ where
             max_bytes
         );
 
+        // with stream id, next records are only sent once client acknowledged previous ones
+        let acks = if msg.stream_id != 0 {
+            let (sender, receiver) = unbounded();
+            if stream_acks.insert(msg.stream_id, sender).is_some() {
+                warn!(
+                    "conn: {}, stream: {} replaced existing stream",
+                    kf_sink.id(),
+                    msg.stream_id
+                );
+            }
+            Some(receiver)
+        } else {
+            None
+        };
+
         let handler = Self {
             ctx,
             isolation,
@@ -71,12 +183,53 @@ where
             max_bytes,
             kf_sink,
             end_event,
+            auth,
+            acks,
+            in_flight: false,
+            processor: Arc::new(BatchProcessor::default()),
         };
 
-        spawn(async move { handler.process(current_offset).await });
+        spawn(async move { handler.process(current_offset, filter, wasm_module).await });
     }
 
-    async fn process(mut self, starting_offset: Offset) -> Result<(), FlvSocketError> {
+    async fn process(
+        mut self,
+        starting_offset: Offset,
+        filter: Option<RecordFilter>,
+        wasm_module: Option<Vec<u8>>,
+    ) -> Result<(), FlvSocketError> {
+        if !self
+            .auth
+            .allow_topic_action(InstanceAction::Read, &self.replica.topic)
+            .await
+        {
+            return self.send_error(ErrorCode::TopicAuthorizationFailed).await;
+        }
+
+        let mut processor = BatchProcessor::default();
+        if let Some(filter) = filter {
+            match RecordMatcher::new(filter) {
+                Ok(matcher) => processor.matcher = Some(matcher),
+                Err(err) => {
+                    warn!(
+                        "conn: {}, invalid record filter: {}",
+                        self.kf_sink.id(),
+                        err
+                    );
+                    return self.send_error(ErrorCode::InvalidRequest).await;
+                }
+            }
+        }
+
+        if let Err(err) = self.load_transforms(&mut processor, wasm_module).await {
+            warn!("conn: {}, {}", self.kf_sink.id(), err);
+            return self.send_error(err.error_code()).await;
+        }
+        self.processor = Arc::new(processor);
+
+        // subscribe before first read so updates written in between are not missed
+        let mut receiver = self.ctx.offset_channel().receiver();
+
         let mut current_offset =
             if let Some(offset) = self.send_back_records(starting_offset).await? {
                 offset
@@ -88,9 +241,6 @@ where
                 return Ok(());
             };
 
-        let mut receiver = self.ctx.offset_channel().receiver();
-        //pin_mut!(receiver);
-
         let mut counter: i32 = 0;
         loop {
             counter += 1;
@@ -107,53 +257,82 @@ where
                     break;
                 },
 
+                ack = next_ack(&mut self.acks) => {
+                    if let Some(offset) = ack {
+                        debug!("conn: {}, replica: {} acknowledged offset: {}",self.kf_sink.id(), self.replica,offset);
+                        self.in_flight = false;
+                        if let Some(offset) = self.send_back_records(offset).await? {
+                            current_offset = offset;
+                        } else {
+                            debug!("conn: {}, no more replica: {} records can be read", self.kf_sink.id(),self.replica);
+                            break;
+                        }
+                    } else {
+                        debug!("conn: {}, stream acknowledgement closed, terminating",self.kf_sink.id());
+                        break;
+                    }
+                },
+
                 offset_event_res = receiver.recv() => {
 
                     match offset_event_res {
                         Ok(offset_event) => {
 
                             debug!("conn: {}, received offset event connection: {:#?}", self.kf_sink.id(),offset_event);
-                            if offset_event.replica_id == self.replica {
-                                // depends on isolation, we need to keep track different offset
-                                let update_offset = match self.isolation {
-                                    Isolation::ReadCommitted => offset_event.hw,
-                                    Isolation::ReadUncommitted => offset_event.leo
-                                };
-                                debug!("conn: {}, update offset: {}",self.kf_sink.id(),update_offset);
-                                if update_offset != current_offset {
-                                    debug!("conn: {}, updated offset replica: {} offset: {} diff from prev: {}",self.kf_sink.id(), self.replica,update_offset,current_offset);
-                                    if let Some(offset) = self.send_back_records(current_offset).await? {
-                                        debug!("conn: {}, replica: {} read offset: {}",self.kf_sink.id(), self.replica,offset);
-                                        current_offset = offset;
-                                    } else {
-                                        debug!("conn: {}, no more replica: {} records can be read", self.kf_sink.id(),self.replica);
-                                        break;
-                                    }
+                            if offset_event.replica_id != self.replica {
+                                debug!("conn: {}, ignoring event because replica does not match",self.kf_sink.id());
+                                continue;
+                            }
+                            if self.in_flight {
+                                debug!("conn: {}, waiting for acknowledgement, ignoring event",self.kf_sink.id());
+                                continue;
+                            }
+
+                            // depends on isolation, we need to keep track different offset
+                            let update_offset = match self.isolation {
+                                Isolation::ReadCommitted => offset_event.hw,
+                                Isolation::ReadUncommitted => offset_event.leo
+                            };
+                            debug!("conn: {}, update offset: {}",self.kf_sink.id(),update_offset);
+                            if update_offset != current_offset {
+                                debug!("conn: {}, updated offset replica: {} offset: {} diff from prev: {}",self.kf_sink.id(), self.replica,update_offset,current_offset);
+                                if let Some(offset) = self.send_back_records(current_offset).await? {
+                                    debug!("conn: {}, replica: {} read offset: {}",self.kf_sink.id(), self.replica,offset);
+                                    current_offset = offset;
                                 } else {
-                                    debug!("conn: {}, no changed in offset: {} offset: {} ignoring",self.kf_sink.id(), self.replica,update_offset);
+                                    debug!("conn: {}, no more replica: {} records can be read", self.kf_sink.id(),self.replica);
+                                    break;
                                 }
                             } else {
-                                debug!("conn: {}, ignoring event because replica does not match",self.kf_sink.id());
+                                debug!("conn: {}, no changed in offset: {} offset: {} ignoring",self.kf_sink.id(), self.replica,update_offset);
                             }
 
-
                         },
                         Err(err) => {
                             match err {
                                 RecvError::Closed => {
                                     warn!("conn: {}, lost connection to leader controller",self.kf_sink.id());
+                                    break;
                                 },
                                 RecvError::Lagged(lag) => {
-                                    error!("conn: {}, lagging: {}",self.kf_sink.id(),lag);
+                                    // events for this replica may have been dropped, re-sync from storage
+                                    warn!("conn: {}, lagging: {}, re-syncing replica: {} from offset: {}",self.kf_sink.id(),lag,self.replica,current_offset);
+                                    if self.in_flight {
+                                        // records are read again once acknowledged
+                                        continue;
+                                    }
+                                    if let Some(offset) = self.send_back_records(current_offset).await? {
+                                        current_offset = offset;
+                                    } else {
+                                        debug!("conn: {}, no more replica: {} records can be read", self.kf_sink.id(),self.replica);
+                                        break;
+                                    }
                                 }
                             }
 
                         }
                     }
 
-
-
-
                 },
             }
         }
@@ -166,10 +345,68 @@ where
         Ok(())
     }
 
+    /// compile transform of topic and of request
+    async fn load_transforms(
+        &self,
+        processor: &mut BatchProcessor,
+        wasm_module: Option<Vec<u8>>,
+    ) -> Result<(), TransformError> {
+        let modules = self.ctx.transform_modules();
+        let topic_transform = self
+            .ctx
+            .replica_localstore()
+            .spec(&self.replica)
+            .and_then(|replica| replica.transform);
+        if let Some(name) = topic_transform {
+            processor.topic_transform = Some(modules.topic_transform(&name).await?);
+        }
+
+        if let Some(wasm_module) = wasm_module {
+            processor.transform = Some(modules.client_transform(wasm_module).await?);
+        }
+
+        Ok(())
+    }
+
+    /// send back response without records, stream is not continued
+    async fn send_error(&mut self, error_code: ErrorCode) -> Result<(), FlvSocketError> {
+        debug!(
+            "conn: {}, replica: {} stream fetch error: {:?}",
+            self.kf_sink.id(),
+            self.replica,
+            error_code
+        );
+        let response = StreamFetchResponse {
+            topic: self.replica.topic.clone(),
+            partition: FilePartitionResponse {
+                partition_index: self.replica.partition,
+                error_code,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let response =
+            RequestMessage::<FileStreamFetchRequest>::response_with_header(&self.header, response);
+
+        let mut inner_sink = self.kf_sink.lock().await;
+        inner_sink
+            .encode_file_slices(&response, self.header.api_version())
+            .await?;
+
+        Ok(())
+    }
+
+    /// read records from offset and send them to client.
+    /// return offset to compare offset updates against, or None if replica is not found.
+    /// with flow control, empty response is not sent and offset only moves with acknowledgement
     async fn send_back_records(
         &mut self,
         offset: Offset,
     ) -> Result<Option<Offset>, FlvSocketError> {
+        if !self.processor.is_empty() {
+            return self.send_back_decoded_records(offset).await;
+        }
+
         let mut partition_response = FilePartitionResponse::default();
         partition_response.partition_index = self.replica.partition;
 
@@ -194,9 +431,22 @@ where
                 hw,
                 leo,
             );
+
+            let flow_control = self.acks.is_some();
+            if flow_control && partition_response.records.len() == 0 {
+                trace!(
+                    "conn: {}, no records from offset: {}, not sending",
+                    self.kf_sink.id(),
+                    offset
+                );
+                return Ok(Some(offset));
+            }
+
+            let records_len = partition_response.records.len();
             let response = StreamFetchResponse {
                 topic: self.replica.topic.clone(),
                 partition: partition_response,
+                ..Default::default()
             };
 
             let response = RequestMessage::<FileStreamFetchRequest>::response_with_header(
@@ -216,6 +466,15 @@ where
 
             trace!("conn: {}, finish sending fetch response", self.kf_sink.id());
 
+            let metrics = self.ctx.metrics();
+            metrics.fetch_requests.inc();
+            metrics.fetch_bytes.inc_by(records_len as u64);
+
+            if flow_control {
+                self.in_flight = true;
+                return Ok(Some(offset));
+            }
+
             // get next offset
             let next_offset = match self.isolation {
                 Isolation::ReadCommitted => hw,
@@ -234,4 +493,599 @@ where
             Ok(None)
         }
     }
+
+    /// read batches from offset and send back records after they are transformed and filtered.
+    /// batches without remaining records are skipped until records are sent or end is reached.
+    /// return offset after last read batch, which is also sent to client to continue from.
+    /// if transform fails, error is sent back and stream is not continued
+    async fn send_back_decoded_records(
+        &mut self,
+        offset: Offset,
+    ) -> Result<Option<Offset>, FlvSocketError> {
+        let flow_control = self.acks.is_some();
+        let mut offset = offset;
+        loop {
+            let (batches, hw, leo) = if let Some(read) = self
+                .ctx
+                .leaders_state()
+                .read_batches(
+                    &self.replica,
+                    offset,
+                    self.max_bytes,
+                    self.isolation.clone(),
+                )
+                .await
+            {
+                read
+            } else {
+                debug!(
+                    "conn: {} unable to retrieve records from replica: {}, from: {}",
+                    self.kf_sink.id(),
+                    self.replica,
+                    offset
+                );
+                return Ok(None);
+            };
+
+            let end_offset = match self.isolation {
+                Isolation::ReadCommitted => hw,
+                Isolation::ReadUncommitted => leo,
+            };
+
+            let mut partition_response = FetchablePartitionResponse::<RecordSet> {
+                partition_index: self.replica.partition,
+                high_watermark: hw,
+                last_stable_offset: hw,
+                ..Default::default()
+            };
+
+            let next_offset = match batches {
+                Ok(batches) => {
+                    let next_offset = batches
+                        .last()
+                        .map(|batch| batch.get_last_offset() + 1)
+                        .unwrap_or(offset);
+                    let processor = self.processor.clone();
+                    match spawn_blocking(move || processor.process_batches(batches, offset)).await {
+                        Ok(batches) => partition_response.records.batches = batches,
+                        Err(err) => {
+                            warn!(
+                                "conn: {}, replica: {} from: {}, {}",
+                                self.kf_sink.id(),
+                                self.replica,
+                                offset,
+                                err
+                            );
+                            self.send_error(err.error_code()).await?;
+                            return Ok(None);
+                        }
+                    }
+                    debug!(
+                        "conn: {}, replica: {} processed from: {} to: {}, batches: {}",
+                        self.kf_sink.id(),
+                        self.replica,
+                        offset,
+                        next_offset,
+                        partition_response.records.batches.len()
+                    );
+
+                    if partition_response.records.batches.is_empty() {
+                        if next_offset < end_offset && next_offset > offset {
+                            offset = next_offset;
+                            continue;
+                        }
+                        return Ok(Some(next_offset));
+                    }
+                    next_offset
+                }
+                Err(err) => {
+                    debug!(
+                        "conn: {}, error reading replica: {} from: {}, {}",
+                        self.kf_sink.id(),
+                        self.replica,
+                        offset,
+                        err
+                    );
+                    partition_response.error_code = match err {
+                        StorageError::OffsetError(_) => ErrorCode::OffsetOutOfRange,
+                        _ => ErrorCode::StorageError,
+                    };
+                    offset
+                }
+            };
+
+            let records_len = partition_response
+                .records
+                .write_size(self.header.api_version());
+            let response = StreamFetchResponse {
+                topic: self.replica.topic.clone(),
+                partition: partition_response,
+                next_offset,
+            };
+            let response = RequestMessage::<DefaultStreamFetchRequest>::response_with_header(
+                &self.header,
+                response,
+            );
+            trace!(
+                "conn: {}, sending back decoded fetch response: {:#?}",
+                self.kf_sink.id(),
+                response
+            );
+
+            let mut inner_sink = self.kf_sink.lock().await;
+            inner_sink
+                .send_response(&response, self.header.api_version())
+                .await?;
+            drop(inner_sink);
+
+            let metrics = self.ctx.metrics();
+            metrics.fetch_requests.inc();
+            metrics.fetch_bytes.inc_by(records_len as u64);
+
+            if flow_control || next_offset >= end_offset || next_offset == offset {
+                self.in_flight = flow_control;
+                return Ok(Some(next_offset));
+            }
+            offset = next_offset;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::env::temp_dir;
+    use std::fs;
+    use std::time::Duration;
+
+    use async_channel::bounded;
+    use futures_util::stream::Stream;
+    use futures_util::stream::StreamExt;
+    use tokio::select;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_future::test_async;
+    use fluvio_future::timer::sleep;
+    use fluvio_socket::FlvSocket;
+    use fluvio_socket::FlvSocketError;
+    use fluvio_socket::MultiplexerSocket;
+    use fluvio_controlplane_metadata::partition::Replica;
+    use fluvio_spu_schema::server::stream_fetch::DefaultStreamFetchRequest;
+    use fluvio_spu_schema::server::stream_fetch::DefaultStreamFetchResponse;
+    use fluvio_spu_schema::server::stream_fetch::StreamFetchAckRequest;
+    use fluvio_spu_schema::server::stream_fetch::RecordFilter;
+    use dataplane::api::RequestMessage;
+    use dataplane::ErrorCode;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::fetch::{DefaultFetchRequest, FetchableTopic, FetchPartition};
+    use dataplane::record::DefaultRecord;
+    use dataplane::record::RecordSet;
+    use dataplane::{Isolation, ReplicaKey};
+
+    use crate::config::SpuConfig;
+    use crate::core::GlobalContext;
+    use crate::core::DefaultSharedGlobalContext;
+    use crate::core::OffsetUpdateEvent;
+    use crate::controllers::leader_replica::LeaderReplicaState;
+    use crate::services::create_public_server;
+
+    const TOPIC: &str = "test";
+
+    fn test_config(name: &str, port: u16) -> SpuConfig {
+        let mut config = SpuConfig::default();
+        config.log.base_dir = temp_dir().join(name);
+        config.public_endpoint = format!("127.0.0.1:{}", port);
+        config.transform.module_dir = Some(config.log.base_dir.join("transform"));
+        config
+    }
+
+    async fn create_context(name: &str, port: u16) -> DefaultSharedGlobalContext {
+        start_context(test_config(name, port)).await
+    }
+
+    async fn start_context(config: SpuConfig) -> DefaultSharedGlobalContext {
+        ensure_clean_dir(&config.log.base_dir);
+
+        let ctx = GlobalContext::new_shared_context(config);
+        let replica_key = ReplicaKey::new(TOPIC, 0);
+        let leader = LeaderReplicaState::create_file_replica(
+            Replica::new(replica_key.clone(), ctx.local_spu_id(), vec![]),
+            &ctx.config().storage().new_config(),
+        )
+        .await
+        .expect("replica");
+        ctx.leaders_state()
+            .insert_replica(replica_key, leader, bounded(10).0)
+            .await;
+
+        let _shutdown =
+            create_public_server(ctx.config().public_socket_addr().to_owned(), ctx.clone()).run();
+        // wait for server to start
+        sleep(Duration::from_millis(100)).await;
+        ctx
+    }
+
+    /// write batch of single record without notifying streams, return end offset
+    async fn write_record(ctx: &DefaultSharedGlobalContext) -> i64 {
+        write_value(ctx, "hello").await
+    }
+
+    async fn write_value(ctx: &DefaultSharedGlobalContext, value: &str) -> i64 {
+        let mut batch = DefaultBatch::default();
+        batch.add_record(DefaultRecord::from(value.to_owned()));
+        let mut leader = ctx
+            .leaders_state()
+            .get_mut_replica(&ReplicaKey::new(TOPIC, 0))
+            .expect("leader");
+        leader
+            .send_records(RecordSet::default().add(batch), true)
+            .await
+            .expect("write");
+        leader.leo()
+    }
+
+    fn notify(ctx: &DefaultSharedGlobalContext, replica_id: ReplicaKey, leo: i64) {
+        let _ = ctx.offset_channel().sender().send(OffsetUpdateEvent {
+            replica_id,
+            leo,
+            hw: leo,
+        });
+    }
+
+    /// next response, or None if none arrives within timeout
+    async fn next_response<S>(stream: &mut S) -> Option<DefaultStreamFetchResponse>
+    where
+        S: Stream<Item = Result<DefaultStreamFetchResponse, FlvSocketError>> + Unpin,
+    {
+        select! {
+            _ = sleep(Duration::from_millis(300)) => None,
+            response = stream.next() => Some(response.expect("stream").expect("response"))
+        }
+    }
+
+    fn base_offsets(response: &DefaultStreamFetchResponse) -> Vec<i64> {
+        response
+            .partition
+            .records
+            .batches
+            .iter()
+            .map(|batch| batch.get_base_offset())
+            .collect()
+    }
+
+    #[test_async]
+    async fn test_stream_fetch_slow_consumer() -> Result<(), ()> {
+        fluvio_future::subscriber::init_logger();
+        let ctx = create_context("stream-fetch-slow-consumer", 9210).await;
+        write_record(&ctx).await;
+
+        let socket = MultiplexerSocket::new(
+            FlvSocket::connect(ctx.config().public_socket_addr())
+                .await
+                .expect("connect"),
+        );
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            max_bytes: 10000,
+            isolation: Isolation::ReadUncommitted,
+            stream_id: 1,
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+
+        let response = next_response(&mut stream).await.expect("first response");
+        assert_eq!(base_offsets(&response), vec![0]);
+
+        // client has not acknowledged, so new records must not be sent
+        let leo = write_record(&ctx).await;
+        notify(&ctx, ReplicaKey::new(TOPIC, 0), leo);
+        assert!(next_response(&mut stream).await.is_none());
+
+        // once acknowledged, streaming continues from acknowledged offset
+        let mut serial = socket.create_serial_socket().await;
+        serial
+            .send_and_receive(RequestMessage::new_request(StreamFetchAckRequest {
+                stream_id: 1,
+                offset: 1,
+            }))
+            .await
+            .expect("ack");
+        let response = next_response(&mut stream).await.expect("second response");
+        assert_eq!(base_offsets(&response), vec![1]);
+
+        // nothing more to read after all records are acknowledged
+        serial
+            .send_and_receive(RequestMessage::new_request(StreamFetchAckRequest {
+                stream_id: 1,
+                offset: 2,
+            }))
+            .await
+            .expect("ack");
+        assert!(next_response(&mut stream).await.is_none());
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_stream_fetch_lag_resync() -> Result<(), ()> {
+        fluvio_future::subscriber::init_logger();
+        let ctx = create_context("stream-fetch-lag-resync", 9211).await;
+
+        let socket = MultiplexerSocket::new(
+            FlvSocket::connect(ctx.config().public_socket_addr())
+                .await
+                .expect("connect"),
+        );
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            max_bytes: 10000,
+            isolation: Isolation::ReadUncommitted,
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+
+        let response = next_response(&mut stream).await.expect("first response");
+        assert!(base_offsets(&response).is_empty());
+
+        // update for replica is never seen since handler lags behind flood of other events
+        write_record(&ctx).await;
+        for leo in 0..500 {
+            notify(&ctx, ReplicaKey::new("other", 0), leo);
+        }
+
+        let response = next_response(&mut stream)
+            .await
+            .expect("re-synced response");
+        assert_eq!(base_offsets(&response), vec![0]);
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_stream_fetch_filter() -> Result<(), ()> {
+        fluvio_future::subscriber::init_logger();
+        let ctx = create_context("stream-fetch-filter", 9212).await;
+        for value in &["a1", "b1", "a2"] {
+            write_value(&ctx, value).await;
+        }
+
+        let socket = MultiplexerSocket::new(
+            FlvSocket::connect(ctx.config().public_socket_addr())
+                .await
+                .expect("connect"),
+        );
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            max_bytes: 10000,
+            isolation: Isolation::ReadUncommitted,
+            filter: Some(RecordFilter::default().with_value_regex("^a")),
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+
+        let response = next_response(&mut stream).await.expect("first response");
+        assert_eq!(base_offsets(&response), vec![0, 2]);
+        assert_eq!(response.next_offset, 3);
+
+        // records not matching are skipped without response
+        let leo = write_value(&ctx, "b2").await;
+        notify(&ctx, ReplicaKey::new(TOPIC, 0), leo);
+        assert!(next_response(&mut stream).await.is_none());
+
+        let leo = write_value(&ctx, "a3").await;
+        notify(&ctx, ReplicaKey::new(TOPIC, 0), leo);
+        let response = next_response(&mut stream).await.expect("second response");
+        assert_eq!(base_offsets(&response), vec![4]);
+        assert_eq!(response.next_offset, 5);
+
+        // invalid regex is rejected
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            max_bytes: 10000,
+            filter: Some(RecordFilter::default().with_value_regex("(")),
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+        let response = next_response(&mut stream).await.expect("error response");
+        assert_eq!(response.partition.error_code, ErrorCode::InvalidRequest);
+
+        Ok(())
+    }
+
+    /// value without first byte, records with empty value are dropped
+    const SKIP_FIRST_BYTE: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (global $next (mut i32) (i32.const 1024))
+            (func (export "alloc") (param $len i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $next))
+                (global.set $next (i32.add (global.get $next) (local.get $len)))
+                (local.get $ptr))
+            (func (export "transform")
+                (param $key_ptr i32) (param $key_len i32)
+                (param $value_ptr i32) (param $value_len i32)
+                (result i64)
+                (if (i32.eqz (local.get $value_len))
+                    (then (return (i64.const -1))))
+                (i64.or
+                    (i64.shl
+                        (i64.extend_i32_u (i32.add (local.get $value_ptr) (i32.const 1)))
+                        (i64.const 32))
+                    (i64.extend_i32_u (i32.sub (local.get $value_len) (i32.const 1))))))
+    "#;
+
+    #[test_async]
+    async fn test_stream_fetch_transform() -> Result<(), ()> {
+        fluvio_future::subscriber::init_logger();
+        let mut config = test_config("stream-fetch-transform", 9213);
+        config.transform.allow_client_modules = true;
+        let ctx = start_context(config).await;
+        for value in &["a1", "", "a2"] {
+            write_value(&ctx, value).await;
+        }
+
+        let socket = MultiplexerSocket::new(
+            FlvSocket::connect(ctx.config().public_socket_addr())
+                .await
+                .expect("connect"),
+        );
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            max_bytes: 10000,
+            isolation: Isolation::ReadUncommitted,
+            wasm_module: Some(SKIP_FIRST_BYTE.as_bytes().to_vec()),
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+
+        let response = next_response(&mut stream).await.expect("first response");
+        assert_eq!(base_offsets(&response), vec![0, 2]);
+        let values: Vec<String> = response
+            .partition
+            .records
+            .batches
+            .iter()
+            .flat_map(|batch| batch.records().iter())
+            .map(|record| record.value.to_string())
+            .collect();
+        assert_eq!(values, vec!["1", "2"]);
+        assert_eq!(response.next_offset, 3);
+
+        // module without transform function is rejected
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            max_bytes: 10000,
+            wasm_module: Some(b"(module)".to_vec()),
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+        let response = next_response(&mut stream).await.expect("error response");
+        assert_eq!(
+            response.partition.error_code,
+            ErrorCode::TransformInvalidModule
+        );
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_stream_fetch_topic_transform() -> Result<(), ()> {
+        fluvio_future::subscriber::init_logger();
+        let ctx = create_context("stream-fetch-topic-transform", 9235).await;
+        let module_dir = ctx.config().transform.module_dir.clone().expect("dir");
+        fs::create_dir_all(&module_dir).expect("create dir");
+        fs::write(module_dir.join("skip-first-byte.wasm"), SKIP_FIRST_BYTE).expect("module");
+
+        let mut replica = Replica::new(ReplicaKey::new(TOPIC, 0), ctx.local_spu_id(), vec![]);
+        replica.transform = Some("skip-first-byte".to_owned());
+        ctx.replica_localstore().insert(replica);
+        for value in &["a1", "", "a2"] {
+            write_value(&ctx, value).await;
+        }
+
+        let socket = MultiplexerSocket::new(
+            FlvSocket::connect(ctx.config().public_socket_addr())
+                .await
+                .expect("connect"),
+        );
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            max_bytes: 10000,
+            isolation: Isolation::ReadUncommitted,
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+
+        let response = next_response(&mut stream).await.expect("first response");
+        assert_eq!(base_offsets(&response), vec![0, 2]);
+        let values: Vec<String> = response
+            .partition
+            .records
+            .batches
+            .iter()
+            .flat_map(|batch| batch.records().iter())
+            .map(|record| record.value.to_string())
+            .collect();
+        assert_eq!(values, vec!["1", "2"]);
+
+        // client modules are not allowed by default
+        let request = DefaultStreamFetchRequest {
+            topic: TOPIC.to_owned(),
+            partition: 0,
+            max_bytes: 10000,
+            wasm_module: Some(SKIP_FIRST_BYTE.as_bytes().to_vec()),
+            ..Default::default()
+        };
+        let mut stream = socket
+            .create_stream(RequestMessage::new_request(request), 10)
+            .await
+            .expect("stream");
+        let response = next_response(&mut stream).await.expect("error response");
+        assert_eq!(
+            response.partition.error_code,
+            ErrorCode::TransformClientModuleDisabled
+        );
+
+        // records of topic with transform are not sent as stored
+        let request = DefaultFetchRequest {
+            max_bytes: 10000,
+            topics: vec![FetchableTopic {
+                name: TOPIC.to_owned(),
+                fetch_partitions: vec![FetchPartition {
+                    partition_index: 0,
+                    fetch_offset: 0,
+                    ..Default::default()
+                }],
+            }],
+            ..Default::default()
+        };
+        let response = socket
+            .create_serial_socket()
+            .await
+            .send_and_receive(RequestMessage::new_request(request))
+            .await
+            .expect("fetch");
+        let partition = &response.topics[0].partitions[0];
+        assert_eq!(
+            partition.error_code,
+            ErrorCode::TransformRequiresStreamFetch
+        );
+        assert!(partition.records.batches.is_empty());
+
+        Ok(())
+    }
 }
@@ -1,13 +1,16 @@
 use fluvio_storage::FileReplica;
+use fluvio_metrics::start_metrics_server;
 
 use crate::config::{SpuConfig, SpuOpt};
 use crate::services::create_internal_server;
 use crate::services::create_public_server;
+use crate::services::create_kafka_server;
 use crate::services::internal::InternalApiServer;
 use crate::services::public::PublicApiServer;
 use crate::core::DefaultSharedGlobalContext;
 use crate::core::GlobalContext;
 use crate::controllers::sc::ScDispatcher;
+use crate::controllers::retention::RetentionCleaner;
 
 type FileReplicaContext = GlobalContext<FileReplica>;
 
@@ -24,11 +27,18 @@ pub fn main_loop(opt: SpuOpt) {
     println!("starting spu server (id:{})", spu_config.id);
 
     run_block_on(async move {
-        let (_ctx, internal_server, public_server) =
-            create_services(spu_config.clone(), true, true);
+        let (ctx, internal_server, public_server) = create_services(spu_config.clone(), true, true);
 
         let _public_shutdown = internal_server.unwrap().run();
         let _private_shutdown = public_server.unwrap().run();
+        let _kafka_shutdown = spu_config
+            .kafka_endpoint
+            .as_ref()
+            .map(|kafka_addr| create_kafka_server(kafka_addr.to_owned(), ctx.clone()).run());
+
+        if let Some(metrics_addr) = &spu_config.metrics_endpoint {
+            start_metrics_server(metrics_addr.to_owned(), ctx.metrics().registry());
+        }
 
         if let Some(tls_config) = tls_acceptor_option {
             proxy::start_proxy(spu_config, tls_config).await;
@@ -73,6 +83,8 @@ pub fn create_services(
     let sc_dispatcher = ScDispatcher::new(ctx.clone(), ctx.config().peer_max_bytes);
     sc_dispatcher.run();
 
+    RetentionCleaner::new(ctx.clone()).run();
+
     (ctx, internal_server, public_server)
 }
 
@@ -84,15 +96,24 @@ mod proxy {
 
     use flv_util::print_cli_err;
     use fluvio_future::rust_tls::TlsAcceptor;
+    use fluvio_auth::x509::X509Authenticator;
     use crate::config::SpuConfig;
     use flv_tls_proxy::start as proxy_start;
+    use flv_tls_proxy::start_with_authenticator as proxy_start_with_authenticator;
 
     pub async fn start_proxy(config: SpuConfig, acceptor: (TlsAcceptor, String)) {
         let (tls_acceptor, proxy_addr) = acceptor;
         let target = config.public_endpoint;
         info!("starting TLS proxy: {}", proxy_addr);
 
-        if let Err(err) = proxy_start(&proxy_addr, tls_acceptor, target).await {
+        let result = if let Some(x509_auth_scopes) = config.x509_auth_scopes {
+            let authenticator = Box::new(X509Authenticator::new(&x509_auth_scopes));
+            proxy_start_with_authenticator(&proxy_addr, tls_acceptor, target, authenticator).await
+        } else {
+            proxy_start(&proxy_addr, tls_acceptor, target).await
+        };
+
+        if let Err(err) = result {
             print_cli_err!(err);
             process::exit(-1);
         } else {
@@ -232,7 +232,7 @@ where
         let msg_string: String = msg.into();
         let record: DefaultRecord = msg_string.into();
         let mut batch = DefaultBatch::default();
-        batch.records.push(record);
+        batch.records_mut().push(record);
 
         let mut topic_request = DefaultKfTopicRequest::default();
         topic_request.name = topic.into();
@@ -59,8 +59,8 @@ async fn test_fetch(runner: Arc<SpuTestRunner<SimpleFetchTest>>) -> Result<(), F
     let batches = &partition_response.records.batches;
     assert_eq!(batches.len(), 1);
     let batch = &batches[0];
-    assert_eq!(batch.records.len(), 1);
-    let record = &batch.records[0];
+    assert_eq!(batch.records().len(), 1);
+    let record = &batch.records()[0];
     let test_record: DefaultRecord = "message".to_owned().into();
     assert_eq!(
         record.value.inner_value_ref(),
@@ -46,6 +46,10 @@ where
         &self.inner
     }
 
+    pub fn into_batch(self) -> Batch<R> {
+        self.inner
+    }
+
     pub fn get_pos(&self) -> Size {
         self.pos
     }
@@ -69,7 +73,7 @@ where
     }
 
     pub fn records_remainder_bytes(&self, remainder: usize) -> usize {
-        self.inner.records.remainder_bytes(remainder)
+        self.inner.records().remainder_bytes(remainder)
     }
 
     /// decode next batch from file
@@ -149,7 +153,7 @@ where
         }
 
         let mut cursor = Cursor::new(bytes);
-        self.inner.records.decode(&mut cursor, 0)?;
+        self.inner.decode_records(&mut cursor, 0)?;
 
         Ok(())
     }
@@ -1,23 +1,36 @@
 use std::path::PathBuf;
+use std::process;
 use std::io::Error as IoError;
+use std::io::ErrorKind;
 
 use structopt::StructOpt;
 
 use fluvio_future::task::run_block_on;
-use fluvio_future::fs::util as fs_util;
 
-use fluvio_storage::DefaultFileBatchStream;
+use fluvio_storage::ConfigOption;
 use fluvio_storage::LogIndex;
 use fluvio_storage::StorageError;
 use fluvio_storage::OffsetPosition;
+use fluvio_storage::{inspect_log, check_replica_dir, rebuild_index, truncate_log};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "storage", about = "Flavio Storage CLI")]
 enum Main {
+    /// dump batches of log file
     #[structopt(name = "log")]
     Log(LogOpt),
+    /// dump entries of index file
     #[structopt(name = "index")]
     Index(IndexOpt),
+    /// check all segments and indexes of replica directory
+    #[structopt(name = "check")]
+    Check(CheckOpt),
+    /// replace index of segment with one built from its log
+    #[structopt(name = "rebuild-index")]
+    RebuildIndex(RebuildIndexOpt),
+    /// cut log back to its last valid batch and rebuild its index
+    #[structopt(name = "truncate")]
+    Truncate(TruncateOpt),
 }
 
 fn main() {
@@ -26,12 +39,16 @@ fn main() {
     let opt = Main::from_args();
 
     let res = match opt {
-        Main::Log(opt) => dump_log(opt),
-        Main::Index(opt) => dump_index(opt),
+        Main::Log(opt) => dump_log(opt).map_err(StorageError::from),
+        Main::Index(opt) => dump_index(opt).map_err(StorageError::from),
+        Main::Check(opt) => run_block_on(check(opt)),
+        Main::RebuildIndex(opt) => run_block_on(rebuild(opt)),
+        Main::Truncate(opt) => run_block_on(truncate(opt)),
     };
 
     if let Err(err) = res {
-        println!("error occured: {:#?}", err)
+        eprintln!("error occured: {}", err);
+        process::exit(1);
     }
 }
 
@@ -39,43 +56,66 @@ fn main() {
 pub(crate) struct LogOpt {
     #[structopt(parse(from_os_str))]
     file_name: PathBuf,
-}
 
-async fn print_logs(path: PathBuf) -> Result<(), IoError> {
-    let file = fs_util::open(path).await?;
+    /// print records of each batch
+    #[structopt(short, long)]
+    records: bool,
+
+    /// print as json
+    #[structopt(long)]
+    json: bool,
+}
 
-    let mut _batch_stream = DefaultFileBatchStream::new(file);
+async fn print_logs(opt: LogOpt) -> Result<(), StorageError> {
+    let inspection = inspect_log(&opt.file_name).await?;
 
-    //  println!("base offset: {}",batch_stream.get_base_offset());
-    /*
-    TODO: comment out
-    while let Some(file_batch) = batch_stream.next().await {
-        // let batch_base_offset = batch.get_base_offset();
-        let batch = file_batch.get_batch();
-        //let header = batch.get_header();
-        // let offset_delta = header.last_offset_delta;
+    if opt.json {
+        let json = serde_json::to_string_pretty(&inspection).map_err(IoError::from)?;
+        println!("{}", json);
+        return Ok(());
+    }
 
+    println!(
+        "base offset: {}, len: {}",
+        inspection.base_offset, inspection.file_len
+    );
+    for batch in &inspection.batches {
         println!(
-            "batch offset: {}, len: {}, pos: {}",
-            batch.get_base_offset(),
-            file_batch.len(),
-            file_batch.get_pos()
+            "batch offset: {}..{}, pos: {}, len: {}, first ts: {}, max ts: {}, producer: {}, crc: {}",
+            batch.base_offset,
+            batch.last_offset,
+            batch.pos,
+            batch.len,
+            batch.first_timestamp,
+            batch.max_timestamp,
+            batch.producer_id,
+            if batch.crc_valid { "ok" } else { "mismatch" }
         );
 
-        for record in &batch.records {
-            println!("record offset: {}", record.get_offset_delta());
+        if opt.records {
+            for record in &batch.records {
+                println!(
+                    "  record offset: {}, ts: {}, key: {}, value: {} bytes",
+                    record.offset,
+                    record.timestamp,
+                    record.key.as_deref().unwrap_or("none"),
+                    record.value_len
+                );
+            }
         }
     }
-    */
+
+    println!("{} batches", inspection.batches.len());
+    if let Some(err) = &inspection.error {
+        println!("invalid from pos: {}, {}", inspection.valid_len, err);
+    }
 
     Ok(())
 }
 
 pub(crate) fn dump_log(opt: LogOpt) -> Result<(), IoError> {
-    let file_path = opt.file_name;
-
-    println!("dumping batch: {:#?}", file_path);
-    let ft = print_logs(file_path);
+    println!("dumping batch: {:#?}", opt.file_name);
+    let ft = print_logs(opt);
     let result = run_block_on(ft);
     if let Err(err) = result {
         println!("error in async: {:#?}", err)
@@ -128,3 +168,107 @@ async fn print_index(path: PathBuf) -> Result<(), StorageError> {
 
     Ok(())
 }
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct CheckOpt {
+    /// replica directory such as `<base dir>/<topic>-<partition>`
+    #[structopt(parse(from_os_str))]
+    dir: PathBuf,
+}
+
+async fn check(opt: CheckOpt) -> Result<(), StorageError> {
+    let checks = check_replica_dir(&opt.dir).await?;
+
+    let mut failed = 0;
+    for check in &checks {
+        match check.end_offset {
+            Some(end_offset) => println!(
+                "segment: {}, offsets: {}..{}",
+                check.log_path.display(),
+                check.base_offset,
+                end_offset
+            ),
+            None => println!("segment: {}", check.log_path.display()),
+        }
+        for issue in &check.issues {
+            println!("  {}", issue);
+        }
+        if !check.is_ok() {
+            failed += 1;
+        }
+    }
+
+    println!("{} segments, {} with issues", checks.len(), failed);
+    if failed > 0 {
+        return Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("{} segments with issues in: {:#?}", failed, opt.dir),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct IndexConfigOpt {
+    /// max bytes of index file
+    #[structopt(long)]
+    index_max_bytes: Option<u32>,
+
+    /// bytes of log between index entries
+    #[structopt(long)]
+    index_max_interval_bytes: Option<u32>,
+}
+
+impl IndexConfigOpt {
+    fn as_config(&self) -> ConfigOption {
+        let mut option = ConfigOption::default();
+        if let Some(index_max_bytes) = self.index_max_bytes {
+            option.index_max_bytes = index_max_bytes;
+        }
+        if let Some(interval) = self.index_max_interval_bytes {
+            option.index_max_interval_bytes = interval;
+        }
+        option
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct RebuildIndexOpt {
+    /// log file of segment
+    #[structopt(parse(from_os_str))]
+    file_name: PathBuf,
+
+    #[structopt(flatten)]
+    config: IndexConfigOpt,
+}
+
+async fn rebuild(opt: RebuildIndexOpt) -> Result<(), StorageError> {
+    rebuild_index(&opt.file_name, &opt.config.as_config()).await?;
+    println!("rebuilt index of: {:#?}", opt.file_name);
+    Ok(())
+}
+
+#[derive(Debug, StructOpt)]
+pub(crate) struct TruncateOpt {
+    /// log file of segment
+    #[structopt(parse(from_os_str))]
+    file_name: PathBuf,
+
+    #[structopt(flatten)]
+    config: IndexConfigOpt,
+}
+
+async fn truncate(opt: TruncateOpt) -> Result<(), StorageError> {
+    let removed = truncate_log(&opt.file_name).await?;
+
+    if removed == 0 {
+        println!("log is valid, nothing to truncate");
+        return Ok(());
+    }
+
+    println!("removed {} bytes from: {:#?}", removed, opt.file_name);
+    rebuild_index(&opt.file_name, &opt.config.as_config()).await?;
+    println!("rebuilt index");
+    Ok(())
+}
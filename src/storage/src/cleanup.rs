@@ -0,0 +1,224 @@
+//!
+//! # Replica cleanup
+//!
+//! Removed replicas are either deleted right away or moved to trash directory
+//! in base directory and deleted once grace period is over.
+//!
+use std::collections::HashSet;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use tracing::debug;
+use tracing::warn;
+
+use fluvio_future::fs::create_dir_all;
+use fluvio_future::fs::remove_dir_all;
+use fluvio_future::fs::rename;
+use dataplane::Size;
+
+/// directory in base directory where removed replicas are kept
+pub const TRASH_DIR: &str = ".trash";
+
+/// topic and partition of replica directory, none if directory is not replica
+pub fn parse_replica_dir_name(name: &str) -> Option<(String, Size)> {
+    let separator = name.rfind('-')?;
+    let topic = &name[..separator];
+    if topic.is_empty() {
+        return None;
+    }
+    let partition = name[separator + 1..].parse::<Size>().ok()?;
+    Some((topic.to_owned(), partition))
+}
+
+/// delete replica directory with segments, indexes and checkpoints
+pub async fn remove_replica_dir(replica_dir: &Path) -> Result<(), IoError> {
+    let name = dir_name(replica_dir)?;
+    if parse_replica_dir_name(&name).is_none() {
+        return Err(IoError::new(
+            ErrorKind::InvalidInput,
+            format!("not replica dir: {}", replica_dir.display()),
+        ));
+    }
+
+    debug!("removing replica dir: {}", replica_dir.display());
+    remove_dir_all(replica_dir).await
+}
+
+/// move replica directory to trash, name is suffixed with time of removal in milliseconds
+pub async fn move_to_trash(replica_dir: &Path) -> Result<PathBuf, IoError> {
+    let name = dir_name(replica_dir)?;
+    if parse_replica_dir_name(&name).is_none() {
+        return Err(IoError::new(
+            ErrorKind::InvalidInput,
+            format!("not replica dir: {}", replica_dir.display()),
+        ));
+    }
+
+    let trash_dir = match replica_dir.parent() {
+        Some(base_dir) => base_dir.join(TRASH_DIR),
+        None => {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!("no base dir: {}", replica_dir.display()),
+            ))
+        }
+    };
+    create_dir_all(&trash_dir).await?;
+
+    let trash_path = trash_dir.join(format!("{}.{}", name, now_millis()));
+    debug!(
+        "moving replica dir: {} to trash: {}",
+        replica_dir.display(),
+        trash_path.display()
+    );
+    rename(replica_dir, &trash_path).await?;
+    Ok(trash_path)
+}
+
+/// delete replicas in trash which were removed before grace period
+pub async fn purge_trash(base_dir: &Path, grace_period: Duration) -> Result<Vec<PathBuf>, IoError> {
+    let trash_dir = base_dir.join(TRASH_DIR);
+    if !trash_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let expired_before = now_millis().saturating_sub(grace_period.as_millis());
+    let mut purged = vec![];
+    for entry in trash_dir.read_dir()? {
+        let path = entry?.path();
+        let removed_at = dir_name(&path)
+            .ok()
+            .and_then(|name| name.rsplit('.').next().map(|suffix| suffix.parse::<u128>()))
+            .and_then(|removed_at| removed_at.ok());
+        match removed_at {
+            Some(removed_at) if removed_at <= expired_before => {
+                debug!("purging replica from trash: {}", path.display());
+                remove_dir_all(&path).await?;
+                purged.push(path);
+            }
+            Some(_) => {}
+            None => warn!("unknown entry in trash: {}", path.display()),
+        }
+    }
+
+    Ok(purged)
+}
+
+/// replica directories in base directory which don't belong to any of replicas
+pub fn find_orphan_replicas(
+    base_dir: &Path,
+    replicas: &HashSet<(String, Size)>,
+) -> Result<Vec<PathBuf>, IoError> {
+    if !base_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut orphans = vec![];
+    for entry in base_dir.read_dir()? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        let name = dir_name(&path)?;
+        if let Some(replica) = parse_replica_dir_name(&name) {
+            if !replicas.contains(&replica) {
+                orphans.push(path);
+            }
+        }
+    }
+    orphans.sort();
+
+    Ok(orphans)
+}
+
+fn dir_name(dir: &Path) -> Result<String, IoError> {
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidInput,
+                format!("invalid dir: {}", dir.display()),
+            )
+        })
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_millis())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashSet;
+    use std::env::temp_dir;
+    use std::fs;
+    use std::time::Duration;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_clean_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_replica_dir_name() {
+        assert_eq!(
+            parse_replica_dir_name("test-topic-2"),
+            Some(("test-topic".to_owned(), 2))
+        );
+        assert_eq!(parse_replica_dir_name("topic-x"), None);
+        assert_eq!(parse_replica_dir_name("-1"), None);
+        assert_eq!(parse_replica_dir_name(TRASH_DIR), None);
+    }
+
+    #[test_async]
+    async fn test_replica_cleanup() -> Result<(), IoError> {
+        let base_dir = temp_dir().join("test-replica-cleanup");
+        ensure_clean_dir(&base_dir);
+        for dir in &["topic1-0", "topic1-1", "topic2-0", "other"] {
+            fs::create_dir_all(base_dir.join(dir))?;
+        }
+        fs::write(base_dir.join("topic1-0").join("replication.chk"), b"0")?;
+
+        let replicas: HashSet<(String, Size)> =
+            vec![("topic1".to_owned(), 0)].into_iter().collect();
+        let orphans = find_orphan_replicas(&base_dir, &replicas)?;
+        assert_eq!(
+            orphans,
+            vec![base_dir.join("topic1-1"), base_dir.join("topic2-0")]
+        );
+
+        remove_replica_dir(&orphans[0]).await?;
+        assert!(!orphans[0].exists());
+        assert!(remove_replica_dir(&base_dir.join("other")).await.is_err());
+
+        let trashed = move_to_trash(&orphans[1]).await?;
+        assert!(!orphans[1].exists());
+        assert!(trashed.exists());
+
+        // trash is kept during grace period
+        assert!(purge_trash(&base_dir, Duration::from_secs(60))
+            .await?
+            .is_empty());
+        assert_eq!(
+            purge_trash(&base_dir, Duration::from_secs(0)).await?,
+            vec![trashed.clone()]
+        );
+        assert!(!trashed.exists());
+
+        assert_eq!(
+            find_orphan_replicas(&base_dir, &replicas)?,
+            Vec::<PathBuf>::new()
+        );
+
+        Ok(())
+    }
+}
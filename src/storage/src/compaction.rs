@@ -0,0 +1,253 @@
+//!
+//! # Key based compaction
+//!
+//! Read only segments are rewritten so only the latest record for each key is kept.
+//! Records keep their offsets, so compacted segments can have gaps between offsets.
+//! Records without key are never removed.
+//! Only committed records, below high watermark, are compacted or supersede other records,
+//! since uncommitted records can still be truncated.
+//!
+//! Compaction is prepared from replica, segments are rewritten without access to replica
+//! and rewritten segments replace originals once compaction is applied.
+//!
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_future::fs::create_dir_all;
+use fluvio_future::fs::remove_file;
+use fluvio_future::fs::rename;
+use fluvio_future::fs::util as file_util;
+use dataplane::{Offset, Size};
+
+use crate::index::EXTENSION as INDEX_EXTENSION;
+use crate::mut_records::MESSAGE_LOG_EXTENSION;
+use crate::segment::MutableSegment;
+use crate::segment::ReadSegment;
+use crate::time_index::EXTENSION as TIME_INDEX_EXTENSION;
+use crate::util::generate_file_name;
+use crate::ConfigOption;
+use crate::DefaultFileBatchStream;
+use crate::StorageError;
+
+/// directory under replica where segments are rewritten before replacing originals
+const COMPACTION_DIR: &str = "compaction";
+
+/// latest offset of each key
+pub(crate) type KeyOffsets = HashMap<Vec<u8>, Offset>;
+
+/// compaction prepared from replica, segments are rewritten by `run` without access to replica
+#[derive(Debug)]
+pub struct Compaction {
+    pub(crate) option: ConfigOption,
+    /// log before this offset is cleaned once compaction is applied
+    pub(crate) cleaned_offset: Offset,
+    /// records at or after high watermark are not committed
+    pub(crate) hw: Offset,
+    /// base offsets of segments, including active one, whose keys supersede older records
+    pub(crate) key_segments: Vec<Offset>,
+    /// base and end offsets of read segments to rewrite
+    pub(crate) segments: Vec<(Offset, Offset)>,
+    pub(crate) delete_tombstone_before: i64,
+}
+
+/// segments rewritten in compaction directory, not yet in place of originals
+#[derive(Debug)]
+pub struct CompactedSegments {
+    pub(crate) cleaned_offset: Offset,
+    /// base and end offsets of rewritten segments
+    pub(crate) segments: Vec<(Offset, Offset)>,
+}
+
+impl Compaction {
+    /// rewrite segments, so they can be applied to replica
+    pub async fn run(self) -> Result<CompactedSegments, StorageError> {
+        let mut keys = KeyOffsets::new();
+        for base_offset in &self.key_segments {
+            let log_path =
+                generate_file_name(&self.option.base_dir, *base_offset, MESSAGE_LOG_EXTENSION);
+            collect_key_offsets(&log_path, self.hw, &mut keys).await?;
+        }
+
+        let mut segments = vec![];
+        for (base_offset, end_offset) in self.segments {
+            if compact_segment(
+                base_offset,
+                &keys,
+                self.delete_tombstone_before,
+                &self.option,
+            )
+            .await?
+            {
+                segments.push((base_offset, end_offset));
+            }
+        }
+
+        Ok(CompactedSegments {
+            cleaned_offset: self.cleaned_offset,
+            segments,
+        })
+    }
+}
+
+/// record latest offset of each key found in log before max offset
+async fn collect_key_offsets(
+    log_path: &Path,
+    max_offset: Offset,
+    keys: &mut KeyOffsets,
+) -> Result<(), StorageError> {
+    let file = file_util::open(log_path).await?;
+    let mut batch_stream = DefaultFileBatchStream::new(file);
+    while let Some(batch_pos) = batch_stream.next().await {
+        let batch = batch_pos.get_batch();
+        let base_offset = batch.get_base_offset();
+        if base_offset >= max_offset {
+            return Ok(());
+        }
+        for record in batch.records() {
+            let offset = base_offset + record.get_offset_delta();
+            if offset >= max_offset {
+                break;
+            }
+            if let Some(key) = record.get_key().inner_value_ref() {
+                keys.insert(key.clone(), offset);
+            }
+        }
+    }
+
+    if let Some(err) = batch_stream.invalid() {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+/// files of segment rewritten in compaction directory
+fn compacted_paths(base_offset: Offset, option: &ConfigOption) -> [PathBuf; 3] {
+    let compaction_dir = option.base_dir.join(COMPACTION_DIR);
+    [
+        generate_file_name(&compaction_dir, base_offset, MESSAGE_LOG_EXTENSION),
+        generate_file_name(&compaction_dir, base_offset, INDEX_EXTENSION),
+        generate_file_name(&compaction_dir, base_offset, TIME_INDEX_EXTENSION),
+    ]
+}
+
+/// rewrite segment into compaction directory without records superseded by later records of same key.
+/// tombstones (records with null value) are removed once their batch is older than `delete_tombstone_before` (millis).
+/// last batch is always kept, possibly empty, so segment still ends at same offset.
+/// return true if any record was removed
+async fn compact_segment(
+    base_offset: Offset,
+    keys: &KeyOffsets,
+    delete_tombstone_before: i64,
+    option: &ConfigOption,
+) -> Result<bool, StorageError> {
+    let compaction_dir = option.base_dir.join(COMPACTION_DIR);
+    create_dir_all(&compaction_dir).await?;
+    let compaction_option = option
+        .clone()
+        .base_dir(compaction_dir.clone())
+        .segment_max_bytes(Size::MAX);
+
+    // clean up leftover of previously failed compaction
+    remove_compacted_segment(base_offset, option).await?;
+
+    let mut compacted = MutableSegment::create(base_offset, &compaction_option).await?;
+    let mut removed = 0;
+
+    let log_path = generate_file_name(&option.base_dir, base_offset, MESSAGE_LOG_EXTENSION);
+    let mut batch_stream = DefaultFileBatchStream::new(file_util::open(log_path).await?);
+    let mut next_batch = batch_stream.next().await;
+    while let Some(batch_pos) = next_batch {
+        next_batch = batch_stream.next().await;
+
+        let mut batch = batch_pos.into_batch();
+        let batch_base_offset = batch.get_base_offset();
+        let expired = batch.get_header().max_time_stamp > 0
+            && batch.get_header().max_time_stamp < delete_tombstone_before;
+        let records_count = batch.records().len();
+
+        batch.records_mut().retain(|record| {
+            let key = match record.get_key().inner_value_ref() {
+                Some(key) => key,
+                None => return true,
+            };
+            let offset = batch_base_offset + record.get_offset_delta();
+            if keys.get(key).copied().unwrap_or(offset) > offset {
+                return false;
+            }
+            !(expired && record.get_value().inner_value_ref().is_none())
+        });
+        removed += records_count - batch.records().len();
+
+        if batch.records().is_empty() && next_batch.is_some() {
+            trace!("dropping empty batch: {}", batch_base_offset);
+            continue;
+        }
+        compacted.send(batch).await?;
+    }
+
+    if let Some(err) = batch_stream.invalid() {
+        return Err(err.into());
+    }
+
+    if removed == 0 {
+        trace!("nothing to compact in segment: {}", base_offset);
+        drop(compacted);
+        remove_compacted_segment(base_offset, option).await?;
+        return Ok(false);
+    }
+
+    compacted.roll_over().await?;
+    drop(compacted);
+
+    debug!(
+        "compacted segment: {}, removed {} records",
+        base_offset, removed
+    );
+    Ok(true)
+}
+
+/// remove rewritten segment which is not applied
+pub(crate) async fn remove_compacted_segment(
+    base_offset: Offset,
+    option: &ConfigOption,
+) -> Result<(), StorageError> {
+    for path in &compacted_paths(base_offset, option) {
+        if path.exists() {
+            remove_file(path).await?;
+        }
+    }
+    Ok(())
+}
+
+/// move rewritten segment in place of original segment
+pub(crate) async fn install_compacted_segment(
+    base_offset: Offset,
+    end_offset: Offset,
+    option: &ConfigOption,
+) -> Result<ReadSegment, StorageError> {
+    let [log_path, index_path, time_index_path] = compacted_paths(base_offset, option);
+    rename(
+        &log_path,
+        generate_file_name(&option.base_dir, base_offset, MESSAGE_LOG_EXTENSION),
+    )
+    .await?;
+    rename(
+        &index_path,
+        generate_file_name(&option.base_dir, base_offset, INDEX_EXTENSION),
+    )
+    .await?;
+    rename(
+        &time_index_path,
+        generate_file_name(&option.base_dir, base_offset, TIME_INDEX_EXTENSION),
+    )
+    .await?;
+
+    let mut new_segment = ReadSegment::open_for_read(base_offset, option).await?;
+    new_segment.set_end_offset(end_offset);
+    Ok(new_segment)
+}
@@ -8,6 +8,7 @@ use serde::Deserialize;
 use fluvio_types::defaults::SPU_LOG_INDEX_MAX_BYTES;
 use fluvio_types::defaults::SPU_LOG_INDEX_MAX_INTERVAL_BYTES;
 use fluvio_types::defaults::SPU_LOG_SEGMENT_MAX_BYTES;
+use fluvio_types::defaults::SPU_LOG_TOMBSTONE_RETENTION_SECONDS;
 
 use dataplane::Size;
 
@@ -22,6 +23,18 @@ pub struct ConfigOption {
     pub index_max_interval_bytes: Size,
     #[serde(default = "default_segment_max_bytes")]
     pub segment_max_bytes: Size,
+    /// segments whose newest batch is older than this are deleted, kept forever if none
+    #[serde(default)]
+    pub retention_seconds: Option<u32>,
+    /// oldest segments are deleted while partition is bigger than this, unlimited if none
+    #[serde(default)]
+    pub max_partition_size: Option<u64>,
+    /// rewrite read only segments so only latest record for each key is kept
+    #[serde(default)]
+    pub compact: bool,
+    /// tombstones of compacted logs are kept at least this long so consumers can see deletes
+    #[serde(default = "default_tombstone_retention_seconds")]
+    pub tombstone_retention_seconds: u32,
 }
 
 impl fmt::Display for ConfigOption {
@@ -46,6 +59,10 @@ fn default_segment_max_bytes() -> Size {
     SPU_LOG_SEGMENT_MAX_BYTES
 }
 
+fn default_tombstone_retention_seconds() -> u32 {
+    SPU_LOG_TOMBSTONE_RETENTION_SECONDS
+}
+
 impl ConfigOption {
     pub fn new(
         base_dir: PathBuf,
@@ -58,6 +75,10 @@ impl ConfigOption {
             index_max_bytes,
             index_max_interval_bytes,
             segment_max_bytes,
+            retention_seconds: None,
+            max_partition_size: None,
+            compact: false,
+            tombstone_retention_seconds: default_tombstone_retention_seconds(),
         }
     }
 
@@ -75,6 +96,26 @@ impl ConfigOption {
         self.segment_max_bytes = bytes;
         self
     }
+
+    pub fn retention_seconds(mut self, seconds: Option<u32>) -> Self {
+        self.retention_seconds = seconds;
+        self
+    }
+
+    pub fn max_partition_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_partition_size = bytes;
+        self
+    }
+
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    pub fn tombstone_retention_seconds(mut self, seconds: u32) -> Self {
+        self.tombstone_retention_seconds = seconds;
+        self
+    }
 }
 
 impl Default for ConfigOption {
@@ -84,6 +125,10 @@ impl Default for ConfigOption {
             index_max_bytes: default_index_max_bytes(),
             index_max_interval_bytes: default_index_max_interval_bytes(),
             segment_max_bytes: default_segment_max_bytes(),
+            retention_seconds: None,
+            max_partition_size: None,
+            compact: false,
+            tombstone_retention_seconds: default_tombstone_retention_seconds(),
         }
     }
 }
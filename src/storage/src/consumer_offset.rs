@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::io::Error as IoError;
+use std::path::PathBuf;
+
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_future::fs::metadata;
+use fluvio_future::fs::read;
+use fluvio_future::fs::rename;
+use fluvio_future::fs::write;
+use dataplane::core::{Version, Decoder, Encoder};
+use dataplane::bytes::Buf;
+use dataplane::bytes::BufMut;
+use dataplane::Offset;
+
+use crate::ConfigOption;
+
+/// file format version of consumer offsets
+const CONSUMER_OFFSETS_VERSION: i16 = 0;
+
+/// offset committed by a single consumer group
+#[derive(Default, Debug)]
+struct GroupOffset {
+    group: String,
+    offset: Offset,
+}
+
+impl Decoder for GroupOffset {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: Buf,
+    {
+        self.group.decode(src, version)?;
+        self.offset.decode(src, version)?;
+        Ok(())
+    }
+}
+
+impl Encoder for GroupOffset {
+    fn write_size(&self, version: Version) -> usize {
+        self.group.write_size(version) + self.offset.write_size(version)
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: BufMut,
+    {
+        self.group.encode(dest, version)?;
+        self.offset.encode(dest, version)?;
+        Ok(())
+    }
+}
+
+/// Offsets committed by consumer groups for a replica.
+///
+/// Committed offset is offset of next record group will read.
+/// All offsets are kept in memory and whole file is rewritten on each commit.
+/// Content is written to temporary file first, so a crash while committing
+/// leaves previous offsets intact.
+#[derive(Debug)]
+pub struct ConsumerOffsets {
+    path: PathBuf,
+    offsets: BTreeMap<String, Offset>,
+}
+
+impl ConsumerOffsets {
+    /// load offsets from file in base directory, create empty one if it doesn't exist
+    pub async fn create(option: &ConfigOption, name: &str) -> Result<Self, IoError> {
+        let path = option.base_dir.join(name);
+
+        let mut consumer_offsets = ConsumerOffsets {
+            path,
+            offsets: BTreeMap::new(),
+        };
+
+        if metadata(&consumer_offsets.path).await.is_ok() {
+            trace!(
+                "consumer offsets {:#?} exists, reading",
+                consumer_offsets.path
+            );
+            consumer_offsets.read().await?;
+        } else {
+            debug!(
+                "no existing consumer offsets {:#?}, starting empty",
+                consumer_offsets.path
+            );
+        }
+
+        Ok(consumer_offsets)
+    }
+
+    /// offset committed by group
+    pub fn get(&self, group: &str) -> Option<Offset> {
+        self.offsets.get(group).cloned()
+    }
+
+    /// all groups with their committed offsets, ordered by group
+    pub fn offsets(&self) -> &BTreeMap<String, Offset> {
+        &self.offsets
+    }
+
+    async fn read(&mut self) -> Result<(), IoError> {
+        let contents = read(&self.path).await?;
+        let mut group_offsets: Vec<GroupOffset> = vec![];
+        group_offsets.decode(&mut Cursor::new(contents), CONSUMER_OFFSETS_VERSION)?;
+
+        self.offsets = group_offsets
+            .into_iter()
+            .map(|group_offset| (group_offset.group, group_offset.offset))
+            .collect();
+        Ok(())
+    }
+
+    /// commit offset for group
+    pub(crate) async fn commit(&mut self, group: &str, offset: Offset) -> Result<(), IoError> {
+        debug!("committing consumer group: {} offset: {}", group, offset);
+        if self.offsets.get(group) == Some(&offset) {
+            trace!("offset is same as committed, skipping");
+            return Ok(());
+        }
+
+        self.offsets.insert(group.to_owned(), offset);
+        self.flush().await
+    }
+
+    /// commit offsets of many groups, file is rewritten once if any offset has changed
+    pub(crate) async fn commit_all(
+        &mut self,
+        offsets: Vec<(String, Offset)>,
+    ) -> Result<(), IoError> {
+        let mut changed = false;
+        for (group, offset) in offsets {
+            if self.offsets.get(&group) != Some(&offset) {
+                debug!("committing consumer group: {} offset: {}", group, offset);
+                self.offsets.insert(group, offset);
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.flush().await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn flush(&self) -> Result<(), IoError> {
+        let group_offsets: Vec<GroupOffset> = self
+            .offsets
+            .iter()
+            .map(|(group, offset)| GroupOffset {
+                group: group.clone(),
+                offset: *offset,
+            })
+            .collect();
+
+        let mut contents = vec![];
+        group_offsets.encode(&mut contents, CONSUMER_OFFSETS_VERSION)?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        write(&tmp_path, contents).await?;
+        rename(&tmp_path, &self.path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+    use std::io::Error as IoError;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+
+    use crate::ConfigOption;
+    use super::ConsumerOffsets;
+
+    #[test_async]
+    async fn consumer_offsets_test() -> Result<(), IoError> {
+        let test_dir = temp_dir().join("consumer-offsets");
+        ensure_new_dir(&test_dir)?;
+
+        let option = ConfigOption {
+            base_dir: test_dir,
+            ..Default::default()
+        };
+
+        let mut offsets = ConsumerOffsets::create(&option, "consumer.offsets").await?;
+        assert!(offsets.offsets().is_empty());
+        assert_eq!(offsets.get("g1"), None);
+
+        offsets.commit("g1", 10).await?;
+        offsets.commit("g2", 5).await?;
+        offsets.commit("g1", 20).await?;
+        assert_eq!(offsets.get("g1"), Some(20));
+
+        drop(offsets);
+
+        let mut restored = ConsumerOffsets::create(&option, "consumer.offsets").await?;
+        assert_eq!(restored.get("g1"), Some(20));
+        assert_eq!(restored.get("g2"), Some(5));
+        assert_eq!(restored.offsets().len(), 2);
+
+        restored
+            .commit_all(vec![("g2".to_owned(), 7), ("g3".to_owned(), 1)])
+            .await?;
+        drop(restored);
+
+        let restored = ConsumerOffsets::create(&option, "consumer.offsets").await?;
+        assert_eq!(restored.get("g1"), Some(20));
+        assert_eq!(restored.get("g2"), Some(7));
+        assert_eq!(restored.get("g3"), Some(1));
+        Ok(())
+    }
+}
@@ -228,6 +228,7 @@ mod tests {
             base_dir: temp_dir(),
             index_max_bytes: 1000,
             index_max_interval_bytes: 0,
+            ..Default::default()
         }
     }
 
@@ -0,0 +1,638 @@
+//!
+//! # Offline inspection and repair
+//!
+//! Used by storage cli to look into segments of replica directory while SPU is not running.
+//! Batches are read with their raw bytes so CRC can be verified,
+//! which is not done when segments are read by replica.
+//!
+use std::fmt;
+use std::io::Cursor;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tracing::debug;
+use tracing::trace;
+use futures_lite::io::AsyncReadExt;
+use serde::Serialize;
+
+use fluvio_future::fs::File;
+use fluvio_future::fs::remove_file;
+use fluvio_future::fs::util as file_util;
+use dataplane::batch::{DefaultBatch, BATCH_HEADER_SIZE, BATCH_PREAMBLE_SIZE};
+use dataplane::core::Decoder;
+use dataplane::{Offset, Size};
+
+use crate::index::EXTENSION as INDEX_EXTENSION;
+use crate::index::LogIndex;
+use crate::index::OffsetPosition;
+use crate::mut_index::MutLogIndex;
+use crate::mut_records::MESSAGE_LOG_EXTENSION;
+use crate::time_index::EXTENSION as TIME_INDEX_EXTENSION;
+use crate::util::log_path_get_offset;
+use crate::validator::validate;
+use crate::validator::LogValidationError;
+use crate::ConfigOption;
+use crate::StorageError;
+
+/// crc covers everything after partition leader epoch, magic and crc itself
+const CRC_START: usize = BATCH_PREAMBLE_SIZE + 4 + 1 + 4;
+
+#[derive(Debug, Serialize)]
+pub struct RecordInfo {
+    pub offset: Offset,
+    pub timestamp: i64,
+    pub key: Option<String>,
+    pub value_len: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchInfo {
+    /// position in the log file
+    pub pos: Size,
+    /// bytes including preamble
+    pub len: Size,
+    pub base_offset: Offset,
+    pub last_offset: Offset,
+    pub first_timestamp: i64,
+    pub max_timestamp: i64,
+    pub producer_id: i64,
+    pub crc: u32,
+    pub crc_valid: bool,
+    pub records: Vec<RecordInfo>,
+}
+
+impl BatchInfo {
+    fn end_pos(&self) -> u64 {
+        self.pos as u64 + self.len as u64
+    }
+}
+
+/// content of segment log
+#[derive(Debug, Serialize)]
+pub struct LogInspection {
+    pub base_offset: Offset,
+    pub file_len: u64,
+    /// end of last batch which can be decoded, has valid crc and ordered offset
+    pub valid_len: u64,
+    pub batches: Vec<BatchInfo>,
+    /// reason reading stopped before end of file
+    pub error: Option<String>,
+}
+
+impl LogInspection {
+    pub fn is_valid(&self) -> bool {
+        self.valid_len == self.file_len
+    }
+
+    /// next offset after last valid batch
+    pub fn valid_end_offset(&self) -> Offset {
+        self.batches
+            .iter()
+            .take_while(|batch| batch.end_pos() <= self.valid_len)
+            .last()
+            .map(|batch| batch.last_offset + 1)
+            .unwrap_or(self.base_offset)
+    }
+}
+
+/// read all batches of log file, stopping at first batch which can't be decoded
+pub async fn inspect_log<P>(path: P) -> Result<LogInspection, StorageError>
+where
+    P: AsRef<Path>,
+{
+    let log_path = path.as_ref();
+    let base_offset = log_path_get_offset(log_path)?;
+    let mut file = file_util::open(log_path).await?;
+    let file_len = file.metadata().await?.len();
+    debug!("inspecting log: {}, len: {}", log_path.display(), file_len);
+
+    let mut inspection = LogInspection {
+        base_offset,
+        file_len,
+        valid_len: 0,
+        batches: vec![],
+        error: None,
+    };
+    let mut pos: u64 = 0;
+    let mut end_offset = base_offset - 1;
+    let mut valid = true;
+
+    loop {
+        let bytes = match read_batch_bytes(&mut file, pos, file_len).await {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => break,
+            Err(err) => {
+                inspection.error = Some(format!("batch at pos: {}, {}", pos, err));
+                break;
+            }
+        };
+
+        let batch = match decode_batch(pos as Size, &bytes) {
+            Ok(batch) => batch,
+            Err(err) => {
+                inspection.error = Some(format!("batch at pos: {}, {}", pos, err));
+                break;
+            }
+        };
+        trace!(
+            "batch pos: {}, base offset: {}, crc valid: {}",
+            pos,
+            batch.base_offset,
+            batch.crc_valid
+        );
+
+        if valid {
+            if !batch.crc_valid {
+                inspection.error = Some(format!("batch at pos: {}, crc mismatch", pos));
+                valid = false;
+            } else if batch.base_offset <= end_offset {
+                inspection.error = Some(format!(
+                    "batch at pos: {}, offset: {} is not after previous offset: {}",
+                    pos, batch.base_offset, end_offset
+                ));
+                valid = false;
+            } else {
+                end_offset = batch.last_offset;
+                inspection.valid_len = batch.end_pos();
+            }
+        }
+
+        pos = batch.end_pos();
+        inspection.batches.push(batch);
+    }
+
+    Ok(inspection)
+}
+
+/// read preamble and rest of batch at position.
+/// none if position is end of file
+async fn read_batch_bytes(
+    file: &mut File,
+    pos: u64,
+    file_len: u64,
+) -> Result<Option<Vec<u8>>, IoError> {
+    if pos == file_len {
+        return Ok(None);
+    }
+    if pos + BATCH_PREAMBLE_SIZE as u64 > file_len {
+        return Err(IoError::new(
+            ErrorKind::UnexpectedEof,
+            "not enough bytes for preamble",
+        ));
+    }
+
+    let mut bytes = vec![0u8; BATCH_PREAMBLE_SIZE];
+    file.read_exact(&mut bytes).await?;
+
+    let mut batch_len_bytes = [0u8; 4];
+    batch_len_bytes.copy_from_slice(&bytes[BATCH_PREAMBLE_SIZE - 4..]);
+    let batch_len = i32::from_be_bytes(batch_len_bytes);
+    if batch_len < BATCH_HEADER_SIZE as i32 {
+        return Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("invalid batch len: {}", batch_len),
+        ));
+    }
+    if pos + (BATCH_PREAMBLE_SIZE + batch_len as usize) as u64 > file_len {
+        return Err(IoError::new(
+            ErrorKind::UnexpectedEof,
+            format!("batch len: {} exceeds end of file", batch_len),
+        ));
+    }
+
+    bytes.resize(BATCH_PREAMBLE_SIZE + batch_len as usize, 0);
+    file.read_exact(&mut bytes[BATCH_PREAMBLE_SIZE..]).await?;
+    Ok(Some(bytes))
+}
+
+fn decode_batch(pos: Size, bytes: &[u8]) -> Result<BatchInfo, IoError> {
+    let batch = DefaultBatch::decode_from(&mut Cursor::new(bytes), 0)?;
+    let header = batch.get_header();
+    let base_offset = batch.get_base_offset();
+
+    let records = batch
+        .records()
+        .iter()
+        .map(|record| RecordInfo {
+            offset: base_offset + record.get_offset_delta(),
+            timestamp: header.first_timestamp + record.preamble.get_timestamp_delta(),
+            key: record
+                .get_key()
+                .inner_value_ref()
+                .as_ref()
+                .map(|key| String::from_utf8_lossy(key).into_owned()),
+            value_len: record.get_value().len(),
+        })
+        .collect();
+
+    Ok(BatchInfo {
+        pos,
+        len: bytes.len() as Size,
+        base_offset,
+        last_offset: batch.get_last_offset(),
+        first_timestamp: header.first_timestamp,
+        max_timestamp: header.max_time_stamp,
+        producer_id: header.producer_id,
+        crc: header.crc,
+        crc_valid: crc32c::crc32c(&bytes[CRC_START..]) == header.crc,
+        records,
+    })
+}
+
+/// problem found by checking segment
+#[derive(Debug)]
+pub enum SegmentIssue {
+    /// segment failed validation by replica
+    Invalid(LogValidationError),
+    /// batch can be decoded but crc doesn't match
+    CrcMismatch {
+        pos: Size,
+        offset: Offset,
+    },
+    /// segment doesn't start where previous segment ended
+    Gap {
+        expected: Offset,
+    },
+    MissingIndex,
+    /// index entry doesn't point to start of batch
+    IndexNoBatch {
+        entry: usize,
+        position: Size,
+    },
+    /// index entry points to batch with different offset
+    IndexOffsetMismatch {
+        entry: usize,
+        expected: Offset,
+        found: Offset,
+    },
+    /// index entry is not after previous entry
+    IndexNotOrdered {
+        entry: usize,
+    },
+}
+
+impl fmt::Display for SegmentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => write!(f, "invalid log: {}", err),
+            Self::CrcMismatch { pos, offset } => {
+                write!(
+                    f,
+                    "crc mismatch of batch offset: {} at pos: {}",
+                    offset, pos
+                )
+            }
+            Self::Gap { expected } => write!(f, "gap, previous segment ends at: {}", expected),
+            Self::MissingIndex => write!(f, "index is missing"),
+            Self::IndexNoBatch { entry, position } => write!(
+                f,
+                "index entry: {} points to pos: {} which is not start of batch",
+                entry, position
+            ),
+            Self::IndexOffsetMismatch {
+                entry,
+                expected,
+                found,
+            } => write!(
+                f,
+                "index entry: {} has offset: {} but batch has offset: {}",
+                entry, expected, found
+            ),
+            Self::IndexNotOrdered { entry } => {
+                write!(f, "index entry: {} is not after previous entry", entry)
+            }
+        }
+    }
+}
+
+/// result of checking single segment
+#[derive(Debug)]
+pub struct SegmentCheck {
+    pub log_path: PathBuf,
+    pub base_offset: Offset,
+    /// next offset after segment, if it is valid
+    pub end_offset: Option<Offset>,
+    pub issues: Vec<SegmentIssue>,
+}
+
+impl SegmentCheck {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+fn index_path(log_path: &Path) -> PathBuf {
+    log_path.with_extension(INDEX_EXTENSION)
+}
+
+/// check every index entry of segment against batches of the log
+pub async fn check_index<P>(
+    log_path: P,
+    inspection: &LogInspection,
+) -> Result<Vec<SegmentIssue>, StorageError>
+where
+    P: AsRef<Path>,
+{
+    let index_path = index_path(log_path.as_ref());
+    if !index_path.exists() {
+        return Ok(vec![SegmentIssue::MissingIndex]);
+    }
+
+    let index = LogIndex::open_from_path(&index_path).await?;
+    let mut issues = vec![];
+    let mut previous: Option<(Size, Size)> = None;
+    for (entry, offset_position) in index.iter().enumerate() {
+        let (offset_delta, position) = (offset_position.offset(), offset_position.position());
+        // unused slots of active segment
+        if position == 0 {
+            continue;
+        }
+
+        if let Some((prev_offset, prev_position)) = previous {
+            if offset_delta <= prev_offset || position <= prev_position {
+                issues.push(SegmentIssue::IndexNotOrdered { entry });
+            }
+        }
+        previous = Some((offset_delta, position));
+
+        let expected = inspection.base_offset + offset_delta as Offset;
+        match inspection
+            .batches
+            .iter()
+            .find(|batch| batch.pos == position)
+        {
+            None => issues.push(SegmentIssue::IndexNoBatch { entry, position }),
+            Some(batch) if batch.base_offset != expected => {
+                issues.push(SegmentIssue::IndexOffsetMismatch {
+                    entry,
+                    expected,
+                    found: batch.base_offset,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(issues)
+}
+
+/// check log and index of segment
+pub async fn check_segment<P>(log_path: P) -> Result<SegmentCheck, StorageError>
+where
+    P: AsRef<Path>,
+{
+    let log_path = log_path.as_ref();
+    let inspection = inspect_log(log_path).await?;
+    let mut issues = vec![];
+
+    let end_offset = match validate(log_path).await {
+        Ok(end_offset) => Some(end_offset),
+        Err(err) => {
+            issues.push(SegmentIssue::Invalid(err));
+            None
+        }
+    };
+
+    for batch in inspection.batches.iter().filter(|batch| !batch.crc_valid) {
+        issues.push(SegmentIssue::CrcMismatch {
+            pos: batch.pos,
+            offset: batch.base_offset,
+        });
+    }
+
+    issues.append(&mut check_index(log_path, &inspection).await?);
+
+    Ok(SegmentCheck {
+        log_path: log_path.to_owned(),
+        base_offset: inspection.base_offset,
+        end_offset,
+        issues,
+    })
+}
+
+/// check all segments of replica directory in offset order
+pub async fn check_replica_dir<P>(dir: P) -> Result<Vec<SegmentCheck>, StorageError>
+where
+    P: AsRef<Path>,
+{
+    let mut log_paths = vec![];
+    for entry in dir.as_ref().read_dir()? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some(MESSAGE_LOG_EXTENSION) {
+            if let Ok(offset) = log_path_get_offset(&path) {
+                log_paths.push((offset, path));
+            }
+        }
+    }
+    log_paths.sort_unstable();
+
+    let mut checks: Vec<SegmentCheck> = vec![];
+    for (_, log_path) in log_paths {
+        let mut check = check_segment(&log_path).await?;
+        if let Some(expected) = checks.last().and_then(|previous| previous.end_offset) {
+            if expected != check.base_offset {
+                check.issues.push(SegmentIssue::Gap { expected });
+            }
+        }
+        checks.push(check);
+    }
+
+    Ok(checks)
+}
+
+/// replace index of segment with one built from valid batches of the log.
+/// time index is removed, it is rebuilt when segment is loaded by replica.
+/// index interval and size are taken from option, base directory is ignored
+pub async fn rebuild_index<P>(log_path: P, option: &ConfigOption) -> Result<(), StorageError>
+where
+    P: AsRef<Path>,
+{
+    let log_path = log_path.as_ref();
+    let inspection = inspect_log(log_path).await?;
+
+    let index_path = index_path(log_path);
+    if index_path.exists() {
+        remove_file(&index_path).await?;
+    }
+    let time_index_path = log_path.with_extension(TIME_INDEX_EXTENSION);
+    if time_index_path.exists() {
+        remove_file(&time_index_path).await?;
+    }
+
+    let mut index_option = option.clone();
+    if let Some(parent) = log_path.parent() {
+        index_option.base_dir = parent.to_owned();
+    }
+
+    // same entries as written by segment when batches are appended
+    let mut index = MutLogIndex::create(inspection.base_offset, &index_option).await?;
+    for batch in &inspection.batches {
+        if batch.end_pos() > inspection.valid_len {
+            break;
+        }
+        let offset_delta = (batch.base_offset - inspection.base_offset) as Size;
+        index
+            .send((offset_delta, batch.pos, batch.end_pos() as Size))
+            .await?;
+    }
+    index.shrink().await?;
+
+    debug!("rebuilt index: {}", index_path.display());
+    Ok(())
+}
+
+/// cut log after last valid batch.
+/// return number of bytes removed
+pub async fn truncate_log<P>(log_path: P) -> Result<u64, StorageError>
+where
+    P: AsRef<Path>,
+{
+    let log_path = log_path.as_ref();
+    let inspection = inspect_log(log_path).await?;
+    if inspection.is_valid() {
+        return Ok(0);
+    }
+
+    debug!(
+        "truncating log: {} from: {} to: {} bytes",
+        log_path.display(),
+        inspection.file_len,
+        inspection.valid_len
+    );
+    let file = file_util::open_read_write(log_path).await?;
+    file.set_len(inspection.valid_len).await?;
+    file.sync_all().await?;
+
+    Ok(inspection.file_len - inspection.valid_len)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+    use std::fs::OpenOptions;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+
+    use crate::ConfigOption;
+    use crate::StorageError;
+    use crate::fixture::create_batch;
+    use crate::segment::MutableSegment;
+    use crate::util::generate_file_name;
+
+    use super::*;
+
+    fn default_option(base_dir: PathBuf) -> ConfigOption {
+        ConfigOption {
+            base_dir,
+            segment_max_bytes: 1000,
+            index_max_bytes: 1000,
+            index_max_interval_bytes: 0,
+            ..Default::default()
+        }
+    }
+
+    /// segment at offset 100 with 3 batches of 2 records, each 79 bytes
+    async fn create_segment(dir: &str) -> Result<(ConfigOption, PathBuf), StorageError> {
+        let test_dir = temp_dir().join(dir);
+        ensure_new_dir(&test_dir)?;
+        let option = default_option(test_dir.clone());
+
+        let mut segment = MutableSegment::create(100, &option).await?;
+        for _ in 0..3 {
+            segment.send(create_batch()).await?;
+        }
+        segment.roll_over().await?;
+
+        Ok((option, generate_file_name(&test_dir, 100, "log")))
+    }
+
+    #[test_async]
+    async fn test_inspect_log() -> Result<(), StorageError> {
+        let (_, log_path) = create_segment("inspect-log").await?;
+
+        let inspection = inspect_log(&log_path).await?;
+        assert!(inspection.is_valid());
+        assert!(inspection.error.is_none());
+        assert_eq!(inspection.batches.len(), 3);
+        assert_eq!(inspection.valid_end_offset(), 106);
+
+        let batch = &inspection.batches[1];
+        assert_eq!(batch.pos, 79);
+        assert_eq!(batch.base_offset, 102);
+        assert_eq!(batch.last_offset, 103);
+        assert!(batch.crc_valid);
+        assert_eq!(batch.records.len(), 2);
+        assert_eq!(batch.records[1].offset, 103);
+        assert_eq!(batch.records[1].value_len, 2);
+
+        assert!(check_segment(&log_path).await?.is_ok());
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_corrupt_log_repair() -> Result<(), StorageError> {
+        let (option, log_path) = create_segment("inspect-corrupt").await?;
+
+        // flip a byte of timestamp in last batch and add partial batch
+        let mut file = OpenOptions::new().write(true).open(&log_path)?;
+        file.seek(SeekFrom::Start(158 + 30))?;
+        file.write_all(&[0xff])?;
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(&[0, 0, 0, 0, 0, 0, 0, 106, 0, 0])?;
+        drop(file);
+
+        let inspection = inspect_log(&log_path).await?;
+        assert_eq!(inspection.batches.len(), 3);
+        assert!(!inspection.batches[2].crc_valid);
+        assert_eq!(inspection.valid_len, 158);
+        assert_eq!(inspection.valid_end_offset(), 104);
+        assert!(!check_segment(&log_path).await?.is_ok());
+
+        assert_eq!(truncate_log(&log_path).await?, 79 + 10);
+        rebuild_index(&log_path, &option).await?;
+
+        let inspection = inspect_log(&log_path).await?;
+        assert!(inspection.is_valid());
+        assert_eq!(inspection.batches.len(), 2);
+        assert!(check_segment(&log_path).await?.is_ok());
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_check_index() -> Result<(), StorageError> {
+        let (option, log_path) = create_segment("inspect-index").await?;
+        let index_path = log_path.with_extension("index");
+
+        // point second entry at middle of batch
+        let mut file = OpenOptions::new().write(true).open(&index_path)?;
+        file.seek(SeekFrom::Start(12))?;
+        file.write_all(&80u32.to_be_bytes())?;
+        drop(file);
+
+        let check = check_segment(&log_path).await?;
+        assert_eq!(check.issues.len(), 1);
+        assert!(matches!(
+            check.issues[0],
+            SegmentIssue::IndexNoBatch {
+                entry: 1,
+                position: 80
+            }
+        ));
+
+        rebuild_index(&log_path, &option).await?;
+        assert!(check_segment(&log_path).await?.is_ok());
+
+        std::fs::remove_file(&index_path)?;
+        let check = check_segment(&log_path).await?;
+        assert!(matches!(check.issues[0], SegmentIssue::MissingIndex));
+        Ok(())
+    }
+}
@@ -4,27 +4,41 @@ mod fixture;
 mod batch;
 mod batch_header;
 mod checkpoint;
+mod cleanup;
+mod compaction;
+mod consumer_offset;
 mod error;
 mod records;
 mod index;
+mod inspect;
 mod mut_records;
 mod mut_index;
+mod producer_state;
 mod range_map;
 mod replica;
 mod segment;
+mod time_index;
 mod util;
 mod validator;
 mod config;
 
 pub use crate::config::ConfigOption;
 pub use crate::batch::DefaultFileBatchStream;
+pub use crate::consumer_offset::ConsumerOffsets;
+pub use crate::producer_state::{ProducerState, ProducerStates};
 pub use crate::batch_header::BatchHeaderPos;
 pub use crate::batch_header::BatchHeaderStream;
 pub use crate::error::StorageError;
 pub use crate::records::FileRecordsSlice;
 pub use crate::index::LogIndex;
 pub use crate::index::OffsetPosition;
-pub use crate::replica::FileReplica;
+pub use crate::inspect::{BatchInfo, RecordInfo, LogInspection, SegmentCheck, SegmentIssue};
+pub use crate::inspect::{inspect_log, check_index, check_segment, check_replica_dir};
+pub use crate::inspect::{rebuild_index, truncate_log};
+pub use crate::replica::{FileReplica, ExpiredSegments};
+pub use crate::compaction::{Compaction, CompactedSegments};
+pub use crate::cleanup::{TRASH_DIR, parse_replica_dir_name, find_orphan_replicas, purge_trash};
+pub use crate::cleanup::{remove_replica_dir, move_to_trash};
 pub(crate) use crate::segment::SegmentSlice;
 
 use dataplane::{ErrorCode, Offset};
@@ -75,4 +89,7 @@ pub trait ReplicaStorage {
 
     /// log end offset ( records that has been stored)
     fn get_leo(&self) -> Offset;
+
+    /// last batch written by idempotent producer
+    fn get_producer_state(&self, producer_id: i64) -> Option<ProducerState>;
 }
@@ -145,11 +145,14 @@ mod tests {
     use dataplane::batch::DefaultBatch;
     use dataplane::core::Decoder;
     use dataplane::core::Encoder;
+    use dataplane::compression::Compression;
+    use fluvio_future::fs::util as file_util;
     use flv_util::fixture::ensure_clean_file;
 
     use super::MutFileRecords;
     use super::StorageError;
     use crate::fixture::create_batch;
+    use crate::fixture::create_batch_with_producer;
     use crate::fixture::read_bytes_from_file;
     use crate::ConfigOption;
 
@@ -177,8 +180,8 @@ mod tests {
 
         let batch = DefaultBatch::decode_from(&mut Cursor::new(bytes), 0)?;
         assert_eq!(batch.get_header().magic, 2, "check magic");
-        assert_eq!(batch.records.len(), 2);
-        let mut records = batch.records;
+        assert_eq!(batch.records().len(), 2);
+        let mut records = batch.into_records();
         assert_eq!(records.len(), 2);
         let record1 = records.remove(0);
         assert_eq!(record1.value.inner_value(), Some(vec![10, 20]));
@@ -194,4 +197,47 @@ mod tests {
 
         Ok(())
     }
+
+    const TEST_COMPRESSED_FILE_NAME: &str = "00000000000000000300.log"; // for offset 300
+
+    #[test_async]
+    async fn test_write_compressed_records() -> Result<(), StorageError> {
+        use crate::batch::DefaultFileBatchStream;
+
+        let test_file = temp_dir().join(TEST_COMPRESSED_FILE_NAME);
+        ensure_clean_file(&test_file);
+
+        let options = ConfigOption {
+            base_dir: temp_dir(),
+            segment_max_bytes: 10000,
+            ..Default::default()
+        };
+        let mut msg_sink = MutFileRecords::create(300, &options).await.expect("create");
+
+        let mut batch = create_batch_with_producer(12, 100);
+        let uncompressed_size = batch.write_size(0);
+        batch.get_mut_header().set_compression(Compression::Gzip);
+        msg_sink.send(batch).await.expect("send");
+
+        let bytes = read_bytes_from_file(&test_file).expect("read bytes");
+        assert!(
+            bytes.len() < uncompressed_size,
+            "batch should be compressed"
+        );
+
+        // batch read from file must be uncompressed
+        let file = file_util::open(&test_file).await.expect("open");
+        let mut stream = DefaultFileBatchStream::new(file);
+        let batch_pos = stream.next().await.expect("batch");
+        let batch = batch_pos.get_batch();
+        assert_eq!(batch.get_header().get_compression()?, Compression::Gzip);
+        assert_eq!(batch.records().len(), 100);
+        assert_eq!(
+            batch.records()[99].value.inner_value_ref(),
+            &Some(vec![10, 20])
+        );
+        assert!(stream.next().await.is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::io::Error as IoError;
+use std::path::PathBuf;
+
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_future::fs::metadata;
+use fluvio_future::fs::read;
+use fluvio_future::fs::rename;
+use fluvio_future::fs::write;
+use dataplane::core::{Version, Decoder, Encoder};
+use dataplane::bytes::Buf;
+use dataplane::bytes::BufMut;
+use dataplane::batch::BatchHeader;
+use dataplane::{Offset, Size};
+
+use crate::segment::Segment;
+use crate::index::Index;
+use crate::records::FileRecords;
+use crate::ConfigOption;
+use crate::StorageError;
+
+/// file format version of producer state snapshot
+const PRODUCER_STATE_VERSION: i16 = 0;
+
+/// Last batch written by idempotent producer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProducerState {
+    pub epoch: i16,
+    /// sequence number of last record written
+    pub last_sequence: i32,
+    /// offset of last record written
+    pub last_offset: Offset,
+}
+
+/// state of single producer as stored in snapshot
+#[derive(Default, Debug)]
+struct ProducerEntry {
+    producer_id: i64,
+    epoch: i16,
+    last_sequence: i32,
+    last_offset: Offset,
+}
+
+impl Decoder for ProducerEntry {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: Buf,
+    {
+        self.producer_id.decode(src, version)?;
+        self.epoch.decode(src, version)?;
+        self.last_sequence.decode(src, version)?;
+        self.last_offset.decode(src, version)?;
+        Ok(())
+    }
+}
+
+impl Encoder for ProducerEntry {
+    fn write_size(&self, version: Version) -> usize {
+        self.producer_id.write_size(version)
+            + self.epoch.write_size(version)
+            + self.last_sequence.write_size(version)
+            + self.last_offset.write_size(version)
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: BufMut,
+    {
+        self.producer_id.encode(dest, version)?;
+        self.epoch.encode(dest, version)?;
+        self.last_sequence.encode(dest, version)?;
+        self.last_offset.encode(dest, version)?;
+        Ok(())
+    }
+}
+
+/// Sequence state of idempotent producers for a replica.
+///
+/// State is updated from headers of every batch written, so followers
+/// have same state as leader.  Snapshot is written when segment is rolled over,
+/// together with end offset of log at that time.  When replica is loaded,
+/// batches written after snapshot are scanned again to restore the rest.
+#[derive(Debug)]
+pub struct ProducerStates {
+    path: PathBuf,
+    snapshot_offset: Offset,
+    producers: BTreeMap<i64, ProducerState>,
+}
+
+impl ProducerStates {
+    /// load snapshot from file in base directory, start empty if it doesn't exist
+    pub async fn create(option: &ConfigOption, name: &str) -> Result<Self, IoError> {
+        let path = option.base_dir.join(name);
+
+        let mut producer_states = ProducerStates {
+            path,
+            snapshot_offset: 0,
+            producers: BTreeMap::new(),
+        };
+
+        if metadata(&producer_states.path).await.is_ok() {
+            trace!("producer state {:#?} exists, reading", producer_states.path);
+            producer_states.read().await?;
+        } else {
+            debug!(
+                "no existing producer state {:#?}, starting empty",
+                producer_states.path
+            );
+        }
+
+        Ok(producer_states)
+    }
+
+    /// state of producer, none if producer has not written to replica
+    pub fn get(&self, producer_id: i64) -> Option<ProducerState> {
+        self.producers.get(&producer_id).cloned()
+    }
+
+    /// end offset of log when snapshot was written
+    pub fn snapshot_offset(&self) -> Offset {
+        self.snapshot_offset
+    }
+
+    /// record batch written at base offset.  batches without producer id or sequence are ignored
+    pub(crate) fn update(&mut self, header: &BatchHeader, base_offset: Offset) {
+        if header.producer_id < 0 || header.first_sequence < 0 {
+            return;
+        }
+
+        let state = ProducerState {
+            epoch: header.producer_epoch,
+            last_sequence: header.last_sequence(),
+            last_offset: base_offset + header.last_offset_delta as Offset,
+        };
+        trace!("producer: {}, state: {:?}", header.producer_id, state);
+        self.producers.insert(header.producer_id, state);
+    }
+
+    /// write snapshot of state up to end offset.
+    /// producers whose last record is before log start offset are forgotten
+    pub(crate) async fn snapshot(
+        &mut self,
+        end_offset: Offset,
+        log_start_offset: Offset,
+    ) -> Result<(), IoError> {
+        self.producers
+            .retain(|_, state| state.last_offset >= log_start_offset);
+        self.snapshot_offset = end_offset;
+
+        let entries: Vec<ProducerEntry> = self
+            .producers
+            .iter()
+            .map(|(producer_id, state)| ProducerEntry {
+                producer_id: *producer_id,
+                epoch: state.epoch,
+                last_sequence: state.last_sequence,
+                last_offset: state.last_offset,
+            })
+            .collect();
+
+        debug!(
+            "writing producer state snapshot at: {}, producers: {}",
+            end_offset,
+            entries.len()
+        );
+        let mut contents = vec![];
+        self.snapshot_offset
+            .encode(&mut contents, PRODUCER_STATE_VERSION)?;
+        entries.encode(&mut contents, PRODUCER_STATE_VERSION)?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        write(&tmp_path, contents).await?;
+        rename(&tmp_path, &self.path).await
+    }
+
+    async fn read(&mut self) -> Result<(), IoError> {
+        let contents = read(&self.path).await?;
+        let mut src = Cursor::new(contents);
+        let mut entries: Vec<ProducerEntry> = vec![];
+        self.snapshot_offset
+            .decode(&mut src, PRODUCER_STATE_VERSION)?;
+        entries.decode(&mut src, PRODUCER_STATE_VERSION)?;
+
+        self.producers = entries
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.producer_id,
+                    ProducerState {
+                        epoch: entry.epoch,
+                        last_sequence: entry.last_sequence,
+                        last_offset: entry.last_offset,
+                    },
+                )
+            })
+            .collect();
+        Ok(())
+    }
+}
+
+/// restore state from batches of segment starting at snapshot offset
+pub(crate) async fn recover_producer_states<I, L>(
+    segment: &Segment<I, L>,
+    producer_states: &mut ProducerStates,
+) -> Result<(), StorageError>
+where
+    I: Index,
+    I: std::ops::Deref<Target = [(Size, Size)]>,
+    L: FileRecords,
+{
+    if segment.get_end_offset() <= producer_states.snapshot_offset() {
+        return Ok(());
+    }
+
+    let mut header_stream = segment.open_batch_header_stream(0).await?;
+    while let Some(batch_pos) = header_stream.next().await {
+        let batch = batch_pos.get_batch();
+        if batch.get_base_offset() >= producer_states.snapshot_offset() {
+            producer_states.update(batch.get_header(), batch.get_base_offset());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+    use dataplane::batch::BatchHeader;
+
+    use super::ProducerStates;
+    use super::ProducerState;
+    use crate::ConfigOption;
+
+    fn header(producer_id: i64, first_sequence: i32, last_offset_delta: i32) -> BatchHeader {
+        BatchHeader {
+            producer_id,
+            producer_epoch: 0,
+            first_sequence,
+            last_offset_delta,
+            ..Default::default()
+        }
+    }
+
+    #[test_async]
+    async fn test_producer_state_snapshot() -> Result<(), std::io::Error> {
+        let test_dir = temp_dir().join("producer-state");
+        ensure_new_dir(&test_dir)?;
+        let option = ConfigOption {
+            base_dir: test_dir,
+            ..Default::default()
+        };
+
+        let mut states = ProducerStates::create(&option, "producer.state").await?;
+        states.update(&header(1, 0, 2), 0);
+        states.update(&header(-1, -1, 0), 3); // not idempotent, ignored
+        states.update(&header(2, 0, 0), 4);
+        states.update(&header(1, 3, 1), 5);
+        assert_eq!(
+            states.get(1),
+            Some(ProducerState {
+                epoch: 0,
+                last_sequence: 4,
+                last_offset: 6
+            })
+        );
+        assert_eq!(states.get(-1), None);
+
+        states.snapshot(7, 5).await?;
+        drop(states);
+
+        let states = ProducerStates::create(&option, "producer.state").await?;
+        assert_eq!(states.snapshot_offset(), 7);
+        assert_eq!(states.get(1).map(|state| state.last_sequence), Some(4));
+        // last record of producer 2 was before log start
+        assert_eq!(states.get(2), None);
+
+        Ok(())
+    }
+}
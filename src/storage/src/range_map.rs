@@ -93,6 +93,29 @@ impl SegmentList {
         self.min_base_offset
     }
 
+    /// total size of message logs in bytes
+    pub fn total_size(&self) -> u64 {
+        self.segments
+            .values()
+            .map(|segment| segment.get_msg_size())
+            .sum()
+    }
+
+    /// oldest segment
+    pub fn first_segment(&self) -> Option<&ReadSegment> {
+        self.segments.values().next()
+    }
+
+    /// remove oldest segment
+    pub fn remove_first_segment(&mut self) -> Option<ReadSegment> {
+        let base_offset = *self.segments.keys().next()?;
+        let segment = self.segments.remove(&base_offset);
+        self.min_base_offset = self.segments.keys().next().copied().unwrap_or(-1);
+        self.max_base_offset = self.segments.keys().next_back().copied().unwrap_or(0);
+        debug!("removed segment base: {}", base_offset);
+        segment
+    }
+
     pub fn add_segment(&mut self, segment: ReadSegment) {
         let base_offset = segment.get_base_offset();
         debug!("inserting segment base: {}", base_offset);
@@ -105,7 +128,11 @@ impl SegmentList {
         self.segments.insert(segment.get_base_offset(), segment);
     }
 
-    #[allow(dead_code)]
+    /// base offsets of all segments in order
+    pub fn base_offsets(&self) -> Vec<Offset> {
+        self.segments.keys().copied().collect()
+    }
+
     pub fn get_segment(&self, offset: Offset) -> Option<&ReadSegment> {
         self.segments.get(&offset)
     }
@@ -154,6 +181,7 @@ mod tests {
             base_dir,
             index_max_bytes: 1000,
             index_max_interval_bytes: 0,
+            ..Default::default()
         }
     }
 
@@ -63,6 +63,11 @@ impl FileRecordsSlice {
         self.base_offset
     }
 
+    /// length of log in bytes
+    pub fn get_len(&self) -> u64 {
+        self.len
+    }
+
     #[allow(dead_code)]
     pub async fn validate(&mut self) -> Result<Offset, LogValidationError> {
         validate(&self.path).await
@@ -1,5 +1,8 @@
 use std::io::Error as IoError;
 use std::mem;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use tracing::debug;
 use tracing::trace;
@@ -11,13 +14,25 @@ use dataplane::batch::DefaultBatch;
 use dataplane::record::RecordSet;
 
 use crate::checkpoint::CheckPoint;
+use crate::cleanup::remove_replica_dir;
+use crate::cleanup::move_to_trash;
+use crate::compaction::Compaction;
+use crate::compaction::CompactedSegments;
+use crate::compaction::install_compacted_segment;
+use crate::compaction::remove_compacted_segment;
+use crate::consumer_offset::ConsumerOffsets;
+use crate::producer_state::ProducerStates;
+use crate::producer_state::recover_producer_states;
 use crate::range_map::SegmentList;
 use crate::segment::MutableSegment;
+use crate::segment::ReadSegment;
 use crate::ConfigOption;
 use crate::SegmentSlice;
 use crate::StorageError;
+use crate::util::OffsetError;
 use crate::SlicePartitionResponse;
 use crate::ReplicaStorage;
+use crate::ProducerState;
 
 /// Replica is public abstraction for commit log which are distributed.
 /// Internally it is stored as list of segments.  Each segment contains finite sets of record batches.
@@ -32,10 +47,36 @@ pub struct FileReplica {
     active_segment: MutableSegment,
     prev_segments: SegmentList,
     commit_checkpoint: CheckPoint<Offset>,
+    /// log before this offset is compacted, only kept if compaction is enabled
+    cleaned_checkpoint: Option<CheckPoint<Offset>>,
+    consumer_offsets: ConsumerOffsets,
+    producer_states: ProducerStates,
 }
 
 impl Unpin for FileReplica {}
 
+/// segments detached from replica by retention, files are deleted by `remove`
+#[derive(Debug, Default)]
+pub struct ExpiredSegments(Vec<ReadSegment>);
+
+impl ExpiredSegments {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// delete files of segments
+    pub async fn remove(self) -> Result<(), StorageError> {
+        for segment in self.0 {
+            segment.remove().await?;
+        }
+        Ok(())
+    }
+}
+
 impl ReplicaStorage for FileReplica {
     fn get_hw(&self) -> Offset {
         *self.commit_checkpoint.get_offset()
@@ -45,6 +86,10 @@ impl ReplicaStorage for FileReplica {
     fn get_leo(&self) -> Offset {
         self.active_segment.get_end_offset()
     }
+
+    fn get_producer_state(&self, producer_id: i64) -> Option<ProducerState> {
+        self.producer_states.get(producer_id)
+    }
 }
 
 impl FileReplica {
@@ -102,6 +147,22 @@ impl FileReplica {
         let commit_checkpoint: CheckPoint<Offset> =
             CheckPoint::create(&rep_option, "replication.chk", last_base_offset).await?;
 
+        let cleaned_checkpoint = if rep_option.compact {
+            Some(CheckPoint::create(&rep_option, "cleaner.chk", 0).await?)
+        } else {
+            None
+        };
+
+        let consumer_offsets = ConsumerOffsets::create(&rep_option, "consumer.offsets").await?;
+
+        let mut producer_states = ProducerStates::create(&rep_option, "producer.state").await?;
+        for base_offset in segments.base_offsets() {
+            if let Some(segment) = segments.get_segment(base_offset) {
+                recover_producer_states(segment, &mut producer_states).await?;
+            }
+        }
+        recover_producer_states(&active_segment, &mut producer_states).await?;
+
         Ok(FileReplica {
             option: rep_option,
             last_base_offset,
@@ -109,6 +170,9 @@ impl FileReplica {
             active_segment,
             prev_segments: segments,
             commit_checkpoint,
+            cleaned_checkpoint,
+            consumer_offsets,
+            producer_states,
         })
     }
 
@@ -136,6 +200,55 @@ impl FileReplica {
         self.update_high_watermark(self.get_leo()).await
     }
 
+    /// directory where segments, indexes and checkpoints are stored
+    pub fn replica_dir(&self) -> &PathBuf {
+        &self.option.base_dir
+    }
+
+    /// Delete replica with all segments, indexes and checkpoints.
+    /// Replica is closed before its directory is deleted.
+    pub async fn remove(self) -> Result<(), StorageError> {
+        let replica_dir = self.option.base_dir.clone();
+        drop(self);
+        remove_replica_dir(&replica_dir).await?;
+        Ok(())
+    }
+
+    /// Close replica and move its directory to trash, returns path in trash.
+    /// New replica with same topic and partition starts empty.
+    pub async fn move_to_trash(self) -> Result<PathBuf, StorageError> {
+        let replica_dir = self.option.base_dir.clone();
+        drop(self);
+        Ok(move_to_trash(&replica_dir).await?)
+    }
+
+    /// offsets committed by consumer groups
+    pub fn consumer_offsets(&self) -> &ConsumerOffsets {
+        &self.consumer_offsets
+    }
+
+    /// commit offset of next record to be read by consumer group
+    pub async fn commit_consumer_offset(
+        &mut self,
+        group: &str,
+        offset: Offset,
+    ) -> Result<(), IoError> {
+        self.consumer_offsets.commit(group, offset).await
+    }
+
+    /// commit offsets of consumer groups replicated from leader
+    pub async fn commit_consumer_offsets(
+        &mut self,
+        offsets: Vec<(String, Offset)>,
+    ) -> Result<(), IoError> {
+        self.consumer_offsets.commit_all(offsets).await
+    }
+
+    /// sequence state of idempotent producers
+    pub fn producer_states(&self) -> &ProducerStates {
+        &self.producer_states
+    }
+
     /// earliest offset
     pub fn get_log_start_offset(&self) -> Offset {
         let min_base_offset = self.prev_segments.min_offset();
@@ -146,6 +259,204 @@ impl FileReplica {
         }
     }
 
+    /// first offset of record with timestamp (millis) at or after given timestamp.
+    /// log end offset if all records are older
+    pub async fn find_offset_by_timestamp(&self, timestamp: i64) -> Result<Offset, StorageError> {
+        for base_offset in self.prev_segments.base_offsets() {
+            if let Some(segment) = self.prev_segments.get_segment(base_offset) {
+                if let Some(offset) = segment.find_offset_by_timestamp(timestamp).await? {
+                    return Ok(offset);
+                }
+            }
+        }
+
+        Ok(self
+            .active_segment
+            .find_offset_by_timestamp(timestamp)
+            .await?
+            .unwrap_or_else(|| self.get_leo()))
+    }
+
+    /// delete oldest read segments which are past retention time or exceed maximum partition size.
+    /// active segment and segments with records above high watermark are never deleted.
+    /// return number of segments deleted
+    pub async fn enforce_retention(&mut self) -> Result<usize, StorageError> {
+        let expired = self.detach_expired_segments().await?;
+        let removed = expired.len();
+        expired.remove().await?;
+        Ok(removed)
+    }
+
+    /// detach oldest read segments which are past retention time or exceed maximum partition size,
+    /// so their files can be deleted without replica.
+    /// records not yet committed are kept, so followers can still sync them
+    pub async fn detach_expired_segments(&mut self) -> Result<ExpiredSegments, StorageError> {
+        let mut expired = vec![];
+
+        if let Some(retention_seconds) = self.option.retention_seconds {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as i64)
+                .unwrap_or(0);
+            let expire_before = now - retention_seconds as i64 * 1000;
+            while let Some(segment) = self.prev_segments.first_segment() {
+                if !self.is_first_segment_committed()
+                    || segment.get_max_timestamp().await? >= expire_before
+                {
+                    break;
+                }
+                if let Some(segment) = self.prev_segments.remove_first_segment() {
+                    debug!(
+                        "segment: {} expired, retention: {} secs",
+                        segment.get_base_offset(),
+                        retention_seconds
+                    );
+                    expired.push(segment);
+                }
+            }
+        }
+
+        if let Some(max_partition_size) = self.option.max_partition_size {
+            let mut size = self.prev_segments.total_size() + self.active_segment.get_msg_size();
+            while size > max_partition_size && self.is_first_segment_committed() {
+                match self.prev_segments.remove_first_segment() {
+                    Some(segment) => {
+                        debug!(
+                            "partition size: {} exceeds: {}, removing segment: {}",
+                            size,
+                            max_partition_size,
+                            segment.get_base_offset()
+                        );
+                        size -= segment.get_msg_size();
+                        expired.push(segment);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if !expired.is_empty() {
+            debug!(
+                "retention detached {} segments, log start offset: {}",
+                expired.len(),
+                self.get_log_start_offset()
+            );
+        }
+
+        Ok(ExpiredSegments(expired))
+    }
+
+    /// rewrite read segments so only latest record for each key is kept, if compaction is enabled.
+    /// return number of segments rewritten
+    pub async fn compact(&mut self) -> Result<usize, StorageError> {
+        match self.prepare_compaction() {
+            Some(compaction) => {
+                let compacted = compaction.run().await?;
+                self.apply_compaction(compacted).await
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// compaction of committed log, if compaction is enabled and segments have been rolled over
+    /// since last run. Keys are collected from those new segments and active segment, older segments
+    /// are already compacted and can only have records superseded by these keys.
+    /// compaction is run without replica, so replica can be written meanwhile
+    pub fn prepare_compaction(&self) -> Option<Compaction> {
+        let cleaned_offset = *self.cleaned_checkpoint.as_ref()?.get_offset();
+        let hw = self.get_hw();
+        let dirty_offset = self.active_segment.get_base_offset().min(hw);
+        if dirty_offset <= cleaned_offset {
+            trace!(
+                "no new committed segments to compact since: {}",
+                cleaned_offset
+            );
+            return None;
+        }
+
+        let mut key_segments = vec![];
+        let mut segments = vec![];
+        for (base_offset, end_offset) in self.prev_segment_ranges() {
+            if end_offset > cleaned_offset {
+                key_segments.push(base_offset);
+            }
+            if end_offset <= hw {
+                segments.push((base_offset, end_offset));
+            }
+        }
+        key_segments.push(self.active_segment.get_base_offset());
+
+        let delete_tombstone_before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0)
+            - self.option.tombstone_retention_seconds as i64 * 1000;
+
+        Some(Compaction {
+            option: self.option.clone(),
+            cleaned_offset: dirty_offset,
+            hw,
+            key_segments,
+            segments,
+            delete_tombstone_before,
+        })
+    }
+
+    /// base and end offsets of read segments, segment ends where next one starts
+    /// true if all records of first read segment are below high watermark
+    fn is_first_segment_committed(&self) -> bool {
+        self.prev_segment_ranges()
+            .first()
+            .map(|(_, end_offset)| *end_offset <= self.get_hw())
+            .unwrap_or(false)
+    }
+
+    fn prev_segment_ranges(&self) -> Vec<(Offset, Offset)> {
+        let base_offsets = self.prev_segments.base_offsets();
+        let end_offsets = base_offsets
+            .iter()
+            .skip(1)
+            .copied()
+            .chain(std::iter::once(self.active_segment.get_base_offset()));
+        base_offsets.iter().copied().zip(end_offsets).collect()
+    }
+
+    /// replace segments with their rewritten ones. segments which were removed or truncated
+    /// while compaction was running are not replaced. return number of segments replaced
+    pub async fn apply_compaction(
+        &mut self,
+        compacted: CompactedSegments,
+    ) -> Result<usize, StorageError> {
+        if self.cleaned_checkpoint.is_none() {
+            return Ok(0);
+        }
+
+        let ranges = self.prev_segment_ranges();
+        let mut applied = 0;
+        for (base_offset, end_offset) in compacted.segments {
+            if !ranges.contains(&(base_offset, end_offset)) {
+                debug!("segment: {} changed during compaction", base_offset);
+                remove_compacted_segment(base_offset, &self.option).await?;
+                continue;
+            }
+            let segment = install_compacted_segment(base_offset, end_offset, &self.option).await?;
+            self.prev_segments.add_segment(segment);
+            applied += 1;
+        }
+        if let Some(checkpoint) = &mut self.cleaned_checkpoint {
+            checkpoint.write(compacted.cleaned_offset).await?;
+        }
+
+        if applied > 0 {
+            debug!(
+                "compacted {} segments, cleaned up to: {}",
+                applied, compacted.cleaned_offset
+            );
+        }
+
+        Ok(applied)
+    }
+
     /// find the segment that contains offsets
     /// segment could be active segment which can be written
     /// or read only segment.
@@ -212,6 +523,31 @@ impl FileReplica {
         response: &mut P,
     ) where
         P: SlicePartitionResponse,
+    {
+        self.read_records_slice(start_offset, max_offset, max_len, false, response)
+            .await
+    }
+
+    /// read whole batches from start offset up to max length.
+    /// first batch is read even if it is longer, so reader which drops incomplete batches,
+    /// such as follower, always makes progress
+    pub async fn read_whole_batches<P>(&self, start_offset: Offset, max_len: u32, response: &mut P)
+    where
+        P: SlicePartitionResponse,
+    {
+        self.read_records_slice(start_offset, None, max_len, true, response)
+            .await
+    }
+
+    async fn read_records_slice<P>(
+        &self,
+        start_offset: Offset,
+        max_offset: Option<Offset>,
+        max_len: u32,
+        whole_batches: bool,
+        response: &mut P,
+    ) where
+        P: SlicePartitionResponse,
     {
         let high_watermark = self.get_hw();
         debug!(
@@ -225,7 +561,7 @@ impl FileReplica {
 
         match self.find_segment(start_offset) {
             Some(segment) => {
-                let slice = match segment {
+                let slice = match &segment {
                     SegmentSlice::MutableSegment(segment) => {
                         // optimization
                         if start_offset == self.get_leo() {
@@ -250,12 +586,25 @@ impl FileReplica {
                     }
                 };
 
+                let slice = match slice {
+                    Ok(Some(slice)) if whole_batches && slice.len() > max_len as u64 => segment
+                        .whole_batches_len(slice.position() as Size, max_len as u64)
+                        .await
+                        .map(|len| {
+                            let len = len.min(slice.len());
+                            Some((slice, len))
+                        }),
+                    Ok(Some(slice)) => Ok(Some((slice, max_len as u64))),
+                    Ok(None) => Ok(None),
+                    Err(err) => Err(err),
+                };
+
                 match slice {
                     Ok(slice) => match slice {
-                        Some(slice) => {
+                        Some((slice, max_len)) => {
                             use fluvio_future::file_slice::AsyncFileSlice;
 
-                            let limited_slice = if slice.len() > max_len as u64 {
+                            let limited_slice = if slice.len() > max_len {
                                 debug!(
                                     "retrieved record slice fd: {}, position: {}, max {} out of len {}",
                                     slice.fd(),
@@ -263,7 +612,7 @@ impl FileReplica {
                                     max_len,
                                     slice.len()
                                 );
-                                AsyncFileSlice::new(slice.fd(), slice.position(), max_len as u64)
+                                AsyncFileSlice::new(slice.fd(), slice.position(), max_len)
                             } else {
                                 debug!(
                                     "retrieved record slice fd: {}, position: {}, len: {}",
@@ -296,8 +645,45 @@ impl FileReplica {
         }
     }
 
+    /// read decoded batches instead of record slice, used when records are inspected before sending.
+    /// first batch may contain records before start offset. batches are read from single segment
+    /// * `max_offset`:  max offset (exclusive), end offset if not set
+    pub async fn read_batches(
+        &self,
+        start_offset: Offset,
+        max_offset: Option<Offset>,
+        max_len: u32,
+    ) -> Result<Vec<DefaultBatch>, StorageError> {
+        let max_offset = max_offset.unwrap_or_else(|| self.get_leo());
+        debug!(
+            "read batches at: {}, max offset: {}, max len: {}",
+            start_offset, max_offset, max_len
+        );
+        if start_offset == max_offset {
+            return Ok(vec![]);
+        }
+        if start_offset > max_offset {
+            return Err(StorageError::OffsetError(OffsetError::NotExistent));
+        }
+
+        match self.find_segment(start_offset) {
+            Some(SegmentSlice::MutableSegment(segment)) => {
+                segment
+                    .read_batches(start_offset, max_offset, max_len)
+                    .await
+            }
+            Some(SegmentSlice::Segment(segment)) => {
+                segment
+                    .read_batches(start_offset, max_offset, max_len)
+                    .await
+            }
+            None => Err(StorageError::OffsetError(OffsetError::NotExistent)),
+        }
+    }
+
     pub async fn send(&mut self, item: DefaultBatch) -> Result<(), StorageError> {
         trace!("start_send");
+        let header = item.get_header().clone();
         if let Err(err) = self.active_segment.send(item).await {
             match err {
                 StorageError::NoRoom(item) => {
@@ -308,11 +694,17 @@ impl FileReplica {
                     let old_mut_segment = mem::replace(&mut self.active_segment, new_segment);
                     let old_segment = old_mut_segment.as_segment().await?;
                     self.prev_segments.add_segment(old_segment);
+                    let log_start_offset = self.get_log_start_offset();
+                    self.producer_states
+                        .snapshot(last_offset, log_start_offset)
+                        .await?;
                     self.active_segment.send(item).await?;
                 }
                 _ => return Err(err),
             }
         }
+        let base_offset = self.get_leo() - header.last_offset_delta as Offset - 1;
+        self.producer_states.update(&header, base_offset);
         Ok(())
     }
 }
@@ -328,8 +720,11 @@ mod tests {
     use tracing::debug;
     use std::env::temp_dir;
     use std::fs;
+    use std::time::SystemTime;
+    use std::time::UNIX_EPOCH;
     use std::fs::metadata;
     use std::io::Cursor;
+    use std::path::PathBuf;
 
     use fluvio_future::test_async;
     use dataplane::batch::DefaultBatch;
@@ -337,14 +732,19 @@ mod tests {
     use dataplane::core::{Decoder, Encoder};
     use dataplane::fetch::FilePartitionResponse;
     use dataplane::record::RecordSet;
+    use dataplane::record::{DefaultAsyncBuffer, DefaultRecord};
+    use fluvio_future::fs::util as file_util;
     use flv_util::fixture::ensure_clean_dir;
 
     use super::FileReplica;
+    use crate::DefaultFileBatchStream;
     use crate::fixture::create_batch;
+    use crate::fixture::create_batch_with_producer;
     use crate::fixture::read_bytes_from_file;
     use crate::ConfigOption;
     use crate::StorageError;
     use crate::ReplicaStorage;
+    use crate::ProducerState;
 
     const TEST_SEG_NAME: &str = "00000000000000000020.log";
     const TEST_SE2_NAME: &str = "00000000000000000022.log";
@@ -360,6 +760,7 @@ mod tests {
             base_dir,
             index_max_interval_bytes: 1000,
             index_max_bytes: 1000,
+            ..Default::default()
         }
     }
 
@@ -371,6 +772,7 @@ mod tests {
             base_dir,
             index_max_bytes: 1000,
             index_max_interval_bytes: 0,
+            ..Default::default()
         }
     }
 
@@ -403,7 +805,7 @@ mod tests {
         assert_eq!(batch.get_header().magic, 2, "check magic");
         assert_eq!(batch.get_base_offset(), START_OFFSET);
         assert_eq!(batch.get_header().last_offset_delta, 1);
-        assert_eq!(batch.records.len(), 2);
+        assert_eq!(batch.records().len(), 2);
 
         // there should not be any segment for offset 0 since base offset is 20
         let segment = replica.find_segment(0);
@@ -532,14 +934,14 @@ mod tests {
         assert_eq!(replica.get_log_start_offset(), START_OFFSET);
         let replica_dir = &option.base_dir.join("test-1");
         let dir_contents = fs::read_dir(&replica_dir)?;
-        assert_eq!(dir_contents.count(), 5, "should be 5 files");
+        assert_eq!(dir_contents.count(), 8, "should be 8 files");
 
         let seg2_file = replica_dir.join(TEST_SE2_NAME);
         let bytes = read_bytes_from_file(&seg2_file)?;
 
         let batch = DefaultBatch::decode_from(&mut Cursor::new(bytes), 0)?;
         assert_eq!(batch.get_header().magic, 2, "check magic");
-        assert_eq!(batch.records.len(), 2);
+        assert_eq!(batch.records().len(), 2);
         assert_eq!(batch.get_base_offset(), 22);
 
         let metadata_res = metadata(replica_dir.join(TEST_SEG2_IDX));
@@ -580,6 +982,69 @@ mod tests {
         Ok(())
     }
 
+    const TEST_CONSUMER_OFFSET_DIR: &str = "test_consumer_offset";
+
+    #[test_async]
+    async fn test_replica_consumer_offset() -> Result<(), StorageError> {
+        let option = base_option(TEST_CONSUMER_OFFSET_DIR);
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        replica.commit_consumer_offset("group1", 2).await?;
+        assert_eq!(replica.consumer_offsets().get("group1"), Some(2));
+
+        drop(replica);
+
+        // restore replica
+        let replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+        assert_eq!(replica.consumer_offsets().get("group1"), Some(2));
+        assert_eq!(replica.consumer_offsets().get("group2"), None);
+
+        Ok(())
+    }
+
+    const TEST_PRODUCER_STATE_DIR: &str = "test_producer_state";
+
+    #[test_async]
+    async fn test_replica_producer_state() -> Result<(), StorageError> {
+        let option = rollover_option(TEST_PRODUCER_STATE_DIR);
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        // each batch has 2 records, every batch after first rolls over segment
+        for sequence in (0..6).step_by(2) {
+            let mut batch = create_batch_with_producer(1, 2);
+            batch.get_mut_header().producer_epoch = 0;
+            batch.get_mut_header().first_sequence = sequence;
+            replica.send(batch).await?;
+        }
+        // not idempotent
+        replica.send(create_batch()).await?;
+
+        let expected = Some(ProducerState {
+            epoch: 0,
+            last_sequence: 5,
+            last_offset: 5,
+        });
+        assert_eq!(replica.get_producer_state(1), expected);
+        assert_eq!(replica.get_producer_state(12), None);
+        assert_eq!(replica.producer_states().snapshot_offset(), 6);
+
+        drop(replica);
+
+        // restore replica, last batch of producer is before snapshot
+        let replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+        assert_eq!(replica.get_producer_state(1), expected);
+
+        Ok(())
+    }
+
     const TEST_COMMIT_FETCH_DIR: &str = "test_commit_fetch";
 
     /// test fetch only committed records
@@ -639,4 +1104,396 @@ mod tests {
 
         Ok(())
     }
+
+    const TEST_READ_BATCHES_DIR: &str = "test_read_batches";
+
+    #[test_async]
+    async fn test_replica_read_batches() -> Result<(), StorageError> {
+        let option = base_option(TEST_READ_BATCHES_DIR);
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        // each batch has 2 records
+        for _ in 0..3 {
+            replica.send(create_batch()).await?;
+        }
+        replica.update_high_watermark(4).await?;
+
+        // batch containing start offset is read first
+        let batches = replica.read_batches(1, None, 1000).await?;
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].get_base_offset(), 0);
+        assert_eq!(batches[2].get_last_offset(), 5);
+        assert_eq!(batches[1].records().len(), 2);
+
+        let batches = replica
+            .read_batches(0, Some(replica.get_hw()), 1000)
+            .await?;
+        assert_eq!(batches.len(), 2);
+
+        // at least one batch is read
+        let batches = replica.read_batches(2, None, 1).await?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].get_base_offset(), 2);
+
+        assert!(replica.read_batches(6, None, 1000).await?.is_empty());
+        assert!(replica.read_batches(7, None, 1000).await.is_err());
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_replica_read_whole_batches() -> Result<(), StorageError> {
+        let option = base_option("test_read_whole_batches");
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        for _ in 0..3 {
+            replica.send(create_batch()).await?;
+        }
+        let batch_len = create_batch().write_size(0);
+
+        // slice is cut at batch boundary
+        let mut partition_response = FilePartitionResponse::default();
+        replica
+            .read_whole_batches(0, (2 * batch_len + 10) as u32, &mut partition_response)
+            .await;
+        assert_eq!(partition_response.records.len(), 2 * batch_len);
+
+        // batch longer than max len is read whole
+        let mut partition_response = FilePartitionResponse::default();
+        replica
+            .read_whole_batches(2, 10, &mut partition_response)
+            .await;
+        assert_eq!(partition_response.records.len(), batch_len);
+
+        // records slice is cut at max len
+        let mut partition_response = FilePartitionResponse::default();
+        replica
+            .read_records(0, None, 10, &mut partition_response)
+            .await;
+        assert_eq!(partition_response.records.len(), 10);
+
+        Ok(())
+    }
+
+    const TEST_RETENTION_SIZE_DIR: &str = "test_retention_size";
+
+    #[test_async]
+    async fn test_replica_retention_size() -> Result<(), StorageError> {
+        let option = rollover_option(TEST_RETENTION_SIZE_DIR).max_partition_size(Some(200));
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        // each batch rolls over segment
+        for _ in 0..4 {
+            replica.send(create_batch()).await?;
+        }
+        assert_eq!(replica.prev_segments.len(), 3);
+        assert_eq!(replica.get_log_start_offset(), 0);
+
+        // segments with records above high watermark are kept
+        assert_eq!(replica.enforce_retention().await?, 0);
+        replica.update_high_watermark(3).await?;
+        assert_eq!(replica.enforce_retention().await?, 1);
+        assert_eq!(replica.get_log_start_offset(), 2);
+
+        replica.update_high_watermark_to_end().await?;
+        let removed = replica.enforce_retention().await?;
+        assert_eq!(removed, 1);
+        assert_eq!(replica.prev_segments.len(), 1);
+        assert_eq!(replica.get_log_start_offset(), 4);
+        assert!(!option
+            .base_dir
+            .join("test-0")
+            .join("00000000000000000000.log")
+            .exists());
+        assert!(!option
+            .base_dir
+            .join("test-0")
+            .join("00000000000000000000.index")
+            .exists());
+
+        // records before log start are gone
+        let mut partition_response = FilePartitionResponse::default();
+        replica
+            .read_records(
+                0,
+                None,
+                FileReplica::PREFER_MAX_LEN,
+                &mut partition_response,
+            )
+            .await;
+        assert_eq!(partition_response.error_code, ErrorCode::OffsetOutOfRange);
+        assert_eq!(partition_response.log_start_offset, 4);
+
+        Ok(())
+    }
+
+    const TEST_RETENTION_TIME_DIR: &str = "test_retention_time";
+
+    #[test_async]
+    async fn test_replica_retention_time() -> Result<(), StorageError> {
+        let option = rollover_option(TEST_RETENTION_TIME_DIR).retention_seconds(Some(3600));
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_millis() as i64;
+        // two expired batches followed by recent ones
+        for timestamp in &[now - 7200 * 1000, now - 7200 * 1000, now, now] {
+            let mut batch = create_batch();
+            batch.get_mut_header().first_timestamp = *timestamp;
+            batch.get_mut_header().max_time_stamp = *timestamp;
+            replica.send(batch).await?;
+        }
+        assert_eq!(replica.prev_segments.len(), 3);
+        replica.update_high_watermark_to_end().await?;
+
+        assert_eq!(replica.enforce_retention().await?, 2);
+        assert_eq!(replica.get_log_start_offset(), 4);
+        assert_eq!(replica.get_leo(), 8);
+
+        // nothing else is expired
+        assert_eq!(replica.enforce_retention().await?, 0);
+
+        Ok(())
+    }
+
+    async fn assert_offsets_by_timestamp(replica: &FileReplica) -> Result<(), StorageError> {
+        assert_eq!(replica.find_offset_by_timestamp(500).await?, 0);
+        assert_eq!(replica.find_offset_by_timestamp(1000).await?, 0);
+        assert_eq!(replica.find_offset_by_timestamp(2000).await?, 2);
+        assert_eq!(replica.find_offset_by_timestamp(2500).await?, 4);
+        assert_eq!(replica.find_offset_by_timestamp(4000).await?, 6);
+        assert_eq!(replica.find_offset_by_timestamp(5000).await?, 8);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_replica_find_offset_by_timestamp() -> Result<(), StorageError> {
+        for option in &[
+            base_option("test_timestamp"),
+            rollover_option("test_timestamp_rollover"),
+        ] {
+            let mut replica = FileReplica::create("test", 0, 0, option)
+                .await
+                .expect("test replica");
+            for timestamp in &[1000, 2000, 3000, 4000] {
+                let mut batch = create_batch();
+                batch.get_mut_header().first_timestamp = *timestamp;
+                batch.get_mut_header().max_time_stamp = *timestamp;
+                replica.send(batch).await?;
+            }
+            assert_offsets_by_timestamp(&replica).await?;
+            drop(replica);
+
+            // time index is loaded again
+            let replica = FileReplica::create("test", 0, 0, option)
+                .await
+                .expect("test replica");
+            assert_offsets_by_timestamp(&replica).await?;
+        }
+
+        Ok(())
+    }
+
+    const TEST_COMPACTION_DIR: &str = "test_compaction";
+
+    fn keyed_batch(records: &[(&str, Option<&str>)], timestamp: i64) -> DefaultBatch {
+        let mut batch = DefaultBatch::default();
+        batch.get_mut_header().magic = 2;
+        batch.get_mut_header().first_timestamp = timestamp;
+        batch.get_mut_header().max_time_stamp = timestamp;
+        for (key, value) in records {
+            batch.add_record(DefaultRecord {
+                key: DefaultAsyncBuffer::new(Some(key.as_bytes().to_vec())),
+                value: DefaultAsyncBuffer::new(value.map(|value| value.as_bytes().to_vec())),
+                ..Default::default()
+            });
+        }
+        batch
+    }
+
+    /// read (offset, key, value) of all records of log file
+    async fn read_log(path: PathBuf) -> Vec<(Offset, String, Option<String>)> {
+        let file = file_util::open(path).await.expect("open log");
+        let mut stream = DefaultFileBatchStream::new(file);
+        let mut records = vec![];
+        while let Some(batch_pos) = stream.next().await {
+            let batch = batch_pos.into_batch();
+            let base_offset = batch.get_base_offset();
+            for record in batch.into_records() {
+                records.push((
+                    base_offset + record.get_offset_delta(),
+                    String::from_utf8(record.get_key().inner_value_ref().clone().unwrap()).unwrap(),
+                    record
+                        .get_value()
+                        .inner_value_ref()
+                        .clone()
+                        .map(|value| String::from_utf8(value).unwrap()),
+                ));
+            }
+        }
+        records
+    }
+
+    #[test_async]
+    async fn test_replica_compaction() -> Result<(), StorageError> {
+        let option = rollover_option(TEST_COMPACTION_DIR).compact(true);
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_millis() as i64;
+        let old = now - (option.tombstone_retention_seconds as i64 + 60) * 1000;
+
+        // each batch rolls over segment
+        replica
+            .send(keyed_batch(&[("a", Some("a1")), ("b", Some("b1"))], old))
+            .await?;
+        replica
+            .send(keyed_batch(&[("a", Some("a2")), ("c", Some("c1"))], old))
+            .await?;
+        replica
+            .send(keyed_batch(&[("b", None), ("d", None)], old))
+            .await?;
+        replica
+            .send(keyed_batch(&[("c", None), ("e", Some("e1"))], now))
+            .await?;
+        replica.send(keyed_batch(&[("a", Some("a3"))], now)).await?;
+        assert_eq!(replica.prev_segments.len(), 4);
+        assert_eq!(replica.get_leo(), 9);
+
+        // uncommitted records are not compacted
+        assert_eq!(replica.compact().await?, 0);
+        replica.update_high_watermark_to_end().await?;
+        assert_eq!(replica.compact().await?, 3);
+
+        let replica_dir = option.base_dir.join("test-0");
+        // first segment is emptied but keeps its last batch
+        assert!(read_log(replica_dir.join("00000000000000000000.log"))
+            .await
+            .is_empty());
+        assert!(read_log(replica_dir.join("00000000000000000002.log"))
+            .await
+            .is_empty());
+        // old tombstones are removed
+        assert!(read_log(replica_dir.join("00000000000000000004.log"))
+            .await
+            .is_empty());
+        // recent tombstone is kept, offsets are preserved
+        assert_eq!(
+            read_log(replica_dir.join("00000000000000000006.log")).await,
+            vec![
+                (6, "c".to_owned(), None),
+                (7, "e".to_owned(), Some("e1".to_owned()))
+            ]
+        );
+
+        // nothing left to compact
+        assert_eq!(replica.compact().await?, 0);
+
+        // uncommitted record doesn't supersede committed record of same key
+        replica.send(keyed_batch(&[("e", Some("e2"))], now)).await?;
+        assert_eq!(replica.compact().await?, 0);
+        assert_eq!(
+            read_log(replica_dir.join("00000000000000000006.log")).await,
+            vec![
+                (6, "c".to_owned(), None),
+                (7, "e".to_owned(), Some("e1".to_owned()))
+            ]
+        );
+
+        // only keys of new segment are compacted from older segments, once it is committed
+        replica.send(keyed_batch(&[("f", Some("f1"))], now)).await?;
+        replica.update_high_watermark_to_end().await?;
+        assert_eq!(replica.compact().await?, 1);
+        assert_eq!(
+            read_log(replica_dir.join("00000000000000000006.log")).await,
+            vec![(6, "c".to_owned(), None)]
+        );
+        assert_eq!(replica.compact().await?, 0);
+
+        // compacted segments can be loaded again
+        let replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("reopen replica");
+        assert_eq!(replica.prev_segments.len(), 6);
+        assert_eq!(replica.get_leo(), 11);
+        assert_eq!(
+            replica
+                .cleaned_checkpoint
+                .as_ref()
+                .map(|checkpoint| *checkpoint.get_offset()),
+            Some(10)
+        );
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_replica_compaction_removed_segment() -> Result<(), StorageError> {
+        let option = rollover_option("test_compaction_removed_segment").compact(true);
+        let mut replica = FileReplica::create("test", 0, 0, &option)
+            .await
+            .expect("test replica");
+        for value in &["a1", "a2", "a3", "a4"] {
+            replica.send(keyed_batch(&[("a", Some(value))], 0)).await?;
+        }
+        replica.update_high_watermark_to_end().await?;
+
+        let compaction = replica.prepare_compaction().expect("compaction");
+        let compacted = compaction.run().await?;
+
+        // segment removed by retention while compaction was running is not replaced
+        let segment = replica
+            .prev_segments
+            .remove_first_segment()
+            .expect("segment");
+        segment.remove().await?;
+        assert_eq!(replica.apply_compaction(compacted).await?, 2);
+        assert!(!option
+            .base_dir
+            .join("test-0")
+            .join("00000000000000000000.log")
+            .exists());
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_replica_remove() -> Result<(), StorageError> {
+        let option = base_option("test_remove");
+        let mut replica = FileReplica::create("test", 0, START_OFFSET, &option)
+            .await
+            .expect("test replica");
+        replica.send(create_batch()).await.expect("send");
+        let replica_dir = option.base_dir.join("test-0");
+        assert!(replica_dir.exists());
+
+        replica.remove().await?;
+        assert!(!replica_dir.exists());
+
+        // replica with same name starts empty
+        let replica = FileReplica::create("test", 0, START_OFFSET, &option)
+            .await
+            .expect("test replica");
+        assert_eq!(replica.get_leo(), START_OFFSET);
+
+        let trash_path = replica.move_to_trash().await?;
+        assert!(!replica_dir.exists());
+        assert!(trash_path.starts_with(option.base_dir.join(crate::TRASH_DIR)));
+
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
 use std::fmt;
 use std::io::Error as IoError;
 use std::ops::Deref;
+use std::time::UNIX_EPOCH;
 
 use tracing::debug;
 use tracing::trace;
@@ -9,11 +10,14 @@ use dataplane::batch::DefaultBatch;
 use dataplane::{Offset, Size};
 use fluvio_future::file_slice::AsyncFileSlice;
 use fluvio_future::fs::util as file_util;
+use fluvio_future::fs::remove_file;
 
 use crate::BatchHeaderStream;
 use crate::mut_index::MutLogIndex;
 use crate::index::LogIndex;
 use crate::index::Index;
+use crate::index::EXTENSION as INDEX_EXTENSION;
+use crate::time_index::TimeIndex;
 use crate::records::FileRecords;
 use crate::mut_records::MutFileRecords;
 use crate::records::FileRecordsSlice;
@@ -24,6 +28,7 @@ use crate::DefaultFileBatchStream;
 use crate::index::OffsetPosition;
 use crate::validator::LogValidationError;
 use crate::util::OffsetError;
+use crate::util::generate_file_name;
 
 pub(crate) type MutableSegment = Segment<MutLogIndex, MutFileRecords>;
 pub(crate) type ReadSegment = Segment<LogIndex, FileRecordsSlice>;
@@ -51,13 +56,26 @@ impl<'a> SegmentSlice<'a> {
             Self::Segment(_) => false,
         }
     }
+
+    /// length of whole batches from position which fit in max len, at least first batch
+    pub async fn whole_batches_len(
+        &self,
+        start_pos: Size,
+        max_len: u64,
+    ) -> Result<u64, StorageError> {
+        match self {
+            Self::MutableSegment(segment) => segment.whole_batches_len(start_pos, max_len).await,
+            Self::Segment(segment) => segment.whole_batches_len(start_pos, max_len).await,
+        }
+    }
 }
 
-/// Segment contains both message log and index
+/// Segment contains message log, offset index and time index
 pub(crate) struct Segment<I, L> {
     option: ConfigOption,
     msg_log: L,
     index: I,
+    time_index: TimeIndex,
     base_offset: Offset,
     end_offset: Offset,
 }
@@ -116,6 +134,138 @@ where
         BatchHeaderStream::new_with_pos(file, start_pos).await
     }
 
+    /// length of whole batches from position which fit in max len.
+    /// first batch is counted even if it is longer
+    pub async fn whole_batches_len(
+        &self,
+        start_pos: Size,
+        max_len: u64,
+    ) -> Result<u64, StorageError> {
+        let mut header_stream = self.open_batch_header_stream(start_pos).await?;
+        let mut len: u64 = 0;
+        while let Some(batch_pos) = header_stream.next().await {
+            let batch_len = batch_pos.total_len() as u64;
+            if len > 0 && len + batch_len > max_len {
+                break;
+            }
+            len += batch_len;
+        }
+        Ok(len)
+    }
+
+    /// add batches written after last entry of time index.
+    /// builds whole time index if segment doesn't have one
+    async fn load_time_index(&mut self) -> Result<(), StorageError> {
+        let start_pos = match self.time_index.last_entry() {
+            Some((_, offset_delta)) => self
+                .index
+                .find_offset(offset_delta)
+                .map(|entry| entry.position())
+                .unwrap_or(0),
+            None => 0,
+        };
+        trace!("loading time index from pos: {}", start_pos);
+
+        let mut header_stream = self.open_batch_header_stream(start_pos).await?;
+        while let Some(batch_pos) = header_stream.next().await {
+            let batch = batch_pos.get_batch();
+            self.time_index
+                .send(
+                    batch.get_header().max_time_stamp,
+                    (batch.get_base_offset() - self.base_offset) as Size,
+                    batch_pos.total_len(),
+                )
+                .await?;
+        }
+        self.time_index.flush().await?;
+        Ok(())
+    }
+
+    /// first offset of record with timestamp (millis) at or after given timestamp.
+    /// none if all records of segment are older
+    pub async fn find_offset_by_timestamp(
+        &self,
+        timestamp: i64,
+    ) -> Result<Option<Offset>, StorageError> {
+        if self.time_index.max_timestamp() < timestamp {
+            return Ok(None);
+        }
+
+        let start_delta = self.time_index.find_start(timestamp);
+        let position = self
+            .index
+            .find_offset(start_delta)
+            .map(|entry| entry.position())
+            .unwrap_or(0);
+        trace!(
+            "finding timestamp: {}, starting at offset delta: {}, pos: {}",
+            timestamp,
+            start_delta,
+            position
+        );
+
+        let file = file_util::open(self.msg_log.get_path()).await?;
+        let mut batch_stream = DefaultFileBatchStream::new_with_pos(file, position).await?;
+        while let Some(batch_pos) = batch_stream.next().await {
+            let batch = batch_pos.get_batch();
+            let header = batch.get_header();
+            if header.max_time_stamp < timestamp {
+                continue;
+            }
+
+            // records may not have timestamp delta, then whole batch is returned
+            let offset = batch
+                .records()
+                .iter()
+                .find(|record| {
+                    header.first_timestamp + record.preamble.get_timestamp_delta() >= timestamp
+                })
+                .map(|record| batch.get_base_offset() + record.get_offset_delta())
+                .unwrap_or_else(|| batch.get_base_offset());
+            return Ok(Some(offset));
+        }
+
+        Ok(None)
+    }
+
+    /// read batches starting with batch containing start offset.
+    /// batches at or after max offset are not read, reading stops once max len is exceeded
+    /// but at least one batch is read
+    pub async fn read_batches(
+        &self,
+        start_offset: Offset,
+        max_offset: Offset,
+        max_len: u32,
+    ) -> Result<Vec<DefaultBatch>, StorageError> {
+        let start_pos = match self.find_offset_position(start_offset).await? {
+            Some(start_pos) => start_pos.get_pos(),
+            None => return Err(StorageError::OffsetError(OffsetError::NotExistent)),
+        };
+        trace!(
+            "reading batches at: {}, pos: {}, max offset: {}",
+            start_offset,
+            start_pos,
+            max_offset
+        );
+
+        let file = file_util::open(self.msg_log.get_path()).await?;
+        let mut batch_stream = DefaultFileBatchStream::new_with_pos(file, start_pos).await?;
+        let mut batches = vec![];
+        let mut total_len: u64 = 0;
+        while let Some(batch_pos) = batch_stream.next().await {
+            if batch_pos.get_base_offset() >= max_offset {
+                break;
+            }
+            total_len += batch_pos.total_len() as u64;
+            if !batches.is_empty() && total_len > max_len as u64 {
+                break;
+            }
+            batches.push(batch_pos.into_batch());
+        }
+
+        Ok(batches)
+    }
+
     #[allow(dead_code)]
     pub async fn open_default_batch_stream(&self) -> Result<DefaultFileBatchStream, StorageError> {
         let file_path = self.msg_log.get_path();
@@ -217,20 +367,58 @@ impl Segment<LogIndex, FileRecordsSlice> {
         let msg_log = FileRecordsSlice::open(base_offset, option).await?;
         let base_offset = msg_log.get_base_offset();
         let index = LogIndex::open_from_offset(base_offset, option).await?;
+        let time_index = TimeIndex::open(base_offset, option).await?;
 
         let base_offset = msg_log.get_base_offset();
-        Ok(Segment {
+        let mut segment = Segment {
             msg_log,
             index,
+            time_index,
             option: option.to_owned(),
             base_offset,
             end_offset: base_offset,
-        })
+        };
+        segment.load_time_index().await?;
+        Ok(segment)
     }
 
     pub fn to_segment_slice(&self) -> SegmentSlice {
         SegmentSlice::new_segment(self)
     }
+
+    /// size of message log in bytes
+    pub fn get_msg_size(&self) -> u64 {
+        self.msg_log.get_len()
+    }
+
+    /// latest timestamp of records in this segment in milliseconds.
+    /// batches without timestamp fall back to modification time of the log
+    pub async fn get_max_timestamp(&self) -> Result<i64, StorageError> {
+        let max_timestamp = self.time_index.max_timestamp();
+        if max_timestamp > 0 {
+            return Ok(max_timestamp);
+        }
+
+        let modified = self.msg_log.get_file().metadata().await?.modified()?;
+        Ok(modified
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0))
+    }
+
+    /// delete message log and indexes of this segment
+    pub async fn remove(self) -> Result<(), StorageError> {
+        let log_path = self.msg_log.get_path().to_owned();
+        let index_path =
+            generate_file_name(&self.option.base_dir, self.base_offset, INDEX_EXTENSION);
+        let time_index_path = self.time_index.get_path().to_owned();
+        debug!("removing segment: {}", log_path.display());
+        drop(self);
+        remove_file(&log_path).await?;
+        remove_file(&index_path).await?;
+        remove_file(&time_index_path).await?;
+        Ok(())
+    }
 }
 
 impl Unpin for Segment<MutLogIndex, MutFileRecords> {}
@@ -246,11 +434,13 @@ impl Segment<MutLogIndex, MutFileRecords> {
         let msg_log = MutFileRecords::create(base_offset, option).await?;
 
         let index = MutLogIndex::create(base_offset, option).await?;
+        let time_index = TimeIndex::open(base_offset, option).await?;
 
         Ok(MutableSegment {
             option: option.to_owned(),
             msg_log,
             index,
+            time_index,
             base_offset,
             end_offset: base_offset,
         })
@@ -268,21 +458,30 @@ impl Segment<MutLogIndex, MutFileRecords> {
         let msg_log = MutFileRecords::open(base_offset, option).await?;
         let base_offset = msg_log.get_base_offset();
         let index = MutLogIndex::open(base_offset, option).await?;
+        let time_index = TimeIndex::open(base_offset, option).await?;
 
         let base_offset = msg_log.get_base_offset();
-        Ok(MutableSegment {
+        let mut segment = MutableSegment {
             option: option.to_owned(),
             msg_log,
             index,
+            time_index,
             base_offset,
             end_offset: base_offset,
-        })
+        };
+        segment.load_time_index().await?;
+        Ok(segment)
     }
 
     fn get_log_pos(&self) -> u32 {
         self.msg_log.get_pos()
     }
 
+    /// size of message log in bytes
+    pub fn get_msg_size(&self) -> u64 {
+        self.get_log_pos() as u64
+    }
+
     /// validate the segment and load last offset
     pub async fn validate(&mut self) -> Result<(), StorageError> {
         self.end_offset = self.msg_log.validate().await?;
@@ -331,7 +530,8 @@ impl Segment<MutLogIndex, MutFileRecords> {
             ));
         }
 
-        let batch_offset_delta = (current_offset - base_offset) as i32;
+        // batches of compacted segments may skip offsets
+        let batch_offset_delta = (item.base_offset - base_offset) as i32;
         debug!(
             "start writing batch base_off: {}, pos: {}, batch record: {}",
             base_offset,
@@ -339,16 +539,21 @@ impl Segment<MutLogIndex, MutFileRecords> {
             compute_batch_record_size(&item)
         );
 
+        let batch_base_offset = item.base_offset;
+        let batch_timestamp = item.get_header().max_time_stamp;
         match self.msg_log.send(item).await {
             Ok(_) => {
                 let batch_len = self.msg_log.get_pos();
                 self.index
                     .send((batch_offset_delta as u32, pos, batch_len))
                     .await?;
+                self.time_index
+                    .send(batch_timestamp, batch_offset_delta as u32, batch_len - pos)
+                    .await?;
 
                 let last_offset_delta = self.msg_log.get_item_last_offset_delta();
                 trace!("flushing: last offset delta: {}", last_offset_delta);
-                self.end_offset = self.end_offset + last_offset_delta as Offset + 1;
+                self.end_offset = batch_base_offset + last_offset_delta as Offset + 1;
                 debug!("send flushed leo: {}", self.end_offset);
                 Ok(())
             }
@@ -365,7 +570,7 @@ impl Segment<MutLogIndex, MutFileRecords> {
 /// compute total number of values in the default batch
 fn compute_batch_record_size(batch: &DefaultBatch) -> usize {
     batch
-        .records
+        .records()
         .iter()
         .fold(0, |acc, batch| acc + batch.value.len())
 }
@@ -401,6 +606,7 @@ mod tests {
             base_dir,
             index_max_interval_bytes,
             index_max_bytes: 1000,
+            ..Default::default()
         }
     }
 
@@ -436,7 +642,7 @@ mod tests {
         let batch = DefaultBatch::decode_from(&mut Cursor::new(bytes), 0).expect("decode");
         assert_eq!(batch.get_base_offset(), 20);
         assert_eq!(batch.get_header().magic, 2, "check magic");
-        assert_eq!(batch.records.len(), 1);
+        assert_eq!(batch.records().len(), 1);
 
         let seg1_metadata = metadata(test_dir.join(SEG_INDEX)).expect("read metadata");
         assert_eq!(seg1_metadata.len(), 1000);
@@ -480,7 +686,7 @@ mod tests {
         let batch = DefaultBatch::decode_from(&mut Cursor::new(bytes), 0)?;
         assert_eq!(batch.get_base_offset(), 20);
         assert_eq!(batch.get_header().magic, 2, "check magic");
-        assert_eq!(batch.records.len(), 4);
+        assert_eq!(batch.records().len(), 4);
 
         let seg1_metadata = metadata(test_dir.join(SEG_INDEX))?;
         assert_eq!(seg1_metadata.len(), 1000);
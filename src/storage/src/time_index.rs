@@ -0,0 +1,201 @@
+use std::convert::TryInto;
+use std::io::Error as IoError;
+use std::mem::size_of;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tracing::debug;
+use tracing::trace;
+use futures_lite::io::AsyncReadExt;
+use futures_lite::io::AsyncWriteExt;
+
+use fluvio_future::fs::File;
+use fluvio_future::fs::util as file_util;
+use dataplane::{Offset, Size};
+
+use crate::util::generate_file_name;
+use crate::ConfigOption;
+
+pub const EXTENSION: &str = "timeindex";
+
+/// timestamp followed by offset delta
+const TIME_INDEX_ENTRY_SIZE: usize = size_of::<i64>() + size_of::<Size>();
+
+/// Segment time index
+///
+/// Maps batch timestamp (millis) into offset delta of the batch.
+/// Entry is only added when timestamp is greater than all previous batches,
+/// so entries are ordered by both timestamp and offset and
+/// no batch before an entry has greater timestamp.
+///
+/// Like offset index, entries are at least `index_max_interval_bytes` apart.
+/// Latest timestamp skipped by interval is kept as pending and written when segment is loaded again.
+///
+/// Index is small, so all entries are kept in memory and appended to file.
+pub(crate) struct TimeIndex {
+    path: PathBuf,
+    file: File,
+    entries: Vec<(i64, Size)>,
+    pending: Option<(i64, Size)>,
+    bytes_delta: Size,
+    max_interval_bytes: Size,
+}
+
+impl TimeIndex {
+    /// open time index of segment, created if not exists
+    pub async fn open(base_offset: Offset, option: &ConfigOption) -> Result<Self, IoError> {
+        let path = generate_file_name(&option.base_dir, base_offset, EXTENSION);
+        debug!("opening time index at: {}", path.display());
+        let mut file = file_util::open_read_append(&path).await?;
+
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes).await?;
+        let entries = bytes
+            .chunks_exact(TIME_INDEX_ENTRY_SIZE)
+            .map(|entry| {
+                let (timestamp, offset_delta) = entry.split_at(size_of::<i64>());
+                (
+                    i64::from_be_bytes(timestamp.try_into().unwrap()),
+                    Size::from_be_bytes(offset_delta.try_into().unwrap()),
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            path,
+            file,
+            entries,
+            pending: None,
+            bytes_delta: 0,
+            max_interval_bytes: option.index_max_interval_bytes,
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn last_entry(&self) -> Option<(i64, Size)> {
+        self.entries.last().copied()
+    }
+
+    /// latest timestamp in segment, -1 if no batch has timestamp
+    pub fn max_timestamp(&self) -> i64 {
+        self.pending
+            .or_else(|| self.last_entry())
+            .map(|(timestamp, _)| timestamp)
+            .unwrap_or(-1)
+    }
+
+    /// offset delta from where batches must be scanned to find timestamp.
+    /// all batches before it have earlier timestamp
+    pub fn find_start(&self, timestamp: i64) -> Size {
+        let idx = self
+            .entries
+            .partition_point(|(entry_timestamp, _)| *entry_timestamp < timestamp);
+        if idx == 0 {
+            0
+        } else {
+            self.entries[idx - 1].1
+        }
+    }
+
+    /// record max timestamp of batch
+    pub async fn send(
+        &mut self,
+        timestamp: i64,
+        offset_delta: Size,
+        batch_len: Size,
+    ) -> Result<(), IoError> {
+        // batches without timestamp are not indexed
+        if timestamp <= 0 || timestamp <= self.max_timestamp() {
+            self.bytes_delta += batch_len;
+            return Ok(());
+        }
+
+        if self.bytes_delta < self.max_interval_bytes && !self.entries.is_empty() {
+            trace!(
+                "time index skipped, accumulated bytes: {}",
+                self.bytes_delta
+            );
+            self.pending = Some((timestamp, offset_delta));
+            self.bytes_delta += batch_len;
+            return Ok(());
+        }
+
+        self.write_entry(timestamp, offset_delta).await
+    }
+
+    /// write pending timestamp, so max timestamp is known without scanning log
+    pub async fn flush(&mut self) -> Result<(), IoError> {
+        if let Some((timestamp, offset_delta)) = self.pending {
+            self.write_entry(timestamp, offset_delta).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_entry(&mut self, timestamp: i64, offset_delta: Size) -> Result<(), IoError> {
+        let mut bytes = Vec::with_capacity(TIME_INDEX_ENTRY_SIZE);
+        bytes.extend_from_slice(&timestamp.to_be_bytes());
+        bytes.extend_from_slice(&offset_delta.to_be_bytes());
+        self.file.write_all(&bytes).await?;
+        self.file.flush().await?;
+        trace!(
+            "time index written, timestamp: {}, offset delta: {}",
+            timestamp,
+            offset_delta
+        );
+
+        self.entries.push((timestamp, offset_delta));
+        self.pending = None;
+        self.bytes_delta = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+
+    use super::TimeIndex;
+    use crate::ConfigOption;
+
+    #[test_async]
+    async fn test_time_index() -> Result<(), std::io::Error> {
+        let test_dir = temp_dir().join("time-index");
+        ensure_new_dir(&test_dir)?;
+        let option = ConfigOption {
+            base_dir: test_dir,
+            index_max_interval_bytes: 100,
+            ..Default::default()
+        };
+
+        let mut index = TimeIndex::open(0, &option).await?;
+        assert_eq!(index.max_timestamp(), -1);
+
+        index.send(1000, 0, 60).await?; // first is always written
+        index.send(900, 2, 60).await?; // older, ignored
+        index.send(1100, 4, 60).await?; // within interval, pending
+        index.send(1200, 6, 60).await?; // written
+        index.send(1300, 8, 60).await?; // pending
+        assert_eq!(index.entries, vec![(1000, 0), (1200, 6)]);
+        assert_eq!(index.max_timestamp(), 1300);
+
+        assert_eq!(index.find_start(500), 0);
+        assert_eq!(index.find_start(1000), 0);
+        assert_eq!(index.find_start(1150), 0);
+        assert_eq!(index.find_start(1250), 6);
+
+        index.flush().await?;
+        drop(index);
+
+        let index = TimeIndex::open(0, &option).await?;
+        assert_eq!(index.entries, vec![(1000, 0), (1200, 6), (1300, 8)]);
+        assert_eq!(index.max_timestamp(), 1300);
+        Ok(())
+    }
+}
@@ -24,11 +24,15 @@ pub const SPU_PRIVATE_HOSTNAME: &str = "0.0.0.0";
 pub const SPU_CREDENTIALS_FILE: &str = "/etc/fluvio/.credentials/token_secret";
 pub const SPU_RETRY_SC_TIMEOUT_MS: u16 = 3000;
 pub const SPU_MIN_IN_SYNC_REPLICAS: u16 = 1;
+pub const SPU_TRANSFORM_FUEL: u64 = 1_000_000;
+pub const SPU_TRANSFORM_MEMORY_PAGES: u32 = 160; // 10MiB
+pub const SPU_TRANSFORM_MAX_MODULE_BYTES: usize = 1048576; // 1MiB
 pub const SPU_LOG_BASE_DIR: &str = "/tmp/fluvio";
 pub const SPU_LOG_SIZE: &str = "1Gi";
 pub const SPU_LOG_INDEX_MAX_BYTES: u32 = 10485760;
 pub const SPU_LOG_INDEX_MAX_INTERVAL_BYTES: u32 = 4096;
 pub const SPU_LOG_SEGMENT_MAX_BYTES: u32 = 1073741824;
+pub const SPU_LOG_TOMBSTONE_RETENTION_SECONDS: u32 = 86400; // 1 day
 
 // CLI config
 pub const CLI_PROFILES_DIR: &str = "profiles";